@@ -70,5 +70,6 @@ fn main() {
         }
     }
 
+    rustc_smir::rustc_internal::json::install_stable_mir_emitter();
     rustc_driver::main()
 }
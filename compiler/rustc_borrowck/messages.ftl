@@ -59,6 +59,9 @@ borrowck_could_not_normalize =
 borrowck_could_not_prove =
     could not prove `{$predicate}`
 
+borrowck_explain_write =
+    failed to write the borrow conflict explanation graph to `{$path}`: {$error}
+
 borrowck_func_take_self_moved_place =
     `{$func}` takes ownership of the receiver `self`, which moves {$place_name}
 
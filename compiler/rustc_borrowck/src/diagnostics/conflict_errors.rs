@@ -929,6 +929,12 @@ pub(crate) fn report_conflicting_borrow(
         let borrow_spans = self.borrow_spans(span, location);
         let span = borrow_spans.args_or_use();
 
+        if self.infcx.tcx.sess.opts.unstable_opts.borrowck_explain.is_some()
+            && let Some(explanation) = self.explain_conflict(location, issued_borrow)
+        {
+            self.buffer_conflict_explanation(span, explanation);
+        }
+
         let container_name = if issued_spans.for_generator() || borrow_spans.for_generator() {
             "generator"
         } else {
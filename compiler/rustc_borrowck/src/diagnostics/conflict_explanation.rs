@@ -0,0 +1,203 @@
+//! Explanations of borrow conflicts for `-Z borrowck-explain`.
+//!
+//! A loan conflicts with an access if the region of the loan is live at the access. This happens
+//! either because the reference created by the loan is itself live there, or because the region
+//! of the loan must outlive a region that is. The explanation is the shortest path of outlives
+//! constraints from the region of the loan to a region that is live at the access, shown both as
+//! annotated source and as a DOT graph, which is written to `<crate>.borrowck_explain.dot` in the
+//! output directory. Loans, regions and points are named as in the `-Z nll-facts` output, so the
+//! explanation can be cross-checked with the facts.
+
+use rustc_errors::{Diagnostic, MultiSpan};
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::mir::{ConstraintCategory, Location};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::BorrowckExplain;
+use rustc_session::Session;
+use rustc_span::Span;
+use std::fmt::Write;
+use std::fs;
+
+use crate::{
+    borrow_set::BorrowData, constraints::OutlivesConstraint, nll::ConstraintDescription,
+    session_diagnostics::ExplainWrite, MirBorrowckCtxt,
+};
+
+/// Why a loan is live at a conflicting access.
+pub(crate) struct ConflictExplanation {
+    /// Labels of the annotated source: the loan, each constraint of the path, and the point where
+    /// the last region of the path is live.
+    labels: Vec<(Span, String)>,
+    /// The same path as a DOT graph.
+    dot: String,
+}
+
+impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
+    /// Explains why `borrow` is still live at `location`, where it conflicts with an access.
+    pub(crate) fn explain_conflict(
+        &self,
+        location: Location,
+        borrow: &BorrowData<'tcx>,
+    ) -> Option<ConflictExplanation> {
+        let regioncx = &self.regioncx;
+        let mut live_location = location;
+        let mut path = regioncx.constraint_path_to_region_live_at(borrow.region, location)?;
+        // Like in `explain_why_borrow_contains_point`, a loan that is live where it is issued is
+        // usually used in a later iteration of a loop, so explain why it is live at the end of it.
+        if path.0.is_empty()
+            && let Some(loop_location) =
+                regioncx.find_loop_terminator_location(borrow.region, &self.body)
+            && let Some(loop_path) =
+                regioncx.constraint_path_to_region_live_at(borrow.region, loop_location)
+        {
+            live_location = loop_location;
+            path = loop_path;
+        }
+        let (constraints, live_region) = path;
+
+        let borrow_index = self.borrow_set.get_index_of(&borrow.reserve_location)?;
+        let source_map = self.infcx.tcx.sess.source_map();
+        let point = |location| {
+            format!(
+                "{:?}",
+                self.location_table.to_location(self.location_table.mid_index(location))
+            )
+        };
+        let issued_point = point(borrow.reserve_location);
+        let live_point = point(live_location);
+        let borrowed_place = self
+            .describe_place(borrow.borrowed_place.as_ref())
+            .unwrap_or_else(|| "value".to_string());
+
+        let mut labels = vec![(
+            self.body.source_info(borrow.reserve_location).span,
+            format!(
+                "loan `{borrow_index:?}` of `{borrowed_place}` is issued at `{issued_point}` \
+                 with region `{:?}`",
+                borrow.region
+            ),
+        )];
+        let mut dot = String::from("digraph borrowck_explain {\n");
+        let _ = writeln!(
+            dot,
+            "    {} [shape=box, label={}];",
+            quote(&format!("{borrow_index:?}")),
+            quote(&format!("loan {borrow_index:?} of `{borrowed_place}`\n{issued_point}")),
+        );
+        let _ = writeln!(
+            dot,
+            "    {} [shape=box, label={}];",
+            quote(&live_point),
+            quote(&format!(
+                "{live_point}\n{}",
+                source_map.span_to_embeddable_string(self.body.source_info(live_location).span)
+            )),
+        );
+        let _ = writeln!(
+            dot,
+            "    {} -> {} [label=\"issued with\"];",
+            quote(&format!("{borrow_index:?}")),
+            quote(&format!("{:?}", borrow.region)),
+        );
+
+        for constraint in &constraints {
+            let span = self.constraint_span(constraint);
+            let origin = constraint_origin(constraint);
+            labels.push((
+                span,
+                format!("`{:?}: {:?}` due to {origin}", constraint.sup, constraint.sub),
+            ));
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [label={}];",
+                quote(&format!("{:?}", constraint.sup)),
+                quote(&format!("{:?}", constraint.sub)),
+                quote(&format!("{origin}\n{}", source_map.span_to_embeddable_string(span))),
+            );
+        }
+
+        labels.push((
+            self.body.source_info(live_location).span,
+            format!("`{live_region:?}` is live at `{live_point}`, so the loan is still in scope"),
+        ));
+        let _ = writeln!(
+            dot,
+            "    {} -> {} [label=\"live at\"];",
+            quote(&format!("{live_region:?}")),
+            quote(&live_point),
+        );
+        dot.push_str("}\n");
+
+        Some(ConflictExplanation { labels, dot })
+    }
+
+    fn constraint_span(&self, constraint: &OutlivesConstraint<'tcx>) -> Span {
+        if constraint.span.is_dummy() {
+            constraint.locations.span(&self.body)
+        } else {
+            constraint.span
+        }
+    }
+}
+
+impl ConflictExplanation {
+    /// Whether `-Z borrowck-explain` selects `diag`, which is about to be emitted.
+    pub(crate) fn is_selected(sess: &Session, diag: &Diagnostic) -> bool {
+        match &sess.opts.unstable_opts.borrowck_explain {
+            None => false,
+            Some(BorrowckExplain::Index(index)) => {
+                diag.is_error() && sess.err_count() + 1 == *index
+            }
+            Some(BorrowckExplain::Location { file, line, col }) => {
+                let Some(span) = diag.span.primary_span() else { return false };
+                let loc = sess.source_map().lookup_char_pos(span.lo());
+                loc.file.name.prefer_local().to_string().ends_with(file.as_str())
+                    && loc.line == *line
+                    && col.map_or(true, |col| loc.col.0 + 1 == col)
+            }
+        }
+    }
+
+    /// Adds the annotated source to `diag`, and writes the DOT graph to a file in the output
+    /// directory.
+    pub(crate) fn emit(self, tcx: TyCtxt<'_>, diag: &mut Diagnostic) {
+        let mut span = MultiSpan::from_spans(self.labels.iter().map(|(span, _)| *span).collect());
+        for (label_span, label) in self.labels {
+            span.push_span_label(label_span, label);
+        }
+        diag.span_note(span, "the loan is still in scope because of these region constraints");
+
+        let path = tcx
+            .output_filenames(())
+            .out_directory
+            .join(format!("{}.borrowck_explain.dot", tcx.crate_name(LOCAL_CRATE)));
+        match fs::write(&path, self.dot) {
+            Ok(()) => {
+                diag.note(format!("the constraint graph was written to `{}`", path.display()));
+            }
+            Err(error) => {
+                tcx.sess.emit_err(ExplainWrite { path, error });
+            }
+        }
+    }
+}
+
+/// Names the origin of `constraint`, including when its category has no description.
+fn constraint_origin(constraint: &OutlivesConstraint<'_>) -> String {
+    let origin = match constraint.category {
+        ConstraintCategory::Predicate(_) => "a where-clause",
+        ConstraintCategory::Boring | ConstraintCategory::BoringNoLocation => "a MIR statement",
+        ConstraintCategory::Internal => "an internal requirement",
+        category => category.description().trim_end(),
+    };
+    if constraint.from_closure {
+        format!("{origin} (required by a closure)")
+    } else {
+        origin.to_string()
+    }
+}
+
+/// Quotes `s` as a DOT identifier.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
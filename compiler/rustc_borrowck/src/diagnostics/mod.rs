@@ -40,12 +40,14 @@
 
 mod bound_region_errors;
 mod conflict_errors;
+mod conflict_explanation;
 mod explain_borrow;
 mod move_errors;
 mod mutability_errors;
 mod region_errors;
 
 pub(crate) use bound_region_errors::{ToUniverseInfo, UniverseInfo};
+pub(crate) use conflict_explanation::ConflictExplanation;
 pub(crate) use mutability_errors::AccessKind;
 pub(crate) use outlives_suggestion::OutlivesSuggestionBuilder;
 pub(crate) use region_errors::{ErrorConstraintInfo, RegionErrorKind, RegionErrors};
@@ -20,6 +20,7 @@
 
 use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
 use rustc_data_structures::graph::dominators::Dominators;
+use rustc_data_structures::profiling::ItemActivity;
 use rustc_errors::{Diagnostic, DiagnosticBuilder, DiagnosticMessage, SubdiagnosticMessage};
 use rustc_fluent_macro::fluent_messages;
 use rustc_hir as hir;
@@ -59,7 +60,7 @@
 
 use crate::session_diagnostics::VarNeedNotMut;
 
-use self::diagnostics::{AccessKind, RegionName};
+use self::diagnostics::{AccessKind, ConflictExplanation, RegionName};
 use self::location::LocationTable;
 use self::prefixes::PrefixSet;
 use consumers::{BodyWithBorrowckFacts, ConsumerOptions};
@@ -138,6 +139,8 @@ fn mir_borrowck(tcx: TyCtxt<'_>, def: LocalDefId) -> &BorrowCheckResult<'_> {
         return tcx.arena.alloc(result);
     }
 
+    let _timer = tcx.item_activity(ItemActivity::Borrowck, def);
+
     let hir_owner = tcx.hir().local_def_id_to_hir_id(def).owner;
 
     let infcx =
@@ -2293,6 +2296,9 @@ pub struct BorrowckErrors<'tcx> {
         buffered_mut_errors: FxIndexMap<Span, (DiagnosticBuilder<'tcx, ErrorGuaranteed>, usize)>,
         /// Diagnostics to be reported buffer.
         buffered: Vec<Diagnostic>,
+        /// Explanations of borrow conflicts for `-Z borrowck-explain`, by the primary span of
+        /// their diagnostic. They are only added to the diagnostic that the flag selects.
+        conflict_explanations: FxIndexMap<Span, ConflictExplanation>,
         /// Set to Some if we emit an error during borrowck
         tainted_by_errors: Option<ErrorGuaranteed>,
     }
@@ -2304,6 +2310,7 @@ pub fn new(tcx: TyCtxt<'tcx>) -> Self {
                 buffered_move_errors: BTreeMap::new(),
                 buffered_mut_errors: Default::default(),
                 buffered: Default::default(),
+                conflict_explanations: Default::default(),
                 tainted_by_errors: None,
             }
         }
@@ -2368,6 +2375,14 @@ pub fn buffer_mut_error(
             self.errors.buffered_mut_errors.insert(span, (t, count));
         }
 
+        pub fn buffer_conflict_explanation(
+            &mut self,
+            span: Span,
+            explanation: ConflictExplanation,
+        ) {
+            self.errors.conflict_explanations.insert(span, explanation);
+        }
+
         pub fn emit_errors(&mut self) -> Option<ErrorGuaranteed> {
             // Buffer any move errors that we collected and de-duplicated.
             for (_, (_, diag)) in std::mem::take(&mut self.errors.buffered_move_errors) {
@@ -2384,8 +2399,15 @@ pub fn emit_errors(&mut self) -> Option<ErrorGuaranteed> {
             if !self.errors.buffered.is_empty() {
                 self.errors.buffered.sort_by_key(|diag| diag.sort_span);
 
+                let sess = self.infcx.tcx.sess;
                 for mut diag in self.errors.buffered.drain(..) {
-                    self.infcx.tcx.sess.diagnostic().emit_diagnostic(&mut diag);
+                    if let Some(span) = diag.span.primary_span()
+                        && let Some(explanation) = self.errors.conflict_explanations.remove(&span)
+                        && ConflictExplanation::is_selected(sess, &diag)
+                    {
+                        explanation.emit(self.infcx.tcx, &mut diag);
+                    }
+                    sess.diagnostic().emit_diagnostic(&mut diag);
                 }
             }
 
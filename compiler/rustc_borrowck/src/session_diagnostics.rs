@@ -2,6 +2,8 @@
 use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_middle::ty::{GenericArg, Ty};
 use rustc_span::Span;
+use std::io;
+use std::path::PathBuf;
 
 use crate::diagnostics::RegionName;
 
@@ -452,3 +454,10 @@ pub(crate) enum TypeNoCopy<'a, 'tcx> {
     #[note(borrowck_ty_no_impl_copy)]
     Note { is_partial_move: bool, ty: Ty<'tcx>, place: &'a str },
 }
+
+#[derive(Diagnostic)]
+#[diag(borrowck_explain_write)]
+pub(crate) struct ExplainWrite {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
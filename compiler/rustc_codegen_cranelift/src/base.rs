@@ -780,6 +780,15 @@ fn is_fat_ptr<'tcx>(fx: &FunctionCx<'_, '_, 'tcx>, ty: Ty<'tcx>) -> bool {
                         NullOp::OffsetOf(fields) => {
                             layout.offset_of_subfield(fx, fields.iter().map(|f| f.index())).bytes()
                         }
+                        NullOp::UbChecks => {
+                            let val = fx.tcx.sess.ub_checks();
+                            let val = CValue::by_val(
+                                fx.bcx.ins().iconst(types::I8, i64::from(val)),
+                                fx.layout_of(fx.tcx.types.bool),
+                            );
+                            lval.write_cvalue(fx, val);
+                            return;
+                        }
                     };
                     let val = CValue::by_val(
                         fx.bcx.ins().iconst(fx.pointer_type, i64::try_from(val).unwrap()),
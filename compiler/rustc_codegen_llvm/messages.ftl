@@ -37,6 +37,9 @@ codegen_llvm_lto_disallowed = lto can only be run for executables, cdylibs and s
 
 codegen_llvm_lto_dylib = lto cannot be used for `dylib` crate type without `-Zdylib-lto`
 
+codegen_llvm_mcdc_requires_llvm_18 =
+    `-Zcoverage-options=mcdc` requires LLVM 18 or later, but this compiler uses LLVM {$llvm_version}
+
 codegen_llvm_missing_features =
     add the missing features in a `target_feature` attribute
 
@@ -1,4 +1,4 @@
-use rustc_middle::mir::coverage::{CounterId, MappedExpressionIndex};
+use rustc_middle::mir::coverage::{ConditionInfo, CounterId, MappedExpressionIndex};
 
 /// Must match the layout of `LLVMRustCounterKind`.
 #[derive(Copy, Clone, Debug)]
@@ -112,6 +112,32 @@ pub enum RegionKind {
     /// associated with two counters, each representing the number of times the
     /// expression evaluates to true or false.
     BranchRegion = 4,
+
+    /// A DecisionRegion represents a top-level boolean expression and is
+    /// associated with a variable length bitmap index and condition number.
+    MCDCDecisionRegion = 5,
+
+    /// A Branch Region can be extended to include IDs to facilitate MC/DC.
+    MCDCBranchRegion = 6,
+}
+
+/// Corresponds to struct `llvm::coverage::CounterMappingRegion::MCDCParameters`, which only
+/// exists as of LLVM 18. A decision region uses `bitmap_idx` and `num_conditions`; an MC/DC branch
+/// region uses the condition IDs.
+///
+/// Must match the layout of `LLVMRustMCDCParameters`.
+#[derive(Copy, Clone, Debug, Default)]
+#[repr(C)]
+pub struct MCDCParameters {
+    /// Byte index of the decision's test vector bitmap in the function's bitmap.
+    bitmap_idx: u32,
+    num_conditions: u32,
+    /// ID of the condition of an MC/DC branch region, counted from 1.
+    condition_id: u32,
+    /// The condition evaluated next if this one is true, or 0 if the decision is decided.
+    true_next_id: u32,
+    /// The condition evaluated next if this one is false, or 0 if the decision is decided.
+    false_next_id: u32,
 }
 
 /// This struct provides LLVM's representation of a "CoverageMappingRegion", encoded into the
@@ -135,6 +161,10 @@ pub struct CounterMappingRegion {
     /// for the false branch of the region.
     false_counter: Counter,
 
+    /// If the `RegionKind` is an `MCDCDecisionRegion` or `MCDCBranchRegion`, the
+    /// MC/DC parameters of the region.
+    mcdc_params: MCDCParameters,
+
     /// An indirect reference to the source filename. In the LLVM Coverage Mapping Format, the
     /// file_id is an index into a function-specific `virtual_file_mapping` array of indexes
     /// that, in turn, are used to look up the filename for this region.
@@ -173,6 +203,7 @@ pub(crate) fn code_region(
         Self {
             counter,
             false_counter: Counter::zero(),
+            mcdc_params: MCDCParameters::default(),
             file_id,
             expanded_file_id: 0,
             start_line,
@@ -183,9 +214,6 @@ pub(crate) fn code_region(
         }
     }
 
-    // This function might be used in the future; the LLVM API is still evolving, as is coverage
-    // support.
-    #[allow(dead_code)]
     pub(crate) fn branch_region(
         counter: Counter,
         false_counter: Counter,
@@ -198,6 +226,7 @@ pub(crate) fn branch_region(
         Self {
             counter,
             false_counter,
+            mcdc_params: MCDCParameters::default(),
             file_id,
             expanded_file_id: 0,
             start_line,
@@ -208,6 +237,63 @@ pub(crate) fn branch_region(
         }
     }
 
+    pub(crate) fn mcdc_branch_region(
+        counter: Counter,
+        false_counter: Counter,
+        condition_info: ConditionInfo,
+        file_id: u32,
+        start_line: u32,
+        start_col: u32,
+        end_line: u32,
+        end_col: u32,
+    ) -> Self {
+        let ConditionInfo { condition_id, true_next_id, false_next_id, .. } = condition_info;
+        Self {
+            counter,
+            false_counter,
+            mcdc_params: MCDCParameters {
+                condition_id,
+                true_next_id,
+                false_next_id,
+                ..MCDCParameters::default()
+            },
+            file_id,
+            expanded_file_id: 0,
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+            kind: RegionKind::MCDCBranchRegion,
+        }
+    }
+
+    pub(crate) fn mcdc_decision_region(
+        bitmap_idx: u32,
+        num_conditions: u16,
+        file_id: u32,
+        start_line: u32,
+        start_col: u32,
+        end_line: u32,
+        end_col: u32,
+    ) -> Self {
+        Self {
+            counter: Counter::zero(),
+            false_counter: Counter::zero(),
+            mcdc_params: MCDCParameters {
+                bitmap_idx,
+                num_conditions: num_conditions.into(),
+                ..MCDCParameters::default()
+            },
+            file_id,
+            expanded_file_id: 0,
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+            kind: RegionKind::MCDCDecisionRegion,
+        }
+    }
+
     // This function might be used in the future; the LLVM API is still evolving, as is coverage
     // support.
     #[allow(dead_code)]
@@ -222,6 +308,7 @@ pub(crate) fn expansion_region(
         Self {
             counter: Counter::zero(),
             false_counter: Counter::zero(),
+            mcdc_params: MCDCParameters::default(),
             file_id,
             expanded_file_id,
             start_line,
@@ -245,6 +332,7 @@ pub(crate) fn skipped_region(
         Self {
             counter: Counter::zero(),
             false_counter: Counter::zero(),
+            mcdc_params: MCDCParameters::default(),
             file_id,
             expanded_file_id: 0,
             start_line,
@@ -269,6 +357,7 @@ pub(crate) fn gap_region(
         Self {
             counter,
             false_counter: Counter::zero(),
+            mcdc_params: MCDCParameters::default(),
             file_id,
             expanded_file_id: 0,
             start_line,
@@ -3,7 +3,7 @@
 use rustc_index::{IndexSlice, IndexVec};
 use rustc_middle::bug;
 use rustc_middle::mir::coverage::{
-    CodeRegion, CounterId, ExpressionId, MappedExpressionIndex, Op, Operand,
+    CodeRegion, ConditionInfo, CounterId, ExpressionId, MappedExpressionIndex, Op, Operand,
 };
 use rustc_middle::ty::Instance;
 use rustc_middle::ty::TyCtxt;
@@ -16,10 +16,30 @@ pub struct Expression {
     region: Option<CodeRegion>,
 }
 
+/// A branch mapping, whose arms are counted by the `true_term` and `false_term` operands.
+#[derive(Clone, Debug)]
+struct Branch {
+    true_term: Operand,
+    false_term: Operand,
+    condition_info: Option<ConditionInfo>,
+    region: CodeRegion,
+}
+
+/// The kind of a mapping region, along with the counters or MC/DC parameters it carries.
+#[derive(Copy, Clone, Debug)]
+pub enum MappingKind {
+    Code(Counter),
+    Branch { true_counter: Counter, false_counter: Counter },
+    MCDCBranch { true_counter: Counter, false_counter: Counter, condition_info: ConditionInfo },
+    MCDCDecision { bitmap_idx: u32, num_conditions: u16 },
+}
+
+type NewIndexes = IndexSlice<ExpressionId, Option<MappedExpressionIndex>>;
+
 /// Collects all of the coverage regions associated with (a) injected counters, (b) counter
-/// expressions (additions or subtraction), and (c) unreachable regions (always counted as zero),
-/// for a given Function. This struct also stores the `function_source_hash`,
-/// computed during instrumentation, and forwarded with counters.
+/// expressions (additions or subtraction), (c) unreachable regions (always counted as zero), and
+/// (d) branch and MC/DC decision mappings, for a given Function. This struct also stores the
+/// `function_source_hash`, computed during instrumentation, and forwarded with counters.
 ///
 /// Note, it may be important to understand LLVM's definitions of `unreachable` regions versus "gap
 /// regions" (or "gap areas"). A gap region is a code region within a counted region (either counter
@@ -35,6 +55,8 @@ pub struct FunctionCoverage<'tcx> {
     counters: IndexVec<CounterId, Option<CodeRegion>>,
     expressions: IndexVec<ExpressionId, Option<Expression>>,
     unreachable_regions: Vec<CodeRegion>,
+    branches: Vec<Branch>,
+    mcdc_decisions: Vec<(u32, u16, CodeRegion)>,
 }
 
 impl<'tcx> FunctionCoverage<'tcx> {
@@ -61,6 +83,8 @@ fn create(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>, is_used: bool) -> Self {
             counters: IndexVec::from_elem_n(None, coverageinfo.num_counters as usize),
             expressions: IndexVec::from_elem_n(None, coverageinfo.num_expressions as usize),
             unreachable_regions: Vec::new(),
+            branches: Vec::new(),
+            mcdc_decisions: Vec::new(),
         }
     }
 
@@ -128,18 +152,36 @@ pub fn add_unreachable_region(&mut self, region: CodeRegion) {
         self.unreachable_regions.push(region)
     }
 
+    /// Adds a branch region, whose arms are counted by `true_term` and `false_term`. An MC/DC
+    /// branch region also has the position of its condition in the decision.
+    pub fn add_branch(
+        &mut self,
+        true_term: Operand,
+        false_term: Operand,
+        condition_info: Option<ConditionInfo>,
+        region: CodeRegion,
+    ) {
+        self.branches.push(Branch { true_term, false_term, condition_info, region });
+    }
+
+    /// Adds an MC/DC decision region, whose test vectors are recorded at byte `bitmap_idx` of the
+    /// function's bitmap.
+    pub fn add_mcdc_decision(&mut self, bitmap_idx: u32, num_conditions: u16, region: CodeRegion) {
+        self.mcdc_decisions.push((bitmap_idx, num_conditions, region));
+    }
+
     /// Return the source hash, generated from the HIR node structure, and used to indicate whether
     /// or not the source code structure changed between different compilations.
     pub fn source_hash(&self) -> u64 {
         self.source_hash
     }
 
-    /// Generate an array of CounterExpressions, and an iterator over all `Counter`s and their
+    /// Generate an array of CounterExpressions, and an iterator over all mappings and their
     /// associated `Regions` (from which the LLVM-specific `CoverageMapGenerator` will create
     /// `CounterMappingRegion`s.
     pub fn get_expressions_and_counter_regions(
         &self,
-    ) -> (Vec<CounterExpression>, impl Iterator<Item = (Counter, &CodeRegion)>) {
+    ) -> (Vec<CounterExpression>, impl Iterator<Item = (MappingKind, &CodeRegion)>) {
         assert!(
             self.source_hash != 0 || !self.is_used,
             "No counters provided the source_hash for used function: {:?}",
@@ -147,11 +189,15 @@ pub fn get_expressions_and_counter_regions(
         );
 
         let counter_regions = self.counter_regions();
-        let (counter_expressions, expression_regions) = self.expressions_with_regions();
+        let (counter_expressions, new_indexes, expression_regions) =
+            self.expressions_with_regions();
         let unreachable_regions = self.unreachable_regions();
+        let branch_regions = self.branch_regions(&new_indexes);
 
-        let counter_regions =
-            counter_regions.chain(expression_regions.into_iter().chain(unreachable_regions));
+        let counter_regions = counter_regions
+            .chain(expression_regions.into_iter().chain(unreachable_regions))
+            .map(|(counter, region)| (MappingKind::Code(counter), region))
+            .chain(branch_regions);
         (counter_expressions, counter_regions)
     }
 
@@ -165,13 +211,17 @@ fn counter_regions(&self) -> impl Iterator<Item = (Counter, &CodeRegion)> {
 
     fn expressions_with_regions(
         &self,
-    ) -> (Vec<CounterExpression>, impl Iterator<Item = (Counter, &CodeRegion)>) {
+    ) -> (
+        Vec<CounterExpression>,
+        IndexVec<ExpressionId, Option<MappedExpressionIndex>>,
+        impl Iterator<Item = (Counter, &CodeRegion)>,
+    ) {
         let mut counter_expressions = Vec::with_capacity(self.expressions.len());
         let mut expression_regions = Vec::with_capacity(self.expressions.len());
         let mut new_indexes = IndexVec::from_elem_n(None, self.expressions.len());
 
-        // This closure converts any `Expression` operand (`lhs` or `rhs` of the `Op::Add` or
-        // `Op::Subtract` operation) into its native `llvm::coverage::Counter::CounterKind` type
+        // `Self::id_to_counter` converts any `Expression` operand (`lhs` or `rhs` of the `Op::Add`
+        // or `Op::Subtract` operation) into its native `llvm::coverage::Counter::CounterKind` type
         // and value.
         //
         // Expressions will be returned from this function in a sequential vector (array) of
@@ -189,21 +239,6 @@ fn expressions_with_regions(
         // `expression_index`s lower than the referencing `Expression`. Therefore, it is
         // reasonable to look up the new index of an expression operand while the `new_indexes`
         // vector is only complete up to the current `ExpressionIndex`.
-        type NewIndexes = IndexSlice<ExpressionId, Option<MappedExpressionIndex>>;
-        let id_to_counter = |new_indexes: &NewIndexes, operand: Operand| match operand {
-            Operand::Zero => Some(Counter::zero()),
-            Operand::Counter(id) => Some(Counter::counter_value_reference(id)),
-            Operand::Expression(id) => {
-                self.expressions
-                    .get(id)
-                    .expect("expression id is out of range")
-                    .as_ref()
-                    // If an expression was optimized out, assume it would have produced a count
-                    // of zero. This ensures that expressions dependent on optimized-out
-                    // expressions are still valid.
-                    .map_or(Some(Counter::zero()), |_| new_indexes[id].map(Counter::expression))
-            }
-        };
 
         for (original_index, expression) in
             self.expressions.iter_enumerated().filter_map(|(original_index, entry)| {
@@ -215,9 +250,10 @@ fn expressions_with_regions(
             let optional_region = &expression.region;
             let Expression { lhs, op, rhs, .. } = *expression;
 
-            if let Some(Some((lhs_counter, mut rhs_counter))) = id_to_counter(&new_indexes, lhs)
-                .map(|lhs_counter| {
-                    id_to_counter(&new_indexes, rhs).map(|rhs_counter| (lhs_counter, rhs_counter))
+            if let Some(Some((lhs_counter, mut rhs_counter))) =
+                self.id_to_counter(&new_indexes, lhs).map(|lhs_counter| {
+                    self.id_to_counter(&new_indexes, rhs)
+                        .map(|rhs_counter| (lhs_counter, rhs_counter))
                 })
             {
                 if lhs_counter.is_zero() && op.is_subtract() {
@@ -302,7 +338,54 @@ fn expressions_with_regions(
                 );
             }
         }
-        (counter_expressions, expression_regions.into_iter())
+        (counter_expressions, new_indexes, expression_regions.into_iter())
+    }
+
+    fn id_to_counter(&self, new_indexes: &NewIndexes, operand: Operand) -> Option<Counter> {
+        match operand {
+            Operand::Zero => Some(Counter::zero()),
+            Operand::Counter(id) => Some(Counter::counter_value_reference(id)),
+            Operand::Expression(id) => {
+                self.expressions
+                    .get(id)
+                    .expect("expression id is out of range")
+                    .as_ref()
+                    // If an expression was optimized out, assume it would have produced a count
+                    // of zero. This ensures that expressions dependent on optimized-out
+                    // expressions are still valid.
+                    .map_or(Some(Counter::zero()), |_| new_indexes[id].map(Counter::expression))
+            }
+        }
+    }
+
+    /// Converts the branch and MC/DC decision mappings, whose operands may refer to expressions
+    /// by their original IDs, once all expressions have a `new_index`.
+    fn branch_regions(&self, new_indexes: &NewIndexes) -> Vec<(MappingKind, &CodeRegion)> {
+        let mut branch_regions = Vec::with_capacity(self.branches.len());
+        for branch in &self.branches {
+            let Branch { true_term, false_term, condition_info, ref region } = *branch;
+            let (Some(true_counter), Some(false_counter)) = (
+                self.id_to_counter(new_indexes, true_term),
+                self.id_to_counter(new_indexes, false_term),
+            ) else {
+                bug!("branch has one or more missing operands: {:?}", branch);
+            };
+            let kind = match condition_info {
+                Some(condition_info) => {
+                    MappingKind::MCDCBranch { true_counter, false_counter, condition_info }
+                }
+                None => MappingKind::Branch { true_counter, false_counter },
+            };
+            branch_regions.push((kind, region));
+        }
+        for (bitmap_idx, num_conditions, region) in &self.mcdc_decisions {
+            let kind = MappingKind::MCDCDecision {
+                bitmap_idx: *bitmap_idx,
+                num_conditions: *num_conditions,
+            };
+            branch_regions.push((kind, region));
+        }
+        branch_regions
     }
 
     fn unreachable_regions(&self) -> impl Iterator<Item = (Counter, &CodeRegion)> {
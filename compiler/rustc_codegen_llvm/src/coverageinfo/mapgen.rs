@@ -1,6 +1,7 @@
 use crate::common::CodegenCx;
 use crate::coverageinfo;
-use crate::coverageinfo::ffi::{Counter, CounterExpression, CounterMappingRegion};
+use crate::coverageinfo::ffi::{CounterExpression, CounterMappingRegion};
+use crate::coverageinfo::map_data::MappingKind;
 use crate::llvm;
 
 use rustc_codegen_ssa::traits::ConstMethods;
@@ -16,8 +17,8 @@
 
 /// Generates and exports the Coverage Map.
 ///
-/// Rust Coverage Map generation supports LLVM Coverage Mapping Format version
-/// 6 (zero-based encoded as 5), as defined at
+/// Rust Coverage Map generation supports LLVM Coverage Mapping Format versions
+/// 6 and 7 (zero-based encoded as 5 and 6), as defined at
 /// [LLVM Code Coverage Mapping Format](https://github.com/rust-lang/llvm-project/blob/rustc/13.0-2021-09-30/llvm/docs/CoverageMappingFormat.rst#llvm-code-coverage-mapping-format).
 /// These versions are supported by the LLVM coverage tools (`llvm-profdata` and `llvm-cov`)
 /// bundled with Rust's fork of LLVM.
@@ -31,9 +32,13 @@ pub fn finalize(cx: &CodegenCx<'_, '_>) {
     let tcx = cx.tcx;
 
     // Ensure the installed version of LLVM supports Coverage Map Version 6
-    // (encoded as a zero-based value: 5), which was introduced with LLVM 13.
+    // (encoded as a zero-based value: 5), which was introduced with LLVM 13,
+    // or Version 7 (encoded as 6), which adds MC/DC regions in LLVM 18.
     let version = coverageinfo::mapping_version();
-    assert_eq!(version, 5, "The `CoverageMappingVersion` exposed by `llvm-wrapper` is out of sync");
+    assert!(
+        version == 5 || version == 6,
+        "The `CoverageMappingVersion` exposed by `llvm-wrapper` is out of sync"
+    );
 
     debug!("Generating coverage map for CodegenUnit: `{}`", cx.codegen_unit.name());
 
@@ -144,7 +149,7 @@ fn new(tcx: TyCtxt<'_>) -> Self {
     fn write_coverage_mapping<'a>(
         &mut self,
         expressions: Vec<CounterExpression>,
-        counter_regions: impl Iterator<Item = (Counter, &'a CodeRegion)>,
+        counter_regions: impl Iterator<Item = (MappingKind, &'a CodeRegion)>,
         coverage_mapping_buffer: &RustString,
     ) {
         let mut counter_regions = counter_regions.collect::<Vec<_>>();
@@ -157,13 +162,13 @@ fn write_coverage_mapping<'a>(
         let mut current_file_name = None;
         let mut current_file_id = 0;
 
-        // Convert the list of (Counter, CodeRegion) pairs to an array of `CounterMappingRegion`, sorted
-        // by filename and position. Capture any new files to compute the `CounterMappingRegion`s
-        // `file_id` (indexing files referenced by the current function), and construct the
-        // function-specific `virtual_file_mapping` from `file_id` to its index in the module's
-        // `filenames` array.
-        counter_regions.sort_unstable_by_key(|(_counter, region)| *region);
-        for (counter, region) in counter_regions {
+        // Convert the list of (MappingKind, CodeRegion) pairs to an array of
+        // `CounterMappingRegion`, sorted by filename and position. Capture any new files to compute
+        // the `CounterMappingRegion`s `file_id` (indexing files referenced by the current
+        // function), and construct the function-specific `virtual_file_mapping` from `file_id` to
+        // its index in the module's `filenames` array.
+        counter_regions.sort_unstable_by_key(|(_kind, region)| *region);
+        for (kind, region) in counter_regions {
             let CodeRegion { file_name, start_line, start_col, end_line, end_col } = *region;
             let same_file = current_file_name.is_some_and(|p| p == file_name);
             if !same_file {
@@ -175,15 +180,47 @@ fn write_coverage_mapping<'a>(
                 let (filenames_index, _) = self.filenames.insert_full(file_name);
                 virtual_file_mapping.push(filenames_index as u32);
             }
-            debug!("Adding counter {:?} to map for {:?}", counter, region);
-            mapping_regions.push(CounterMappingRegion::code_region(
-                counter,
-                current_file_id,
-                start_line,
-                start_col,
-                end_line,
-                end_col,
-            ));
+            debug!("Adding {:?} to map for {:?}", kind, region);
+            let file_id = current_file_id;
+            mapping_regions.push(match kind {
+                MappingKind::Code(counter) => CounterMappingRegion::code_region(
+                    counter, file_id, start_line, start_col, end_line, end_col,
+                ),
+                MappingKind::Branch { true_counter, false_counter } => {
+                    CounterMappingRegion::branch_region(
+                        true_counter,
+                        false_counter,
+                        file_id,
+                        start_line,
+                        start_col,
+                        end_line,
+                        end_col,
+                    )
+                }
+                MappingKind::MCDCBranch { true_counter, false_counter, condition_info } => {
+                    CounterMappingRegion::mcdc_branch_region(
+                        true_counter,
+                        false_counter,
+                        condition_info,
+                        file_id,
+                        start_line,
+                        start_col,
+                        end_line,
+                        end_col,
+                    )
+                }
+                MappingKind::MCDCDecision { bitmap_idx, num_conditions } => {
+                    CounterMappingRegion::mcdc_decision_region(
+                        bitmap_idx,
+                        num_conditions,
+                        file_id,
+                        start_line,
+                        start_col,
+                        end_line,
+                        end_col,
+                    )
+                }
+            });
         }
 
         // Encode and append the current function's coverage mapping data
@@ -39,6 +39,9 @@ pub struct CrateCoverageContext<'ll, 'tcx> {
     /// Coverage data for each instrumented function identified by DefId.
     pub(crate) function_coverage_map: RefCell<FxHashMap<Instance<'tcx>, FunctionCoverage<'tcx>>>,
     pub(crate) pgo_func_name_var_map: RefCell<FxHashMap<Instance<'tcx>, &'ll llvm::Value>>,
+    /// The `alloca`s holding the MC/DC condition bitmaps of each instrumented function, one per
+    /// decision depth.
+    pub(crate) mcdc_condition_bitmap_map: RefCell<FxHashMap<Instance<'tcx>, Vec<&'ll llvm::Value>>>,
 }
 
 impl<'ll, 'tcx> CrateCoverageContext<'ll, 'tcx> {
@@ -46,12 +49,28 @@ pub fn new() -> Self {
         Self {
             function_coverage_map: Default::default(),
             pgo_func_name_var_map: Default::default(),
+            mcdc_condition_bitmap_map: Default::default(),
         }
     }
 
     pub fn take_function_coverage_map(&self) -> FxHashMap<Instance<'tcx>, FunctionCoverage<'tcx>> {
         self.function_coverage_map.replace(FxHashMap::default())
     }
+
+    /// The condition bitmap of the MC/DC decisions at `decision_depth` in `instance`, if
+    /// `init_coverage` allocated one. There is none in functions that were inlined into
+    /// `instance`'s caller.
+    fn try_get_mcdc_condition_bitmap(
+        &self,
+        instance: &Instance<'tcx>,
+        decision_depth: u16,
+    ) -> Option<&'ll llvm::Value> {
+        self.mcdc_condition_bitmap_map
+            .borrow()
+            .get(instance)
+            .and_then(|bitmaps| bitmaps.get(decision_depth as usize))
+            .copied()
+    }
 }
 
 // These methods used to be part of trait `CoverageInfoMethods`, which no longer
@@ -101,6 +120,35 @@ fn define_unused_fn(&self, def_id: DefId) {
 }
 
 impl<'tcx> CoverageInfoBuilderMethods<'tcx> for Builder<'_, '_, 'tcx> {
+    fn init_coverage(&mut self, instance: Instance<'tcx>) {
+        let Some(coverage_context) = self.cx.coverage_context() else { return };
+
+        let coverageinfo = self.tcx().coverageinfo(instance.def);
+        if coverageinfo.mcdc_bitmap_bytes == 0 {
+            return;
+        }
+
+        // Declare the test vector bitmap of the function, and allocate a condition bitmap for
+        // each level of nesting of its MC/DC decisions.
+        let fn_name = self.get_pgo_func_name_var(instance);
+        let hash = self.const_u64(coverageinfo.function_source_hash);
+        let bitmap_bytes = self.const_u32(coverageinfo.mcdc_bitmap_bytes);
+        self.mcdc_parameters(fn_name, hash, bitmap_bytes);
+
+        let mut cond_bitmaps = vec![];
+        for i in 0..coverageinfo.mcdc_num_condition_bitmaps {
+            // MC/DC intrinsics will perform loads/stores that use the ABI default
+            // alignment for i32, so our variable declaration should match.
+            let align = self.tcx.data_layout.i32_align.abi;
+            let cond_bitmap = self.alloca(self.cx.type_i32(), align);
+            llvm::set_value_name(cond_bitmap, format!("mcdc.addr.{i}").as_bytes());
+            self.store(self.const_i32(0), cond_bitmap, align);
+            cond_bitmaps.push(cond_bitmap);
+        }
+
+        coverage_context.mcdc_condition_bitmap_map.borrow_mut().insert(instance, cond_bitmaps);
+    }
+
     fn add_coverage(&mut self, instance: Instance<'tcx>, coverage: &Coverage) {
         let bx = self;
 
@@ -161,6 +209,65 @@ fn add_coverage(&mut self, instance: Instance<'tcx>, coverage: &Coverage) {
                 );
                 func_coverage.add_unreachable_region(code_region);
             }
+            // Block markers are removed after borrowck, and have no effect on codegen anyway.
+            CoverageKind::BlockMarker { .. } => {}
+            CoverageKind::Branch { true_term, false_term, condition_info } => {
+                let code_region = code_region.expect("branch mappings always have code regions");
+                debug!(
+                    "adding branch to coverage_map: instance={:?}, true={:?}, false={:?}, \
+                    condition={:?}, at {:?}",
+                    instance, true_term, false_term, condition_info, code_region,
+                );
+                func_coverage.add_branch(true_term, false_term, condition_info, code_region);
+            }
+            CoverageKind::MCDCDecision { bitmap_idx, num_conditions } => {
+                let code_region =
+                    code_region.expect("MC/DC decision mappings always have code regions");
+                debug!(
+                    "adding MC/DC decision to coverage_map: instance={:?}, bitmap_idx={}, \
+                    conditions={}, at {:?}",
+                    instance, bitmap_idx, num_conditions, code_region,
+                );
+                func_coverage.add_mcdc_decision(bitmap_idx, num_conditions, code_region);
+            }
+            CoverageKind::CondBitmapReset { decision_depth } => {
+                drop(coverage_map);
+                let Some(cond_bitmap) =
+                    coverage_context.try_get_mcdc_condition_bitmap(&instance, decision_depth)
+                else {
+                    return;
+                };
+                let align = bx.tcx.data_layout.i32_align.abi;
+                bx.store(bx.const_i32(0), cond_bitmap, align);
+            }
+            CoverageKind::CondBitmapUpdate { id, decision_depth } => {
+                drop(coverage_map);
+                let Some(cond_bitmap) =
+                    coverage_context.try_get_mcdc_condition_bitmap(&instance, decision_depth)
+                else {
+                    return;
+                };
+                let coverageinfo = bx.tcx().coverageinfo(instance.def);
+                let fn_name = bx.get_pgo_func_name_var(instance);
+                let hash = bx.const_u64(coverageinfo.function_source_hash);
+                let cond_id = bx.const_u32(id);
+                let cond_val = bx.const_bool(true);
+                bx.mcdc_condbitmap_update(fn_name, hash, cond_id, cond_bitmap, cond_val);
+            }
+            CoverageKind::TestVectorBitmapUpdate { bitmap_idx, decision_depth } => {
+                drop(coverage_map);
+                let Some(cond_bitmap) =
+                    coverage_context.try_get_mcdc_condition_bitmap(&instance, decision_depth)
+                else {
+                    return;
+                };
+                let coverageinfo = bx.tcx().coverageinfo(instance.def);
+                let fn_name = bx.get_pgo_func_name_var(instance);
+                let hash = bx.const_u64(coverageinfo.function_source_hash);
+                let bitmap_bytes = bx.const_u32(coverageinfo.mcdc_bitmap_bytes);
+                let bitmap_idx = bx.const_u32(bitmap_idx);
+                bx.mcdc_tvbitmap_update(fn_name, hash, bitmap_bytes, bitmap_idx, cond_bitmap);
+            }
         }
     }
 }
@@ -61,6 +61,12 @@ pub(crate) struct InvalidMinimumAlignmentTooLarge {
     pub align: u64,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_llvm_mcdc_requires_llvm_18)]
+pub(crate) struct McdcRequiresLlvm18 {
+    pub llvm_version: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_llvm_sanitizer_memtag_requires_mte)]
 pub(crate) struct SanitizerMemtagRequiresMte;
@@ -40,7 +40,7 @@
 use rustc_middle::dep_graph::{WorkProduct, WorkProductId};
 use rustc_middle::query::Providers;
 use rustc_middle::ty::TyCtxt;
-use rustc_session::config::{OptLevel, OutputFilenames, PrintKind, PrintRequest};
+use rustc_session::config::{OptLevel, OutputFilenames, PrintFormat, PrintKind, PrintRequest};
 use rustc_session::Session;
 use rustc_span::symbol::Symbol;
 
@@ -273,38 +273,21 @@ fn provide(&self, providers: &mut Providers) {
     }
 
     fn print(&self, req: &PrintRequest, out: &mut dyn PrintBackendInfo, sess: &Session) {
-        match req.kind {
-            PrintKind::RelocationModels => {
-                writeln!(out, "Available relocation models:");
-                for name in &[
-                    "static",
-                    "pic",
-                    "pie",
-                    "dynamic-no-pic",
-                    "ropi",
-                    "rwpi",
-                    "ropi-rwpi",
-                    "default",
-                ] {
-                    writeln!(out, "    {name}");
-                }
-                writeln!(out);
-            }
+        let (names, key, title): (&[&str], _, _) = match req.kind {
+            PrintKind::RelocationModels => (
+                &["static", "pic", "pie", "dynamic-no-pic", "ropi", "rwpi", "ropi-rwpi", "default"],
+                "relocation_models",
+                "relocation models",
+            ),
             PrintKind::CodeModels => {
-                writeln!(out, "Available code models:");
-                for name in &["tiny", "small", "kernel", "medium", "large"] {
-                    writeln!(out, "    {name}");
-                }
-                writeln!(out);
+                (&["tiny", "small", "kernel", "medium", "large"], "code_models", "code models")
             }
-            PrintKind::TlsModels => {
-                writeln!(out, "Available TLS models:");
-                for name in &["global-dynamic", "local-dynamic", "initial-exec", "local-exec"] {
-                    writeln!(out, "    {name}");
-                }
-                writeln!(out);
-            }
-            PrintKind::StackProtectorStrategies => {
+            PrintKind::TlsModels => (
+                &["global-dynamic", "local-dynamic", "initial-exec", "local-exec"],
+                "tls_models",
+                "TLS models",
+            ),
+            PrintKind::StackProtectorStrategies if req.format == PrintFormat::Text => {
                 writeln!(
                     out,
                     r#"Available stack protector strategies:
@@ -329,8 +312,24 @@ fn print(&self, req: &PrintRequest, out: &mut dyn PrintBackendInfo, sess: &Sessi
         Do not generate stack canaries.
 "#
                 );
+                return;
+            }
+            PrintKind::StackProtectorStrategies => (
+                &["all", "strong", "basic", "none"],
+                "stack_protector_strategies",
+                "stack protector strategies",
+            ),
+            _other => return llvm_util::print(req, out, sess),
+        };
+        match req.format {
+            PrintFormat::Text => {
+                writeln!(out, "Available {title}:");
+                for name in names {
+                    writeln!(out, "    {name}");
+                }
+                writeln!(out);
             }
-            _other => llvm_util::print(req, out, sess),
+            PrintFormat::Json => out.write_json(req.kind, serde_json::json!({ key: names })),
         }
     }
 
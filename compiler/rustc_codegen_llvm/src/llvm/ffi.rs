@@ -1487,6 +1487,9 @@ pub fn LLVMBuildFCmp<'a>(
     // Miscellaneous instructions
     pub fn LLVMBuildPhi<'a>(B: &Builder<'a>, Ty: &'a Type, Name: *const c_char) -> &'a Value;
     pub fn LLVMRustGetInstrProfIncrementIntrinsic(M: &Module) -> &Value;
+    pub fn LLVMRustGetInstrProfMCDCParametersIntrinsic(M: &Module) -> &Value;
+    pub fn LLVMRustGetInstrProfMCDCTVBitmapUpdateIntrinsic(M: &Module) -> &Value;
+    pub fn LLVMRustGetInstrProfMCDCCondBitmapIntrinsic(M: &Module) -> &Value;
     pub fn LLVMRustBuildCall<'a>(
         B: &Builder<'a>,
         Ty: &'a Type,
@@ -2103,6 +2106,13 @@ pub fn LLVMRustPrintTargetCPUs(
         print: unsafe extern "C" fn(out: *mut c_void, string: *const c_char, len: usize),
         out: *mut c_void,
     );
+    pub fn LLVMRustGetTargetCPUs(
+        T: &TargetMachine,
+        cpu: *const c_char,
+        print: unsafe extern "C" fn(out: *mut c_void, string: *const c_char, len: usize),
+        out: *mut c_void,
+    );
+    pub fn LLVMRustTargetMatchesHostArch(T: &TargetMachine) -> bool;
     pub fn LLVMRustGetTargetFeaturesCount(T: &TargetMachine) -> size_t;
     pub fn LLVMRustGetTargetFeature(
         T: &TargetMachine,
@@ -2110,6 +2120,12 @@ pub fn LLVMRustGetTargetFeature(
         Feature: &mut *const c_char,
         Desc: &mut *const c_char,
     );
+    pub fn LLVMRustGetTargetFeatureImplies(
+        T: &TargetMachine,
+        Index: size_t,
+        print: unsafe extern "C" fn(out: *mut c_void, string: *const c_char, len: usize),
+        out: *mut c_void,
+    );
 
     pub fn LLVMRustGetHostCPUName(len: *mut usize) -> *const c_char;
     pub fn LLVMRustCreateTargetMachine(
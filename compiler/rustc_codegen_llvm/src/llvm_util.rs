@@ -1,6 +1,6 @@
 use crate::back::write::create_informational_target_machine;
 use crate::errors::{
-    PossibleFeature, TargetFeatureDisableOrEnable, UnknownCTargetFeature,
+    McdcRequiresLlvm18, PossibleFeature, TargetFeatureDisableOrEnable, UnknownCTargetFeature,
     UnknownCTargetFeaturePrefix,
 };
 use crate::llvm;
@@ -13,11 +13,12 @@
 use rustc_data_structures::small_c_str::SmallCStr;
 use rustc_fs_util::path_to_c_string;
 use rustc_middle::bug;
-use rustc_session::config::{PrintKind, PrintRequest};
+use rustc_session::config::{PrintFormat, PrintKind, PrintRequest};
 use rustc_session::Session;
 use rustc_span::symbol::Symbol;
 use rustc_target::spec::{MergeFunctions, PanicStrategy};
 
+use std::collections::BTreeSet;
 use std::ffi::{c_char, c_void, CStr, CString};
 use std::path::Path;
 use std::ptr;
@@ -37,6 +38,12 @@ pub(crate) fn init(sess: &Session) {
             configure_llvm(sess);
         });
     }
+
+    // The MC/DC intrinsics and mapping regions were added in LLVM 18.
+    if sess.instrument_coverage_mcdc() && get_version() < (18, 0, 0) {
+        let (major, minor, patch) = get_version();
+        sess.emit_err(McdcRequiresLlvm18 { llvm_version: format!("{major}.{minor}.{patch}") });
+    }
 }
 
 fn require_inited() {
@@ -405,10 +412,164 @@ fn print_target_features(out: &mut dyn PrintBackendInfo, sess: &Session, tm: &ll
     writeln!(out, "and may be renamed or removed in a future version of LLVM or rustc.\n");
 }
 
+/// Callback for the LLVM functions that report a list of strings, `out` must point to a
+/// `Vec<String>`.
+unsafe extern "C" fn push_string(out: *mut c_void, string: *const c_char, len: usize) {
+    let out = &mut *(out as *mut Vec<String>);
+    let bytes = slice::from_raw_parts(string as *const u8, len);
+    out.push(String::from_utf8_lossy(bytes).into_owned());
+}
+
+fn print_target_cpus_json(
+    out: &mut dyn PrintBackendInfo,
+    sess: &Session,
+    tm: &llvm::TargetMachine,
+) {
+    let default_cpu = handle_native(sess.target.cpu.as_ref());
+    let cpu_cstring = SmallCStr::new(default_cpu);
+    let mut cpus = Vec::new();
+    // Before LLVM 17, this only returns the default CPU.
+    unsafe {
+        llvm::LLVMRustGetTargetCPUs(
+            tm,
+            cpu_cstring.as_ptr(),
+            push_string,
+            &mut cpus as *mut Vec<String> as *mut c_void,
+        );
+    }
+    // Like the text output, only offer `native` when it refers to a CPU of the target
+    // architecture.
+    let host_cpu = unsafe { llvm::LLVMRustTargetMatchesHostArch(tm) }.then(|| {
+        cpus.insert(0, "native".to_owned());
+        handle_native("native")
+    });
+    out.write_json(
+        PrintKind::TargetCPUs,
+        serde_json::json!({
+            "default_cpu": default_cpu,
+            "host_cpu": host_cpu,
+            "cpus": cpus,
+        }),
+    );
+}
+
+/// Returns the features directly implied by each LLVM feature of `tm`, in the order of
+/// [`llvm_target_features`].
+fn llvm_target_feature_implies(tm: &llvm::TargetMachine) -> Vec<Vec<String>> {
+    let len = unsafe { llvm::LLVMRustGetTargetFeaturesCount(tm) };
+    (0..len)
+        .map(|i| {
+            let mut implies = Vec::new();
+            unsafe {
+                llvm::LLVMRustGetTargetFeatureImplies(
+                    tm,
+                    i,
+                    push_string,
+                    &mut implies as *mut Vec<String> as *mut c_void,
+                );
+            }
+            implies
+        })
+        .collect()
+}
+
+/// Returns every LLVM feature that is transitively enabled by enabling `features`, other than
+/// `features` themselves.
+fn implied_llvm_features<'a>(
+    implies: &'a FxHashMap<&str, Vec<String>>,
+    features: &[&'a str],
+) -> BTreeSet<&'a str> {
+    let mut implied = BTreeSet::new();
+    let mut stack = features.to_vec();
+    while let Some(feature) = stack.pop() {
+        for implied_feature in implies.get(feature).into_iter().flatten() {
+            if implied.insert(implied_feature.as_str()) {
+                stack.push(implied_feature);
+            }
+        }
+    }
+    for feature in features {
+        implied.remove(feature);
+    }
+    implied
+}
+
+fn print_target_features_json(
+    out: &mut dyn PrintBackendInfo,
+    sess: &Session,
+    tm: &llvm::TargetMachine,
+) {
+    let llvm_target_features = llvm_target_features(tm);
+    let implies: FxHashMap<&str, Vec<String>> = llvm_target_features
+        .iter()
+        .map(|&(feature, _desc)| feature)
+        .zip(llvm_target_feature_implies(tm))
+        .collect();
+    let llvm_desc = |llvm_feature: &str| {
+        llvm_target_features
+            .binary_search_by_key(&llvm_feature, |(f, _d)| f)
+            .map_or("", |index| llvm_target_features[index].1)
+    };
+
+    let rustc_target_features = supported_target_features(sess);
+    let mut known_llvm_target_features = FxHashSet::<&str>::default();
+    let mut features = Vec::new();
+    for &(feature, gate) in rustc_target_features {
+        let llvm_features: Vec<&str> = to_llvm_features(sess, feature).into_iter().collect();
+        known_llvm_target_features.insert(llvm_features[0]);
+        let implied = implied_llvm_features(&implies, &llvm_features);
+        // A rustc feature is implied if all of the LLVM features it maps to are.
+        let implied_features: Vec<&str> = rustc_target_features
+            .iter()
+            .map(|&(other, _gate)| other)
+            .filter(|&other| {
+                other != feature
+                    && to_llvm_features(sess, other)
+                        .into_iter()
+                        .all(|f| implied.contains(f) || llvm_features.contains(&f))
+            })
+            .collect();
+        features.push(serde_json::json!({
+            "name": feature,
+            "description": llvm_desc(llvm_features[0]),
+            "stability": if gate.is_some() { "unstable" } else { "stable" },
+            "feature_gate": gate.map(|gate| gate.as_str()),
+            "implied_features": implied_features,
+        }));
+    }
+    features.push(serde_json::json!({
+        "name": "crt-static",
+        "description": "Enables C Run-time Libraries to be statically linked",
+        "stability": "stable",
+        "feature_gate": null,
+        "implied_features": [],
+    }));
+    for &(feature, desc) in &llvm_target_features {
+        if known_llvm_target_features.contains(feature) {
+            continue;
+        }
+        let implied_features = implied_llvm_features(&implies, &[feature]);
+        features.push(serde_json::json!({
+            "name": feature,
+            "description": desc,
+            "stability": "codegen-only",
+            "feature_gate": null,
+            "implied_features": implied_features,
+        }));
+    }
+    out.write_json(PrintKind::TargetFeatures, serde_json::json!({ "features": features }));
+}
+
 pub(crate) fn print(req: &PrintRequest, mut out: &mut dyn PrintBackendInfo, sess: &Session) {
     require_inited();
     let tm = create_informational_target_machine(sess);
     match req.kind {
+        PrintKind::TargetCPUs if req.format == PrintFormat::Json => {
+            print_target_cpus_json(out, sess, tm)
+        }
+        PrintKind::TargetFeatures if req.format == PrintFormat::Json => {
+            print_target_features_json(out, sess, tm)
+        }
         PrintKind::TargetCPUs => {
             // SAFETY generate a C compatible string from a byte slice to pass
             // the target CPU name into LLVM, the lifetime of the reference is
@@ -136,6 +136,8 @@ codegen_ssa_invalid_no_sanitize = invalid argument for `no_sanitize`
 
 codegen_ssa_invalid_windows_subsystem = invalid windows subsystem `{$subsystem}`, only `windows` and `console` are allowed
 
+codegen_ssa_item_time_report_write = failed to write item time report to `{$path}`: {$error}
+
 codegen_ssa_ld64_unimplemented_modifier = `as-needed` modifier not implemented yet for ld64
 
 codegen_ssa_lib_def_write_failure = failed to write lib.def file: {$error}
@@ -195,8 +197,16 @@ codegen_ssa_select_cpp_build_tool_workload = in the Visual Studio installer, ens
 
 codegen_ssa_shuffle_indices_evaluation = could not evaluate shuffle_indices at compile time
 
+codegen_ssa_size_report_read_object = failed to read `{$path}` for the size report: {$error}
+
+codegen_ssa_size_report_write = failed to write size report to `{$path}`: {$error}
+
 codegen_ssa_specify_libraries_to_link = use the `-l` flag to specify native libraries to link
 
+codegen_ssa_stack_report_read_object = failed to read `{$path}` for the stack report: {$error}
+
+codegen_ssa_stack_report_write = failed to write stack report to `{$path}`: {$error}
+
 codegen_ssa_static_library_native_artifacts = Link against the following native artifacts when linking against this static library. The order and any duplication can be significant on some platforms.
 
 codegen_ssa_static_library_native_artifacts_to_file = Native artifacts to link against have been written to {$path}. The order and any duplication can be significant on some platforms.
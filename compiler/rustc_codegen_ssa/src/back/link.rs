@@ -13,7 +13,7 @@
 use rustc_middle::middle::dependency_format::Linkage;
 use rustc_middle::middle::exported_symbols::SymbolExportKind;
 use rustc_session::config::{self, CFGuard, CrateType, DebugInfo, OutFileName, Strip};
-use rustc_session::config::{OutputFilenames, OutputType, PrintKind, SplitDwarfKind};
+use rustc_session::config::{OutputFilenames, OutputType, PrintFormat, PrintKind, SplitDwarfKind};
 use rustc_session::cstore::DllImport;
 use rustc_session::output::{check_file_is_writeable, invalid_output_for_target, out_filename};
 use rustc_session::search_paths::PathKind;
@@ -31,6 +31,7 @@
 use super::linker::{self, Linker};
 use super::metadata::{create_wrapper_file, MetadataPosition};
 use super::rpath::{self, RPathConfig};
+use crate::traits::print_json_document;
 use crate::{
     errors, looks_like_rust_object_file, CodegenResults, CompiledModule, CrateInfo, NativeLib,
 };
@@ -598,7 +599,13 @@ fn link_staticlib<'a>(
 
     for print in &sess.opts.prints {
         if print.kind == PrintKind::NativeStaticLibs {
-            print_native_static_libs(sess, &print.out, &all_native_libs, &all_rust_dylibs);
+            print_native_static_libs(
+                sess,
+                &print.out,
+                print.format,
+                &all_native_libs,
+                &all_rust_dylibs,
+            );
         }
     }
 
@@ -748,7 +755,30 @@ fn link_natively<'a>(
 
     for print in &sess.opts.prints {
         if print.kind == PrintKind::LinkArgs {
-            let content = format!("{cmd:?}");
+            let content = match print.format {
+                PrintFormat::Text => format!("{cmd:?}"),
+                PrintFormat::Json => {
+                    let command = cmd.command();
+                    let args: Vec<_> =
+                        command.get_args().map(|arg| arg.to_string_lossy()).collect();
+                    let env: Vec<_> = command
+                        .get_envs()
+                        .map(|(key, value)| {
+                            // A `null` value means that the variable is removed.
+                            serde_json::json!({
+                                "name": key.to_string_lossy(),
+                                "value": value.map(|value| value.to_string_lossy()),
+                            })
+                        })
+                        .collect();
+                    let data = serde_json::json!({
+                        "linker": command.get_program().to_string_lossy(),
+                        "args": args,
+                        "env": env,
+                    });
+                    print_json_document(PrintKind::LinkArgs, data)
+                }
+            };
             print.out.overwrite(&content, sess);
         }
     }
@@ -1391,10 +1421,13 @@ enum RlibFlavor {
 fn print_native_static_libs(
     sess: &Session,
     out: &OutFileName,
+    format: PrintFormat,
     all_native_libs: &[NativeLib],
     all_rust_dylibs: &[&Path],
 ) {
-    let mut lib_args: Vec<_> = all_native_libs
+    // The linker arguments needed for each library, together with its `--print-format=json`
+    // description.
+    let mut libs: Vec<(Vec<String>, serde_json::Value)> = all_native_libs
         .iter()
         .filter(|l| relevant_lib(sess, l))
         // Deduplication of successive repeated libraries, see rust-lang/rust#113209
@@ -1404,33 +1437,42 @@ fn print_native_static_libs(
         .dedup_by(|l1, l2| l1.name == l2.name && l1.kind == l2.kind && l1.verbatim == l2.verbatim)
         .filter_map(|lib| {
             let name = lib.name;
-            match lib.kind {
+            let arg = match lib.kind {
                 NativeLibKind::Static { bundle: Some(false), .. }
                 | NativeLibKind::Dylib { .. }
                 | NativeLibKind::Unspecified => {
                     let verbatim = lib.verbatim;
                     if sess.target.is_like_msvc {
-                        Some(format!("{}{}", name, if verbatim { "" } else { ".lib" }))
+                        format!("{}{}", name, if verbatim { "" } else { ".lib" })
                     } else if sess.target.linker_flavor.is_gnu() {
-                        Some(format!("-l{}{}", if verbatim { ":" } else { "" }, name))
+                        format!("-l{}{}", if verbatim { ":" } else { "" }, name)
                     } else {
-                        Some(format!("-l{name}"))
+                        format!("-l{name}")
                     }
                 }
                 NativeLibKind::Framework { .. } => {
                     // ld-only syntax, since there are no frameworks in MSVC
-                    Some(format!("-framework {name}"))
+                    format!("-framework {name}")
                 }
                 // These are included, no need to print them
                 NativeLibKind::Static { bundle: None | Some(true), .. }
                 | NativeLibKind::LinkArg
                 | NativeLibKind::WasmImportModule
-                | NativeLibKind::RawDylib => None,
-            }
+                | NativeLibKind::RawDylib => return None,
+            };
+            let (kind, modifiers) = native_lib_kind_and_modifiers(lib);
+            let json = serde_json::json!({
+                "name": name.as_str(),
+                "kind": kind,
+                "modifiers": modifiers,
+                "args": [&arg],
+            });
+            Some((vec![arg], json))
         })
         .collect();
     for path in all_rust_dylibs {
         // FIXME deduplicate with add_dynamic_crate
+        let mut args = vec![];
 
         // Just need to tell the linker about where the library lives and
         // what its name is
@@ -1440,10 +1482,10 @@ fn print_native_static_libs(
             if sess.target.is_like_msvc {
                 let mut arg = String::from("/LIBPATH:");
                 arg.push_str(&dir.display().to_string());
-                lib_args.push(arg);
+                args.push(arg);
             } else {
-                lib_args.push("-L".to_owned());
-                lib_args.push(dir.display().to_string());
+                args.push("-L".to_owned());
+                args.push(dir.display().to_string());
             }
         }
         let stem = path.file_stem().unwrap().to_str().unwrap();
@@ -1458,13 +1500,28 @@ fn print_native_static_libs(
             // not present.
             let name = format!("{lib}.dll.lib");
             if path.join(&name).exists() {
-                lib_args.push(name);
+                args.push(name);
             }
         } else {
-            lib_args.push(format!("-l{lib}"));
+            args.push(format!("-l{lib}"));
         }
+        let json = serde_json::json!({
+            "name": lib,
+            "kind": "rust-dylib",
+            "modifiers": [],
+            "args": &args,
+        });
+        libs.push((args, json));
     }
 
+    if format == PrintFormat::Json {
+        let libs: Vec<_> = libs.into_iter().map(|(_, json)| json).collect();
+        let data = serde_json::json!({ "libs": libs });
+        out.overwrite(&print_json_document(PrintKind::NativeStaticLibs, data), sess);
+        return;
+    }
+
+    let lib_args: Vec<String> = libs.into_iter().flat_map(|(args, _)| args).collect();
     match out {
         OutFileName::Real(path) => {
             out.overwrite(&lib_args.join(" "), sess);
@@ -1483,6 +1540,40 @@ fn print_native_static_libs(
     }
 }
 
+/// Returns the kind of `lib` and its explicitly specified modifiers, using the same
+/// names as `-l KIND:MODIFIERS=NAME` and `#[link(kind = "..", modifiers = "..")]`.
+fn native_lib_kind_and_modifiers(lib: &NativeLib) -> (&'static str, Vec<String>) {
+    let mut modifiers = vec![];
+    let mut modifier = |name: &str, value: Option<bool>| {
+        if let Some(value) = value {
+            modifiers.push(format!("{}{name}", if value { '+' } else { '-' }));
+        }
+    };
+    let kind = match lib.kind {
+        NativeLibKind::Static { bundle, whole_archive } => {
+            modifier("bundle", bundle);
+            modifier("whole-archive", whole_archive);
+            "static"
+        }
+        NativeLibKind::Dylib { as_needed } => {
+            modifier("as-needed", as_needed);
+            "dylib"
+        }
+        NativeLibKind::Framework { as_needed } => {
+            modifier("as-needed", as_needed);
+            "framework"
+        }
+        NativeLibKind::RawDylib => "raw-dylib",
+        NativeLibKind::LinkArg => "link-arg",
+        NativeLibKind::WasmImportModule => "wasm-import-module",
+        NativeLibKind::Unspecified => "unspecified",
+    };
+    if lib.verbatim {
+        modifier("verbatim", Some(true));
+    }
+    (kind, modifiers)
+}
+
 fn get_object_file_path(sess: &Session, name: &str, self_contained: bool) -> PathBuf {
     let fs = sess.target_filesearch(PathKind::Native);
     let file_path = fs.get_lib_path().join(name);
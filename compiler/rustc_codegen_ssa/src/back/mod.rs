@@ -4,6 +4,7 @@
 pub mod linker;
 pub mod lto;
 pub mod metadata;
+pub mod object_symbols;
 pub mod rpath;
 pub mod symbol_export;
 pub mod write;
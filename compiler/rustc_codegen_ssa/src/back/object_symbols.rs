@@ -0,0 +1,64 @@
+//! Reading back the symbols of the object files emitted by the backend, for the reports that
+//! attribute their contents to mono items: `-Z size-report` and `-Z stack-report`.
+
+use object::{BinaryFormat, Object, ObjectSymbol, SectionIndex, SymbolKind};
+use std::fs;
+use std::path::Path;
+
+/// A function or data symbol defined in an object file.
+pub(crate) struct DefinedSymbol<'data> {
+    /// The name of the symbol, as returned by [`normalize_symbol_name`].
+    pub name: &'data str,
+    pub kind: SymbolKind,
+    pub section: SectionIndex,
+    pub address: u64,
+    /// The size of the symbol, or 0 if the object file format doesn't record it.
+    pub size: u64,
+}
+
+/// Reads and parses the object file at `path`, and calls `f` with it.
+pub(crate) fn read_object_file<R>(
+    path: &Path,
+    f: impl FnOnce(&object::File<'_>) -> Result<R, String>,
+) -> Result<R, String> {
+    let data = fs::read(path).map_err(|error| error.to_string())?;
+    let file = object::File::parse(&*data).map_err(|error| error.to_string())?;
+    f(&file)
+}
+
+/// The function and data symbols defined in `file`.
+pub(crate) fn defined_symbols<'data, 'file>(
+    file: &'file object::File<'data>,
+) -> impl Iterator<Item = DefinedSymbol<'data>> + 'file {
+    file.symbols().filter_map(|symbol| {
+        if !symbol.is_definition() || !matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Data)
+        {
+            return None;
+        }
+        Some(DefinedSymbol {
+            name: normalize_symbol_name(file, symbol.name().ok()?),
+            kind: symbol.kind(),
+            section: symbol.section_index()?,
+            address: symbol.address(),
+            size: symbol.size(),
+        })
+    })
+}
+
+/// Removes the additions made to the symbol name `name` by the backend or the object file format
+/// of `file`, so that it matches the symbol name of the mono item.
+pub(crate) fn normalize_symbol_name<'data>(
+    file: &object::File<'_>,
+    name: &'data str,
+) -> &'data str {
+    // Local symbols that ThinLTO promotes to globals get a unique suffix.
+    let name = match name.find(".llvm.") {
+        Some(index) => &name[..index],
+        None => name,
+    };
+    // Mach-O prefixes all symbols with an underscore.
+    match file.format() {
+        BinaryFormat::MachO => name.strip_prefix('_').unwrap_or(name),
+        _ => name,
+    }
+}
@@ -3,6 +3,9 @@
 use super::symbol_export::symbol_name_for_instance_in_crate;
 
 use crate::errors;
+use crate::item_time_report::ItemTimeReport;
+use crate::size_report::SizeReport;
+use crate::stack_report::StackReport;
 use crate::traits::*;
 use crate::{
     CachedModuleCodegen, CodegenResults, CompiledModule, CrateInfo, ModuleCodegen, ModuleKind,
@@ -11,7 +14,7 @@
 use rustc_ast::attr;
 use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_data_structures::memmap::Mmap;
-use rustc_data_structures::profiling::{SelfProfilerRef, VerboseTimingGuard};
+use rustc_data_structures::profiling::{ItemActivity, SelfProfilerRef, VerboseTimingGuard};
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::emitter::Emitter;
 use rustc_errors::{translation::Translate, DiagnosticId, FatalError, Handler, Level};
@@ -450,6 +453,9 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
     let is_compiler_builtins = attr::contains_name(crate_attrs, sym::compiler_builtins);
 
     let crate_info = CrateInfo::new(tcx, target_cpu);
+    let item_time_report = ItemTimeReport::new(tcx);
+    let size_report = SizeReport::new(tcx);
+    let stack_report = StackReport::new(tcx);
 
     let regular_config =
         ModuleConfig::new(ModuleKind::Regular, tcx, no_builtins, is_compiler_builtins);
@@ -489,6 +495,9 @@ pub fn start_async_codegen<B: ExtraBackendMethods>(
             phantom: PhantomData,
         },
         output_filenames: tcx.output_filenames(()).clone(),
+        item_time_report,
+        size_report,
+        stack_report,
     }
 }
 
@@ -608,7 +617,11 @@ fn produce_final_output_artifacts(
                 user_wants_objects = true;
                 copy_if_one_unit(OutputType::Object, true);
             }
-            OutputType::Mir | OutputType::Metadata | OutputType::Exe | OutputType::DepInfo => {}
+            OutputType::Mir
+            | OutputType::StableMir
+            | OutputType::Metadata
+            | OutputType::Exe
+            | OutputType::DepInfo => {}
         }
     }
 
@@ -1735,6 +1748,8 @@ fn drop(&mut self) {
                 WorkItem::Optimize(m) => {
                     let _timer =
                         cgcx.prof.generic_activity_with_arg("codegen_module_optimize", &*m.name);
+                    let _item_timer =
+                        cgcx.prof.item_activity(ItemActivity::Backend, || m.name.clone());
                     execute_optimize_work_item(&cgcx, m, module_config)
                 }
                 WorkItem::CopyPostLtoArtifacts(m) => {
@@ -1747,6 +1762,8 @@ fn drop(&mut self) {
                 WorkItem::LTO(m) => {
                     let _timer =
                         cgcx.prof.generic_activity_with_arg("codegen_module_perform_lto", m.name());
+                    let _item_timer =
+                        cgcx.prof.item_activity(ItemActivity::Backend, || m.name().to_owned());
                     execute_lto_work_item(&cgcx, m, module_config)
                 }
             })
@@ -1937,6 +1954,12 @@ pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub shared_emitter_main: SharedEmitterMain,
     pub output_filenames: Arc<OutputFilenames>,
     pub coordinator: Coordinator<B>,
+    /// The mono items of the crate, if `-Z item-time-report` is enabled.
+    pub item_time_report: Option<ItemTimeReport>,
+    /// The symbols of the mono items of the crate, if `-Z size-report` is enabled.
+    pub size_report: Option<SizeReport>,
+    /// The call graph of the crate, if `-Z stack-report` is enabled.
+    pub stack_report: Option<StackReport>,
 }
 
 impl<B: ExtraBackendMethods> OngoingCodegen<B> {
@@ -1959,10 +1982,28 @@ pub fn join(self, sess: &Session) -> (CodegenResults, FxIndexMap<WorkProductId,
 
         sess.abort_if_errors();
 
+        // These need the object files, which may be removed by `produce_final_output_artifacts`.
+        if let Some(size_report) = self.size_report {
+            size_report.write(
+                sess,
+                compiled_modules.modules.iter().chain(&compiled_modules.allocator_module),
+            );
+        }
+        if let Some(stack_report) = self.stack_report {
+            stack_report.write(
+                sess,
+                compiled_modules.modules.iter().chain(&compiled_modules.allocator_module),
+            );
+        }
+
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
 
+        if let Some(item_time_report) = self.item_time_report {
+            item_time_report.write(sess);
+        }
+
         // FIXME: time_llvm_passes support - does this use a global context or
         // something?
         if sess.codegen_units().as_usize() == 1 && sess.opts.unstable_opts.time_llvm_passes {
@@ -101,6 +101,41 @@ pub struct IgnoringOutput {
     pub extension: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_item_time_report_write)]
+pub struct ItemTimeReportWrite {
+    pub path: PathBuf,
+    pub error: Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_size_report_read_object)]
+pub struct SizeReportReadObject {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_size_report_write)]
+pub struct SizeReportWrite {
+    pub path: PathBuf,
+    pub error: Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_stack_report_read_object)]
+pub struct StackReportReadObject {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_stack_report_write)]
+pub struct StackReportWrite {
+    pub path: PathBuf,
+    pub error: Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_create_temp_dir)]
 pub struct CreateTempDir {
@@ -0,0 +1,336 @@
+//! The `-Z item-time-report`, which attributes compile time to individual items.
+//!
+//! Type-checking, borrow-checking and MIR optimizations are timed per body while they run,
+//! and lowering to the backend's IR per mono item, see [`ItemActivity`]. The backend only
+//! works on whole codegen units, so the time spent optimizing and emitting a codegen unit is
+//! split among its mono items in proportion to their size estimate.
+//!
+//! The crates that requested each generic instantiation are taken from the uses of the mono items
+//! recorded by the mono item collector.
+//!
+//! The report can only be written once the backend is done, when the `TyCtxt` is gone, so
+//! [`ItemTimeReport::new`] records everything it needs to know about the mono items when
+//! codegen starts.
+
+use crate::errors;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::profiling::ItemActivity;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::SwitchWithOptPath;
+use rustc_session::Session;
+use std::cmp;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The name of `mono_item` in the report.
+pub(crate) fn mono_item_name(tcx: TyCtxt<'_>, mono_item: MonoItem<'_>) -> String {
+    match mono_item {
+        // All `global_asm!` items would otherwise have the same name.
+        MonoItem::GlobalAsm(item_id) => {
+            format!("global_asm {}", tcx.item_time_report_path(item_id.owner_id.to_def_id()))
+        }
+        MonoItem::Fn(_) | MonoItem::Static(_) => with_no_trimmed_paths!(mono_item.to_string()),
+    }
+}
+
+struct MonoItemInfo {
+    name: String,
+    /// The path of the item this is an instance of, as used for the per-body timings.
+    def_path: String,
+    defining_crate: String,
+    /// Whether this is one of possibly many instantiations of a generic item.
+    generic: bool,
+    /// For generic items, the crates whose items requested this instantiation.
+    requested_by: Vec<String>,
+    size_estimate: usize,
+    cgus: Vec<usize>,
+}
+
+struct CguInfo {
+    name: String,
+    /// Indices into `ItemTimeReport::mono_items`.
+    mono_items: Vec<usize>,
+    size_estimate: usize,
+}
+
+pub struct ItemTimeReport {
+    crate_name: String,
+    output_dir: PathBuf,
+    mono_items: Vec<MonoItemInfo>,
+    cgus: Vec<CguInfo>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Times {
+    typeck: Duration,
+    borrowck: Duration,
+    mir_opt: Duration,
+    codegen: Duration,
+    backend: Duration,
+}
+
+impl Times {
+    fn add(&mut self, activity: ItemActivity, time: Duration) {
+        *match activity {
+            ItemActivity::Typeck => &mut self.typeck,
+            ItemActivity::Borrowck => &mut self.borrowck,
+            ItemActivity::MirOpt => &mut self.mir_opt,
+            ItemActivity::Codegen => &mut self.codegen,
+            ItemActivity::Backend => &mut self.backend,
+        } += time;
+    }
+
+    fn total(&self) -> Duration {
+        self.typeck + self.borrowck + self.mir_opt + self.codegen + self.backend
+    }
+
+    /// The backend time is only an estimate, see the module documentation, so its JSON field is
+    /// labeled as such.
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "total_ns": self.total().as_nanos() as u64,
+            "typeck_ns": self.typeck.as_nanos() as u64,
+            "borrowck_ns": self.borrowck.as_nanos() as u64,
+            "mir_opt_ns": self.mir_opt.as_nanos() as u64,
+            "codegen_ns": self.codegen.as_nanos() as u64,
+            "estimated_backend_ns": self.backend.as_nanos() as u64,
+        })
+    }
+}
+
+#[derive(Default)]
+struct ItemEntry {
+    times: Times,
+    /// Indices into `ItemTimeReport::mono_items`.
+    instances: Vec<usize>,
+}
+
+impl ItemTimeReport {
+    /// Records the mono items and codegen units of the crate, if `-Z item-time-report` is
+    /// enabled.
+    pub fn new(tcx: TyCtxt<'_>) -> Option<ItemTimeReport> {
+        let SwitchWithOptPath::Enabled(output_dir) = &tcx.sess.opts.unstable_opts.item_time_report
+        else {
+            return None;
+        };
+
+        let mut mono_items = Vec::new();
+        let mut indices = FxHashMap::default();
+        let mut cgus = Vec::new();
+        for cgu in tcx.collect_and_partition_mono_items(()).1 {
+            let cgu_index = cgus.len();
+            let mut cgu_items = Vec::new();
+            let mut cgu_size = 0;
+            for (mono_item, data) in cgu.items_in_deterministic_order(tcx) {
+                let index = *indices.entry(mono_item).or_insert_with(|| {
+                    let def_id = mono_item.def_id();
+                    let generic = matches!(mono_item, MonoItem::Fn(_))
+                        && tcx.generics_of(def_id).requires_monomorphization(tcx);
+                    let requested_by = if generic {
+                        let symbol_name = mono_item.symbol_name(tcx).name;
+                        tcx.sess.code_stats.mono_item_requesters(symbol_name).unwrap_or_default()
+                    } else {
+                        Vec::new()
+                    };
+                    mono_items.push(MonoItemInfo {
+                        name: mono_item_name(tcx, mono_item),
+                        def_path: tcx.item_time_report_path(def_id),
+                        defining_crate: tcx.crate_name(def_id.krate).to_string(),
+                        generic,
+                        requested_by,
+                        size_estimate: data.size_estimate,
+                        cgus: Vec::new(),
+                    });
+                    mono_items.len() - 1
+                });
+                mono_items[index].cgus.push(cgu_index);
+                cgu_items.push(index);
+                cgu_size += data.size_estimate;
+            }
+            cgus.push(CguInfo {
+                name: cgu.name().to_string(),
+                mono_items: cgu_items,
+                size_estimate: cgu_size,
+            });
+        }
+
+        Some(ItemTimeReport {
+            crate_name: tcx.crate_name(LOCAL_CRATE).to_string(),
+            output_dir: output_dir.clone().unwrap_or_else(|| PathBuf::from(".")),
+            mono_items,
+            cgus,
+        })
+    }
+
+    /// Writes the report as `<crate>.item_times.json` and as a text table sorted by total time,
+    /// `<crate>.item_times.txt`. Must be called once the backend has finished.
+    pub fn write(self, sess: &Session) {
+        let Some(timings) = sess.prof.item_timings() else {
+            return;
+        };
+
+        let mono_item_indices: FxHashMap<&str, usize> =
+            self.mono_items.iter().enumerate().map(|(i, item)| (&*item.name, i)).collect();
+        let cgu_indices: FxHashMap<&str, usize> =
+            self.cgus.iter().enumerate().map(|(i, cgu)| (&*cgu.name, i)).collect();
+
+        let mut items: FxIndexMap<&str, ItemEntry> = FxIndexMap::default();
+        let mut instance_times = vec![Times::default(); self.mono_items.len()];
+        // Backend time of modules that don't correspond to a codegen unit, like the
+        // allocator shim or a fat LTO module.
+        let mut unattributed = Duration::ZERO;
+
+        let mut timings: Vec<_> = timings.take().into_iter().collect();
+        timings.sort_unstable_by(|(a, _), (b, _)| a.1.cmp(&b.1));
+        for ((activity, key), time) in &timings {
+            match activity {
+                ItemActivity::Typeck | ItemActivity::Borrowck | ItemActivity::MirOpt => {
+                    items.entry(key.as_str()).or_default().times.add(*activity, *time);
+                }
+                ItemActivity::Codegen => match mono_item_indices.get(key.as_str()) {
+                    Some(&index) => instance_times[index].codegen += *time,
+                    None => items.entry(key.as_str()).or_default().times.codegen += *time,
+                },
+                ItemActivity::Backend => {
+                    let cgu = cgu_indices.get(key.as_str()).map(|&index| &self.cgus[index]);
+                    match cgu {
+                        Some(cgu) if cgu.size_estimate > 0 => {
+                            for &index in &cgu.mono_items {
+                                let share = self.mono_items[index].size_estimate as f64
+                                    / cgu.size_estimate as f64;
+                                instance_times[index].backend += time.mul_f64(share);
+                            }
+                        }
+                        _ => unattributed += *time,
+                    }
+                }
+            }
+        }
+        for (index, mono_item) in self.mono_items.iter().enumerate() {
+            let item = items.entry(mono_item.def_path.as_str()).or_default();
+            item.times.codegen += instance_times[index].codegen;
+            item.times.backend += instance_times[index].backend;
+            item.instances.push(index);
+        }
+
+        let mut items: Vec<_> = items.into_iter().collect();
+        items.sort_by_key(|(_, item)| cmp::Reverse(item.times.total()));
+        for (_, item) in &mut items {
+            item.instances.sort_by_key(|&index| cmp::Reverse(instance_times[index].total()));
+        }
+
+        let json = self.to_json(&items, &instance_times, unattributed);
+        let text = self.to_text(&items, unattributed);
+        for (extension, contents) in [("json", json), ("txt", text)] {
+            let path = self.output_dir.join(format!("{}.item_times.{extension}", self.crate_name));
+            if let Err(error) = write_file(&path, &contents) {
+                sess.emit_err(errors::ItemTimeReportWrite { path, error });
+            }
+        }
+    }
+
+    fn to_json(
+        &self,
+        items: &[(&str, ItemEntry)],
+        instance_times: &[Times],
+        unattributed: Duration,
+    ) -> String {
+        let items: Vec<_> = items
+            .iter()
+            .map(|(path, item)| {
+                let instances: Vec<_> = item
+                    .instances
+                    .iter()
+                    .map(|&index| {
+                        let mono_item = &self.mono_items[index];
+                        let cgus: Vec<_> =
+                            mono_item.cgus.iter().map(|&cgu| &self.cgus[cgu].name).collect();
+                        serde_json::json!({
+                            "name": mono_item.name,
+                            "generic": mono_item.generic,
+                            "defining_crate": mono_item.defining_crate,
+                            "requested_by": mono_item.requested_by,
+                            "size_estimate": mono_item.size_estimate,
+                            "codegen_units": cgus,
+                            "times": instance_times[index].to_json(),
+                        })
+                    })
+                    .collect();
+                serde_json::json!({
+                    "path": path,
+                    "times": item.times.to_json(),
+                    "instances": instances,
+                })
+            })
+            .collect();
+        let report = serde_json::json!({
+            "crate": self.crate_name,
+            "unattributed_backend_ns": unattributed.as_nanos() as u64,
+            "items": items,
+        });
+        serde_json::to_string_pretty(&report).unwrap()
+    }
+
+    fn to_text(&self, items: &[(&str, ItemEntry)], unattributed: Duration) -> String {
+        let ms = |time: Duration| format!("{:.3}", time.as_secs_f64() * 1000.0);
+        let mut out = String::new();
+        writeln!(out, "Time spent on each item of `{}`, in milliseconds.", self.crate_name)
+            .unwrap();
+        writeln!(out, "Backend times are estimates, the time of a codegen unit is split by size.")
+            .unwrap();
+        writeln!(out, "Generic items are followed by the crates that requested their instances.")
+            .unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "{:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>9}  item",
+            "total", "typeck", "borrowck", "mir-opt", "codegen", "backend", "instances"
+        )
+        .unwrap();
+        for (path, item) in items {
+            let Times { typeck, borrowck, mir_opt, codegen, backend } = item.times;
+            let mut requested_by: Vec<_> = item
+                .instances
+                .iter()
+                .flat_map(|&index| &self.mono_items[index].requested_by)
+                .map(String::as_str)
+                .collect();
+            requested_by.sort_unstable();
+            requested_by.dedup();
+            let requested_by = if requested_by.is_empty() {
+                String::new()
+            } else {
+                format!(" (requested by {})", requested_by.join(", "))
+            };
+            writeln!(
+                out,
+                "{:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>9}  {path}{requested_by}",
+                ms(item.times.total()),
+                ms(typeck),
+                ms(borrowck),
+                ms(mir_opt),
+                ms(codegen),
+                ms(backend),
+                item.instances.len(),
+            )
+            .unwrap();
+        }
+        if unattributed > Duration::ZERO {
+            writeln!(out).unwrap();
+            writeln!(out, "Backend time not attributed to any item: {}", ms(unattributed)).unwrap();
+        }
+        out
+    }
+}
+
+fn write_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)
+}
@@ -50,9 +50,12 @@
 pub mod debuginfo;
 pub mod errors;
 pub mod glue;
+pub mod item_time_report;
 pub mod meth;
 pub mod mir;
 pub mod mono_item;
+pub mod size_report;
+pub mod stack_report;
 pub mod target_features;
 pub mod traits;
 
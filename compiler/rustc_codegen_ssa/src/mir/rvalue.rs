@@ -682,6 +682,13 @@ pub fn codegen_rvalue_operand(
                     mir::NullOp::OffsetOf(fields) => {
                         layout.offset_of_subfield(bx.cx(), fields.iter().map(|f| f.index())).bytes()
                     }
+                    mir::NullOp::UbChecks => {
+                        let val = bx.tcx().sess.ub_checks();
+                        return OperandRef {
+                            val: OperandValue::Immediate(bx.cx().const_bool(val)),
+                            layout: self.cx.layout_of(bx.tcx().types.bool),
+                        };
+                    }
                 };
                 let val = bx.cx().const_usize(val);
                 let tcx = self.cx.tcx();
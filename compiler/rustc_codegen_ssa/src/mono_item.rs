@@ -1,6 +1,8 @@
 use crate::base;
 use crate::common;
+use crate::item_time_report;
 use crate::traits::*;
+use rustc_data_structures::profiling::ItemActivity;
 use rustc_hir as hir;
 use rustc_middle::mir::mono::MonoItem;
 use rustc_middle::mir::mono::{Linkage, Visibility};
@@ -28,6 +30,12 @@ fn define<Bx: BuilderMethods<'a, 'tcx>>(&self, cx: &'a Bx::CodegenCx) {
             cx.codegen_unit().name()
         );
 
+        let tcx = cx.tcx();
+        let _timer = tcx
+            .sess
+            .prof
+            .item_activity(ItemActivity::Codegen, || item_time_report::mono_item_name(tcx, *self));
+
         match *self {
             MonoItem::Static(def_id) => {
                 cx.codegen_static(def_id, cx.tcx().is_mutable_static(def_id));
@@ -0,0 +1,238 @@
+//! The `-Z size-report`, which attributes the bytes of the emitted object files to mono items.
+//!
+//! The symbol names of all mono items are recorded when codegen starts, together with the
+//! item, module and crate they come from. Once the backend has emitted the object files, the
+//! size of every defined symbol is read back from them and added up per mono item, per generic
+//! origin (the item all instances of a generic function share, e.g. `Vec::<T>::push`), per
+//! module and per crate. Symbols that don't belong to a mono item, like vtables or constant
+//! allocations, are listed separately.
+//!
+//! Everything is sorted by name rather than by size, so that the reports of two builds can be
+//! compared with a plain `diff`.
+
+use crate::back::object_symbols::{defined_symbols, read_object_file};
+use crate::errors;
+use crate::CompiledModule;
+use object::{Object, ObjectSection, SectionIndex, SectionKind};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::SwitchWithOptPath;
+use rustc_session::Session;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct MonoItemInfo {
+    name: String,
+    /// The generic origin of the mono item: the path of the item it is an instance of.
+    item: String,
+    module: String,
+    krate: String,
+}
+
+pub struct SizeReport {
+    crate_name: String,
+    output_dir: PathBuf,
+    /// The mono items of the crate, by symbol name.
+    symbols: FxHashMap<String, MonoItemInfo>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Sizes {
+    /// Bytes of machine code.
+    code: u64,
+    /// Bytes of data, including read-only and zero-initialized data.
+    data: u64,
+}
+
+impl Sizes {
+    fn add(&mut self, other: Sizes) {
+        self.code += other.code;
+        self.data += other.data;
+    }
+
+    /// Adds the sizes to the JSON object `json`.
+    fn to_json(self, mut json: serde_json::Value) -> serde_json::Value {
+        json["code"] = self.code.into();
+        json["data"] = self.data.into();
+        json["total"] = (self.code + self.data).into();
+        json
+    }
+}
+
+/// The module containing `def_id`.
+fn parent_module(tcx: TyCtxt<'_>, mut def_id: DefId) -> DefId {
+    while tcx.def_kind(def_id) != DefKind::Mod {
+        def_id = tcx.parent(def_id);
+    }
+    def_id
+}
+
+impl SizeReport {
+    /// Records the symbol names of the mono items of the crate, if `-Z size-report` is enabled.
+    pub fn new(tcx: TyCtxt<'_>) -> Option<SizeReport> {
+        let SwitchWithOptPath::Enabled(output_dir) = &tcx.sess.opts.unstable_opts.size_report
+        else {
+            return None;
+        };
+
+        let mut symbols = FxHashMap::default();
+        for cgu in tcx.collect_and_partition_mono_items(()).1 {
+            for mono_item in cgu.items().keys() {
+                if let MonoItem::GlobalAsm(_) = mono_item {
+                    // `global_asm!` doesn't have a symbol of its own.
+                    continue;
+                }
+                let symbol_name = mono_item.symbol_name(tcx).name.to_string();
+                symbols.entry(symbol_name).or_insert_with(|| {
+                    let def_id = mono_item.def_id();
+                    MonoItemInfo {
+                        name: with_no_trimmed_paths!(mono_item.to_string()),
+                        item: with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+                        module: with_no_trimmed_paths!(
+                            tcx.def_path_str(parent_module(tcx, def_id))
+                        ),
+                        krate: tcx.crate_name(def_id.krate).to_string(),
+                    }
+                });
+            }
+        }
+
+        Some(SizeReport {
+            crate_name: tcx.crate_name(LOCAL_CRATE).to_string(),
+            output_dir: output_dir.clone().unwrap_or_else(|| PathBuf::from(".")),
+            symbols,
+        })
+    }
+
+    /// Reads the object files of `modules` and writes the report as
+    /// `<crate>.size_report.json`. Must be called before the object files are removed.
+    pub fn write<'a>(self, sess: &Session, modules: impl Iterator<Item = &'a CompiledModule>) {
+        let mut symbol_sizes: BTreeMap<String, Sizes> = BTreeMap::new();
+        for module in modules {
+            let Some(path) = &module.object else { continue };
+            if let Err(error) = read_symbol_sizes(path, &mut symbol_sizes) {
+                sess.emit_warning(errors::SizeReportReadObject { path: path.clone(), error });
+            }
+        }
+
+        let mut total = Sizes::default();
+        let mut mono_items = BTreeMap::new();
+        let mut items: BTreeMap<&str, (Sizes, usize)> = BTreeMap::new();
+        let mut modules: BTreeMap<(&str, &str), Sizes> = BTreeMap::new();
+        let mut crates: BTreeMap<&str, Sizes> = BTreeMap::new();
+        let mut unattributed = Vec::new();
+        for (symbol, &sizes) in &symbol_sizes {
+            total.add(sizes);
+            let Some(info) = self.symbols.get(symbol) else {
+                unattributed.push(sizes.to_json(serde_json::json!({ "symbol": symbol })));
+                continue;
+            };
+            // Different instances can print the same, e.g. if they only differ in lifetimes.
+            mono_items.insert((&*info.name, &**symbol), (info, sizes));
+            let (item_sizes, instances) = items.entry(info.item.as_str()).or_default();
+            item_sizes.add(sizes);
+            *instances += 1;
+            modules.entry((info.module.as_str(), info.krate.as_str())).or_default().add(sizes);
+            crates.entry(info.krate.as_str()).or_default().add(sizes);
+        }
+
+        let report = serde_json::json!({
+            "crate": self.crate_name,
+            "total": total.to_json(serde_json::json!({})),
+            "crates": crates
+                .into_iter()
+                .map(|(name, sizes)| sizes.to_json(serde_json::json!({ "name": name })))
+                .collect::<Vec<_>>(),
+            "modules": modules
+                .into_iter()
+                .map(|((path, krate), sizes)| {
+                    sizes.to_json(serde_json::json!({ "path": path, "crate": krate }))
+                })
+                .collect::<Vec<_>>(),
+            "items": items
+                .into_iter()
+                .map(|(path, (sizes, instances))| {
+                    sizes.to_json(serde_json::json!({ "path": path, "instances": instances }))
+                })
+                .collect::<Vec<_>>(),
+            "mono_items": mono_items
+                .into_iter()
+                .map(|((_, symbol), (info, sizes))| {
+                    sizes.to_json(serde_json::json!({
+                        "name": info.name,
+                        "symbol": symbol,
+                        "item": info.item,
+                        "module": info.module,
+                        "crate": info.krate,
+                    }))
+                })
+                .collect::<Vec<_>>(),
+            "unattributed": unattributed,
+        });
+
+        let path = self.output_dir.join(format!("{}.size_report.json", self.crate_name));
+        let contents = serde_json::to_string_pretty(&report).unwrap();
+        if let Err(error) =
+            fs::create_dir_all(&self.output_dir).and_then(|()| fs::write(&path, contents))
+        {
+            sess.emit_err(errors::SizeReportWrite { path, error });
+        }
+    }
+}
+
+/// Adds the size of every symbol defined in the object file at `path` to `sizes`.
+fn read_symbol_sizes(path: &Path, sizes: &mut BTreeMap<String, Sizes>) -> Result<(), String> {
+    read_object_file(path, |file| {
+        // Not all object file formats record the size of a symbol, so compute it from the
+        // address of the next symbol in the same section where necessary.
+        let mut by_section: FxHashMap<SectionIndex, Vec<(u64, u64, &str)>> = FxHashMap::default();
+        for symbol in defined_symbols(file) {
+            by_section.entry(symbol.section).or_default().push((
+                symbol.address,
+                symbol.size,
+                symbol.name,
+            ));
+        }
+
+        for (section_index, mut section_symbols) in by_section {
+            let section =
+                file.section_by_index(section_index).map_err(|error| error.to_string())?;
+            let is_code = match section.kind() {
+                SectionKind::Text => true,
+                SectionKind::Data
+                | SectionKind::ReadOnlyData
+                | SectionKind::ReadOnlyString
+                | SectionKind::UninitializedData
+                | SectionKind::Tls
+                | SectionKind::UninitializedTls => false,
+                _ => continue,
+            };
+            let section_end = section.address() + section.size();
+            section_symbols.sort_unstable();
+            for (index, &(address, size, name)) in section_symbols.iter().enumerate() {
+                let size = if size != 0 {
+                    size
+                } else {
+                    let next = section_symbols[index + 1..]
+                        .iter()
+                        .map(|&(next, ..)| next)
+                        .find(|&next| next > address)
+                        .unwrap_or(section_end);
+                    next.saturating_sub(address)
+                };
+                let symbol_sizes = sizes.entry(name.to_owned()).or_default();
+                if is_code {
+                    symbol_sizes.code += size;
+                } else {
+                    symbol_sizes.data += size;
+                }
+            }
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,447 @@
+//! The `-Z stack-report`, which computes the worst-case stack depth of the entry points of the
+//! crate.
+//!
+//! When codegen starts, the calls of every function among the mono items are recorded, as the
+//! monomorphized call graph. The frame sizes of the functions come from the `.stack_sizes`
+//! sections LLVM emits with `-Z emit-stack-sizes`, which `-Z stack-report` implies, and are read
+//! back from the object files once they have been emitted.
+//!
+//! The depth of a function is its frame size plus the largest depth of the functions it calls.
+//! It is a proven bound only if nothing it can reach is
+//!
+//! - recursive,
+//! - an indirect call, through a function pointer or a trait object, or
+//! - a call of a function whose frame size isn't known, like a function of another crate that
+//!   wasn't instantiated in this one.
+//!
+//! Otherwise the depth is still reported, as a lower bound, together with the reasons why it
+//! isn't a proven bound. Functions that were inlined into all their callers have no symbol of
+//! their own, and their frames are part of the frames of their callers.
+
+use crate::back::object_symbols::{defined_symbols, normalize_symbol_name, read_object_file};
+use crate::errors;
+use crate::CompiledModule;
+use object::{
+    Object, ObjectSection, ObjectSymbol, RelocationTarget, SectionIndex, SymbolIndex, SymbolKind,
+};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_hir::LangItem;
+use rustc_middle::mir::{self, mono::MonoItem};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_session::config::SwitchWithOptPath;
+use rustc_session::Session;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+enum Callee {
+    /// A call of the function with the given symbol name.
+    Direct { symbol: String, name: String },
+    /// A call through a function pointer or a trait object.
+    Indirect(&'static str),
+}
+
+struct Call {
+    callee: Callee,
+    /// Where the call is, for the report.
+    location: String,
+}
+
+struct FunctionInfo {
+    name: String,
+    entry_point: bool,
+    calls: Vec<Call>,
+}
+
+pub struct StackReport {
+    crate_name: String,
+    output_dir: PathBuf,
+    /// The functions among the mono items of the crate, by symbol name.
+    functions: BTreeMap<String, FunctionInfo>,
+}
+
+/// Why the depth of a function isn't a proven bound: the kind of the problem, the function it is
+/// in and the details.
+type Problem = (&'static str, String, String);
+
+/// The worst-case stack depth of a function.
+struct Depth {
+    bytes: u64,
+    /// The frame size of the function itself, if known.
+    frame: Option<u64>,
+    /// The symbol of the callee on the deepest path, if any.
+    deepest_callee: Option<String>,
+    problems: BTreeSet<Problem>,
+}
+
+/// Records the calls in the body of `instance`.
+fn calls_of<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> Vec<Call> {
+    let body = tcx.instance_mir(instance.def);
+    let monomorphize = |ty| {
+        instance.subst_mir_and_normalize_erasing_regions(
+            tcx,
+            ty::ParamEnv::reveal_all(),
+            ty::EarlyBinder::bind(ty),
+        )
+    };
+    let direct = |callee: Instance<'tcx>| Callee::Direct {
+        symbol: tcx.symbol_name(callee).name.to_owned(),
+        name: with_no_trimmed_paths!(callee.to_string()),
+    };
+
+    let mut calls = Vec::new();
+    for block in body.basic_blocks.iter() {
+        let terminator = block.terminator();
+        let callee = match &terminator.kind {
+            mir::TerminatorKind::Call { func, .. } => {
+                match *monomorphize(func.ty(body, tcx)).kind() {
+                    ty::FnDef(def_id, args) => {
+                        let callee =
+                            Instance::expect_resolve(tcx, ty::ParamEnv::reveal_all(), def_id, args);
+                        match callee.def {
+                            // Intrinsics are expanded in place.
+                            InstanceDef::Intrinsic(_) => continue,
+                            InstanceDef::Virtual(..) => {
+                                Callee::Indirect("a call of a trait object")
+                            }
+                            _ => direct(callee),
+                        }
+                    }
+                    ty::FnPtr(_) => Callee::Indirect("a call through a function pointer"),
+                    _ => continue,
+                }
+            }
+            mir::TerminatorKind::Drop { place, .. } => {
+                let ty = monomorphize(place.ty(body, tcx).ty);
+                let callee = Instance::resolve_drop_in_place(tcx, ty);
+                if let InstanceDef::DropGlue(_, None) = callee.def {
+                    // Dropping the value does nothing.
+                    continue;
+                }
+                direct(callee)
+            }
+            mir::TerminatorKind::Assert { msg, .. } => {
+                let lang_item = match &**msg {
+                    mir::AssertKind::BoundsCheck { .. } => LangItem::PanicBoundsCheck,
+                    _ => LangItem::Panic,
+                };
+                direct(Instance::mono(tcx, tcx.require_lang_item(lang_item, None)))
+            }
+            mir::TerminatorKind::UnwindTerminate(reason) => {
+                direct(Instance::mono(tcx, tcx.require_lang_item(reason.lang_item(), None)))
+            }
+            _ => continue,
+        };
+        let location = tcx.sess.source_map().span_to_embeddable_string(terminator.source_info.span);
+        calls.push(Call { callee, location });
+    }
+    calls
+}
+
+impl StackReport {
+    /// Records the calls of the functions of the crate, if `-Z stack-report` is enabled.
+    pub fn new(tcx: TyCtxt<'_>) -> Option<StackReport> {
+        let SwitchWithOptPath::Enabled(output_dir) = &tcx.sess.opts.unstable_opts.stack_report
+        else {
+            return None;
+        };
+
+        let entry_fn = tcx.entry_fn(()).map(|(def_id, _)| def_id);
+        let mut functions = BTreeMap::new();
+        for cgu in tcx.collect_and_partition_mono_items(()).1 {
+            for mono_item in cgu.items().keys() {
+                let MonoItem::Fn(instance) = *mono_item else { continue };
+                let symbol = mono_item.symbol_name(tcx).name.to_owned();
+                if functions.contains_key(&symbol) {
+                    continue;
+                }
+                // `main` and the functions exported from the crate, as for the roots of the mono
+                // item collection.
+                let def_id = instance.def_id();
+                let entry_point = matches!(instance.def, InstanceDef::Item(_))
+                    && def_id.is_local()
+                    && (entry_fn == Some(def_id) || tcx.is_reachable_non_generic(def_id));
+                let info = FunctionInfo {
+                    name: with_no_trimmed_paths!(instance.to_string()),
+                    entry_point,
+                    calls: calls_of(tcx, instance),
+                };
+                functions.insert(symbol, info);
+            }
+        }
+
+        Some(StackReport {
+            crate_name: tcx.crate_name(LOCAL_CRATE).to_string(),
+            output_dir: output_dir.clone().unwrap_or_else(|| PathBuf::from(".")),
+            functions,
+        })
+    }
+
+    /// Reads the frame sizes from the object files of `modules` and writes the report as
+    /// `<crate>.stack_report.json`. Must be called before the object files are removed.
+    pub fn write<'a>(self, sess: &Session, modules: impl Iterator<Item = &'a CompiledModule>) {
+        let mut frames = FxHashMap::default();
+        let mut defined = FxHashSet::default();
+        for module in modules {
+            let Some(path) = &module.object else { continue };
+            if let Err(error) = read_stack_sizes(path, &mut frames, &mut defined) {
+                sess.emit_warning(errors::StackReportReadObject { path: path.clone(), error });
+            }
+        }
+
+        let mut analysis = Analysis {
+            functions: &self.functions,
+            frames: &frames,
+            defined: &defined,
+            depths: FxHashMap::default(),
+            index: FxHashMap::default(),
+            lowlink: FxHashMap::default(),
+            stack: Vec::new(),
+            on_stack: FxHashSet::default(),
+            next_index: 0,
+        };
+        for symbol in self.functions.keys() {
+            if !analysis.index.contains_key(symbol.as_str()) {
+                ensure_sufficient_stack(|| analysis.visit(symbol));
+            }
+        }
+        let depths = analysis.depths;
+
+        let depth_to_json = |symbol: &str, json: &mut serde_json::Value| {
+            let depth = &depths[symbol];
+            json["stack"] = depth.bytes.into();
+            json["frame"] = depth.frame.into();
+            json["bounded"] = depth.problems.is_empty().into();
+            json["problems"] = depth
+                .problems
+                .iter()
+                .map(|(kind, function, detail)| {
+                    serde_json::json!({ "kind": kind, "function": function, "detail": detail })
+                })
+                .collect::<Vec<_>>()
+                .into();
+        };
+
+        let mut entry_points = Vec::new();
+        let mut functions = Vec::new();
+        for (symbol, info) in &self.functions {
+            let mut json = serde_json::json!({ "name": info.name, "symbol": symbol });
+            depth_to_json(symbol, &mut json);
+            if info.entry_point {
+                let mut entry_point = json.clone();
+                let mut path = Vec::new();
+                let mut current = Some(symbol.as_str());
+                while let Some(symbol) = current {
+                    let depth = &depths[symbol];
+                    path.push(serde_json::json!({
+                        "name": self.functions[symbol].name,
+                        "frame": depth.frame,
+                    }));
+                    current = depth.deepest_callee.as_deref();
+                }
+                entry_point["deepest_path"] = path.into();
+                entry_points.push(entry_point);
+            }
+            functions.push(json);
+        }
+        entry_points.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+        functions.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+
+        let report = serde_json::json!({
+            "crate": self.crate_name,
+            "entry_points": entry_points,
+            "functions": functions,
+        });
+
+        let path = self.output_dir.join(format!("{}.stack_report.json", self.crate_name));
+        let contents = serde_json::to_string_pretty(&report).unwrap();
+        if let Err(error) =
+            fs::create_dir_all(&self.output_dir).and_then(|()| fs::write(&path, contents))
+        {
+            sess.emit_err(errors::StackReportWrite { path, error });
+        }
+    }
+}
+
+/// Computes the depths of the functions, finding the recursive ones with Tarjan's algorithm for
+/// strongly connected components. A component is complete when the DFS leaves its root, and at
+/// that point all components it calls are complete too, so its depth can be computed right away.
+struct Analysis<'a> {
+    functions: &'a BTreeMap<String, FunctionInfo>,
+    frames: &'a FxHashMap<String, u64>,
+    defined: &'a FxHashSet<String>,
+    depths: FxHashMap<&'a str, Depth>,
+    index: FxHashMap<&'a str, usize>,
+    lowlink: FxHashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: FxHashSet<&'a str>,
+    next_index: usize,
+}
+
+impl<'a> Analysis<'a> {
+    fn visit(&mut self, symbol: &'a str) {
+        self.index.insert(symbol, self.next_index);
+        self.lowlink.insert(symbol, self.next_index);
+        self.next_index += 1;
+        self.stack.push(symbol);
+        self.on_stack.insert(symbol);
+
+        let functions = self.functions;
+        for call in &functions[symbol].calls {
+            let Callee::Direct { symbol: callee, .. } = &call.callee else { continue };
+            if !functions.contains_key(callee) {
+                continue;
+            }
+            if !self.index.contains_key(callee.as_str()) {
+                ensure_sufficient_stack(|| self.visit(callee));
+                let lowlink = self.lowlink[symbol].min(self.lowlink[callee.as_str()]);
+                self.lowlink.insert(symbol, lowlink);
+            } else if self.on_stack.contains(callee.as_str()) {
+                let lowlink = self.lowlink[symbol].min(self.index[callee.as_str()]);
+                self.lowlink.insert(symbol, lowlink);
+            }
+        }
+
+        if self.lowlink[symbol] == self.index[symbol] {
+            let start = self.stack.iter().rposition(|&s| s == symbol).unwrap();
+            let component: Vec<_> = self.stack.drain(start..).collect();
+            for symbol in &component {
+                self.on_stack.remove(symbol);
+            }
+            self.complete(&component);
+        }
+    }
+
+    /// Computes the depths of the functions of a strongly connected component.
+    fn complete(&mut self, component: &[&'a str]) {
+        let functions = self.functions;
+        let recursive = component.len() > 1
+            || functions[component[0]].calls.iter().any(|call| {
+                matches!(&call.callee, Callee::Direct { symbol, .. } if symbol == component[0])
+            });
+        let mut cycle: Vec<_> =
+            component.iter().map(|&symbol| functions[symbol].name.as_str()).collect();
+        cycle.sort_unstable();
+        let cycle = cycle.join("`, `");
+
+        for &symbol in component {
+            let info = &functions[symbol];
+            let mut problems = BTreeSet::new();
+            let frame = self.frames.get(symbol).copied();
+            if frame.is_none() && self.defined.contains(symbol) {
+                problems.insert(("unknown-frame", info.name.clone(), String::new()));
+            }
+            if recursive {
+                problems.insert(("recursion", info.name.clone(), format!("`{cycle}`")));
+            }
+
+            let mut deepest: Option<(u64, &str)> = None;
+            for call in &info.calls {
+                match &call.callee {
+                    Callee::Direct { symbol: callee, name } => {
+                        // The calls within the component are covered by the recursion.
+                        if component.contains(&callee.as_str()) {
+                            continue;
+                        }
+                        let Some(depth) = self.depths.get(callee.as_str()) else {
+                            problems.insert((
+                                "unknown-callee",
+                                info.name.clone(),
+                                format!("`{name}` at {}", call.location),
+                            ));
+                            continue;
+                        };
+                        problems.extend(depth.problems.iter().cloned());
+                        if deepest.map_or(true, |(bytes, _)| depth.bytes > bytes) {
+                            deepest = Some((depth.bytes, callee));
+                        }
+                    }
+                    Callee::Indirect(what) => {
+                        problems.insert((
+                            "indirect-call",
+                            info.name.clone(),
+                            format!("{what} at {}", call.location),
+                        ));
+                    }
+                }
+            }
+
+            let depth = Depth {
+                bytes: frame.unwrap_or(0) + deepest.map_or(0, |(bytes, _)| bytes),
+                frame,
+                deepest_callee: deepest.map(|(_, callee)| callee.to_owned()),
+                problems,
+            };
+            self.depths.insert(symbol, depth);
+        }
+    }
+}
+
+/// Reads the frame sizes from the `.stack_sizes` sections of the object file at `path` into
+/// `frames`, and the names of the functions it defines into `defined`.
+fn read_stack_sizes(
+    path: &Path,
+    frames: &mut FxHashMap<String, u64>,
+    defined: &mut FxHashSet<String>,
+) -> Result<(), String> {
+    read_object_file(path, |file| {
+        // The functions by their section and address, for relocations against section symbols.
+        let mut by_address: FxHashMap<(SectionIndex, u64), &str> = FxHashMap::default();
+        for symbol in defined_symbols(file) {
+            if symbol.kind != SymbolKind::Text {
+                continue;
+            }
+            defined.insert(symbol.name.to_owned());
+            by_address.insert((symbol.section, symbol.address), symbol.name);
+        }
+        let function_at = |index: SymbolIndex, addend: i64| -> Option<String> {
+            let symbol = file.symbol_by_index(index).ok()?;
+            let name = if symbol.kind() == SymbolKind::Section {
+                let address = symbol.address().checked_add_signed(addend)?;
+                *by_address.get(&(symbol.section_index()?, address))?
+            } else {
+                normalize_symbol_name(file, symbol.name().ok()?)
+            };
+            Some(name.to_owned())
+        };
+
+        let address_size = if file.is_64() { 8 } else { 4 };
+        for section in file.sections() {
+            if section.name() != Ok(".stack_sizes") {
+                continue;
+            }
+            let data = section.data().map_err(|error| error.to_string())?;
+            // Every entry is the address of a function, which is relocated against its symbol,
+            // followed by its frame size as ULEB128.
+            for (offset, relocation) in section.relocations() {
+                let RelocationTarget::Symbol(index) = relocation.target() else { continue };
+                let Ok(offset) = usize::try_from(offset) else { continue };
+                let addend = if relocation.has_implicit_addend() {
+                    let mut bytes =
+                        data.get(offset..offset + address_size).unwrap_or_default().to_vec();
+                    if file.is_little_endian() {
+                        bytes.reverse();
+                    }
+                    bytes.iter().fold(0, |value, &byte| (value << 8) | i64::from(byte))
+                } else {
+                    relocation.addend()
+                };
+                let Some(function) = function_at(index, addend) else { continue };
+                let mut size = 0u64;
+                for (shift, &byte) in data.iter().skip(offset + address_size).enumerate() {
+                    size |= u64::from(byte & 0x7f) << (7 * shift);
+                    if byte & 0x80 == 0 {
+                        break;
+                    }
+                }
+                // The same function may be in several codegen units.
+                let frame = frames.entry(function).or_default();
+                *frame = (*frame).max(size);
+            }
+        }
+        Ok(())
+    })
+}
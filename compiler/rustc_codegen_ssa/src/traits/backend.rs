@@ -15,7 +15,7 @@
 use rustc_middle::ty::layout::{FnAbiOf, HasTyCtxt, LayoutOf, TyAndLayout};
 use rustc_middle::ty::{Ty, TyCtxt};
 use rustc_session::{
-    config::{self, OutputFilenames, PrintRequest},
+    config::{self, OutputFilenames, PrintKind, PrintRequest, PRINT_JSON_VERSION},
     cstore::MetadataLoaderDyn,
     Session,
 };
@@ -170,4 +170,21 @@ impl dyn PrintBackendInfo + '_ {
     pub fn write_fmt(&mut self, args: fmt::Arguments<'_>) {
         self.infallible_write_fmt(args);
     }
+
+    /// Writes the `--print-format=json` document for a `kind` request, see
+    /// [`print_json_document`].
+    pub fn write_json(&mut self, kind: PrintKind, data: serde_json::Value) {
+        writeln!(self, "{}", print_json_document(kind, data));
+    }
+}
+
+/// Wraps the `data` of a `kind` print request into the versioned document printed for
+/// `--print-format=json`.
+pub fn print_json_document(kind: PrintKind, data: serde_json::Value) -> String {
+    let document = serde_json::json!({
+        "version": PRINT_JSON_VERSION,
+        "kind": kind.name(),
+        "data": data,
+    });
+    serde_json::to_string_pretty(&document).unwrap()
 }
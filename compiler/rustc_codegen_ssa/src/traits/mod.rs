@@ -31,7 +31,8 @@
 pub use self::abi::AbiBuilderMethods;
 pub use self::asm::{AsmBuilderMethods, AsmMethods, GlobalAsmOperandRef, InlineAsmOperandRef};
 pub use self::backend::{
-    Backend, BackendTypes, CodegenBackend, ExtraBackendMethods, PrintBackendInfo,
+    print_json_document, Backend, BackendTypes, CodegenBackend, ExtraBackendMethods,
+    PrintBackendInfo,
 };
 pub use self::builder::{BuilderMethods, OverflowOp};
 pub use self::consts::ConstMethods;
@@ -317,8 +317,20 @@ pub fn eval_to_allocation_raw_provider<'tcx>(
         ),
     );
 
+    if tcx.sess.opts.unstable_opts.const_eval_profile {
+        ecx.machine.profile = Some(Default::default());
+    }
+
     let res = ecx.load_mir(cid.instance.def, cid.promoted);
-    match res.and_then(|body| eval_body_using_ecx(&mut ecx, cid, &body)) {
+    let res = res.and_then(|body| eval_body_using_ecx(&mut ecx, cid, &body));
+    if let Some(profile) = ecx.machine.profile.take() {
+        let functions = profile
+            .functions
+            .into_iter()
+            .map(|(instance, stats)| (with_no_trimmed_paths!(instance.to_string()), stats));
+        tcx.sess.code_stats.record_const_eval(cid.display(tcx), profile.total, functions);
+    }
+    match res {
         Err(error) => {
             let (error, backtrace) = error.into_parts();
             backtrace.print_backtrace();
@@ -23,7 +23,7 @@
 use crate::errors::{LongRunning, LongRunningWarn};
 use crate::interpret::{
     self, compile_time_machine, AllocId, ConstAllocation, FnArg, FnVal, Frame, ImmTy, InterpCx,
-    InterpResult, OpTy, PlaceTy, Pointer, Scalar,
+    InterpProfile, InterpResult, OpTy, PlaceTy, Pointer, Scalar,
 };
 use crate::{errors, fluent_generated as fluent};
 
@@ -61,6 +61,9 @@ pub struct CompileTimeInterpreter<'mir, 'tcx> {
 
     /// Whether to check alignment during evaluation.
     pub(super) check_alignment: CheckAlignment,
+
+    /// The work done so far, if requested by `-Z const-eval-profile`.
+    pub(super) profile: Option<InterpProfile<'tcx>>,
 }
 
 #[derive(Copy, Clone)]
@@ -105,6 +108,7 @@ pub(crate) fn new(
             stack: Vec::new(),
             can_access_statics,
             check_alignment,
+            profile: None,
         }
     }
 }
@@ -367,6 +371,14 @@ fn enforce_validity(ecx: &InterpCx<'mir, 'tcx, Self>, layout: TyAndLayout<'tcx>)
         ecx.tcx.sess.opts.unstable_opts.extra_const_ub_checks || layout.abi.is_uninhabited()
     }
 
+    #[inline(always)]
+    fn ub_checks(_ecx: &InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx, bool> {
+        // We can't look at `tcx.sess` here, as the result would then depend on the crate that
+        // evaluates the constant. The library only runs its checks at runtime anyway, via
+        // `const_eval_select`.
+        Ok(true)
+    }
+
     fn alignment_check_failed(
         ecx: &InterpCx<'mir, 'tcx, Self>,
         has: Align,
@@ -600,7 +612,33 @@ fn binary_ptr_op(
         throw_unsup_format!("pointer arithmetic or comparison is not supported at compile-time");
     }
 
+    #[inline(always)]
+    fn before_statement(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
+        let function = ecx.profiled_function();
+        if let Some(profile) = &mut ecx.machine.profile {
+            profile.record(function, |stats| stats.statements += 1);
+        }
+        Ok(())
+    }
+
+    fn after_memory_allocation(
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        size: Size,
+        _kind: interpret::MemoryKind<Self::MemoryKind>,
+    ) -> InterpResult<'tcx> {
+        let function = ecx.profiled_function();
+        if let Some(profile) = &mut ecx.machine.profile {
+            profile.record(function, |stats| stats.allocated_bytes += size.bytes());
+        }
+        Ok(())
+    }
+
     fn increment_const_eval_counter(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
+        let function = ecx.profiled_function();
+        if let Some(profile) = &mut ecx.machine.profile {
+            profile.record(function, |stats| stats.steps += 1);
+        }
+
         // The step limit has already been hit in a previous call to `increment_const_eval_counter`.
 
         if let Some(new_steps) = ecx.machine.num_evaluated_steps.checked_add(1) {
@@ -673,6 +711,12 @@ fn init_frame_extra(
         if !ecx.recursion_limit.value_within_limit(ecx.stack().len() + 1) {
             throw_exhaust!(StackFrameLimitReached)
         } else {
+            // The first frame is the one of the global being evaluated, not a call.
+            if let Some(profile) = &mut ecx.machine.profile
+                && !ecx.machine.stack.is_empty()
+            {
+                profile.record(Some(frame.instance), |stats| stats.calls += 1);
+            }
             Ok(frame)
         }
     }
@@ -13,6 +13,12 @@
 //! (In principle, we could skip this type-based part for `const` and promoteds, as they need to be
 //! always immutable. At least for `const` however we use this opportunity to reject any `const`
 //! that contains allocations whose mutability we cannot identify.)
+//!
+//! A `const` may also leak memory it allocated on the const heap, e.g. to build a lookup table.
+//! Such memory is interned as immutable like everything else in a `const`, as long as the types
+//! say that it is not meant to be mutated: it must be reachable through shared references, and
+//! through `*const` pointers that are themselves behind a shared reference and not inside an
+//! `UnsafeCell`. Heap memory that is only reachable in other ways is rejected.
 
 use super::validity::RefTracking;
 use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
@@ -64,8 +70,20 @@ enum InternMode {
     /// this is *immutable*, and below mutable references inside an `UnsafeCell`, this
     /// is *mutable*.
     Static(hir::Mutability),
-    /// A `const`.
-    Const,
+    /// A `const`, and how the memory we are looking at was reached.
+    Const(ConstReach),
+}
+
+/// How memory of a `const` was reached by the type-based traversal. Only ever goes up.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum ConstReach {
+    /// The value of the `const` itself.
+    Value,
+    /// Memory behind a shared reference.
+    SharedRef,
+    /// Const heap memory behind a `*const` pointer. Validation does not look at it, so problems
+    /// encountered while interning it must be reported right away.
+    RawPtr,
 }
 
 /// Signalling data structure to ensure we don't recurse
@@ -152,6 +170,32 @@ fn intern_shallow(
     ) -> Option<IsStaticOrFn> {
         intern_shallow(self.ecx, self.leftover_allocations, alloc_id, mode, ty)
     }
+
+    /// Interns the pointee of the `*const` pointer stored in `mplace` like the pointee of a shared
+    /// reference, if it is a value in a live const heap allocation. Anything else is left to the
+    /// `leftover_allocations` logic, which rejects it.
+    fn intern_const_heap_pointee(&mut self, mplace: &MPlaceTy<'tcx>) {
+        let Ok(value) = self.ecx.read_immediate(mplace) else { return };
+        let Ok(pointee) = self.ecx.ref_to_mplace(&value) else { return };
+        let Some(alloc_id) = pointee.ptr().provenance else { return };
+        let Some((kind, _)) = self.ecx.memory.alloc_map.get(&alloc_id) else { return };
+        if *kind != MemoryKind::Machine(const_eval::MemoryKind::Heap) {
+            return;
+        }
+        // Unlike references, raw pointers may point to memory that does not fit their pointee, so
+        // make sure the pointee is in bounds before looking at it.
+        let in_bounds = match self.ecx.size_and_align_of_mplace(&pointee) {
+            Ok(Some((size, align))) => self.ecx.get_ptr_alloc(pointee.ptr(), size, align).is_ok(),
+            _ => false,
+        };
+        if !in_bounds {
+            return;
+        }
+        let mode = InternMode::Const(ConstReach::RawPtr);
+        if self.intern_shallow(alloc_id, mode, Some(pointee.layout.ty)).is_none() {
+            self.ref_tracking.track((pointee, mode), || ());
+        }
+    }
 }
 
 impl<'rt, 'mir, 'tcx: 'mir, M: CompileTimeMachine<'mir, 'tcx, const_eval::MemoryKind>>
@@ -166,7 +210,8 @@ fn ecx(&self) -> &InterpCx<'mir, 'tcx, M> {
 
     fn visit_value(&mut self, mplace: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
         // Handle Reference types, as these are the only types with provenance supported by const eval.
-        // Raw pointers (and boxes) are handled by the `leftover_allocations` logic.
+        // Raw pointers (and boxes) are handled by the `leftover_allocations` logic, except for
+        // `*const` pointers to the const heap in a `const`, which are handled below.
         let tcx = self.ecx.tcx;
         let ty = mplace.layout.ty;
         if let ty::Ref(_, referenced_ty, ref_mutability) = *ty.kind() {
@@ -181,7 +226,7 @@ fn visit_value(&mut self, mplace: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
                 if let Some(alloc_id) = ptr.provenance {
                     // Explicitly choose const mode here, since vtables are immutable, even
                     // if the reference of the fat pointer is mutable.
-                    self.intern_shallow(alloc_id, InternMode::Const, None);
+                    self.intern_shallow(alloc_id, InternMode::Const(ConstReach::Value), None);
                 } else {
                     // Validation will error (with a better message) on an invalid vtable pointer.
                     // Let validation show the error message, but make sure it *does* error.
@@ -227,11 +272,14 @@ fn visit_value(&mut self, mplace: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
                             }
                         }
                     }
-                    InternMode::Const => {
+                    InternMode::Const(reach) => {
                         // Ignore `UnsafeCell`, everything is immutable. Validity does some sanity
                         // checking for mutable references that we encounter -- they must all be
                         // ZST.
-                        InternMode::Const
+                        match ref_mutability {
+                            Mutability::Not => InternMode::Const(reach.max(ConstReach::SharedRef)),
+                            Mutability::Mut => InternMode::Const(reach),
+                        }
                     }
                 };
                 match self.intern_shallow(alloc_id, ref_mode, Some(referenced_ty)) {
@@ -245,6 +293,13 @@ fn visit_value(&mut self, mplace: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
                 }
             }
             Ok(())
+        } else if let ty::RawPtr(ty::TypeAndMut { mutbl: Mutability::Not, .. }) = *ty.kind()
+            && let InternMode::Const(reach) = self.mode
+            && reach >= ConstReach::SharedRef
+            && !self.inside_unsafe_cell
+        {
+            self.intern_const_heap_pointee(mplace);
+            Ok(())
         } else {
             // Not a reference. Check if we want to recurse.
             let is_walk_needed = |mplace: &MPlaceTy<'tcx>| -> InterpResult<'tcx, bool> {
@@ -336,7 +391,7 @@ pub fn intern_const_alloc_recursive<
     let base_intern_mode = match intern_kind {
         InternKind::Static(mutbl) => InternMode::Static(mutbl),
         // `Constant` includes array lengths.
-        InternKind::Constant | InternKind::Promoted => InternMode::Const,
+        InternKind::Constant | InternKind::Promoted => InternMode::Const(ConstReach::Value),
     };
 
     // Type based interning.
@@ -371,9 +426,13 @@ pub fn intern_const_alloc_recursive<
         .visit_value(&mplace);
         // We deliberately *ignore* interpreter errors here. When there is a problem, the remaining
         // references are "leftover"-interned, and later validation will show a proper error
-        // and point at the right part of the value causing the problem.
+        // and point at the right part of the value causing the problem. Validation does not look
+        // behind raw pointers though, so we have to report problems there ourselves.
         match res {
             Ok(()) => {}
+            Err(_) if mode == InternMode::Const(ConstReach::RawPtr) => {
+                ecx.tcx.sess.emit_err(UnsupportedUntypedPointer { span: ecx.tcx.span });
+            }
             Err(error) => {
                 ecx.tcx.sess.delay_span_bug(
                     ecx.tcx.span,
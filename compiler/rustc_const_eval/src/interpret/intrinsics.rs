@@ -154,6 +154,11 @@ pub fn emulate_intrinsic(
                 self.write_scalar(Scalar::from_target_usize(result, self), dest)?;
             }
 
+            sym::ub_checks => {
+                let ub_checks = M::ub_checks(self)?;
+                self.write_scalar(Scalar::from_bool(ub_checks), dest)?;
+            }
+
             sym::pref_align_of
             | sym::needs_drop
             | sym::type_id
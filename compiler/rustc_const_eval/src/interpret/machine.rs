@@ -158,6 +158,9 @@ fn enforce_abi(_ecx: &InterpCx<'mir, 'tcx, Self>) -> bool {
     /// check for overflow.
     fn ignore_optional_overflow_checks(_ecx: &InterpCx<'mir, 'tcx, Self>) -> bool;
 
+    /// Determines the result of a `NullaryOp::UbChecks` invocation.
+    fn ub_checks(_ecx: &InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx, bool>;
+
     /// Entry point for obtaining the MIR of anything that should get evaluated.
     /// So not just functions and shims, but also const/static initializers, anonymous
     /// constants, ...
@@ -255,6 +258,12 @@ fn before_access_local_mut<'a>(
         Ok(())
     }
 
+    /// Called before a statement is executed.
+    #[inline]
+    fn before_statement(_ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
+        Ok(())
+    }
+
     /// Called before a basic block terminator is executed.
     #[inline]
     fn before_terminator(_ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
@@ -388,6 +397,16 @@ fn before_memory_write(
         Ok(())
     }
 
+    /// Called after a new allocation of `size` bytes was created in the machine's memory.
+    #[inline(always)]
+    fn after_memory_allocation(
+        _ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        _size: Size,
+        _kind: MemoryKind<Self::MemoryKind>,
+    ) -> InterpResult<'tcx> {
+        Ok(())
+    }
+
     /// Hook for performing extra operations on a memory deallocation.
     #[inline(always)]
     fn before_memory_deallocation(
@@ -232,8 +232,10 @@ pub fn allocate_raw_ptr(
             M::GLOBAL_KIND.map(MemoryKind::Machine),
             "dynamically allocating global memory"
         );
+        let size = alloc.size();
         let alloc = M::adjust_allocation(self, id, Cow::Owned(alloc), Some(kind))?;
         self.memory.alloc_map.insert(id, (kind, alloc.into_owned()));
+        M::after_memory_allocation(self, size, kind)?;
         M::adjust_alloc_base_pointer(self, Pointer::from(id))
     }
 
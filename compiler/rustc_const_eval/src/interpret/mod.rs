@@ -10,6 +10,7 @@
 mod operand;
 mod operator;
 mod place;
+mod profile;
 mod projection;
 mod step;
 mod terminator;
@@ -26,6 +27,7 @@
 pub use self::memory::{AllocKind, AllocRef, AllocRefMut, FnVal, Memory, MemoryKind};
 pub use self::operand::{ImmTy, Immediate, OpTy, Readable};
 pub use self::place::{MPlaceTy, MemPlaceMeta, PlaceTy, Writeable};
+pub use self::profile::InterpProfile;
 pub use self::projection::Projectable;
 pub use self::terminator::FnArg;
 pub use self::validity::{CtfeValidationMode, RefTracking};
@@ -0,0 +1,44 @@
+//! Bookkeeping for `-Z const-eval-profile`.
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_middle::ty;
+use rustc_session::code_stats::ConstEvalStats;
+
+use super::{InterpCx, Machine};
+
+/// The work done while evaluating a single global.
+#[derive(Default, Debug)]
+pub struct InterpProfile<'tcx> {
+    /// The work done in the whole evaluation.
+    pub total: ConstEvalStats,
+    /// The work done in the frames of each called function, not including the work done in the
+    /// functions it called in turn.
+    pub functions: FxIndexMap<ty::Instance<'tcx>, ConstEvalStats>,
+}
+
+impl<'tcx> InterpProfile<'tcx> {
+    /// Adds to the totals and to the stats of `function`, the function in whose frame the work
+    /// was done. `function` is `None` for the frame of the global itself.
+    pub fn record(
+        &mut self,
+        function: Option<ty::Instance<'tcx>>,
+        update: impl Fn(&mut ConstEvalStats),
+    ) {
+        update(&mut self.total);
+        if let Some(function) = function {
+            update(self.functions.entry(function).or_default());
+        }
+    }
+}
+
+impl<'mir, 'tcx: 'mir, M: Machine<'mir, 'tcx>> InterpCx<'mir, 'tcx, M> {
+    /// The function executing in the topmost frame, unless that is the frame of the global being
+    /// evaluated.
+    pub(crate) fn profiled_function(&self) -> Option<ty::Instance<'tcx>> {
+        if self.stack().len() > 1 {
+            Some(self.frame().instance)
+        } else {
+            None
+        }
+    }
+}
@@ -34,6 +34,7 @@ pub fn step(&mut self) -> InterpResult<'tcx, bool> {
 
         if let Some(stmt) = basic_block.statements.get(loc.statement_index) {
             let old_frames = self.frame_idx();
+            M::before_statement(self)?;
             self.statement(stmt)?;
             // Make sure we are not updating `statement_index` of the wrong frame.
             assert_eq!(old_frames, self.frame_idx());
@@ -275,13 +276,19 @@ pub fn eval_rvalue_into_place(
                     );
                 }
                 let val = match null_op {
-                    mir::NullOp::SizeOf => layout.size.bytes(),
-                    mir::NullOp::AlignOf => layout.align.abi.bytes(),
+                    mir::NullOp::SizeOf => Scalar::from_target_usize(layout.size.bytes(), self),
+                    mir::NullOp::AlignOf => {
+                        Scalar::from_target_usize(layout.align.abi.bytes(), self)
+                    }
                     mir::NullOp::OffsetOf(fields) => {
-                        layout.offset_of_subfield(self, fields.iter().map(|f| f.index())).bytes()
+                        let offset = layout
+                            .offset_of_subfield(self, fields.iter().map(|f| f.index()))
+                            .bytes();
+                        Scalar::from_target_usize(offset, self)
                     }
+                    mir::NullOp::UbChecks => Scalar::from_bool(M::ub_checks(self)?),
                 };
-                self.write_scalar(Scalar::from_target_usize(val, self), &dest)?;
+                self.write_scalar(val, &dest)?;
             }
 
             ShallowInitBox(ref operand, _) => {
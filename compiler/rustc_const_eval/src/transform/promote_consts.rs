@@ -516,6 +516,9 @@ fn validate_rvalue(&mut self, rvalue: &Rvalue<'tcx>) -> Result<(), Unpromotable>
                 NullOp::SizeOf => {}
                 NullOp::AlignOf => {}
                 NullOp::OffsetOf(_) => {}
+                // The value depends on the crate that the function is codegened in, so it must not
+                // be evaluated at compile-time.
+                NullOp::UbChecks => return Err(Unpromotable),
             },
 
             Rvalue::ShallowInitBox(_, _) => return Err(Unpromotable),
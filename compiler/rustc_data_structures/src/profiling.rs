@@ -83,8 +83,10 @@
 
 use crate::cold_path;
 use crate::fx::FxHashMap;
+use crate::sync::Lock;
 
 use std::borrow::Borrow;
+use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::error::Error;
 use std::fmt::Display;
@@ -170,19 +172,28 @@ pub struct SelfProfilerRef {
 
     // Print verbose generic activities to stderr.
     print_verbose_generic_activities: Option<TimePassesFormat>,
+
+    // The per-item timings collected for `-Z item-time-report`, if enabled.
+    item_timings: Option<Arc<ItemTimings>>,
 }
 
 impl SelfProfilerRef {
     pub fn new(
         profiler: Option<Arc<SelfProfiler>>,
         print_verbose_generic_activities: Option<TimePassesFormat>,
+        item_timings: Option<Arc<ItemTimings>>,
     ) -> SelfProfilerRef {
         // If there is no SelfProfiler then the filter mask is set to NONE,
         // ensuring that nothing ever tries to actually access it.
         let event_filter_mask =
             profiler.as_ref().map_or(EventFilter::empty(), |p| p.event_filter_mask);
 
-        SelfProfilerRef { profiler, event_filter_mask, print_verbose_generic_activities }
+        SelfProfilerRef {
+            profiler,
+            event_filter_mask,
+            print_verbose_generic_activities,
+            item_timings,
+        }
     }
 
     /// This shim makes sure that calls only get executed if the filter mask
@@ -474,6 +485,37 @@ fn instant_query_event(
         );
     }
 
+    /// Start profiling `activity` on a single item, e.g. type-checking a function. The time
+    /// until the returned guard is dropped, minus the time of item activities nested in it, is
+    /// attributed to `item` in the `-Z item-time-report`.
+    ///
+    /// This is also recorded as a generic activity, with `item` as its argument, so that the
+    /// same data is available in `-Z self-profile` traces.
+    #[inline(always)]
+    pub fn item_activity<F>(&self, activity: ItemActivity, item: F) -> ItemTimingGuard<'_>
+    where
+        F: FnOnce() -> String,
+    {
+        let Some(item_timings) = &self.item_timings else {
+            let mut item = Some(item);
+            let guard = self.generic_activity_with_arg_recorder(activity.event_label(), |r| {
+                r.record_arg(item.take().unwrap()())
+            });
+            return ItemTimingGuard { _timing: None, _guard: guard };
+        };
+        let item = item();
+        let guard = self.generic_activity_with_arg(activity.event_label(), &*item);
+        ItemTimingGuard {
+            _timing: Some(ItemTiming::start(item_timings, activity, item)),
+            _guard: guard,
+        }
+    }
+
+    /// The timings collected for `-Z item-time-report`, if it is enabled.
+    pub fn item_timings(&self) -> Option<&ItemTimings> {
+        self.item_timings.as_deref()
+    }
+
     pub fn with_profiler(&self, f: impl FnOnce(&SelfProfiler)) {
         if let Some(profiler) = &self.profiler {
             f(profiler)
@@ -763,6 +805,100 @@ fn drop(&mut self) {
     }
 }
 
+/// The compilation steps that `-Z item-time-report` attributes to individual items.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ItemActivity {
+    /// Type-checking a body, keyed by the path of its `DefId`.
+    Typeck,
+    /// Borrow-checking a body, keyed by the path of its `DefId`.
+    Borrowck,
+    /// Running the MIR optimization passes on a body, keyed by the path of its `DefId`.
+    MirOpt,
+    /// Lowering a mono item to the backend's IR, keyed by the mono item.
+    Codegen,
+    /// Optimizing and emitting a codegen unit in the backend, keyed by the codegen unit name.
+    Backend,
+}
+
+impl ItemActivity {
+    pub fn event_label(self) -> &'static str {
+        match self {
+            ItemActivity::Typeck => "item_typeck",
+            ItemActivity::Borrowck => "item_borrowck",
+            ItemActivity::MirOpt => "item_mir_opt",
+            ItemActivity::Codegen => "item_codegen",
+            ItemActivity::Backend => "item_backend",
+        }
+    }
+}
+
+/// The self time spent on each item, per [`ItemActivity`].
+#[derive(Default)]
+pub struct ItemTimings {
+    times: Lock<FxHashMap<(ItemActivity, String), Duration>>,
+}
+
+impl ItemTimings {
+    fn record(&self, activity: ItemActivity, item: String, time: Duration) {
+        *self.times.lock().entry((activity, item)).or_default() += time;
+    }
+
+    /// Returns the timings recorded so far and resets them.
+    pub fn take(&self) -> FxHashMap<(ItemActivity, String), Duration> {
+        std::mem::take(&mut *self.times.lock())
+    }
+}
+
+thread_local! {
+    // For each item activity running on this thread, the time spent in item
+    // activities nested in it.
+    static NESTED_ITEM_TIME: RefCell<Vec<Duration>> = RefCell::new(Vec::new());
+}
+
+struct ItemTiming<'a> {
+    timings: &'a ItemTimings,
+    activity: ItemActivity,
+    item: String,
+    start_time: Instant,
+}
+
+impl<'a> ItemTiming<'a> {
+    fn start(timings: &'a ItemTimings, activity: ItemActivity, item: String) -> Self {
+        NESTED_ITEM_TIME.with(|nested| nested.borrow_mut().push(Duration::ZERO));
+        ItemTiming { timings, activity, item, start_time: Instant::now() }
+    }
+}
+
+impl Drop for ItemTiming<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.start_time.elapsed();
+        let nested_time = NESTED_ITEM_TIME.with(|nested| {
+            let mut nested = nested.borrow_mut();
+            let nested_time = nested.pop().unwrap();
+            if let Some(parent) = nested.last_mut() {
+                *parent += elapsed;
+            }
+            nested_time
+        });
+        let item = std::mem::take(&mut self.item);
+        self.timings.record(self.activity, item, elapsed.saturating_sub(nested_time));
+    }
+}
+
+#[must_use]
+pub struct ItemTimingGuard<'a> {
+    _timing: Option<ItemTiming<'a>>,
+    _guard: TimingGuard<'a>,
+}
+
+impl ItemTimingGuard<'_> {
+    #[inline(always)]
+    pub fn run<R>(self, f: impl FnOnce() -> R) -> R {
+        let _timer = self;
+        f()
+    }
+}
+
 struct JsonTimePassesEntry<'a> {
     pass: &'a str,
     time: f64,
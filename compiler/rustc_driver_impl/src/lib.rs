@@ -20,7 +20,8 @@
 pub extern crate rustc_plugin_impl as plugin;
 
 use rustc_ast as ast;
-use rustc_codegen_ssa::{traits::CodegenBackend, CodegenErrors, CodegenResults};
+use rustc_codegen_ssa::traits::{print_json_document, CodegenBackend};
+use rustc_codegen_ssa::{CodegenErrors, CodegenResults};
 use rustc_data_structures::profiling::{
     get_resident_set_size, print_time_passes_entry, TimePassesFormat,
 };
@@ -35,13 +36,16 @@
 use rustc_lint::{unerased_lint_store, LintStore};
 use rustc_metadata::locator;
 use rustc_session::config::{nightly_options, CG_OPTIONS, Z_OPTIONS};
-use rustc_session::config::{ErrorOutputType, Input, OutFileName, OutputType, TrimmedDefPaths};
+use rustc_session::config::{
+    ErrorOutputType, Input, MirInliningFormat, OutFileName, OutputType, PrintFormat,
+    TrimmedDefPaths,
+};
 use rustc_session::cstore::MetadataLoader;
 use rustc_session::getopts::{self, Matches};
 use rustc_session::lint::{Lint, LintId};
 use rustc_session::{config, EarlyErrorHandler, Session};
 use rustc_span::source_map::{FileLoader, FileName};
-use rustc_span::symbol::sym;
+use rustc_span::symbol::{sym, Symbol};
 use rustc_target::json::ToJson;
 use rustc_target::spec::{Target, TargetTriple};
 
@@ -454,6 +458,18 @@ fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if sess.opts.unstable_opts.print_future_layouts {
+                sess.code_stats.print_future_layouts();
+            }
+
+            if sess.opts.unstable_opts.const_eval_profile {
+                sess.code_stats.print_const_eval_profile();
+            }
+
+            if let Some(format) = sess.opts.unstable_opts.print_mir_inlining {
+                print_mir_inlining(sess, format);
+            }
+
             if sess.opts.unstable_opts.print_vtable_sizes {
                 let crate_name =
                     compiler.session().opts.crate_name.as_deref().unwrap_or("<UNKNOWN_CRATE>");
@@ -486,6 +502,33 @@ fn run_compiler(
     })
 }
 
+/// Prints the callsites recorded by the MIR inliner for `-Z print-mir-inlining`.
+fn print_mir_inlining(sess: &Session, format: MirInliningFormat) {
+    match format {
+        MirInliningFormat::Text => sess.code_stats.print_mir_inlining(),
+        MirInliningFormat::Json => {
+            let callsites: Vec<_> = sess
+                .code_stats
+                .mir_inlining()
+                .into_iter()
+                .map(|info| {
+                    serde_json::json!({
+                        "caller": info.caller,
+                        "callee": info.callee,
+                        "span": info.span,
+                        "depth": info.depth,
+                        "inlined": info.rejection.is_none(),
+                        "reason": info.rejection,
+                        "cost": info.cost.map(|(cost, _)| cost),
+                        "threshold": info.cost.map(|(_, threshold)| threshold),
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::json!({ "callsites": callsites }));
+        }
+    }
+}
+
 // Extract output directory and file from matches.
 fn make_output(matches: &getopts::Matches) -> (Option<PathBuf>, Option<OutFileName>) {
     let odir = matches.opt_str("out-dir").map(|o| PathBuf::from(&o));
@@ -755,15 +798,28 @@ fn print_crate_info(
         macro println_info($($arg:tt)*) {
             crate_info.write_fmt(format_args!("{}\n", format_args!($($arg)*))).unwrap()
         }
+        let json = req.format == PrintFormat::Json;
+        macro println_json($($data:tt)+) {
+            println_info!("{}", print_json_document(req.kind, serde_json::json!($($data)+)))
+        }
 
         match req.kind {
             TargetList => {
                 let mut targets = rustc_target::spec::TARGETS.to_vec();
                 targets.sort_unstable();
-                println_info!("{}", targets.join("\n"));
+                if json {
+                    println_json!({ "targets": targets });
+                } else {
+                    println_info!("{}", targets.join("\n"));
+                }
             }
+            Sysroot if json => println_json!({ "path": sess.sysroot.to_string_lossy() }),
             Sysroot => println_info!("{}", sess.sysroot.display()),
+            TargetLibdir if json => {
+                println_json!({ "path": sess.target_tlib_path.dir.to_string_lossy() })
+            }
             TargetLibdir => println_info!("{}", sess.target_tlib_path.dir.display()),
+            TargetSpec if json => println_json!({ "target": sess.target.to_json() }),
             TargetSpec => {
                 println_info!("{}", serde_json::to_string_pretty(&sess.target.to_json()).unwrap());
             }
@@ -774,7 +830,11 @@ fn print_crate_info(
                     let target = Target::expect_builtin(&triple);
                     targets.insert(name, target.to_json());
                 }
-                println_info!("{}", serde_json::to_string_pretty(&targets).unwrap());
+                if json {
+                    println_json!({ "targets": targets });
+                } else {
+                    println_info!("{}", serde_json::to_string_pretty(&targets).unwrap());
+                }
             }
             FileNames => {
                 let Some(attrs) = attrs.as_ref() else {
@@ -784,10 +844,20 @@ fn print_crate_info(
                 let t_outputs = rustc_interface::util::build_output_filenames(attrs, sess);
                 let id = rustc_session::output::find_crate_name(sess, attrs);
                 let crate_types = collect_crate_types(sess, attrs);
-                for &style in &crate_types {
-                    let fname =
-                        rustc_session::output::filename_for_input(sess, style, id, &t_outputs);
-                    println_info!("{}", fname.as_path().file_name().unwrap().to_string_lossy());
+                let file_names: Vec<_> = crate_types
+                    .iter()
+                    .map(|&style| {
+                        let fname =
+                            rustc_session::output::filename_for_input(sess, style, id, &t_outputs);
+                        fname.as_path().file_name().unwrap().to_string_lossy().into_owned()
+                    })
+                    .collect();
+                if json {
+                    println_json!({ "file_names": file_names });
+                } else {
+                    for fname in file_names {
+                        println_info!("{fname}");
+                    }
                 }
             }
             CrateName => {
@@ -796,10 +866,14 @@ fn print_crate_info(
                     return Compilation::Continue;
                 };
                 let id = rustc_session::output::find_crate_name(sess, attrs);
-                println_info!("{id}");
+                if json {
+                    println_json!({ "crate_name": id.as_str() });
+                } else {
+                    println_info!("{id}");
+                }
             }
             Cfg => {
-                let mut cfgs = sess
+                let cfgs = sess
                     .parse_sess
                     .config
                     .iter()
@@ -819,23 +893,46 @@ fn print_crate_info(
                             return None;
                         }
 
-                        if let Some(value) = value {
-                            Some(format!("{name}=\"{value}\""))
-                        } else {
-                            Some(name.to_string())
-                        }
+                        Some((name, value))
                     })
-                    .collect::<Vec<String>>();
-
-                cfgs.sort();
-                for cfg in cfgs {
-                    println_info!("{cfg}");
+                    .collect::<Vec<_>>();
+
+                if json {
+                    let mut cfgs = cfgs
+                        .iter()
+                        .map(|(name, value)| (name.as_str(), value.as_ref().map(Symbol::as_str)))
+                        .collect::<Vec<_>>();
+                    cfgs.sort();
+                    let cfgs = cfgs
+                        .into_iter()
+                        .map(|(name, value)| serde_json::json!({ "name": name, "value": value }))
+                        .collect::<Vec<_>>();
+                    println_json!({ "cfg": cfgs });
+                } else {
+                    let mut cfgs = cfgs
+                        .into_iter()
+                        .map(|(name, value)| {
+                            if let Some(value) = value {
+                                format!("{name}=\"{value}\"")
+                            } else {
+                                name.to_string()
+                            }
+                        })
+                        .collect::<Vec<String>>();
+                    cfgs.sort();
+                    for cfg in cfgs {
+                        println_info!("{cfg}");
+                    }
                 }
             }
             CallingConventions => {
                 let mut calling_conventions = rustc_target::spec::abi::all_names();
                 calling_conventions.sort_unstable();
-                println_info!("{}", calling_conventions.join("\n"));
+                if json {
+                    println_json!({ "calling_conventions": calling_conventions });
+                } else {
+                    println_info!("{}", calling_conventions.join("\n"));
+                }
             }
             RelocationModels
             | CodeModels
@@ -851,8 +948,15 @@ fn print_crate_info(
             SplitDebuginfo => {
                 use rustc_target::spec::SplitDebuginfo::{Off, Packed, Unpacked};
 
-                for split in &[Off, Packed, Unpacked] {
-                    if sess.target.options.supported_split_debuginfo.contains(split) {
+                let splits = [Off, Packed, Unpacked]
+                    .into_iter()
+                    .filter(|split| sess.target.options.supported_split_debuginfo.contains(split))
+                    .map(|split| split.to_string())
+                    .collect::<Vec<_>>();
+                if json {
+                    println_json!({ "split_debuginfo": splits });
+                } else {
+                    for split in splits {
                         println_info!("{split}");
                     }
                 }
@@ -863,7 +967,11 @@ fn print_crate_info(
                 if sess.target.is_like_osx {
                     let (major, minor) = current_apple_deployment_target(&sess.target)
                         .expect("unknown Apple target OS");
-                    println_info!("deployment_target={}", format!("{major}.{minor}"))
+                    if json {
+                        println_json!({ "deployment_target": format!("{major}.{minor}") });
+                    } else {
+                        println_info!("deployment_target={}", format!("{major}.{minor}"))
+                    }
                 } else {
                     handler
                         .early_error("only Apple targets currently support deployment version info")
@@ -122,6 +122,10 @@ pub struct Diagnostic {
     /// otherwise hash is based on the all the fields
     pub is_lint: bool,
 
+    /// For lints, the path of the item they are emitted in, if known. `-Z lint-baseline` uses it
+    /// to tell apart identical lints in different items.
+    pub lint_item: Option<String>,
+
     /// With `-Ztrack_diagnostics` enabled,
     /// we print where in rustc this error was emitted.
     pub emitted_at: DiagnosticLocation,
@@ -229,6 +233,7 @@ pub fn new_with_messages(level: Level, messages: Vec<(DiagnosticMessage, Style)>
             args: Default::default(),
             sort_span: DUMMY_SP,
             is_lint: false,
+            lint_item: None,
             emitted_at: DiagnosticLocation::caller(),
         }
     }
@@ -249,6 +254,7 @@ pub fn new_with_code<M: Into<DiagnosticMessage>>(
             args: Default::default(),
             sort_span: DUMMY_SP,
             is_lint: false,
+            lint_item: None,
             emitted_at: DiagnosticLocation::caller(),
         }
     }
@@ -939,6 +945,11 @@ pub fn set_is_lint(&mut self) -> &mut Self {
         self
     }
 
+    pub fn set_lint_item(&mut self, item: String) -> &mut Self {
+        self.lint_item = Some(item);
+        self
+    }
+
     pub fn code(&mut self, s: DiagnosticId) -> &mut Self {
         self.code = Some(s);
         self
@@ -665,6 +665,7 @@ pub fn span_labels(
         msg: impl Into<SubdiagnosticMessage>,
     ) -> &mut Self);
     forward!(pub fn set_is_lint(&mut self,) -> &mut Self);
+    forward!(pub fn set_lint_item(&mut self, item: String) -> &mut Self);
 
     forward!(pub fn disable_suggestions(&mut self,) -> &mut Self);
     forward!(pub fn clear_suggestions(&mut self,) -> &mut Self);
@@ -0,0 +1,256 @@
+//! Batch application of `MachineApplicable` suggestions for `-Z fix-suggestions`.
+//!
+//! While the flag is enabled, the [`Handler`](crate::Handler) records the parts of every
+//! emitted lint suggestion that is marked [`Applicability::MachineApplicable`]. At the end
+//! of the session these are resolved into a set of non-overlapping edits per source file,
+//! which the caller can then write back to disk or render as a unified diff.
+//!
+//! Like `rustfix`, a suggestion is applied either entirely or not at all: if any of its
+//! parts overlaps an edit from a suggestion that was accepted earlier, the whole
+//! suggestion is skipped. Running the compiler again will pick up the skipped
+//! suggestions once the conflicting code has been fixed.
+
+use crate::{Diagnostic, DiagnosticId, SubstitutionPart};
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_lint_defs::Applicability;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, SourceFileHash};
+use std::fmt::Write;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// A machine-applicable suggestion recorded by the `Handler`.
+#[derive(Clone, Debug)]
+pub struct CollectedSuggestion {
+    /// The name of the lint whose diagnostic carried the suggestion, as in
+    /// [`DiagnosticId::Lint`].
+    pub lint_name: String,
+    pub parts: Vec<SubstitutionPart>,
+}
+
+impl CollectedSuggestion {
+    /// Returns the machine-applicable suggestions of `diag`, if it is a lint.
+    ///
+    /// Suggestions offering several alternative substitutions are ambiguous and
+    /// never collected.
+    pub(crate) fn from_diagnostic(diag: &Diagnostic) -> impl Iterator<Item = Self> + '_ {
+        let lint_name = match &diag.code {
+            Some(DiagnosticId::Lint { name, .. }) => Some(name),
+            _ => None,
+        };
+        lint_name.into_iter().flat_map(move |lint_name| {
+            diag.suggestions
+                .iter()
+                .flatten()
+                .filter(|sugg| {
+                    sugg.applicability == Applicability::MachineApplicable
+                        && sugg.substitutions.len() == 1
+                })
+                .map(move |sugg| CollectedSuggestion {
+                    lint_name: lint_name.clone(),
+                    parts: sugg.substitutions[0].parts.clone(),
+                })
+        })
+    }
+}
+
+/// A single replacement, in byte offsets into the file as it is on disk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Edit {
+    range: Range<usize>,
+    snippet: String,
+}
+
+impl Edit {
+    fn conflicts_with(&self, other: &Edit) -> bool {
+        if self.range.is_empty() && other.range.is_empty() {
+            // Two different insertions at the same position have no well-defined order.
+            return self.range.start == other.range.start;
+        }
+        self.range.start < other.range.end && other.range.start < self.range.end
+    }
+}
+
+/// The accepted edits for one source file.
+pub struct FileEdits {
+    /// The local path of the file.
+    pub path: PathBuf,
+    /// The hash of the file contents the edits were computed against. Callers should
+    /// check it against the current contents before applying the edits.
+    pub src_hash: SourceFileHash,
+    /// Sorted by position.
+    edits: Vec<Edit>,
+}
+
+/// The result of [`resolve_suggestions`].
+pub struct ResolvedSuggestions {
+    pub files: Vec<FileEdits>,
+    /// The number of suggestions that will be applied.
+    pub applied: usize,
+    /// The number of suggestions that were dropped because they conflict with an
+    /// accepted suggestion or do not point into a local source file.
+    pub skipped: usize,
+}
+
+/// Resolves `suggestions`, in emission order, into non-overlapping edits per file.
+pub fn resolve_suggestions(
+    sm: &SourceMap,
+    suggestions: &[CollectedSuggestion],
+) -> ResolvedSuggestions {
+    let mut files: FxIndexMap<PathBuf, (SourceFileHash, Vec<Edit>)> = FxIndexMap::default();
+    let mut applied = 0;
+    let mut skipped = 0;
+
+    'suggestions: for suggestion in suggestions {
+        let mut new_edits = Vec::with_capacity(suggestion.parts.len());
+        for part in &suggestion.parts {
+            match resolve_part(sm, part) {
+                Some(edit) => new_edits.push(edit),
+                None => {
+                    skipped += 1;
+                    continue 'suggestions;
+                }
+            }
+        }
+
+        // The same lint may fire more than once on the same code, e.g. for code that is
+        // compiled for several `cfg`s or reached through several macro invocations.
+        let mut is_duplicate = true;
+        for (path, _, edit) in &new_edits {
+            let existing = files.get(path).map_or(&[][..], |(_, edits)| &edits[..]);
+            if existing.contains(edit) {
+                continue;
+            }
+            is_duplicate = false;
+            if existing.iter().any(|other| other.conflicts_with(edit)) {
+                skipped += 1;
+                continue 'suggestions;
+            }
+        }
+        if is_duplicate {
+            continue;
+        }
+
+        for (path, src_hash, edit) in new_edits {
+            let (_, edits) = files.entry(path).or_insert_with(|| (src_hash, Vec::new()));
+            if !edits.contains(&edit) {
+                edits.push(edit);
+            }
+        }
+        applied += 1;
+    }
+
+    let files = files
+        .into_iter()
+        .map(|(path, (src_hash, mut edits))| {
+            edits.sort_by_key(|edit| (edit.range.start, edit.range.end));
+            FileEdits { path, src_hash, edits }
+        })
+        .collect();
+    ResolvedSuggestions { files, applied, skipped }
+}
+
+fn resolve_part(
+    sm: &SourceMap,
+    part: &SubstitutionPart,
+) -> Option<(PathBuf, SourceFileHash, Edit)> {
+    // Spans inside macro expansions point at the macro definition (or nowhere at all),
+    // rewriting those would change every use of the macro.
+    if part.span.from_expansion() {
+        return None;
+    }
+    let lo = sm.lookup_byte_offset(part.span.lo());
+    let hi = sm.lookup_byte_offset(part.span.hi());
+    if !Lrc::ptr_eq(&lo.sf, &hi.sf) {
+        return None;
+    }
+    let sf = lo.sf;
+    let FileName::Real(real_name) = &sf.name else {
+        return None;
+    };
+    let path = real_name.local_path()?.to_path_buf();
+    let start = sf.original_relative_byte_pos(part.span.lo()).0 as usize;
+    let end = sf.original_relative_byte_pos(part.span.hi()).0 as usize;
+    Some((path, sf.src_hash, Edit { range: start..end, snippet: part.snippet.clone() }))
+}
+
+impl FileEdits {
+    /// Returns `src` with all edits applied.
+    pub fn apply(&self, src: &str) -> String {
+        apply_edits(src, 0, &self.edits)
+    }
+
+    /// Renders the edits as a unified diff without context lines, suitable for `patch`
+    /// (or `git apply --unidiff-zero`).
+    pub fn unified_diff(&self, src: &str) -> String {
+        let line_starts: Vec<usize> =
+            std::iter::once(0).chain(src.match_indices('\n').map(|(i, _)| i + 1)).collect();
+        // 0-based index of the line containing `offset`.
+        let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+
+        let path = self.path.display();
+        let mut out = format!("--- a/{path}\n+++ b/{path}\n");
+        // The difference in line count between the new and old file so far.
+        let mut line_delta: isize = 0;
+        let mut edits = &self.edits[..];
+        while let Some(first) = edits.first() {
+            // Group all edits touching the same lines into one hunk.
+            let first_line = line_of(first.range.start);
+            let mut last_line = line_of(first.range.end);
+            let mut len = 1;
+            while let Some(next) = edits.get(len)
+                && line_of(next.range.start) <= last_line
+            {
+                last_line = last_line.max(line_of(next.range.end));
+                len += 1;
+            }
+            let (hunk, rest) = edits.split_at(len);
+            edits = rest;
+
+            let old_start = line_starts[first_line];
+            let old_end = line_starts.get(last_line + 1).copied().unwrap_or(src.len());
+            let old = &src[old_start..old_end];
+            let new = apply_edits(old, old_start, hunk);
+            let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+            let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+            let old_first = first_line as isize + 1;
+            let new_first = old_first + line_delta;
+            // By convention, an empty range starts at the line *before* the hunk.
+            let new_first = if new_lines.is_empty() { new_first - 1 } else { new_first };
+            line_delta += new_lines.len() as isize - old_lines.len() as isize;
+
+            writeln!(
+                out,
+                "@@ -{old_first},{} +{new_first},{} @@",
+                old_lines.len(),
+                new_lines.len()
+            )
+            .unwrap();
+            for (prefix, lines) in [('-', &old_lines), ('+', &new_lines)] {
+                for line in lines.iter() {
+                    out.push(prefix);
+                    out.push_str(line);
+                    if !line.ends_with('\n') {
+                        out.push_str("\n\\ No newline at end of file\n");
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Applies the sorted `edits` to `src`, which starts at byte `base` of the file.
+fn apply_edits(src: &str, base: usize, edits: &[Edit]) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut pos = 0;
+    for edit in edits {
+        out.push_str(&src[pos..edit.range.start - base]);
+        out.push_str(&edit.snippet);
+        pos = edit.range.end - base;
+    }
+    out.push_str(&src[pos..]);
+    out
+}
@@ -31,6 +31,8 @@
 use Level::*;
 
 use emitter::{is_case_difference, DynEmitter, Emitter, EmitterWriter};
+use fix_suggestions::CollectedSuggestion;
+use lint_baseline::LintBaseline;
 use registry::Registry;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap, FxIndexSet};
 use rustc_data_structures::stable_hasher::{Hash128, StableHasher};
@@ -65,10 +67,13 @@
 mod diagnostic_impls;
 pub mod emitter;
 pub mod error;
+pub mod fix_suggestions;
 pub mod json;
+pub mod lint_baseline;
 mod lock;
 pub mod markdown;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 #[cfg(test)]
@@ -461,6 +466,12 @@ struct HandlerInner {
 
     future_breakage_diagnostics: Vec<Diagnostic>,
 
+    /// Suggestions recorded for `-Z fix-suggestions`, in emission order.
+    machine_applicable_suggestions: Vec<CollectedSuggestion>,
+
+    /// Lints that are not reported because they are recorded in the `-Z lint-baseline` file.
+    lint_baseline: Option<LintBaseline>,
+
     /// The [`Self::unstable_expect_diagnostics`] should be empty when this struct is
     /// dropped. However, it can have values if the compilation is stopped early
     /// or is only partially executed. To avoid ICEs, like in rust#94953 we only
@@ -535,6 +546,9 @@ pub struct HandlerFlags {
     pub deduplicate_diagnostics: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`.
     pub track_diagnostics: bool,
+    /// If true, record the machine-applicable suggestions of emitted lints.
+    /// (rustc: see `-Z fix-suggestions`)
+    pub collect_machine_applicable_suggestions: bool,
 }
 
 impl Drop for HandlerInner {
@@ -596,6 +610,11 @@ pub fn with_ice_file(mut self, ice_file: PathBuf) -> Self {
         self
     }
 
+    pub fn with_lint_baseline(mut self, lint_baseline: LintBaseline) -> Self {
+        self.inner.get_mut().lint_baseline = Some(lint_baseline);
+        self
+    }
+
     pub fn with_emitter(emitter: Box<DynEmitter>) -> Self {
         Self {
             inner: Lock::new(HandlerInner {
@@ -614,6 +633,8 @@ pub fn with_emitter(emitter: Box<DynEmitter>) -> Self {
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
                 future_breakage_diagnostics: Vec::new(),
+                machine_applicable_suggestions: Vec::new(),
+                lint_baseline: None,
                 check_unstable_expect_diagnostics: false,
                 unstable_expect_diagnostics: Vec::new(),
                 fulfilled_expectations: Default::default(),
@@ -1103,6 +1124,15 @@ pub fn take_future_breakage_diagnostics(&self) -> Vec<Diagnostic> {
         std::mem::take(&mut self.inner.borrow_mut().future_breakage_diagnostics)
     }
 
+    pub fn take_machine_applicable_suggestions(&self) -> Vec<CollectedSuggestion> {
+        std::mem::take(&mut self.inner.borrow_mut().machine_applicable_suggestions)
+    }
+
+    /// Renders the lints emitted so far for the `-Z lint-baseline` file, if a baseline is in use.
+    pub fn serialize_lint_baseline(&self) -> Option<String> {
+        self.inner.borrow().lint_baseline.as_ref().map(LintBaseline::serialize)
+    }
+
     pub fn abort_if_errors(&self) {
         self.inner.borrow_mut().abort_if_errors()
     }
@@ -1370,6 +1400,15 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
             return None;
         }
 
+        if diagnostic.is_lint
+            && !diagnostic.is_force_warn()
+            && let Some(lint_baseline) = &mut self.lint_baseline
+            && lint_baseline.record(diagnostic, self.emitter.source_map().map(|sm| &**sm))
+        {
+            (*TRACK_DIAGNOSTICS)(diagnostic, &mut |_| {});
+            return None;
+        }
+
         let mut guaranteed = None;
         (*TRACK_DIAGNOSTICS)(diagnostic, &mut |diagnostic| {
             if let Some(ref code) = diagnostic.code {
@@ -1402,6 +1441,11 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
 
                 diagnostic.children.extract_if(already_emitted_sub).for_each(|_| {});
 
+                if self.flags.collect_machine_applicable_suggestions {
+                    self.machine_applicable_suggestions
+                        .extend(CollectedSuggestion::from_diagnostic(diagnostic));
+                }
+
                 self.emitter.emit_diagnostic(diagnostic);
                 if diagnostic.is_error() {
                     self.deduplicated_err_count += 1;
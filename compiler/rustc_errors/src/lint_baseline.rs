@@ -0,0 +1,129 @@
+//! Filtering of lints against a `-Z lint-baseline` file.
+//!
+//! A baseline records the lints a crate emitted at some point in time. While one is
+//! installed, the [`Handler`](crate::Handler) checks every lint diagnostic against it
+//! before emitting it, and drops the ones that are recorded. Doing this at emission
+//! covers all lints alike, including early lints and the ones replayed from the
+//! incremental cache. Lints carry the path of the item they are emitted in, see
+//! [`Diagnostic::lint_item`], so that identical lints in different items are counted
+//! separately.
+
+use crate::{Diagnostic, DiagnosticId};
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::stable_hasher::{Hash64, StableHasher};
+use rustc_span::source_map::SourceMap;
+use std::hash::Hash;
+
+/// Identifies a lint emission recorded in a baseline.
+///
+/// The key deliberately does not contain the position of the lint, so that
+/// unrelated edits moving code around do not invalidate the baseline.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct LintBaselineKey {
+    /// The name of the lint, as in [`DiagnosticId::Lint`].
+    pub lint: String,
+    /// The path of the item the lint is emitted in, as in [`Diagnostic::lint_item`]. It is empty
+    /// if the item is unknown.
+    pub item: String,
+    /// A hash of the source code the lint points at, ignoring whitespace.
+    pub fingerprint: u64,
+}
+
+impl LintBaselineKey {
+    /// Returns the key of `diag`, if it is a lint.
+    fn from_diagnostic(diag: &Diagnostic, source_map: Option<&SourceMap>) -> Option<Self> {
+        let Some(DiagnosticId::Lint { name, .. }) = &diag.code else {
+            return None;
+        };
+        let snippet = diag
+            .span
+            .primary_span()
+            .zip(source_map)
+            .and_then(|(span, sm)| sm.span_to_snippet(span.source_callsite()).ok())
+            .unwrap_or_default();
+        let mut hasher = StableHasher::new();
+        for word in snippet.split_whitespace() {
+            word.hash(&mut hasher);
+        }
+        let fingerprint: Hash64 = hasher.finish();
+        Some(LintBaselineKey {
+            lint: name.clone(),
+            item: diag.lint_item.clone().unwrap_or_default(),
+            fingerprint: fingerprint.as_u64(),
+        })
+    }
+}
+
+/// The lints recorded in a baseline, and the ones emitted during this session.
+#[derive(Debug, Default)]
+pub struct LintBaseline {
+    /// How many more emissions of each key are covered by the baseline.
+    remaining: FxHashMap<LintBaselineKey, usize>,
+    /// Every emission seen during this session, used by `-Z update-lint-baseline`.
+    seen: FxHashMap<LintBaselineKey, usize>,
+    /// Whether the baseline is being rewritten. All lints are covered in that case.
+    update: bool,
+}
+
+impl LintBaseline {
+    const HEADER: &'static str = "# rustc lint baseline v1";
+
+    /// Creates an empty baseline, which is only useful when rewriting it.
+    pub fn for_update() -> Self {
+        LintBaseline { update: true, ..Default::default() }
+    }
+
+    /// Parses the contents of a baseline file, as written by [`LintBaseline::serialize`].
+    ///
+    /// On failure, returns the 1-based number of the malformed line.
+    pub fn parse(src: &str) -> Result<Self, usize> {
+        let mut remaining = FxHashMap::default();
+        for (i, line) in src.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parse_line = || {
+                let mut fields = line.split('\t');
+                let lint = fields.next()?.to_owned();
+                let count = fields.next()?.parse().ok()?;
+                let fingerprint = u64::from_str_radix(fields.next()?, 16).ok()?;
+                let item = fields.next()?.to_owned();
+                let key = LintBaselineKey { lint, item, fingerprint };
+                fields.next().is_none().then_some((key, count))
+            };
+            let (key, count) = parse_line().ok_or(i + 1)?;
+            *remaining.entry(key).or_default() += count;
+        }
+        Ok(LintBaseline { remaining, ..Default::default() })
+    }
+
+    /// Records an emission of `diag`, returning whether it is a lint covered by the baseline.
+    pub(crate) fn record(&mut self, diag: &Diagnostic, source_map: Option<&SourceMap>) -> bool {
+        let Some(key) = LintBaselineKey::from_diagnostic(diag, source_map) else {
+            return false;
+        };
+        let covered = self.update
+            || match self.remaining.get_mut(&key) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    true
+                }
+                _ => false,
+            };
+        *self.seen.entry(key).or_default() += 1;
+        covered
+    }
+
+    /// Renders every emission seen so far in the format understood by [`LintBaseline::parse`].
+    ///
+    /// Entries are sorted so that the file can be checked into version control and diffed.
+    pub fn serialize(&self) -> String {
+        let mut entries: Vec<_> = self.seen.iter().collect();
+        entries.sort();
+        let mut out = format!("{}\n", Self::HEADER);
+        for (LintBaselineKey { lint, item, fingerprint }, count) in entries {
+            out.push_str(&format!("{lint}\t{count}\t{fingerprint:016x}\t{item}\n"));
+        }
+        out
+    }
+}
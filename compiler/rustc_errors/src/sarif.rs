@@ -0,0 +1,475 @@
+//! A [SARIF] emitter for errors.
+//!
+//! Unlike the JSON emitter, which writes one object per diagnostic as soon as
+//! it is emitted, SARIF describes a whole analysis run as a single log. This
+//! emitter therefore buffers every diagnostic it receives and writes the log
+//! once, when it is dropped at the end of the compilation session.
+//!
+//! Error codes and lint names become SARIF rules, primary spans become result
+//! locations, secondary spans and child diagnostics (notes and helps) become
+//! related locations, and code suggestions become SARIF fixes.
+//!
+//! The subset of SARIF we emit follows version 2.1.0 of the specification.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use rustc_span::source_map::SourceMap;
+use rustc_span::{Span, DUMMY_SP};
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::translation::{to_fluent_args, Translate};
+use crate::{
+    CodeSuggestion, DiagnosticId, FluentBundle, LazyFallbackBundle, Level, SubstitutionPart,
+};
+use rustc_lint_defs::Applicability;
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::{IntoDynSyncSend, Lrc};
+use rustc_error_messages::FluentArgs;
+use std::error::Report;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+const SARIF_VERSION: &str = "2.1.0";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+pub struct SarifEmitter {
+    dst: IntoDynSyncSend<Box<dyn Write + Send>>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    fluent_bundle: Option<Lrc<FluentBundle>>,
+    fallback_bundle: LazyFallbackBundle,
+    ui_testing: bool,
+    /// Every error code or lint name seen so far, in order of first appearance.
+    /// The index of a rule in this map is the `ruleIndex` of its results.
+    rules: FxIndexMap<String, ReportingDescriptor>,
+    results: Vec<SarifResult>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            registry,
+            source_map,
+            fluent_bundle,
+            fallback_bundle,
+        )
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst: IntoDynSyncSend(dst),
+            registry,
+            sm: source_map,
+            fluent_bundle,
+            fallback_bundle,
+            ui_testing: false,
+            rules: FxIndexMap::default(),
+            results: Vec::new(),
+        }
+    }
+
+    pub fn ui_testing(self, ui_testing: bool) -> Self {
+        Self { ui_testing, ..self }
+    }
+
+    /// Returns the index of the rule for `code`, registering it on first use.
+    fn rule_index(&mut self, code: &DiagnosticId) -> usize {
+        let (id, is_lint) = match code {
+            DiagnosticId::Error(s) => (s, false),
+            DiagnosticId::Lint { name, .. } => (name, true),
+        };
+        if let Some(index) = self.rules.get_index_of(id.as_str()) {
+            return index;
+        }
+
+        let explanation = if is_lint {
+            None
+        } else {
+            self.registry.as_ref().and_then(|registry| registry.try_find_description(id).ok())
+        };
+        let rule = ReportingDescriptor {
+            id: id.clone(),
+            full_description: explanation.map(|markdown| MultiformatMessageString {
+                text: None,
+                markdown: Some(markdown.trim().to_owned()),
+            }),
+            help_uri: explanation
+                .map(|_| format!("https://doc.rust-lang.org/error_codes/{id}.html")),
+            properties: RuleProperties { kind: if is_lint { "lint" } else { "error-code" } },
+        };
+        self.rules.insert_full(id.clone(), rule).0
+    }
+
+    fn location(&self, span: Span, message: Option<String>) -> Option<Location> {
+        if span.is_dummy() {
+            return message
+                .map(|text| Location { physical_location: None, message: Some(Message { text }) });
+        }
+        let span = span.source_callsite();
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let uri = self.sm.filename_for_diagnostics(&start.file.name).to_string().replace('\\', "/");
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        Some(Location {
+            physical_location: Some(PhysicalLocation {
+                artifact_location: ArtifactLocation { uri },
+                region: Region {
+                    start_line: start.line,
+                    start_column: start.col.0 + 1,
+                    end_line: end.line,
+                    end_column: end.col.0 + 1,
+                    byte_offset: byte_start,
+                    byte_length: byte_end - byte_start,
+                },
+            }),
+            message: message.map(|text| Message { text }),
+        })
+    }
+
+    /// Converts a suggestion into one SARIF fix per alternative substitution.
+    fn fixes(&self, suggestion: &CodeSuggestion, args: &FluentArgs<'_>) -> Vec<Fix> {
+        let description =
+            self.translate_message(&suggestion.msg, args).map_err(Report::new).unwrap().to_string();
+        suggestion
+            .substitutions
+            .iter()
+            .filter_map(|substitution| {
+                // SARIF groups replacements by the file they apply to.
+                let mut changes: FxIndexMap<String, Vec<Replacement>> = FxIndexMap::default();
+                for SubstitutionPart { span, snippet } in &substitution.parts {
+                    let Some(Location { physical_location: Some(loc), .. }) =
+                        self.location(*span, None)
+                    else {
+                        continue;
+                    };
+                    changes.entry(loc.artifact_location.uri).or_default().push(Replacement {
+                        deleted_region: loc.region,
+                        inserted_content: ArtifactContent { text: snippet.clone() },
+                    });
+                }
+                if changes.is_empty() {
+                    return None;
+                }
+                Some(Fix {
+                    description: Message { text: description.clone() },
+                    artifact_changes: changes
+                        .into_iter()
+                        .map(|(uri, replacements)| ArtifactChange {
+                            artifact_location: ArtifactLocation { uri },
+                            replacements,
+                        })
+                        .collect(),
+                    properties: FixProperties {
+                        applicability: applicability_str(suggestion.applicability),
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "rustc",
+                        // Keep UI test output stable across releases.
+                        version: if self.ui_testing { None } else { option_env!("CFG_RELEASE") },
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: std::mem::take(&mut self.rules).into_values().collect(),
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results: std::mem::take(&mut self.results),
+            }],
+        };
+        writeln!(&mut self.dst, "{}", serde_json::to_string_pretty(&log).unwrap())?;
+        self.dst.flush()
+    }
+}
+
+impl Translate for SarifEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.fluent_bundle.as_ref()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &self.fallback_bundle
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        let level = match diag.level {
+            Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error { .. } => "error",
+            Level::Warning(_) => "warning",
+            Level::Note | Level::OnceNote | Level::Help | Level::OnceHelp => "note",
+            // "aborting due to previous error" and friends describe the
+            // compilation session, not the analyzed code.
+            Level::FailureNote => return,
+            Level::Allow | Level::Expect(_) => return,
+        };
+
+        let args = to_fluent_args(diag.args());
+        let message = self.translate_messages(&diag.message, &args).to_string();
+        let (rule_id, rule_index) = match &diag.code {
+            Some(code) => {
+                let index = self.rule_index(code);
+                (Some(self.rules.get_index(index).unwrap().0.clone()), Some(index))
+            }
+            None => (None, None),
+        };
+
+        let primary_spans = diag.span.primary_spans();
+        let locations = if primary_spans.is_empty() {
+            vec![]
+        } else {
+            let primary_label = diag
+                .span
+                .span_labels()
+                .into_iter()
+                .find(|label| label.is_primary)
+                .and_then(|label| label.label)
+                .map(|label| self.translate_message(&label, &args).unwrap().to_string());
+            primary_spans
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &span)| {
+                    self.location(span, if i == 0 { primary_label.clone() } else { None })
+                })
+                .collect()
+        };
+
+        let secondary_labels = diag.span.span_labels().into_iter().filter(|l| !l.is_primary);
+        let children = diag.children.iter().map(|child| {
+            let level = child.level.to_str();
+            let text = self.translate_messages(&child.message, &args);
+            let span = child
+                .render_span
+                .as_ref()
+                .unwrap_or(&child.span)
+                .primary_span()
+                .unwrap_or(DUMMY_SP);
+            (span, Some(format!("{level}: {text}")))
+        });
+        let related_locations = secondary_labels
+            .map(|label| {
+                let text =
+                    label.label.map(|m| self.translate_message(&m, &args).unwrap().to_string());
+                (label.span, text)
+            })
+            .chain(children)
+            .filter_map(|(span, text)| self.location(span, text))
+            .collect();
+
+        let fixes = diag
+            .suggestions
+            .iter()
+            .flatten()
+            .flat_map(|suggestion| self.fixes(suggestion, &args))
+            .collect();
+
+        self.results.push(SarifResult {
+            rule_id,
+            rule_index,
+            level,
+            message: Message { text: message },
+            locations,
+            related_locations,
+            fixes,
+        });
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if let Err(e) = self.write_log() {
+            panic!("failed to print SARIF log: {e:?}");
+        }
+    }
+}
+
+fn applicability_str(applicability: Applicability) -> &'static str {
+    match applicability {
+        Applicability::MachineApplicable => "MachineApplicable",
+        Applicability::MaybeIncorrect => "MaybeIncorrect",
+        Applicability::HasPlaceholders => "HasPlaceholders",
+        Applicability::Unspecified => "Unspecified",
+    }
+}
+
+// The following data types are provided just for serialisation. They mirror
+// the object names used by the SARIF specification.
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    /// How `startColumn` and `endColumn` are counted; rustc counts characters.
+    column_kind: &'static str,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: ToolComponent,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent {
+    name: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'static str>,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    /// The error code (e.g. `E0308`) or lint name (e.g. `unused_variables`).
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<MultiformatMessageString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    properties: RuleProperties,
+}
+
+#[derive(Serialize)]
+struct RuleProperties {
+    /// Either `"error-code"` or `"lint"`.
+    kind: &'static str,
+}
+
+#[derive(Serialize)]
+struct MultiformatMessageString {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    markdown: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning" or "note".
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    physical_location: Option<PhysicalLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    /// 0-based, relative to the start of the original file.
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+struct FixProperties {
+    applicability: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
@@ -53,6 +53,7 @@
 use crate::expectation::Expectation;
 use crate::fn_ctxt::RawTy;
 use crate::gather_locals::GatherLocalsVisitor;
+use rustc_data_structures::profiling::ItemActivity;
 use rustc_data_structures::unord::UnordSet;
 use rustc_errors::{
     struct_span_err, DiagnosticId, DiagnosticMessage, ErrorGuaranteed, MultiSpan,
@@ -171,6 +172,8 @@ fn typeck_with_fallback<'tcx>(
         return tcx.typeck(typeck_root_def_id);
     }
 
+    let _timer = tcx.item_activity(ItemActivity::Typeck, def_id);
+
     let id = tcx.hir().local_def_id_to_hir_id(def_id);
     let node = tcx.hir().get(id);
     let span = tcx.hir().span(id);
@@ -0,0 +1,293 @@
+//! Export of the dependency graph for build systems, with `-Z export-dep-graph`.
+//!
+//! Build systems only know that a crate depends on the whole of its upstream crates, so they
+//! have to rebuild it whenever anything upstream changes. The dependency graph knows better: it
+//! records the fingerprint of every query result that was used, and the results of the queries
+//! about upstream items are exactly what a crate depends on from its dependencies.
+//!
+//! The export is written once the dependency graph has been saved, by reading the saved graph
+//! back, and lists
+//!
+//! - `upstream`: the fingerprints of the queries about the items of other crates that this crate
+//!   used, grouped by the `DefPathHash` of the item,
+//! - `provides`: the fingerprints of the queries about the items of this crate, in the same form,
+//!   so that they can be compared with the `upstream` entries of dependent crates, and whether
+//!   the items are reachable from other crates,
+//! - `upstream_crates`: the fingerprints of the queries about other crates as a whole, such as
+//!   the list of their exported items, and
+//! - `source_files`: the source files of this crate with their hashes.
+//!
+//! A dependent crate doesn't need to be rebuilt if its own source files didn't change and every
+//! entry of its `upstream` and `upstream_crates` is found with the same fingerprint in the
+//! `provides` of the new build of the crate defining the item. An entry that is not found counts
+//! as changed: some queries only exist for the items of other crates, like those decoding
+//! metadata that isn't the result of a query of the same name, and nothing tells whether their
+//! results changed. To find as many entries as possible, [`prepare_dep_graph_export`] runs the
+//! queries that dependent crates usually read about the reachable items.
+//!
+//! The queries that describe how a dependent crate loaded another one, like `used_crate_source`,
+//! are not exported: they only depend on the inputs of the dependent crate itself.
+//!
+//! The format is versioned: fields may be added without bumping `version`, but removing a field
+//! or changing the meaning of one bumps it. Everything is sorted, so that the same inputs always
+//! produce the same file.
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::stable_hasher::Hash64;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{CrateNum, DefId, DefPathHash, StableCrateId, LOCAL_CRATE};
+use rustc_middle::dep_graph::{
+    DepContext, DepKind, FingerprintStyle, SerializedDepGraph, SerializedDepNodeIndex,
+};
+use rustc_middle::middle::privacy::Level;
+use rustc_middle::ty::TyCtxt;
+use rustc_serialize::opaque::MemDecoder;
+use rustc_serialize::Decodable;
+use rustc_session::config::SwitchWithOptPath;
+use rustc_span::{FileName, SourceFileHashAlgorithm};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::file_format;
+
+/// The version of the export format.
+const VERSION: u32 = 2;
+
+/// The queries that describe how the local crate loaded an upstream crate.
+const LOADING_QUERIES: &[DepKind] = &[
+    DepKind::crate_extern_paths,
+    DepKind::dep_kind,
+    DepKind::extern_crate,
+    DepKind::extra_filename,
+    DepKind::is_private_dep,
+    DepKind::missing_extern_crate_item,
+    DepKind::used_crate_source,
+];
+
+/// The fingerprints of the queries about a single item, by query name.
+type Queries = BTreeMap<String, String>;
+
+/// What [`export_dep_graph`] needs to know about the crate, collected while queries can still
+/// run.
+pub struct DepGraphExport {
+    output_dir: PathBuf,
+    /// The `DefPathHash`es of the items of the local crate that are reachable from other crates.
+    reachable: FxHashSet<DefPathHash>,
+    /// The crates the exported queries can be about.
+    crates: FxHashMap<StableCrateId, CrateNum>,
+}
+
+/// Prepares the `-Z export-dep-graph` export, if enabled, by running the queries that dependent
+/// crates usually read about the reachable items of the crate, so that they are part of the saved
+/// dependency graph.
+///
+/// This runs queries, so it has to be called before [`save_dep_graph`](super::save_dep_graph).
+pub fn prepare_dep_graph_export(tcx: TyCtxt<'_>) -> Option<DepGraphExport> {
+    let SwitchWithOptPath::Enabled(output_dir) = &tcx.sess.opts.unstable_opts.export_dep_graph
+    else {
+        return None;
+    };
+    if tcx.sess.opts.incremental.is_none() || tcx.sess.has_errors_or_delayed_span_bugs().is_some() {
+        return None;
+    }
+
+    let mut reachable = FxHashSet::default();
+    for (&def_id, effective_vis) in tcx.effective_visibilities(()).iter() {
+        if !effective_vis.is_public_at_level(Level::Reachable) {
+            continue;
+        }
+        let def_id = def_id.to_def_id();
+        tcx.ensure().visibility(def_id);
+        tcx.ensure().def_span(def_id);
+        tcx.ensure().lookup_stability(def_id);
+        tcx.ensure().lookup_deprecation_entry(def_id);
+        match tcx.def_kind(def_id) {
+            DefKind::Fn | DefKind::AssocFn => {
+                tcx.ensure().fn_sig(def_id);
+                tcx.ensure().generics_of(def_id);
+                tcx.ensure().predicates_of(def_id);
+                tcx.ensure().codegen_fn_attrs(def_id);
+            }
+            DefKind::Struct
+            | DefKind::Enum
+            | DefKind::Union
+            | DefKind::TyAlias { .. }
+            | DefKind::Const
+            | DefKind::AssocConst
+            | DefKind::Static(_) => {
+                tcx.ensure().type_of(def_id);
+                tcx.ensure().generics_of(def_id);
+                tcx.ensure().predicates_of(def_id);
+            }
+            DefKind::Trait => {
+                tcx.ensure().generics_of(def_id);
+                tcx.ensure().predicates_of(def_id);
+            }
+            _ => {}
+        }
+        reachable.insert(tcx.def_path_hash(def_id));
+    }
+
+    let crates = tcx
+        .crates(())
+        .iter()
+        .chain([&LOCAL_CRATE])
+        .map(|&cnum| (tcx.stable_crate_id(cnum), cnum))
+        .collect();
+    Some(DepGraphExport {
+        output_dir: output_dir.clone().unwrap_or_else(|| PathBuf::from(".")),
+        reachable,
+        crates,
+    })
+}
+
+impl DepGraphExport {
+    pub(super) fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+}
+
+/// Reads back the dependency graph saved at `dep_graph_path` and writes the export to
+/// `<output_dir>/<crate>.dep_graph.json`.
+///
+/// This runs after the dependency graph has been saved, so it must not run any queries.
+pub(super) fn export_dep_graph(
+    tcx: TyCtxt<'_>,
+    dep_graph_path: &Path,
+    export: &DepGraphExport,
+) -> io::Result<()> {
+    let sess = tcx.sess;
+    let Some((data, start_pos)) =
+        file_format::read_file(dep_graph_path, false, sess.is_nightly_build(), sess.cfg_version)?
+    else {
+        return Err(io::ErrorKind::NotFound.into());
+    };
+    let mut decoder = MemDecoder::new(&data, start_pos);
+    let _commandline_args_hash = u64::decode(&mut decoder);
+    let graph = SerializedDepGraph::decode(&mut decoder);
+
+    let local_crate_id = tcx.stable_crate_id(LOCAL_CRATE);
+    let mut upstream: BTreeMap<DefPathHash, Queries> = BTreeMap::new();
+    let mut upstream_crates: BTreeMap<DefPathHash, Queries> = BTreeMap::new();
+    let mut provides: BTreeMap<DefPathHash, Queries> = BTreeMap::new();
+    for index in 0..graph.node_count() {
+        let index = SerializedDepNodeIndex::from_usize(index);
+        let node = graph.index_to_node(index);
+        if tcx.fingerprint_style(node.kind) != FingerprintStyle::DefPathHash {
+            continue;
+        }
+        let def_path_hash = DefPathHash(node.hash.into());
+        let items = if def_path_hash.stable_crate_id() == local_crate_id {
+            &mut provides
+        } else if node.kind == DepKind::crate_hash || LOADING_QUERIES.contains(&node.kind) {
+            // Every query about an upstream item depends on the hash of its crate, so that any
+            // change upstream is noticed. What changed is then told by the fingerprints of the
+            // queries themselves. The loading queries don't depend on the upstream crate at all.
+            continue;
+        } else if def_path_hash.local_hash() == Hash64::ZERO {
+            // Queries about a whole crate are keyed by its root module, whose local hash is zero.
+            &mut upstream_crates
+        } else {
+            &mut upstream
+        };
+        let fingerprint = graph.fingerprint_by_index(index);
+        items
+            .entry(def_path_hash)
+            .or_default()
+            .insert(format!("{:?}", node.kind), hex(fingerprint));
+    }
+
+    // The saved graph can refer to items that no longer exist, e.g. local items that were removed
+    // since the previous session and whose nodes were never executed again. These are exported
+    // without a `crate` and `path`.
+    let local_def_ids: FxHashMap<DefPathHash, DefId> = tcx
+        .iter_local_def_id()
+        .map(|def_id| (tcx.def_path_hash(def_id.to_def_id()), def_id.to_def_id()))
+        .collect();
+    let lookup_def_id = |def_path_hash: DefPathHash| {
+        if def_path_hash.stable_crate_id() == local_crate_id {
+            return local_def_ids.get(&def_path_hash).copied();
+        }
+        // The metadata of upstream crates doesn't change during the session, so the items of a
+        // loaded crate that the graph refers to exist.
+        let cnum = *export.crates.get(&def_path_hash.stable_crate_id())?;
+        Some(tcx.cstore_untracked().def_path_hash_to_def_id(cnum, def_path_hash))
+    };
+    let item_to_json = |def_path_hash: DefPathHash, queries: Queries| {
+        let mut item = serde_json::json!({
+            "def_path_hash": hex(def_path_hash.0),
+            "queries": queries,
+        });
+        if let Some(def_id) = lookup_def_id(def_path_hash) {
+            let crate_name = if def_id.is_local() {
+                tcx.crate_name(LOCAL_CRATE)
+            } else {
+                tcx.cstore_untracked().crate_name(def_id.krate)
+            };
+            let path = format!("{crate_name}{}", tcx.def_path(def_id).to_string_no_crate_verbose());
+            item["crate"] = crate_name.as_str().into();
+            item["path"] = path.into();
+        }
+        item
+    };
+    let upstream: Vec<_> = upstream
+        .into_iter()
+        .map(|(def_path_hash, queries)| item_to_json(def_path_hash, queries))
+        .collect();
+    let upstream_crates: Vec<_> = upstream_crates
+        .into_iter()
+        .map(|(def_path_hash, queries)| item_to_json(def_path_hash, queries))
+        .collect();
+    let provides: Vec<_> = provides
+        .into_iter()
+        .map(|(def_path_hash, queries)| {
+            let mut item = item_to_json(def_path_hash, queries);
+            item["reachable"] = export.reachable.contains(&def_path_hash).into();
+            item
+        })
+        .collect();
+
+    let mut source_files: Vec<_> = sess
+        .source_map()
+        .files()
+        .iter()
+        .filter(|file| !file.is_imported() && matches!(file.name, FileName::Real(_)))
+        .map(|file| {
+            let algorithm = match file.src_hash.kind {
+                SourceFileHashAlgorithm::Md5 => "md5",
+                SourceFileHashAlgorithm::Sha1 => "sha1",
+                SourceFileHashAlgorithm::Sha256 => "sha256",
+            };
+            let hash: String =
+                file.src_hash.hash_bytes().iter().map(|byte| format!("{byte:02x}")).collect();
+            (file.name.prefer_local().to_string(), format!("{algorithm}:{hash}"))
+        })
+        .collect();
+    source_files.sort();
+    source_files.dedup();
+
+    let json = serde_json::json!({
+        "version": VERSION,
+        "crate": tcx.crate_name(LOCAL_CRATE).as_str(),
+        "stable_crate_id": format!("{:016x}", local_crate_id.as_u64()),
+        "source_files": source_files
+            .into_iter()
+            .map(|(path, hash)| serde_json::json!({ "path": path, "hash": hash }))
+            .collect::<Vec<_>>(),
+        "upstream": upstream,
+        "upstream_crates": upstream_crates,
+        "provides": provides,
+    });
+
+    fs::create_dir_all(&export.output_dir)?;
+    let path = export.output_dir.join(format!("{}.dep_graph.json", tcx.crate_name(LOCAL_CRATE)));
+    fs::write(path, serde_json::to_string_pretty(&json).unwrap())
+}
+
+/// Formats `fingerprint` with a fixed number of digits, unlike `Fingerprint::to_hex`.
+fn hex(fingerprint: Fingerprint) -> String {
+    fingerprint.to_le_bytes().iter().map(|byte| format!("{byte:02x}")).collect()
+}
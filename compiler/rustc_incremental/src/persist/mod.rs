@@ -4,12 +4,14 @@
 
 mod data;
 mod dirty_clean;
+mod export;
 mod file_format;
 mod fs;
 mod load;
 mod save;
 mod work_product;
 
+pub use export::{prepare_dep_graph_export, DepGraphExport};
 pub use fs::finalize_session_directory;
 pub use fs::garbage_collect_session_directories;
 pub use fs::in_incr_comp_dir;
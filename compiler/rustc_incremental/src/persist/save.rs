@@ -12,6 +12,7 @@
 
 use super::data::*;
 use super::dirty_clean;
+use super::export::{self, DepGraphExport};
 use super::file_format;
 use super::fs::*;
 use super::work_product;
@@ -23,7 +24,10 @@
 ///
 /// This function should only run after all queries have completed.
 /// Trying to execute a query afterwards would attempt to read the result cache we just dropped.
-pub fn save_dep_graph(tcx: TyCtxt<'_>) {
+///
+/// The `-Z export-dep-graph` export is written together with the dep-graph, if it was prepared
+/// with [`export::prepare_dep_graph_export`].
+pub fn save_dep_graph(tcx: TyCtxt<'_>, dep_graph_export: Option<DepGraphExport>) {
     debug!("save_dep_graph()");
     tcx.dep_graph.with_ignore(|| {
         let sess = tcx.sess;
@@ -72,6 +76,16 @@ pub fn save_dep_graph(tcx: TyCtxt<'_>) {
                         });
                     }
                 });
+                if let Some(dep_graph_export) = dep_graph_export {
+                    sess.time("incr_comp_export_dep_graph", || {
+                        if let Err(err) =
+                            export::export_dep_graph(tcx, &dep_graph_path, &dep_graph_export)
+                        {
+                            let path = dep_graph_export.output_dir();
+                            sess.emit_err(errors::ExportDepGraph { path, err });
+                        }
+                    });
+                }
             },
         );
     })
@@ -1,6 +1,9 @@
 interface_cant_emit_mir =
     could not emit MIR: {$error}
 
+interface_cant_emit_stable_mir =
+    could not emit stable MIR: {$error}
+
 interface_emoji_identifier =
     identifiers cannot contain emoji: `{$ident}`
 
@@ -49,5 +52,8 @@ interface_rustc_error_unexpected_annotation =
 interface_temps_dir_error =
     failed to find or create the directory specified by `--temps-dir`
 
+interface_unknown_fix_suggestions_lint =
+    unknown lint or lint group `{$name}` passed to `-Z fix-suggestions-lints`
+
 interface_unsupported_crate_type_for_target =
     dropping unsupported crate type `{$crate_type}` for target `{$target_triple}`
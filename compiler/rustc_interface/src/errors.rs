@@ -65,6 +65,12 @@ pub struct CantEmitMIR {
     pub error: io::Error,
 }
 
+#[derive(Diagnostic)]
+#[diag(interface_cant_emit_stable_mir)]
+pub struct CantEmitStableMIR {
+    pub error: io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(interface_rustc_error_fatal)]
 pub struct RustcErrorFatal {
@@ -112,3 +118,9 @@ pub struct UnsupportedCrateTypeForTarget<'a> {
 #[derive(Diagnostic)]
 #[diag(interface_multiple_output_types_to_stdout)]
 pub struct MultipleOutputTypesToStdout;
+
+#[derive(Diagnostic)]
+#[diag(interface_unknown_fix_suggestions_lint)]
+pub struct UnknownFixSuggestionsLint<'a> {
+    pub name: &'a str,
+}
@@ -6,15 +6,17 @@
 use rustc_ast::{self as ast, visit};
 use rustc_borrowck as mir_borrowck;
 use rustc_codegen_ssa::traits::CodegenBackend;
+use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::parallel;
 use rustc_data_structures::steal::Steal;
 use rustc_data_structures::sync::{Lrc, OnceLock, WorkerLocal};
+use rustc_data_structures::AtomicRef;
 use rustc_errors::PResult;
 use rustc_expand::base::{ExtCtxt, LintStoreExpand};
 use rustc_feature::Features;
 use rustc_fs_util::try_canonicalize;
 use rustc_hir::def_id::{StableCrateId, LOCAL_CRATE};
-use rustc_lint::{unerased_lint_store, BufferedEarlyLint, EarlyCheckNode, LintStore};
+use rustc_lint::{unerased_lint_store, BufferedEarlyLint, EarlyCheckNode, LintId, LintStore};
 use rustc_metadata::creader::CStore;
 use rustc_middle::arena::Arena;
 use rustc_middle::dep_graph::DepGraph;
@@ -94,6 +96,21 @@ pub(crate) fn create_lint_store(
         }
     });
 
+    if let (Some(_), Some(names)) =
+        (sess.opts.unstable_opts.fix_suggestions, &sess.opts.unstable_opts.fix_suggestions_lints)
+    {
+        let mut lints = FxHashSet::default();
+        for name in names {
+            match lint_store.find_lints(&name.replace('-', "_")) {
+                Ok(ids) => lints.extend(ids.iter().map(LintId::to_string)),
+                Err(_) => {
+                    sess.emit_err(errors::UnknownFixSuggestionsLint { name });
+                }
+            }
+        }
+        *sess.fix_suggestions_lints.lock() = Some(lints);
+    }
+
     lint_store
 }
 
@@ -486,6 +503,11 @@ fn write_out_deps(tcx: TyCtxt<'_>, outputs: &OutputFilenames, out_filenames: &[P
             files.push(normalize_path(profile_sample.as_path().to_path_buf()));
         }
 
+        // The layering rules are only read during analysis, after this is written.
+        if let Some(ref layering_rules) = sess.opts.unstable_opts.dependency_layering {
+            files.push(normalize_path(layering_rules.clone()));
+        }
+
         // Debugger visualizer files
         for debugger_visualizer in tcx.debugger_visualizers(LOCAL_CRATE) {
             files.push(normalize_path(debugger_visualizer.path.clone().unwrap()));
@@ -769,6 +791,11 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
                     rustc_passes::stability::check_unused_or_stable_features(tcx)
                 });
             },
+            {
+                sess.time("dependency_layering_checking", || {
+                    rustc_passes::layering::check_crate(tcx)
+                });
+            },
             {
                 // We force these queries to run,
                 // since they might not otherwise get called.
@@ -818,6 +845,7 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
 
     sess.time("layout_testing", || layout_test::test_layout(tcx));
     sess.time("abi_testing", || abi_test::test_abi(tcx));
+    sess.time("future_size_checking", || rustc_ty_utils::future_size::check_future_sizes(tcx));
 
     // Avoid overwhelming user with errors if borrow checking failed.
     // I'm not sure how helpful this is, to be honest, but it avoids a
@@ -863,6 +891,9 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
         // This check has to be run after all lints are done processing. We don't
         // define a lint filter, as all lint checks should have finished at this point.
         sess.time("check_lint_expectations", || tcx.ensure().check_expectations(None));
+        sess.time("unused_pub_items_checking", || {
+            rustc_passes::used_items::report_unused_pub_items(tcx)
+        });
     });
 
     if sess.opts.unstable_opts.print_vtable_sizes {
@@ -984,9 +1015,25 @@ pub fn start_codegen<'tcx>(
         }
     }
 
+    if tcx.sess.opts.output_types.contains_key(&OutputType::StableMir) {
+        if let Err(error) = (*EMIT_STABLE_MIR)(tcx) {
+            tcx.sess.emit_err(errors::CantEmitStableMIR { error });
+            tcx.sess.abort_if_errors();
+        }
+    }
+
     codegen
 }
 
+fn default_emit_stable_mir(_: TyCtxt<'_>) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "this compiler cannot serialize stable MIR"))
+}
+
+/// Writes the output of `--emit=stable-mir`. The serializer lives in `rustc_smir`, which depends
+/// on this crate, so the `rustc` binary installs it before running the compiler.
+pub static EMIT_STABLE_MIR: AtomicRef<fn(TyCtxt<'_>) -> io::Result<()>> =
+    AtomicRef::new(&(default_emit_stable_mir as _));
+
 fn get_recursion_limit(krate_attrs: &[ast::Attribute], sess: &Session) -> Limit {
     if let Some(attr) = krate_attrs
         .iter()
@@ -376,6 +376,10 @@ pub fn enter<F, T>(&self, f: F) -> T
         // since that likely means there was a parse error.
         if let Some(Ok(gcx)) = &mut *queries.gcx.result.borrow_mut() {
             let gcx = gcx.get_mut();
+            // Preparing `-Z export-dep-graph` runs queries, so it has to happen before the
+            // dep-graph is saved.
+            let dep_graph_export = gcx.enter(rustc_incremental::prepare_dep_graph_export);
+
             // We assume that no queries are run past here. If there are new queries
             // after this point, they'll show up as "<unknown>" in self-profiling data.
             {
@@ -384,8 +388,9 @@ pub fn enter<F, T>(&self, f: F) -> T
                 gcx.enter(rustc_query_impl::alloc_self_profile_query_strings);
             }
 
-            self.session()
-                .time("serialize_dep_graph", || gcx.enter(rustc_incremental::save_dep_graph));
+            self.session().time("serialize_dep_graph", || {
+                gcx.enter(|tcx| rustc_incremental::save_dep_graph(tcx, dep_graph_export))
+            });
         }
 
         _timer = Some(self.session().timer("free_global_ctxt"));
@@ -5,6 +5,7 @@
 use rustc_data_structures::profiling::TimePassesFormat;
 use rustc_errors::{emitter::HumanReadableErrorType, registry, ColorConfig};
 use rustc_session::config::rustc_optgroups;
+use rustc_session::config::BorrowckExplain;
 use rustc_session::config::DebugInfo;
 use rustc_session::config::Input;
 use rustc_session::config::InstrumentXRay;
@@ -12,13 +13,14 @@
 use rustc_session::config::TraitSolver;
 use rustc_session::config::{build_configuration, build_session_options, to_crate_config};
 use rustc_session::config::{
-    BranchProtection, Externs, OomStrategy, OutFileName, OutputType, OutputTypes, PAuthKey, PacRet,
-    ProcMacroExecutionStrategy, SymbolManglingVersion, WasiExecModel,
+    BranchProtection, ExplainTraitErrors, Externs, OomStrategy, OutFileName, OutputType,
+    OutputTypes, PAuthKey, PacRet, ProcMacroExecutionStrategy, SymbolManglingVersion,
+    WasiExecModel,
 };
 use rustc_session::config::{CFGuard, ExternEntry, LinkerPluginLto, LtoCli, SwitchWithOptPath};
-use rustc_session::config::{DumpMonoStatsFormat, MirSpanview};
+use rustc_session::config::{CoverageOptions, InstrumentCoverage, Passes};
+use rustc_session::config::{DumpMonoStatsFormat, MirInliningFormat, MirSpanview};
 use rustc_session::config::{ErrorOutputType, ExternLocation, LocationDetail, Options, Strip};
-use rustc_session::config::{InstrumentCoverage, Passes};
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
 use rustc_session::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
@@ -679,6 +681,7 @@ macro_rules! untracked {
     // Make sure that changing an [UNTRACKED] option leaves the hash unchanged.
     // tidy-alphabetical-start
     untracked!(assert_incr_state, Some(String::from("loaded")));
+    untracked!(borrowck_explain, Some(BorrowckExplain::Index(2)));
     untracked!(deduplicate_diagnostics, false);
     untracked!(dep_tasks, true);
     untracked!(dont_buffer_diagnostics, true);
@@ -694,6 +697,7 @@ macro_rules! untracked {
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
+    untracked!(explain_trait_errors, Some(ExplainTraitErrors::Json));
     untracked!(future_incompat_test, true);
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
@@ -702,6 +706,7 @@ macro_rules! untracked {
     untracked!(input_stats, true);
     untracked!(keep_hygiene_data, true);
     untracked!(link_native_libraries, false);
+    untracked!(lint_baseline, Some(PathBuf::from("lints.baseline")));
     untracked!(llvm_time_trace, true);
     untracked!(ls, true);
     untracked!(macro_backtrace, true);
@@ -716,6 +721,7 @@ macro_rules! untracked {
     // `pre_link_arg` is omitted because it just forwards to `pre_link_args`.
     untracked!(pre_link_args, vec![String::from("abc"), String::from("def")]);
     untracked!(print_codegen_stats, true);
+    untracked!(print_future_layouts, true);
     untracked!(print_llvm_passes, true);
     untracked!(print_mono_items, Some(String::from("abc")));
     untracked!(print_type_sizes, true);
@@ -738,6 +744,7 @@ macro_rules! untracked {
     untracked!(ui_testing, true);
     untracked!(unpretty, Some("expanded".to_string()));
     untracked!(unstable_options, true);
+    untracked!(update_lint_baseline, true);
     untracked!(validate_mir, true);
     untracked!(verbose, true);
     untracked!(write_long_types_to_disk, false);
@@ -768,6 +775,8 @@ macro_rules! tracked {
         })
     );
     tracked!(codegen_backend, Some("abc".to_string()));
+    tracked!(const_eval_profile, true);
+    tracked!(coverage_options, CoverageOptions { branch: true, mcdc: false });
     tracked!(crate_attr, vec!["abc".to_string()]);
     tracked!(debug_info_for_profiling, true);
     tracked!(debug_macros, true);
@@ -782,6 +791,7 @@ macro_rules! tracked {
     tracked!(force_unstable_if_unmarked, true);
     tracked!(fuel, Some(("abc".to_string(), 99)));
     tracked!(function_sections, Some(false));
+    tracked!(future_size_limit, Some(4096));
     tracked!(human_readable_cgu_names, true);
     tracked!(incremental_ignore_spans, true);
     tracked!(inline_in_all_cgus, Some(true));
@@ -818,6 +828,7 @@ macro_rules! tracked {
     tracked!(polonius, true);
     tracked!(precise_enum_drop_elaboration, false);
     tracked!(print_fuel, Some("abc".to_string()));
+    tracked!(print_mir_inlining, Some(MirInliningFormat::Json));
     tracked!(profile, true);
     tracked!(profile_emit, Some(PathBuf::from("abc")));
     tracked!(profile_sample_use, Some(PathBuf::from("abc")));
@@ -850,6 +861,7 @@ macro_rules! tracked {
     tracked!(trap_unreachable, Some(false));
     tracked!(treat_err_as_bug, NonZeroUsize::new(1));
     tracked!(tune_cpu, Some(String::from("abc")));
+    tracked!(ub_checks, Some(false));
     tracked!(uninit_const_chunk_threshold, 123);
     tracked!(unleash_the_miri_inside_of_you, true);
     tracked!(use_ctors_section, Some(true));
@@ -571,6 +571,9 @@ pub struct LateContext<'tcx> {
 pub struct EarlyContext<'a> {
     pub builder: LintLevelsBuilder<'a, crate::levels::TopDown>,
     pub buffered: LintBuffer,
+    /// The names of the items around the current node, which `-Z lint-baseline` records with the
+    /// lints emitted in them.
+    pub(crate) item_path: Vec<Symbol>,
 }
 
 pub trait LintPassObject: Sized {}
@@ -1097,6 +1100,7 @@ pub(crate) fn new(
                 registered_tools,
             ),
             buffered,
+            item_path: Vec::new(),
         }
     }
 }
@@ -1158,7 +1162,17 @@ fn lookup<S: Into<MultiSpan>>(
             &'b mut DiagnosticBuilder<'a, ()>,
         ) -> &'b mut DiagnosticBuilder<'a, ()>,
     ) {
-        self.builder.struct_lint(lint, span.map(|s| s.into()), msg, decorate)
+        let item = if self.sess().opts.unstable_opts.lint_baseline.is_some() {
+            Some(self.item_path.iter().map(|name| format!("::{name}")).collect())
+        } else {
+            None
+        };
+        self.builder.struct_lint(lint, span.map(|s| s.into()), msg, |diag| {
+            if let Some(item) = item {
+                diag.set_lint_item(item);
+            }
+            decorate(diag)
+        })
     }
 
     fn get_lint_level(&self, lint: &'static Lint) -> Level {
@@ -24,7 +24,7 @@
 use rustc_middle::ty::RegisteredTools;
 use rustc_session::lint::{BufferedEarlyLint, LintBuffer, LintPass};
 use rustc_session::Session;
-use rustc_span::symbol::Ident;
+use rustc_span::symbol::{kw, Ident};
 use rustc_span::Span;
 
 macro_rules! lint_callback { ($cx:expr, $f:ident, $($args:expr),*) => ({
@@ -79,6 +79,22 @@ fn with_lint_attrs<F>(&mut self, id: ast::NodeId, attrs: &'a [ast::Attribute], f
         lint_callback!(self, exit_lint_attrs, attrs);
         self.context.builder.pop(push);
     }
+
+    /// Calls `f` inside the item named `ident`, which is added to the item path recorded by
+    /// `-Z lint-baseline`. Unnamed items, like impls, are not part of the path.
+    fn with_item_name<F>(&mut self, ident: Ident, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let named = ident.name != kw::Empty;
+        if named {
+            self.context.item_path.push(ident.name);
+        }
+        f(self);
+        if named {
+            self.context.item_path.pop();
+        }
+    }
 }
 
 impl<'a, T: EarlyLintPass> ast_visit::Visitor<'a> for EarlyContextAndPass<'a, T> {
@@ -90,16 +106,20 @@ fn visit_param(&mut self, param: &'a ast::Param) {
     }
 
     fn visit_item(&mut self, it: &'a ast::Item) {
-        self.with_lint_attrs(it.id, &it.attrs, |cx| {
-            lint_callback!(cx, check_item, it);
-            ast_visit::walk_item(cx, it);
-            lint_callback!(cx, check_item_post, it);
+        self.with_item_name(it.ident, |cx| {
+            cx.with_lint_attrs(it.id, &it.attrs, |cx| {
+                lint_callback!(cx, check_item, it);
+                ast_visit::walk_item(cx, it);
+                lint_callback!(cx, check_item_post, it);
+            })
         })
     }
 
     fn visit_foreign_item(&mut self, it: &'a ast::ForeignItem) {
-        self.with_lint_attrs(it.id, &it.attrs, |cx| {
-            ast_visit::walk_foreign_item(cx, it);
+        self.with_item_name(it.ident, |cx| {
+            cx.with_lint_attrs(it.id, &it.attrs, |cx| {
+                ast_visit::walk_foreign_item(cx, it);
+            })
         })
     }
 
@@ -260,15 +280,17 @@ fn visit_poly_trait_ref(&mut self, t: &'a ast::PolyTraitRef) {
     }
 
     fn visit_assoc_item(&mut self, item: &'a ast::AssocItem, ctxt: ast_visit::AssocCtxt) {
-        self.with_lint_attrs(item.id, &item.attrs, |cx| match ctxt {
-            ast_visit::AssocCtxt::Trait => {
-                lint_callback!(cx, check_trait_item, item);
-                ast_visit::walk_assoc_item(cx, item, ctxt);
-            }
-            ast_visit::AssocCtxt::Impl => {
-                lint_callback!(cx, check_impl_item, item);
-                ast_visit::walk_assoc_item(cx, item, ctxt);
-            }
+        self.with_item_name(item.ident, |cx| {
+            cx.with_lint_attrs(item.id, &item.attrs, |cx| match ctxt {
+                ast_visit::AssocCtxt::Trait => {
+                    lint_callback!(cx, check_trait_item, item);
+                    ast_visit::walk_assoc_item(cx, item, ctxt);
+                }
+                ast_visit::AssocCtxt::Impl => {
+                    lint_callback!(cx, check_impl_item, item);
+                    ast_visit::walk_assoc_item(cx, item, ctxt);
+                }
+            })
         });
     }
 
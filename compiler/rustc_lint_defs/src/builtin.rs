@@ -3058,6 +3058,105 @@
     "detects large moves or copies",
 }
 
+declare_lint! {
+    /// The `oversized_futures` lint detects the futures of `async` functions and
+    /// blocks which are larger than the limit given with `-Z future-size-limit`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs -Z future-size-limit)
+    /// // rustc --edition 2021 -Z future-size-limit=1024
+    /// async fn wait() {}
+    ///
+    /// pub async fn buffered() {
+    ///     let buffer = [0u8; 4096];
+    ///     wait().await;
+    ///     drop(buffer);
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: this future is 4097 bytes, which is more than the limit of 1024 bytes
+    ///  --> src/lib.rs:4:1
+    ///   |
+    /// 4 | pub async fn buffered() {
+    ///   | ^^^^^^^^^^^^^^^^^^^^^^^
+    ///   |
+    /// note: the future is 4097 bytes while it is suspended at this await point
+    ///  --> src/lib.rs:6:11
+    ///   |
+    /// 6 |     wait().await;
+    ///   |           ^^^^^^
+    /// note: the largest value saved across it is `buffer`, with 4096 bytes
+    ///  --> src/lib.rs:5:9
+    ///   |
+    /// 5 |     let buffer = [0u8; 4096];
+    ///   |         ^^^^^^
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// A future stores all the values that are live across an await point,
+    /// including the futures it awaits, so futures grow with every level of
+    /// nesting. Large futures are expensive to move and can overflow the stack
+    /// of the thread which polls them. Moving large values to the heap, or
+    /// boxing large nested futures, keeps the future small. Use
+    /// `-Z print-future-layouts` to see what takes up the space.
+    ///
+    /// The size of a generic future depends on its arguments, so only the
+    /// futures of non-generic functions and blocks are checked.
+    pub OVERSIZED_FUTURES,
+    Warn,
+    "detects futures larger than the limit given with `-Z future-size-limit`",
+}
+
+declare_lint! {
+    /// The `layering_violations` lint detects paths that resolve to items
+    /// which the layering rules given with `-Z dependency-layering` forbid
+    /// the current module to use.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs a rules file)
+    /// // rustc -Z dependency-layering=layers.txt
+    /// // where layers.txt contains `deny crate::domain -> crate::infra`
+    /// mod infra {
+    ///     pub struct Database;
+    /// }
+    ///
+    /// mod domain {
+    ///     pub fn load(_: &crate::infra::Database) {}
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// error: `crate::domain` must not depend on `crate::infra::Database`
+    ///  --> src/lib.rs:6:20
+    ///   |
+    /// 6 |     pub fn load(_: &crate::infra::Database) {}
+    ///   |                    ^^^^^^^^^^^^^^^^^^^^^^
+    ///   |
+    ///   = note: denied by `deny crate::domain -> crate::infra` on line 1 of `layers.txt`
+    ///   = note: `#[deny(layering_violations)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Larger crates are often organized in layers, where lower layers must
+    /// not depend on higher ones. The rules file states which modules may use
+    /// the items of which other modules or crates, so that violations are
+    /// caught at compile time. This lint only fires when `-Z
+    /// dependency-layering` is passed; without it there are no rules to
+    /// violate.
+    pub LAYERING_VIOLATIONS,
+    Deny,
+    "paths that violate the layering rules of `-Z dependency-layering`",
+}
+
 declare_lint! {
     /// The `deprecated_cfg_attr_crate_type_name` lint detects uses of the
     /// `#![cfg_attr(..., crate_type = "...")]` and
@@ -3360,6 +3459,7 @@
         IRREFUTABLE_LET_PATTERNS,
         LARGE_ASSIGNMENTS,
         LATE_BOUND_LIFETIME_ARGUMENTS,
+        LAYERING_VIOLATIONS,
         LEGACY_DERIVE_HELPERS,
         LONG_RUNNING_CONST_EVAL,
         LOSSY_PROVENANCE_CASTS,
@@ -3374,6 +3474,7 @@
         NONTRIVIAL_STRUCTURAL_MATCH,
         ORDER_DEPENDENT_TRAIT_OBJECTS,
         OVERLAPPING_RANGE_ENDPOINTS,
+        OVERSIZED_FUTURES,
         PATTERNS_IN_FNS_WITHOUT_BODY,
         POINTER_STRUCTURAL_MATCH,
         PRIVATE_BOUNDS,
@@ -43,6 +43,8 @@ enum class LLVMRustCounterMappingRegionKind {
   SkippedRegion = 2,
   GapRegion = 3,
   BranchRegion = 4,
+  MCDCDecisionRegion = 5,
+  MCDCBranchRegion = 6,
 };
 
 static coverage::CounterMappingRegion::RegionKind
@@ -58,15 +60,45 @@ fromRust(LLVMRustCounterMappingRegionKind Kind) {
     return coverage::CounterMappingRegion::GapRegion;
   case LLVMRustCounterMappingRegionKind::BranchRegion:
     return coverage::CounterMappingRegion::BranchRegion;
+#if LLVM_VERSION_GE(18, 0)
+  case LLVMRustCounterMappingRegionKind::MCDCDecisionRegion:
+    return coverage::CounterMappingRegion::MCDCDecisionRegion;
+  case LLVMRustCounterMappingRegionKind::MCDCBranchRegion:
+    return coverage::CounterMappingRegion::MCDCBranchRegion;
+#endif
   }
   report_fatal_error("Bad LLVMRustCounterMappingRegionKind!");
 }
 
+// FFI equivalent of struct `llvm::coverage::CounterMappingRegion::MCDCParameters`
+// https://github.com/llvm/llvm-project/blob/llvmorg-18.1.0/llvm/include/llvm/ProfileData/Coverage/CoverageMapping.h#L253-L263
+struct LLVMRustMCDCParameters {
+  uint32_t BitmapIdx;
+  uint32_t NumConditions;
+  uint32_t ID;
+  uint32_t TrueID;
+  uint32_t FalseID;
+};
+
+#if LLVM_VERSION_GE(18, 0)
+static coverage::CounterMappingRegion::MCDCParameters
+fromRust(LLVMRustMCDCParameters Params) {
+  coverage::CounterMappingRegion::MCDCParameters LLVMParams;
+  LLVMParams.BitmapIdx = Params.BitmapIdx;
+  LLVMParams.NumConditions = Params.NumConditions;
+  LLVMParams.ID = Params.ID;
+  LLVMParams.TrueID = Params.TrueID;
+  LLVMParams.FalseID = Params.FalseID;
+  return LLVMParams;
+}
+#endif
+
 // FFI equivalent of struct `llvm::coverage::CounterMappingRegion`
 // https://github.com/rust-lang/llvm-project/blob/ea6fa9c2/llvm/include/llvm/ProfileData/Coverage/CoverageMapping.h#L211-L304
 struct LLVMRustCounterMappingRegion {
   LLVMRustCounter Count;
   LLVMRustCounter FalseCount;
+  LLVMRustMCDCParameters MCDCParameters;
   uint32_t FileID;
   uint32_t ExpandedFileID;
   uint32_t LineStart;
@@ -139,6 +171,9 @@ extern "C" void LLVMRustCoverageWriteMappingToBuffer(
            RustMappingRegions, NumMappingRegions)) {
     MappingRegions.emplace_back(
         fromRust(Region.Count), fromRust(Region.FalseCount),
+#if LLVM_VERSION_GE(18, 0)
+        fromRust(Region.MCDCParameters),
+#endif
         Region.FileID, Region.ExpandedFileID,
         Region.LineStart, Region.ColumnStart, Region.LineEnd, Region.ColumnEnd,
         fromRust(Region.Kind));
@@ -202,5 +237,9 @@ extern "C" void LLVMRustCoverageWriteMappingVarNameToString(RustStringRef Str) {
 }
 
 extern "C" uint32_t LLVMRustCoverageMappingVersion() {
+#if LLVM_VERSION_GE(18, 0)
+  return coverage::CovMapVersion::Version7;
+#else
   return coverage::CovMapVersion::Version6;
+#endif
 }
@@ -363,6 +363,29 @@ extern "C" void LLVMRustPrintTargetCPUs(LLVMTargetMachineRef TM,
   Print(Out, BufString.data(), BufString.size());
 }
 
+// Calls `Print` once with the name of every CPU known for the target. LLVM
+// only exposes the CPU table since LLVM 17, so older versions only report
+// `TargetCPU`, like the text output of `LLVMRustPrintTargetCPUs`.
+extern "C" void LLVMRustGetTargetCPUs(LLVMTargetMachineRef TM,
+                                      const char* TargetCPU,
+                                      PrintBackendInfo Print,
+                                      void* Out) {
+#if LLVM_VERSION_GE(17, 0)
+  const TargetMachine *Target = unwrap(TM);
+  const MCSubtargetInfo *MCInfo = Target->getMCSubtargetInfo();
+  for (auto &CPU : MCInfo->getAllProcessorDescriptions())
+    Print(Out, CPU.Key, std::strlen(CPU.Key));
+#else
+  Print(Out, TargetCPU, std::strlen(TargetCPU));
+#endif
+}
+
+extern "C" bool LLVMRustTargetMatchesHostArch(LLVMTargetMachineRef TM) {
+  const TargetMachine *Target = unwrap(TM);
+  const Triple::ArchType HostArch = Triple(sys::getDefaultTargetTriple()).getArch();
+  return HostArch == Target->getTargetTriple().getArch();
+}
+
 extern "C" size_t LLVMRustGetTargetFeaturesCount(LLVMTargetMachineRef TM) {
 #ifdef LLVM_RUSTLLVM
   const TargetMachine *Target = unwrap(TM);
@@ -386,6 +409,21 @@ extern "C" void LLVMRustGetTargetFeature(LLVMTargetMachineRef TM, size_t Index,
 #endif
 }
 
+// Calls `Print` once with the name of every feature directly implied by the
+// feature at `Index` in the feature table.
+extern "C" void LLVMRustGetTargetFeatureImplies(LLVMTargetMachineRef TM, size_t Index,
+                                                PrintBackendInfo Print, void* Out) {
+#ifdef LLVM_RUSTLLVM
+  const TargetMachine *Target = unwrap(TM);
+  const MCSubtargetInfo *MCInfo = Target->getMCSubtargetInfo();
+  const ArrayRef<SubtargetFeatureKV> FeatTable = MCInfo->getFeatureTable();
+  const FeatureBitset Implies = FeatTable[Index].Implies.getAsBitset();
+  for (auto &Feat : FeatTable)
+    if (Implies.test(Feat.Value))
+      Print(Out, Feat.Key, std::strlen(Feat.Key));
+#endif
+}
+
 extern "C" const char* LLVMRustGetHostCPUName(size_t *len) {
   StringRef Name = sys::getHostCPUName();
   *len = Name.size();
@@ -360,6 +360,7 @@ fn into_args(self) -> (DefId, SimplifiedType) {
 
     used_crate_source => { Lrc::clone(&cdata.source) }
     debugger_visualizers => { cdata.get_debugger_visualizers() }
+    used_items => { cdata.get_used_items() }
 
     exported_symbols => {
         let syms = cdata.exported_symbols(tcx);
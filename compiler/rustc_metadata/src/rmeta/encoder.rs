@@ -649,6 +649,8 @@ macro_rules! stat {
         let debugger_visualizers =
             stat!("debugger-visualizers", || self.encode_debugger_visualizers());
 
+        let used_items = stat!("used-items", || self.encode_used_items());
+
         // Encode exported symbols info. This is prefetched in `encode_metadata` so we encode
         // this as late as possible to give the prefetching as much time as possible to complete.
         let exported_symbols = stat!("exported-symbols", || {
@@ -689,6 +691,7 @@ macro_rules! stat {
                 has_default_lib_allocator: attr::contains_name(&attrs, sym::default_lib_allocator),
                 proc_macro_data,
                 debugger_visualizers,
+                used_items,
                 compiler_builtins: attr::contains_name(&attrs, sym::compiler_builtins),
                 needs_allocator: attr::contains_name(&attrs, sym::needs_allocator),
                 needs_panic_runtime: attr::contains_name(&attrs, sym::needs_panic_runtime),
@@ -1835,6 +1838,17 @@ fn encode_debugger_visualizers(&mut self) -> LazyArray<DebuggerVisualizerFile> {
         )
     }
 
+    fn encode_used_items(&mut self) -> Option<UsedItemsData> {
+        if self.is_proc_macro {
+            return None;
+        }
+        let used_items = self.tcx.used_items(LOCAL_CRATE).as_ref()?;
+        Some(UsedItemsData {
+            items: self.lazy_array(&used_items.items),
+            unused_dependencies: self.lazy_array(&used_items.unused_dependencies),
+        })
+    }
+
     fn encode_crate_deps(&mut self) -> LazyArray<CrateDep> {
         empty_proc_macro!(self);
 
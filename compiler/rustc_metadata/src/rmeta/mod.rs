@@ -211,6 +211,12 @@ pub(crate) struct ProcMacroData {
     macros: LazyArray<DefIndex>,
 }
 
+#[derive(MetadataEncodable, MetadataDecodable)]
+pub(crate) struct UsedItemsData {
+    items: LazyArray<DefId>,
+    unused_dependencies: LazyArray<Symbol>,
+}
+
 /// Serialized crate metadata.
 ///
 /// This contains just enough information to determine if we should load the `CrateRoot` or not.
@@ -280,6 +286,7 @@ pub(crate) struct CrateRoot {
 
     tables: LazyTables,
     debugger_visualizers: LazyArray<DebuggerVisualizerFile>,
+    used_items: Option<UsedItemsData>,
 
     exported_symbols: LazyArray<(ExportedSymbol<'static>, SymbolExportInfo)>,
 
@@ -32,6 +32,7 @@ pub fn to_vec(&self) -> Vec<(Symbol, Option<Symbol>)> {
 pub mod region;
 pub mod resolve_bound_vars;
 pub mod stability;
+pub mod used_items;
 
 pub fn provide(providers: &mut crate::query::Providers) {
     limits::provide(providers);
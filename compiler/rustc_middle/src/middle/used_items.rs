@@ -0,0 +1,11 @@
+use rustc_hir::def_id::DefId;
+use rustc_span::Symbol;
+
+/// What a crate uses of its dependencies, recorded with `-Z record-used-items`.
+#[derive(HashStable, Debug)]
+pub struct UsedItems {
+    /// The items of other crates that the crate refers to.
+    pub items: Vec<DefId>,
+    /// The names of the crates passed with `--extern` that the crate doesn't refer to.
+    pub unused_dependencies: Vec<Symbol>,
+}
@@ -1,7 +1,7 @@
 //! Metadata from source code coverage analysis and instrumentation.
 
 use rustc_macros::HashStable;
-use rustc_span::Symbol;
+use rustc_span::{Span, Symbol};
 
 use std::fmt::{self, Debug, Formatter};
 
@@ -55,6 +55,17 @@ pub fn next_id(self) -> Self {
     pub struct MappedExpressionIndex {}
 }
 
+rustc_index::newtype_index! {
+    /// ID of a block that branch coverage refers to. MIR building marks the blocks that are
+    /// entered when a branch condition is true or false with [`CoverageKind::BlockMarker`]
+    /// statements, and the `InstrumentCoverage` pass later looks up the counters of the marked
+    /// blocks, to use them as the counts of the branch arms.
+    #[derive(HashStable)]
+    #[max = 0xFFFF_FFFF]
+    #[debug_format = "BlockMarkerId({})"]
+    pub struct BlockMarkerId {}
+}
+
 /// Operand of a coverage-counter expression.
 ///
 /// Operands can be a constant zero value, an actual coverage counter, or another
@@ -94,6 +105,45 @@ pub enum CoverageKind {
         rhs: Operand,
     },
     Unreachable,
+    /// Marks a block that branch coverage refers to; see [`BlockMarkerId`]. Has no effect on
+    /// codegen, and is removed after the `InstrumentCoverage` pass has seen it.
+    BlockMarker {
+        id: BlockMarkerId,
+    },
+    /// A branch mapping, with the code region of the branch condition. Like intermediate
+    /// expressions, it does not generate executable code.
+    Branch {
+        /// The number of times the condition was true.
+        true_term: Operand,
+        /// The number of times the condition was false.
+        false_term: Operand,
+        /// For a condition of an MC/DC decision, the position of the condition in the decision.
+        condition_info: Option<ConditionInfo>,
+    },
+    /// An MC/DC decision mapping, with the code region of the decision. Does not generate
+    /// executable code.
+    MCDCDecision {
+        /// Byte offset of the test vector bitmap of the decision in the bitmap of the function.
+        bitmap_idx: u32,
+        num_conditions: u16,
+    },
+    /// Clears the condition bitmap of an MC/DC decision at the start of its evaluation.
+    CondBitmapReset {
+        decision_depth: u16,
+    },
+    /// Records in the condition bitmap of an MC/DC decision that condition `id` (counted from 0)
+    /// was true. Generates a call to `llvm.instrprof.mcdc.condbitmap.update`.
+    CondBitmapUpdate {
+        id: u32,
+        decision_depth: u16,
+    },
+    /// Marks the end of the evaluation of an MC/DC decision, recording the test vector in the
+    /// condition bitmap in the test vector bitmap of the decision. Generates a call to
+    /// `llvm.instrprof.mcdc.tvbitmap.update`.
+    TestVectorBitmapUpdate {
+        bitmap_idx: u32,
+        decision_depth: u16,
+    },
 }
 
 impl Debug for CoverageKind {
@@ -113,6 +163,27 @@ fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
                 rhs,
             ),
             Unreachable => write!(fmt, "Unreachable"),
+            BlockMarker { id } => write!(fmt, "BlockMarker({:?})", id.index()),
+            Branch { true_term, false_term, condition_info: None } => {
+                write!(fmt, "Branch(true: {true_term:?}, false: {false_term:?})")
+            }
+            Branch { true_term, false_term, condition_info: Some(info) } => write!(
+                fmt,
+                "Branch(true: {true_term:?}, false: {false_term:?}, condition: {:?})",
+                info
+            ),
+            MCDCDecision { bitmap_idx, num_conditions } => {
+                write!(fmt, "MCDCDecision(bitmap_idx: {bitmap_idx}, conditions: {num_conditions})")
+            }
+            CondBitmapReset { decision_depth } => {
+                write!(fmt, "CondBitmapReset(depth: {decision_depth})")
+            }
+            CondBitmapUpdate { id, decision_depth } => {
+                write!(fmt, "CondBitmapUpdate({id}, depth: {decision_depth})")
+            }
+            TestVectorBitmapUpdate { bitmap_idx, decision_depth } => {
+                write!(fmt, "TestVectorBitmapUpdate({bitmap_idx}, depth: {decision_depth})")
+            }
         }
     }
 }
@@ -153,3 +224,73 @@ pub fn is_subtract(&self) -> bool {
         matches!(self, Self::Subtract)
     }
 }
+
+/// Branch coverage information recorded during MIR building, from which the `InstrumentCoverage`
+/// pass creates branch and MC/DC mappings.
+#[derive(Clone, Debug)]
+#[derive(TyEncodable, TyDecodable, Hash, HashStable, TypeFoldable, TypeVisitable)]
+pub struct BranchInfo {
+    /// 1 more than the highest-numbered [`CoverageKind::BlockMarker`] that was injected into the
+    /// MIR body. This makes it possible to allocate per-ID data structures without having to
+    /// scan the entire body first.
+    pub num_block_markers: usize,
+    /// The branch conditions of `if`, `&&`, `||` and `while`.
+    pub branch_spans: Vec<BranchSpan>,
+    /// The arms of `match` expressions.
+    pub match_arm_spans: Vec<MatchArmSpan>,
+    /// The compound conditions of `if` that MC/DC coverage treats as decisions.
+    pub mcdc_decision_spans: Vec<MCDCDecisionSpan>,
+}
+
+#[derive(Clone, Debug)]
+#[derive(TyEncodable, TyDecodable, Hash, HashStable, TypeFoldable, TypeVisitable)]
+pub struct BranchSpan {
+    pub span: Span,
+    pub true_marker: BlockMarkerId,
+    pub false_marker: BlockMarkerId,
+    pub condition_info: Option<ConditionInfo>,
+}
+
+/// An arm of a `match`, which is taken as often as the block marked by `arm_marker` is entered,
+/// and not taken the remaining times that the block marked by `match_marker` is entered.
+#[derive(Clone, Debug)]
+#[derive(TyEncodable, TyDecodable, Hash, HashStable, TypeFoldable, TypeVisitable)]
+pub struct MatchArmSpan {
+    pub span: Span,
+    pub match_marker: BlockMarkerId,
+    pub arm_marker: BlockMarkerId,
+}
+
+/// The position of a condition in an MC/DC decision, in the form LLVM expects it: conditions are
+/// numbered from 1 in evaluation order, and `true_next_id`/`false_next_id` are the conditions
+/// evaluated next if this one is true/false, or 0 if the decision is decided by then.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(TyEncodable, TyDecodable, Hash, HashStable, TypeFoldable, TypeVisitable)]
+pub struct ConditionInfo {
+    pub condition_id: u32,
+    pub true_next_id: u32,
+    pub false_next_id: u32,
+    /// The [`MCDCDecisionSpan::decision_depth`] of the decision the condition belongs to.
+    pub decision_depth: u16,
+}
+
+#[derive(Clone, Debug)]
+#[derive(TyEncodable, TyDecodable, Hash, HashStable, TypeFoldable, TypeVisitable)]
+pub struct MCDCDecisionSpan {
+    pub span: Span,
+    pub num_conditions: u16,
+    /// How many decisions enclose this one, as the conditions of decisions can contain other
+    /// decisions.
+    pub decision_depth: u16,
+    /// Marks the start of the evaluation of the decision, where its condition bitmap is cleared.
+    pub start_marker: BlockMarkerId,
+    /// Mark the blocks entered once the decision is decided, where its test vector is recorded.
+    pub end_markers: Vec<BlockMarkerId>,
+}
+
+/// The number of bytes of the test vector bitmap of an MC/DC decision with `num_conditions`
+/// conditions, which has a bit for each of the `2^num_conditions` test vectors. LLVM allocates
+/// at least one byte per decision.
+pub fn mcdc_bitmap_bytes(num_conditions: u16) -> u32 {
+    (1u32 << num_conditions).max(8) / 8
+}
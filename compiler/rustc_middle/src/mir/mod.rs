@@ -304,6 +304,13 @@ pub struct Body<'tcx> {
     pub injection_phase: Option<MirPhase>,
 
     pub tainted_by_errors: Option<ErrorGuaranteed>,
+
+    /// Branch coverage information collected during MIR building, to be used by the
+    /// `InstrumentCoverage` pass.
+    ///
+    /// Only present if branch coverage is enabled (`-Zcoverage-options=branch`) and this function
+    /// has branches worth instrumenting.
+    pub coverage_branch_info: Option<Box<coverage::BranchInfo>>,
 }
 
 impl<'tcx> Body<'tcx> {
@@ -351,6 +358,7 @@ pub fn new(
             is_polymorphic: false,
             injection_phase: None,
             tainted_by_errors,
+            coverage_branch_info: None,
         };
         body.is_polymorphic = body.has_non_region_param();
         body
@@ -379,6 +387,7 @@ pub fn new_cfg_only(basic_blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>) ->
             is_polymorphic: false,
             injection_phase: None,
             tainted_by_errors: None,
+            coverage_branch_info: None,
         };
         body.is_polymorphic = body.has_non_region_param();
         body
@@ -2082,6 +2091,7 @@ fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
                 NullOp::SizeOf => write!(fmt, "SizeOf({t:?})"),
                 NullOp::AlignOf => write!(fmt, "AlignOf({t:?})"),
                 NullOp::OffsetOf(fields) => write!(fmt, "OffsetOf({t:?}, {fields:?})"),
+                NullOp::UbChecks => write!(fmt, "UbChecks()"),
             },
             ThreadLocalRef(did) => ty::tls::with(|tcx| {
                 let muta = tcx.static_mutability(did).unwrap().prefix_str();
@@ -677,12 +677,58 @@ pub fn write_mir_intro<'tcx>(
 
     write_scope_tree(tcx, body, &scope_tree, w, OUTERMOST_SOURCE_SCOPE, 1)?;
 
+    if let Some(branch_info) = &body.coverage_branch_info {
+        write_coverage_branch_info(branch_info, w)?;
+    }
+
     // Add an empty line before the first block is printed.
     writeln!(w)?;
 
     Ok(())
 }
 
+fn write_coverage_branch_info(
+    branch_info: &coverage::BranchInfo,
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    let coverage::BranchInfo { branch_spans, match_arm_spans, mcdc_decision_spans, .. } =
+        branch_info;
+
+    for coverage::BranchSpan { span, true_marker, false_marker, condition_info } in branch_spans {
+        write!(w, "{INDENT}coverage branch {{ true: {true_marker:?}, false: {false_marker:?} }}")?;
+        if let Some(condition_info) = condition_info {
+            write!(w, " {condition_info:?}")?;
+        }
+        writeln!(w, " => {span:?}")?;
+    }
+    for coverage::MatchArmSpan { span, match_marker, arm_marker } in match_arm_spans {
+        writeln!(
+            w,
+            "{INDENT}coverage match arm {{ match: {match_marker:?}, arm: {arm_marker:?} }} \
+            => {span:?}",
+        )?;
+    }
+    for coverage::MCDCDecisionSpan {
+        span,
+        num_conditions,
+        decision_depth,
+        start_marker,
+        end_markers,
+    } in mcdc_decision_spans
+    {
+        writeln!(
+            w,
+            "{INDENT}coverage mcdc decision {{ conditions: {num_conditions}, \
+            depth: {decision_depth}, start: {start_marker:?}, end: {end_markers:?} }} => {span:?}",
+        )?;
+    }
+    if !branch_spans.is_empty() || !match_arm_spans.is_empty() || !mcdc_decision_spans.is_empty() {
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
 /// Find all `AllocId`s mentioned (recursively) in the MIR body and print their corresponding
 /// allocations.
 pub fn write_allocations<'tcx>(
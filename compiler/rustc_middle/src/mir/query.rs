@@ -457,4 +457,16 @@ pub struct CoverageInfo {
 
     /// The total number of coverage region counter expressions added to the MIR `Body`.
     pub num_expressions: u32,
+
+    /// The number of bytes in the MC/DC test vector bitmap of the function, which records the
+    /// combinations of condition values its MC/DC decisions were evaluated with.
+    pub mcdc_bitmap_bytes: u32,
+
+    /// The number of MC/DC condition bitmaps the function needs, one for each level of
+    /// nesting of its MC/DC decisions.
+    pub mcdc_num_condition_bitmaps: u32,
+
+    /// The hash of the function source that its counters were injected with, which the MC/DC
+    /// intrinsics take as well.
+    pub function_source_hash: u64,
 }
@@ -1315,6 +1315,10 @@ pub enum NullOp<'tcx> {
     AlignOf,
     /// Returns the offset of a field
     OffsetOf(&'tcx List<FieldIdx>),
+    /// Returns whether runtime checks for Undefined Behavior should be performed. This is decided
+    /// by `-Zub-checks` of the crate that the function is codegened in, rather than of the crate
+    /// that it was defined in. The type operand is always `()`.
+    UbChecks,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
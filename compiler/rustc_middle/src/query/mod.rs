@@ -18,6 +18,7 @@
 use crate::middle::privacy::EffectiveVisibilities;
 use crate::middle::resolve_bound_vars::{ObjectLifetimeDefault, ResolveBoundVars, ResolvedArg};
 use crate::middle::stability::{self, DeprecationEntry};
+use crate::middle::used_items::UsedItems;
 use crate::mir;
 use crate::mir::interpret::GlobalId;
 use crate::mir::interpret::{
@@ -1824,6 +1825,14 @@
         eval_always
     }
 
+    /// Returns the items of other crates that this crate uses and the dependencies it doesn't
+    /// use, if it was compiled with `-Z record-used-items`.
+    query used_items(_: CrateNum) -> &'tcx Option<UsedItems> {
+        arena_cache
+        desc { "collecting the items of other crates used by this crate" }
+        separate_provide_extern
+    }
+
     query postorder_cnums(_: ()) -> &'tcx [CrateNum] {
         eval_always
         desc { "generating a postorder list of CrateNums" }
@@ -35,7 +35,7 @@
 use rustc_data_structures::fingerprint::Fingerprint;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::intern::Interned;
-use rustc_data_structures::profiling::SelfProfilerRef;
+use rustc_data_structures::profiling::{ItemActivity, ItemTimingGuard, SelfProfilerRef};
 use rustc_data_structures::sharded::{IntoPointer, ShardedHashMap};
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
 use rustc_data_structures::steal::Steal;
@@ -911,6 +911,28 @@ pub fn def_path_debug_str(self, def_id: DefId) -> String {
             self.def_path(def_id).to_string_no_crate_verbose()
         )
     }
+
+    /// Attributes the time until the returned guard is dropped to `def_id` in the
+    /// `-Z item-time-report`, see [`SelfProfilerRef::item_activity`].
+    pub fn item_activity(
+        self,
+        activity: ItemActivity,
+        def_id: impl IntoQueryParam<DefId>,
+    ) -> ItemTimingGuard<'tcx> {
+        let def_id = def_id.into_query_param();
+        self.sess.prof.item_activity(activity, || self.item_time_report_path(def_id))
+    }
+
+    /// The name of `def_id` in the `-Z item-time-report`. Unlike `def_path_str`, this doesn't
+    /// depend on any query, so it can be used while the item is still being type-checked.
+    pub fn item_time_report_path(self, def_id: DefId) -> String {
+        let crate_name = if def_id.is_local() {
+            self.crate_name(LOCAL_CRATE)
+        } else {
+            self.cstore_untracked().crate_name(def_id.krate)
+        };
+        format!("{crate_name}{}", self.def_path(def_id).to_string_no_crate_verbose())
+    }
 }
 
 impl<'tcx> TyCtxtAt<'tcx> {
@@ -1918,7 +1940,13 @@ pub fn struct_span_lint_hir(
         ) -> &'b mut DiagnosticBuilder<'a, ()>,
     ) {
         let (level, src) = self.lint_level_at_node(lint, hir_id);
-        struct_lint_level(self.sess, lint, level, src, Some(span.into()), msg, decorate);
+        let item = self.lint_baseline_item(hir_id);
+        struct_lint_level(self.sess, lint, level, src, Some(span.into()), msg, |diag| {
+            if let Some(item) = item {
+                diag.set_lint_item(item);
+            }
+            decorate(diag)
+        });
     }
 
     /// Emit a lint from a lint struct (some type that implements `DecorateLint`, typically
@@ -1950,7 +1978,22 @@ pub fn struct_lint_node(
         ) -> &'b mut DiagnosticBuilder<'a, ()>,
     ) {
         let (level, src) = self.lint_level_at_node(lint, id);
-        struct_lint_level(self.sess, lint, level, src, None, msg, decorate);
+        let item = self.lint_baseline_item(id);
+        struct_lint_level(self.sess, lint, level, src, None, msg, |diag| {
+            if let Some(item) = item {
+                diag.set_lint_item(item);
+            }
+            decorate(diag)
+        });
+    }
+
+    /// The path of the item containing `id`, which `-Z lint-baseline` records with the lints
+    /// emitted in it.
+    fn lint_baseline_item(self, id: HirId) -> Option<String> {
+        if self.sess.opts.unstable_opts.lint_baseline.is_none() {
+            return None;
+        }
+        Some(self.def_path(id.owner.to_def_id()).to_string_no_crate_verbose())
     }
 
     pub fn in_scope_traits(self, id: HirId) -> Option<&'tcx [TraitCandidate]> {
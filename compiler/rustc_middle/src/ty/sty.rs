@@ -492,6 +492,9 @@ impl<'tcx> GeneratorArgs<'tcx> {
     pub const RETURNED: usize = 1;
     /// Generator has been poisoned.
     pub const POISONED: usize = 2;
+    /// Number of variants reserved for the states above. The variants after them are the
+    /// suspension points of the generator, in order.
+    pub const RESERVED_VARIANTS: usize = 3;
 
     const UNRESUMED_NAME: &'static str = "Unresumed";
     const RETURNED_NAME: &'static str = "Returned";
@@ -539,7 +542,7 @@ pub fn variant_name(v: VariantIdx) -> Cow<'static, str> {
             Self::UNRESUMED => Cow::from(Self::UNRESUMED_NAME),
             Self::RETURNED => Cow::from(Self::RETURNED_NAME),
             Self::POISONED => Cow::from(Self::POISONED_NAME),
-            _ => Cow::from(format!("Suspend{}", v.as_usize() - 3)),
+            _ => Cow::from(format!("Suspend{}", v.as_usize() - Self::RESERVED_VARIANTS)),
         }
     }
 
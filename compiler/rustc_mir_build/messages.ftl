@@ -182,6 +182,10 @@ mir_build_lower_range_bound_must_be_less_than_or_equal_to_upper =
 
 mir_build_lower_range_bound_must_be_less_than_upper = lower range bound must be less than upper
 
+mir_build_mcdc_exceeds_condition_limit =
+    this decision has {$num_conditions} conditions, more than the {$max_conditions} that MC/DC coverage supports
+    .note = MC/DC coverage will not be recorded for this decision, but branch coverage will
+
 mir_build_more_information = for more information, visit https://doc.rust-lang.org/book/ch18-02-refutability.html
 
 mir_build_moved = value is moved into `{$name}` here
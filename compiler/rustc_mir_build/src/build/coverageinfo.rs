@@ -0,0 +1,367 @@
+//! Collection of the branch coverage information (`-Zcoverage-options=branch` and `mcdc`) that
+//! the `InstrumentCoverage` pass turns into branch and MC/DC mappings.
+//!
+//! MIR building marks the blocks that branch mappings refer to with
+//! [`CoverageKind::BlockMarker`] statements, and records what the markers stand for in a
+//! [`BranchInfo`] that is attached to the MIR body.
+
+use rustc_hir::def_id::LocalDefId;
+use rustc_middle::mir::coverage::{
+    BlockMarkerId, BranchInfo, BranchSpan, ConditionInfo, CoverageKind, MCDCDecisionSpan,
+    MatchArmSpan,
+};
+use rustc_middle::mir::{BasicBlock, Coverage, SourceInfo, Statement, StatementKind};
+use rustc_middle::thir::{Arm, Expr, ExprId, ExprKind, LogicalOp, Thir, UnOp};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+
+use crate::build::{Builder, CFG};
+use crate::errors::MCDCExceedsConditionLimit;
+
+/// The maximum number of conditions in an MC/DC decision that LLVM supports.
+const MAX_CONDITIONS_IN_DECISION: usize = 6;
+
+pub(crate) struct BranchInfoBuilder {
+    num_block_markers: usize,
+    branch_spans: Vec<BranchSpan>,
+    match_arm_spans: Vec<MatchArmSpan>,
+    /// The `!` operators that apply to the condition being lowered, if any.
+    not_info: Option<NotInfo>,
+    mcdc: Option<MCDCInfoBuilder>,
+}
+
+/// The `!` operators that apply directly (possibly through parentheses) to a branch condition.
+/// The branch of such a condition covers all of them, like `!!x`, rather than just `x`.
+#[derive(Clone, Copy)]
+pub(crate) struct NotInfo {
+    /// The span of the outermost `!`.
+    enclosing_not_span: Span,
+    /// Whether an odd number of `!` apply, so that the condition is true when `x` is false.
+    is_flipped: bool,
+}
+
+struct MCDCInfoBuilder {
+    decision_spans: Vec<MCDCDecisionSpan>,
+    /// The decisions whose conditions are being lowered, innermost last. The conditions of a
+    /// decision can contain other decisions, as in `if a && (if b && c { d } else { e }) {}`.
+    decision_stack: Vec<MCDCDecision>,
+}
+
+struct MCDCDecision {
+    /// Index of the decision in [`MCDCInfoBuilder::decision_spans`].
+    index: usize,
+    /// The leaf conditions of the decision, with their positions in it.
+    conditions: Vec<(ExprId, ConditionInfo)>,
+}
+
+impl BranchInfoBuilder {
+    /// Creates a new branch info builder, but only if branch coverage instrumentation is enabled
+    /// and `def_id` represents a function that is eligible for coverage.
+    pub(crate) fn new_if_enabled(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Option<Self> {
+        if !tcx.sess.instrument_coverage_branch()
+            || !tcx.hir().body_owner_kind(def_id).is_fn_or_closure()
+        {
+            return None;
+        }
+
+        let mcdc = tcx
+            .sess
+            .instrument_coverage_mcdc()
+            .then(|| MCDCInfoBuilder { decision_spans: Vec::new(), decision_stack: Vec::new() });
+        Some(Self {
+            num_block_markers: 0,
+            branch_spans: Vec::new(),
+            match_arm_spans: Vec::new(),
+            not_info: None,
+            mcdc,
+        })
+    }
+
+    fn inject_block_marker(
+        &mut self,
+        cfg: &mut CFG<'_>,
+        source_info: SourceInfo,
+        block: BasicBlock,
+    ) -> BlockMarkerId {
+        let id = BlockMarkerId::from_usize(self.num_block_markers);
+        self.num_block_markers += 1;
+
+        let marker_statement = Statement {
+            source_info,
+            kind: StatementKind::Coverage(Box::new(Coverage {
+                kind: CoverageKind::BlockMarker { id },
+                code_region: None,
+            })),
+        };
+        cfg.push(block, marker_statement);
+
+        id
+    }
+
+    pub(crate) fn into_done(self) -> Option<Box<BranchInfo>> {
+        let Self { num_block_markers, branch_spans, match_arm_spans, mcdc, .. } = self;
+
+        if num_block_markers == 0 {
+            assert!(branch_spans.is_empty() && match_arm_spans.is_empty());
+            return None;
+        }
+
+        let mcdc_decision_spans = mcdc.map(|mcdc| mcdc.decision_spans).unwrap_or_default();
+        Some(Box::new(BranchInfo {
+            num_block_markers,
+            branch_spans,
+            match_arm_spans,
+            mcdc_decision_spans,
+        }))
+    }
+}
+
+impl MCDCInfoBuilder {
+    /// The position of `expr` in the innermost decision being lowered, if it is one of its
+    /// conditions. Conditions of other decisions, and boolean expressions nested in a condition,
+    /// are not part of the decision.
+    fn condition_info(&self, thir: &Thir<'_>, expr: &Expr<'_>) -> Option<ConditionInfo> {
+        let decision = self.decision_stack.last()?;
+        decision
+            .conditions
+            .iter()
+            .find(|&&(condition, _)| std::ptr::eq(&thir[condition], expr))
+            .map(|&(_, condition_info)| condition_info)
+    }
+}
+
+/// Collects the leaf conditions of the decision `expr`, in evaluation order, with the IDs of the
+/// conditions that are evaluated after them. The IDs are assigned the same way as clang does:
+/// conditions are numbered from 1 as they are reached, and 0 stands for "the decision is decided".
+///
+/// Returns `false` if `expr` can't be a decision, because it contains a `let`.
+fn collect_conditions(
+    thir: &Thir<'_>,
+    expr: ExprId,
+    info: ConditionInfo,
+    next_id: &mut u32,
+    conditions: &mut Vec<(ExprId, ConditionInfo)>,
+) -> bool {
+    match thir[expr].kind {
+        ExprKind::Scope { value: inner, .. } | ExprKind::Use { source: inner } => {
+            collect_conditions(thir, inner, info, next_id, conditions)
+        }
+        ExprKind::LogicalOp { op, lhs, rhs } => {
+            *next_id += 1;
+            let rhs_id = *next_id;
+            let (lhs_info, rhs_info) = match op {
+                LogicalOp::And => (
+                    ConditionInfo { true_next_id: rhs_id, ..info },
+                    ConditionInfo { condition_id: rhs_id, ..info },
+                ),
+                LogicalOp::Or => (
+                    ConditionInfo { false_next_id: rhs_id, ..info },
+                    ConditionInfo { condition_id: rhs_id, ..info },
+                ),
+            };
+            collect_conditions(thir, lhs, lhs_info, next_id, conditions)
+                && collect_conditions(thir, rhs, rhs_info, next_id, conditions)
+        }
+        ExprKind::Unary { op: UnOp::Not, arg } => {
+            if is_leaf_condition(thir, arg) {
+                // The condition is `!x` as a whole, see `NotInfo`.
+                collect_conditions(thir, arg, info, next_id, conditions)
+            } else {
+                let info = ConditionInfo {
+                    true_next_id: info.false_next_id,
+                    false_next_id: info.true_next_id,
+                    ..info
+                };
+                collect_conditions(thir, arg, info, next_id, conditions)
+            }
+        }
+        ExprKind::Let { .. } => false,
+        _ => {
+            conditions.push((expr, info));
+            true
+        }
+    }
+}
+
+/// Whether `expr` is a leaf condition, possibly wrapped in `!` and parentheses.
+fn is_leaf_condition(thir: &Thir<'_>, expr: ExprId) -> bool {
+    match thir[expr].kind {
+        ExprKind::Scope { value: inner, .. }
+        | ExprKind::Use { source: inner }
+        | ExprKind::Unary { op: UnOp::Not, arg: inner } => is_leaf_condition(thir, inner),
+        ExprKind::LogicalOp { .. } | ExprKind::Let { .. } => false,
+        _ => true,
+    }
+}
+
+impl<'tcx> Builder<'_, 'tcx> {
+    /// Called when lowering a `!` condition, to make the branch of the condition it applies to
+    /// cover it. Returns the previous state, to be restored with
+    /// [`Self::restore_coverage_not_info`] once the operand is lowered.
+    pub(crate) fn visit_coverage_unary_not(&mut self, not_span: Span) -> Option<NotInfo> {
+        let branch_info = self.coverage_branch_info.as_mut()?;
+        let outer = branch_info.not_info;
+        branch_info.not_info = Some(match outer {
+            Some(NotInfo { enclosing_not_span, is_flipped }) => {
+                NotInfo { enclosing_not_span, is_flipped: !is_flipped }
+            }
+            None => NotInfo { enclosing_not_span: not_span, is_flipped: true },
+        });
+        outer
+    }
+
+    /// Takes the `!` operators that apply to the expression being lowered, so that they don't
+    /// apply to the conditions nested in it.
+    pub(crate) fn take_coverage_not_info(&mut self) -> Option<NotInfo> {
+        self.coverage_branch_info.as_mut()?.not_info.take()
+    }
+
+    pub(crate) fn restore_coverage_not_info(&mut self, not_info: Option<NotInfo>) {
+        if let Some(branch_info) = self.coverage_branch_info.as_mut() {
+            branch_info.not_info = not_info;
+        }
+    }
+
+    /// Records a branch for the leaf condition `expr`, which continues in `then_block` if it is
+    /// true, and in `else_block` if it is false.
+    pub(crate) fn visit_coverage_branch_condition(
+        &mut self,
+        expr: &Expr<'tcx>,
+        not_info: Option<NotInfo>,
+        then_block: BasicBlock,
+        else_block: BasicBlock,
+    ) {
+        let Some(branch_info) = self.coverage_branch_info.as_mut() else { return };
+
+        // Branches in the expansion of a macro, and in desugarings, don't correspond to
+        // conditions the user wrote.
+        if expr.span.from_expansion() {
+            return;
+        }
+
+        let source_info = SourceInfo { span: expr.span, scope: self.source_scope };
+        let then_marker = branch_info.inject_block_marker(&mut self.cfg, source_info, then_block);
+        let else_marker = branch_info.inject_block_marker(&mut self.cfg, source_info, else_block);
+
+        let (span, true_marker, false_marker) = match not_info {
+            Some(NotInfo { enclosing_not_span, is_flipped: true }) => {
+                (enclosing_not_span, else_marker, then_marker)
+            }
+            Some(NotInfo { enclosing_not_span, is_flipped: false }) => {
+                (enclosing_not_span, then_marker, else_marker)
+            }
+            None => (expr.span, then_marker, else_marker),
+        };
+
+        let condition_info =
+            branch_info.mcdc.as_ref().and_then(|mcdc| mcdc.condition_info(self.thir, expr));
+        branch_info.branch_spans.push(BranchSpan {
+            span,
+            true_marker,
+            false_marker,
+            condition_info,
+        });
+    }
+
+    /// Starts an MC/DC decision for the condition `cond` of an `if`, whose evaluation starts in
+    /// `block`. Returns the index of the decision, if it is one: a decision has between 2 and
+    /// [`MAX_CONDITIONS_IN_DECISION`] conditions combined with `&&`, `||` and `!`, and no `let`.
+    pub(crate) fn visit_coverage_mcdc_decision(
+        &mut self,
+        block: BasicBlock,
+        cond: ExprId,
+    ) -> Option<usize> {
+        let branch_info = self.coverage_branch_info.as_mut()?;
+        let span = self.thir[cond].span;
+        if branch_info.mcdc.is_none() || span.from_expansion() {
+            return None;
+        }
+
+        let decision_depth = branch_info.mcdc.as_ref().unwrap().decision_stack.len() as u16;
+        let mut conditions = Vec::new();
+        let root_info =
+            ConditionInfo { condition_id: 1, true_next_id: 0, false_next_id: 0, decision_depth };
+        if !collect_conditions(self.thir, cond, root_info, &mut 1, &mut conditions)
+            || conditions.len() < 2
+        {
+            return None;
+        }
+        if conditions.len() > MAX_CONDITIONS_IN_DECISION {
+            self.tcx.sess.emit_warning(MCDCExceedsConditionLimit {
+                span,
+                num_conditions: conditions.len(),
+                max_conditions: MAX_CONDITIONS_IN_DECISION,
+            });
+            return None;
+        }
+
+        let source_info = SourceInfo { span, scope: self.source_scope };
+        let start_marker = branch_info.inject_block_marker(&mut self.cfg, source_info, block);
+        let mcdc = branch_info.mcdc.as_mut().unwrap();
+        let index = mcdc.decision_spans.len();
+        mcdc.decision_spans.push(MCDCDecisionSpan {
+            span,
+            num_conditions: conditions.len() as u16,
+            decision_depth,
+            start_marker,
+            end_markers: Vec::new(),
+        });
+        mcdc.decision_stack.push(MCDCDecision { index, conditions });
+        Some(index)
+    }
+
+    /// Marks `block` as one of the blocks the MC/DC decision `decision` continues in once it is
+    /// decided. The first call ends the lowering of the conditions of the decision.
+    pub(crate) fn visit_coverage_mcdc_decision_end(
+        &mut self,
+        decision: Option<usize>,
+        block: BasicBlock,
+    ) {
+        let Some(index) = decision else { return };
+        let Some(branch_info) = self.coverage_branch_info.as_mut() else { return };
+        let mcdc = branch_info.mcdc.as_mut().expect("MC/DC decisions require MC/DC coverage");
+        if mcdc.decision_stack.last().is_some_and(|decision| decision.index == index) {
+            mcdc.decision_stack.pop();
+        }
+
+        let span = mcdc.decision_spans[index].span;
+        let source_info = SourceInfo { span, scope: self.source_scope };
+        let end_marker = branch_info.inject_block_marker(&mut self.cfg, source_info, block);
+        let mcdc = branch_info.mcdc.as_mut().unwrap();
+        mcdc.decision_spans[index].end_markers.push(end_marker);
+    }
+
+    /// Marks `block`, which is entered every time the `match` expression `span` is, so that the
+    /// branches of its arms can be counted. Returns `None` for matches that don't have arms to
+    /// choose from, or that come from a desugaring like `?` or `for`.
+    pub(crate) fn visit_coverage_match(
+        &mut self,
+        span: Span,
+        block: BasicBlock,
+        num_arms: usize,
+    ) -> Option<BlockMarkerId> {
+        let branch_info = self.coverage_branch_info.as_mut()?;
+        if num_arms < 2 || span.from_expansion() {
+            return None;
+        }
+
+        let source_info = SourceInfo { span, scope: self.source_scope };
+        Some(branch_info.inject_block_marker(&mut self.cfg, source_info, block))
+    }
+
+    /// Records a branch for `arm`, whose body is entered from `arm_block`.
+    pub(crate) fn visit_coverage_match_arm(
+        &mut self,
+        match_marker: Option<BlockMarkerId>,
+        arm: &Arm<'tcx>,
+        arm_block: BasicBlock,
+    ) {
+        let Some(match_marker) = match_marker else { return };
+        let Some(branch_info) = self.coverage_branch_info.as_mut() else { return };
+
+        let span = arm.pattern.span;
+        let source_info = SourceInfo { span, scope: self.source_scope };
+        let arm_marker = branch_info.inject_block_marker(&mut self.cfg, source_info, arm_block);
+        branch_info.match_arm_spans.push(MatchArmSpan { span, match_marker, arm_marker });
+    }
+}
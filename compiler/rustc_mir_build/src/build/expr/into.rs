@@ -55,6 +55,7 @@ pub(crate) fn expr_into_dest(
                 let then_expr = &this.thir[then];
                 let then_source_info = this.source_info(then_expr.span);
                 let condition_scope = this.local_scope();
+                let mut mcdc_decision = None;
 
                 let mut else_blk = unpack!(
                     then_blk = this.in_scope(
@@ -74,6 +75,7 @@ pub(crate) fn expr_into_dest(
                             };
                             let (then_block, else_block) =
                                 this.in_if_then_scope(condition_scope, then_expr.span, |this| {
+                                    mcdc_decision = this.visit_coverage_mcdc_decision(block, cond);
                                     let then_blk = unpack!(this.then_else_break(
                                         block,
                                         &this.thir[cond],
@@ -81,6 +83,7 @@ pub(crate) fn expr_into_dest(
                                         condition_scope,
                                         source_info
                                     ));
+                                    this.visit_coverage_mcdc_decision_end(mcdc_decision, then_blk);
 
                                     this.expr_into_dest(destination, then_blk, then_expr)
                                 });
@@ -88,6 +91,7 @@ pub(crate) fn expr_into_dest(
                         },
                     )
                 );
+                this.visit_coverage_mcdc_decision_end(mcdc_decision, else_blk);
 
                 else_blk = if let Some(else_opt) = else_opt {
                     unpack!(this.expr_into_dest(destination, else_blk, &this.thir[else_opt]))
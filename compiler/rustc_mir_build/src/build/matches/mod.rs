@@ -16,6 +16,7 @@
 };
 use rustc_index::bit_set::BitSet;
 use rustc_middle::middle::region;
+use rustc_middle::mir::coverage::BlockMarkerId;
 use rustc_middle::mir::*;
 use rustc_middle::thir::{self, *};
 use rustc_middle::ty::{self, CanonicalUserTypeAnnotation, Ty};
@@ -46,6 +47,7 @@ pub(crate) fn then_else_break(
 
         match expr.kind {
             ExprKind::LogicalOp { op: LogicalOp::And, lhs, rhs } => {
+                let outer_not_info = this.take_coverage_not_info();
                 let lhs_then_block = unpack!(this.then_else_break(
                     block,
                     &this.thir[lhs],
@@ -61,10 +63,12 @@ pub(crate) fn then_else_break(
                     break_scope,
                     variable_source_info,
                 ));
+                this.restore_coverage_not_info(outer_not_info);
 
                 rhs_then_block.unit()
             }
             ExprKind::LogicalOp { op: LogicalOp::Or, lhs, rhs } => {
+                let outer_not_info = this.take_coverage_not_info();
                 let local_scope = this.local_scope();
                 let (lhs_success_block, failure_block) =
                     this.in_if_then_scope(local_scope, expr_span, |this| {
@@ -83,10 +87,21 @@ pub(crate) fn then_else_break(
                     break_scope,
                     variable_source_info,
                 ));
-                this.cfg.goto(lhs_success_block, variable_source_info, rhs_success_block);
-                rhs_success_block.unit()
+                this.restore_coverage_not_info(outer_not_info);
+                if this.coverage_branch_info.is_some() {
+                    // The success block of `rhs` is also where its branch counts being true, so
+                    // the success of `lhs` must not go through it.
+                    let success_block = this.cfg.start_new_block();
+                    this.cfg.goto(lhs_success_block, variable_source_info, success_block);
+                    this.cfg.goto(rhs_success_block, variable_source_info, success_block);
+                    success_block.unit()
+                } else {
+                    this.cfg.goto(lhs_success_block, variable_source_info, rhs_success_block);
+                    rhs_success_block.unit()
+                }
             }
             ExprKind::Unary { op: UnOp::Not, arg } => {
+                let outer_not_info = this.visit_coverage_unary_not(expr_span);
                 let local_scope = this.local_scope();
                 let (success_block, failure_block) =
                     this.in_if_then_scope(local_scope, expr_span, |this| {
@@ -98,6 +113,7 @@ pub(crate) fn then_else_break(
                             variable_source_info,
                         )
                     });
+                this.restore_coverage_not_info(outer_not_info);
                 this.break_for_else(success_block, break_scope, variable_source_info);
                 failure_block.unit()
             }
@@ -130,6 +146,7 @@ pub(crate) fn then_else_break(
                 true,
             ),
             _ => {
+                let not_info = this.take_coverage_not_info();
                 let temp_scope = temp_scope_override.unwrap_or_else(|| this.local_scope());
                 let mutability = Mutability::Mut;
                 let place =
@@ -142,6 +159,7 @@ pub(crate) fn then_else_break(
 
                 let source_info = this.source_info(expr_span);
                 this.cfg.terminate(block, source_info, term);
+                this.visit_coverage_branch_condition(expr, not_info, then_block, else_block);
                 this.break_for_else(else_block, break_scope, source_info);
 
                 then_block.unit()
@@ -219,6 +237,7 @@ pub(crate) fn match_expr(
             arm_candidates.iter_mut().map(|(_, candidate)| candidate).collect::<Vec<_>>();
 
         let match_start_span = span.shrink_to_lo().to(scrutinee.span);
+        let match_coverage_marker = self.visit_coverage_match(span, block, arms.len());
 
         let fake_borrow_temps = self.lower_match_tree(
             block,
@@ -235,6 +254,7 @@ pub(crate) fn match_expr(
             arm_candidates,
             self.source_info(span),
             fake_borrow_temps,
+            match_coverage_marker,
         )
     }
 
@@ -367,6 +387,7 @@ fn lower_match_arms(
         arm_candidates: Vec<(&'_ Arm<'tcx>, Candidate<'_, 'tcx>)>,
         outer_source_info: SourceInfo,
         fake_borrow_temps: Vec<(Place<'tcx>, Local)>,
+        match_coverage_marker: Option<BlockMarkerId>,
     ) -> BlockAnd<()> {
         let arm_end_blocks: Vec<_> = arm_candidates
             .into_iter()
@@ -407,6 +428,7 @@ fn lower_match_arms(
                         Some((arm, match_scope)),
                         false,
                     );
+                    this.visit_coverage_match_arm(match_coverage_marker, arm, arm_block);
 
                     if let Some(source_scope) = scope {
                         this.source_scope = source_scope;
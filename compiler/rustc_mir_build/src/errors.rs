@@ -561,6 +561,16 @@ pub struct LowerRangeBoundMustBeLessThanUpper {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(mir_build_mcdc_exceeds_condition_limit)]
+#[note]
+pub(crate) struct MCDCExceedsConditionLimit {
+    #[primary_span]
+    pub span: Span,
+    pub num_conditions: usize,
+    pub max_conditions: usize,
+}
+
 #[derive(LintDiagnostic)]
 #[diag(mir_build_leading_irrefutable_let_patterns)]
 #[note]
@@ -58,6 +58,7 @@ fn add_args(
         throw_machine_stop!(Zst)
     }};
 }
+pub(crate) use throw_machine_stop_str;
 
 pub struct ConstProp;
 
@@ -160,6 +161,11 @@ fn enforce_alignment(_ecx: &InterpCx<'mir, 'tcx, Self>) -> CheckAlignment {
     fn enforce_validity(_ecx: &InterpCx<'mir, 'tcx, Self>, _layout: TyAndLayout<'tcx>) -> bool {
         false // for now, we don't enforce validity
     }
+
+    fn ub_checks(_ecx: &InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx, bool> {
+        // The value depends on the crate that the function is codegened in.
+        throw_machine_stop_str!("`UbChecks` can't be evaluated in ConstProp")
+    }
     fn alignment_check_failed(
         ecx: &InterpCx<'mir, 'tcx, Self>,
         _has: Align,
@@ -0,0 +1,175 @@
+use super::graph::{BasicCoverageBlock, CoverageGraph};
+
+use rustc_index::IndexVec;
+use rustc_middle::mir::coverage::{
+    mcdc_bitmap_bytes, BlockMarkerId, BranchInfo, ConditionInfo, CoverageKind,
+};
+use rustc_middle::mir::{self, Coverage, Location, StatementKind};
+use rustc_span::Span;
+
+/// A branch of a condition, which is taken as often as `true_bcb` is entered, and not taken as
+/// often as `false_bcb` is entered.
+#[derive(Debug)]
+pub(super) struct BcbBranch {
+    pub(super) span: Span,
+    pub(super) true_bcb: BasicCoverageBlock,
+    pub(super) false_bcb: BasicCoverageBlock,
+    pub(super) condition_info: Option<ConditionInfo>,
+}
+
+/// An arm of a `match`, which is taken as often as `arm_bcb` is entered, and not taken the
+/// remaining times that `match_bcb` is entered.
+#[derive(Debug)]
+pub(super) struct BcbMatchArm {
+    pub(super) span: Span,
+    pub(super) match_bcb: BasicCoverageBlock,
+    pub(super) arm_bcb: BasicCoverageBlock,
+}
+
+#[derive(Debug)]
+pub(super) struct BcbMCDCDecision {
+    pub(super) span: Span,
+    pub(super) bitmap_idx: u32,
+    pub(super) num_conditions: u16,
+}
+
+/// The branch and MC/DC mappings of a function, with the block markers that MIR building
+/// injected resolved to the `BasicCoverageBlock`s they mark.
+#[derive(Debug, Default)]
+pub(super) struct BranchMappings {
+    pub(super) branches: Vec<BcbBranch>,
+    pub(super) match_arms: Vec<BcbMatchArm>,
+    pub(super) mcdc_decisions: Vec<BcbMCDCDecision>,
+}
+
+impl BranchMappings {
+    /// The `BasicCoverageBlock`s whose counters the mappings refer to, which need counters even
+    /// if they have no `CoverageSpan`s.
+    pub(super) fn bcbs_needing_counters(&self) -> Vec<BasicCoverageBlock> {
+        let mut bcbs = Vec::new();
+        for branch in &self.branches {
+            bcbs.extend([branch.true_bcb, branch.false_bcb]);
+        }
+        for arm in &self.match_arms {
+            bcbs.extend([arm.match_bcb, arm.arm_bcb]);
+        }
+        bcbs
+    }
+}
+
+/// Resolves the branch coverage information that MIR building attached to `mir_body`. Branches
+/// whose spans lie outside of `body_span`, or whose blocks were removed as unreachable, are
+/// dropped.
+///
+/// If MC/DC decisions were recorded, this also replaces the block markers of the decisions and
+/// their conditions with the statements that update the condition and test vector bitmaps. As
+/// LLVM requires a decision to have a branch mapping for each of its conditions, MC/DC is given up
+/// for the whole function if any of them can't be resolved.
+pub(super) fn extract_branch_mappings(
+    mir_body: &mut mir::Body<'_>,
+    body_span: Span,
+    basic_coverage_blocks: &CoverageGraph,
+) -> BranchMappings {
+    let Some(branch_info) = mir_body.coverage_branch_info.clone() else {
+        return BranchMappings::default();
+    };
+    let BranchInfo { num_block_markers, ref branch_spans, ref match_arm_spans, .. } = *branch_info;
+
+    // Find the location of each block marker that still exists in the body.
+    let mut marker_locations =
+        IndexVec::<BlockMarkerId, Option<Location>>::from_elem_n(None, num_block_markers);
+    for (block, data) in mir_body.basic_blocks.iter_enumerated() {
+        for (statement_index, statement) in data.statements.iter().enumerate() {
+            if let StatementKind::Coverage(box Coverage {
+                kind: CoverageKind::BlockMarker { id },
+                ..
+            }) = statement.kind
+            {
+                marker_locations[id] = Some(Location { block, statement_index });
+            }
+        }
+    }
+    let marker_bcb = |marker: BlockMarkerId| {
+        let location: Location = marker_locations[marker]?;
+        basic_coverage_blocks.bcb_from_bb(location.block)
+    };
+    let resolve_span =
+        |span: Span| span.find_ancestor_inside(body_span).filter(|span| span.eq_ctxt(body_span));
+
+    let mut mappings = BranchMappings::default();
+    let mut mcdc_is_complete = true;
+    for branch_span in branch_spans {
+        let branch: Option<_> = try {
+            BcbBranch {
+                span: resolve_span(branch_span.span)?,
+                true_bcb: marker_bcb(branch_span.true_marker)?,
+                false_bcb: marker_bcb(branch_span.false_marker)?,
+                condition_info: branch_span.condition_info,
+            }
+        };
+        match branch {
+            Some(branch) => mappings.branches.push(branch),
+            None => mcdc_is_complete &= branch_span.condition_info.is_none(),
+        }
+    }
+    for arm_span in match_arm_spans {
+        let arm: Option<_> = try {
+            BcbMatchArm {
+                span: resolve_span(arm_span.span)?,
+                match_bcb: marker_bcb(arm_span.match_marker)?,
+                arm_bcb: marker_bcb(arm_span.arm_marker)?,
+            }
+        };
+        mappings.match_arms.extend(arm);
+    }
+
+    let decision_spans = &branch_info.mcdc_decision_spans;
+    mcdc_is_complete &= decision_spans.iter().all(|decision| {
+        resolve_span(decision.span).is_some() && marker_locations[decision.start_marker].is_some()
+    });
+    if !mcdc_is_complete {
+        for branch in &mut mappings.branches {
+            branch.condition_info = None;
+        }
+        return mappings;
+    }
+
+    let mut replace_marker = |marker: BlockMarkerId, kind: CoverageKind| {
+        if let Some(location) = marker_locations[marker] {
+            let statement = &mut mir_body[location.block].statements[location.statement_index];
+            statement.kind =
+                StatementKind::Coverage(Box::new(Coverage { kind, code_region: None }));
+        }
+    };
+
+    let mut next_bitmap_idx = 0;
+    for decision in decision_spans {
+        let bitmap_idx = next_bitmap_idx;
+        next_bitmap_idx += mcdc_bitmap_bytes(decision.num_conditions);
+
+        let decision_depth = decision.decision_depth;
+        replace_marker(decision.start_marker, CoverageKind::CondBitmapReset { decision_depth });
+        for &end_marker in &decision.end_markers {
+            replace_marker(
+                end_marker,
+                CoverageKind::TestVectorBitmapUpdate { bitmap_idx, decision_depth },
+            );
+        }
+        mappings.mcdc_decisions.push(BcbMCDCDecision {
+            span: resolve_span(decision.span).unwrap(),
+            bitmap_idx,
+            num_conditions: decision.num_conditions,
+        });
+    }
+    for branch_span in branch_spans {
+        if let Some(ConditionInfo { condition_id, decision_depth, .. }) = branch_span.condition_info
+        {
+            replace_marker(
+                branch_span.true_marker,
+                CoverageKind::CondBitmapUpdate { id: condition_id - 1, decision_depth },
+            );
+        }
+    }
+
+    mappings
+}
@@ -103,14 +103,25 @@ pub fn enable_debug(&mut self) {
     }
 
     /// Makes [`BcbCounter`] `Counter`s and `Expressions` for the `BasicCoverageBlock`s directly or
-    /// indirectly associated with `CoverageSpans`, and accumulates additional `Expression`s
-    /// representing intermediate values.
+    /// indirectly associated with `CoverageSpans` or branch mappings (`branch_bcbs`), and
+    /// accumulates additional `Expression`s representing intermediate values.
     pub fn make_bcb_counters(
         &mut self,
         basic_coverage_blocks: &CoverageGraph,
         coverage_spans: &[CoverageSpan],
+        branch_bcbs: &[BasicCoverageBlock],
     ) -> Result<(), Error> {
-        MakeBcbCounters::new(self, basic_coverage_blocks).make_bcb_counters(coverage_spans)
+        MakeBcbCounters::new(self, basic_coverage_blocks)
+            .make_bcb_counters(coverage_spans, branch_bcbs)
+    }
+
+    /// Makes an `Expression` that only a branch mapping refers to, and accumulates it with the
+    /// other intermediate expressions.
+    pub(super) fn make_branch_expression(&mut self, lhs: Operand, op: Op, rhs: Operand) -> Operand {
+        let expression = self.make_expression(lhs, op, rhs, || None);
+        let operand = expression.as_operand();
+        self.intermediate_expressions.push(expression);
+        operand
     }
 
     fn make_counter<F>(&mut self, debug_block_label_fn: F) -> BcbCounter
@@ -270,7 +281,11 @@ fn new(
     /// Returns any non-code-span expressions created to represent intermediate values (such as to
     /// add two counters so the result can be subtracted from another counter), or an Error with
     /// message for subsequent debugging.
-    fn make_bcb_counters(&mut self, coverage_spans: &[CoverageSpan]) -> Result<(), Error> {
+    fn make_bcb_counters(
+        &mut self,
+        coverage_spans: &[CoverageSpan],
+        branch_bcbs: &[BasicCoverageBlock],
+    ) -> Result<(), Error> {
         debug!("make_bcb_counters(): adding a counter or expression to each BasicCoverageBlock");
         let num_bcbs = self.basic_coverage_blocks.num_nodes();
 
@@ -278,6 +293,9 @@ fn make_bcb_counters(&mut self, coverage_spans: &[CoverageSpan]) -> Result<(), E
         for covspan in coverage_spans {
             bcbs_with_coverage.insert(covspan.bcb);
         }
+        for &bcb in branch_bcbs {
+            bcbs_with_coverage.insert(bcb);
+        }
 
         // Walk the `CoverageGraph`. For each `BasicCoverageBlock` node with an associated
         // `CoverageSpan`, add a counter. If the `BasicCoverageBlock` branches, add a counter or
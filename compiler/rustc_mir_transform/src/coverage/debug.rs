@@ -514,6 +514,15 @@ pub fn add_expression_operands(&mut self, expression: &BcbCounter) {
         }
     }
 
+    /// Records that a branch mapping refers to `counter_kind`, which makes it used even if it is
+    /// not associated with a coverage span.
+    pub fn add_branch_mapping_operand(&mut self, counter_kind: &BcbCounter) {
+        if let Some(used_expression_operands) = self.some_used_expression_operands.as_mut() {
+            used_expression_operands.entry(counter_kind.as_operand()).or_insert_with(Vec::new);
+        }
+        self.add_expression_operands(counter_kind);
+    }
+
     pub fn expression_is_used(&self, expression: &BcbCounter) -> bool {
         if let Some(used_expression_operands) = self.some_used_expression_operands.as_ref() {
             used_expression_operands.contains_key(&expression.as_operand())
@@ -1,5 +1,6 @@
 pub mod query;
 
+mod branches;
 mod counters;
 mod debug;
 mod graph;
@@ -8,6 +9,7 @@
 #[cfg(test)]
 mod tests;
 
+use self::branches::BranchMappings;
 use self::counters::{BcbCounter, CoverageCounters};
 use self::graph::{BasicCoverageBlock, BasicCoverageBlockData, CoverageGraph};
 use self::spans::{CoverageSpan, CoverageSpans};
@@ -196,17 +198,30 @@ fn inject_counters(&'a mut self) {
             );
         }
 
+        ////////////////////////////////////////////////////
+        // Resolve the branches that MIR building recorded for branch coverage, if enabled, to the
+        // `BasicCoverageBlock`s whose counters count how often they are taken.
+        let branch_mappings = branches::extract_branch_mappings(
+            self.mir_body,
+            body_span,
+            &self.basic_coverage_blocks,
+        );
+
         ////////////////////////////////////////////////////
         // Create an optimized mix of `Counter`s and `Expression`s for the `CoverageGraph`. Ensure
-        // every `CoverageSpan` has a `Counter` or `Expression` assigned to its `BasicCoverageBlock`
-        // and all `Expression` dependencies (operands) are also generated, for any other
-        // `BasicCoverageBlock`s not already associated with a `CoverageSpan`.
+        // every `CoverageSpan` and branch mapping has a `Counter` or `Expression` assigned to its
+        // `BasicCoverageBlock`s and all `Expression` dependencies (operands) are also generated,
+        // for any other `BasicCoverageBlock`s not already associated with a `CoverageSpan`.
         //
         // Intermediate expressions (used to compute other `Expression` values), which have no
         // direct association with any `BasicCoverageBlock`, are accumulated inside `coverage_counters`.
-        let result = self
-            .coverage_counters
-            .make_bcb_counters(&mut self.basic_coverage_blocks, &coverage_spans);
+        let result = self.coverage_counters.make_bcb_counters(
+            &mut self.basic_coverage_blocks,
+            &coverage_spans,
+            &branch_mappings.bcbs_needing_counters(),
+        );
+
+        let mut branch_mapping_kinds = Vec::new();
 
         if let Ok(()) = result {
             // If debugging, add any intermediate expressions (which are not associated with any
@@ -217,6 +232,12 @@ fn inject_counters(&'a mut self) {
                 }
             }
 
+            ////////////////////////////////////////////////////
+            // Look up the counters of the branch mappings while every `BasicCoverageBlock` still
+            // has its counter, before they are injected.
+            branch_mapping_kinds =
+                self.make_branch_mapping_kinds(&branch_mappings, &mut debug_used_expressions);
+
             ////////////////////////////////////////////////////
             // Remove the counter or edge counter from of each `CoverageSpan`s associated
             // `BasicCoverageBlock`, and inject a `Coverage` statement into the MIR.
@@ -271,13 +292,73 @@ fn inject_counters(&'a mut self) {
         debug_used_expressions.alert_on_unused_expressions(&self.coverage_counters.debug_counters);
 
         ////////////////////////////////////////////////////
-        // Finally, inject the intermediate expressions collected along the way.
+        // Finally, inject the intermediate expressions and branch mappings collected along the way.
         for intermediate_expression in &self.coverage_counters.intermediate_expressions {
             inject_intermediate_expression(
                 self.mir_body,
                 self.make_mir_coverage_kind(intermediate_expression),
             );
         }
+        for (mapping_kind, code_region) in branch_mapping_kinds {
+            inject_mapping_statement(self.mir_body, mapping_kind, code_region);
+        }
+    }
+
+    /// Makes the `Branch` and `MCDCDecision` mappings, with their code regions, for the branches
+    /// and decisions in `branch_mappings`. The `false_term` of a `match` arm is computed by an
+    /// intermediate expression that subtracts the count of the arm from the count of the `match`.
+    fn make_branch_mapping_kinds(
+        &mut self,
+        branch_mappings: &BranchMappings,
+        debug_used_expressions: &mut debug::UsedExpressions,
+    ) -> Vec<(CoverageKind, CodeRegion)> {
+        let source_map = self.tcx.sess.source_map();
+        let body_span = self.body_span;
+        let file_name = Symbol::intern(&self.source_file.name.prefer_remapped().to_string_lossy());
+        let code_region = |span| make_code_region(source_map, file_name, span, body_span);
+
+        let mut mapping_kinds = Vec::new();
+        for branch in &branch_mappings.branches {
+            let true_term = self.bcb_operand(branch.true_bcb, debug_used_expressions);
+            let false_term = self.bcb_operand(branch.false_bcb, debug_used_expressions);
+            let condition_info = branch.condition_info;
+            mapping_kinds.push((
+                CoverageKind::Branch { true_term, false_term, condition_info },
+                code_region(branch.span),
+            ));
+        }
+        for arm in &branch_mappings.match_arms {
+            let match_term = self.bcb_operand(arm.match_bcb, debug_used_expressions);
+            let true_term = self.bcb_operand(arm.arm_bcb, debug_used_expressions);
+            let false_term =
+                self.coverage_counters.make_branch_expression(match_term, Op::Subtract, true_term);
+            mapping_kinds.push((
+                CoverageKind::Branch { true_term, false_term, condition_info: None },
+                code_region(arm.span),
+            ));
+        }
+        for decision in &branch_mappings.mcdc_decisions {
+            let bitmap_idx = decision.bitmap_idx;
+            let num_conditions = decision.num_conditions;
+            mapping_kinds.push((
+                CoverageKind::MCDCDecision { bitmap_idx, num_conditions },
+                code_region(decision.span),
+            ));
+        }
+        mapping_kinds
+    }
+
+    /// The operand of the counter of `bcb`, which must have one.
+    fn bcb_operand(
+        &self,
+        bcb: BasicCoverageBlock,
+        debug_used_expressions: &mut debug::UsedExpressions,
+    ) -> Operand {
+        let Some(counter_kind) = self.coverage_counters.bcb_counter(bcb) else {
+            bug!("{bcb:?} of a branch mapping should have a Counter or Expression");
+        };
+        debug_used_expressions.add_branch_mapping_operand(counter_kind);
+        counter_kind.as_operand()
     }
 
     /// Inject a counter for each `CoverageSpan`. There can be multiple `CoverageSpan`s for a given
@@ -505,6 +586,30 @@ fn inject_intermediate_expression(mir_body: &mut mir::Body<'_>, expression: Cove
     data.statements.push(statement);
 }
 
+// Branch mappings are injected into the coverage map, with code regions but without generating
+// executable code.
+fn inject_mapping_statement(
+    mir_body: &mut mir::Body<'_>,
+    mapping_kind: CoverageKind,
+    code_region: CodeRegion,
+) {
+    debug_assert!(matches!(
+        mapping_kind,
+        CoverageKind::Branch { .. } | CoverageKind::MCDCDecision { .. }
+    ));
+    debug!("  injecting mapping {:?} at code region: {:?}", mapping_kind, code_region);
+    let data = &mut mir_body[mir::START_BLOCK];
+    let source_info = data.terminator().source_info;
+    let statement = Statement {
+        source_info,
+        kind: StatementKind::Coverage(Box::new(Coverage {
+            kind: mapping_kind,
+            code_region: Some(code_region),
+        })),
+    };
+    data.statements.push(statement);
+}
+
 /// Convert the Span into its file name, start line and column, and end line and column
 fn make_code_region(
     source_map: &SourceMap,
@@ -19,6 +19,10 @@ pub(crate) fn provide(providers: &mut Providers) {
 ///
 /// `num_expressions` is the number of counter expressions added to the MIR body.
 ///
+/// `mcdc_bitmap_bytes` is the size of the test vector bitmaps of all MC/DC decisions, which are
+/// laid out one after the other, and `mcdc_num_condition_bitmaps` is the number of condition
+/// bitmaps needed to evaluate nested decisions (the maximum decision depth plus 1).
+///
 /// Both `num_counters` and `num_expressions` are used to initialize new vectors, during backend
 /// code generate, to lookup counters and expressions by simple u32 indexes.
 ///
@@ -57,6 +61,19 @@ fn update_num_expressions(&mut self, expression_id: ExpressionId) {
         self.info.num_expressions = std::cmp::max(self.info.num_expressions, expression_id + 1);
     }
 
+    /// Updates `mcdc_bitmap_bytes` to cover the test vector bitmap of a decision.
+    fn update_mcdc_bitmap_bytes(&mut self, bitmap_idx: u32, num_conditions: u16) {
+        let end = bitmap_idx + mcdc_bitmap_bytes(num_conditions);
+        self.info.mcdc_bitmap_bytes = std::cmp::max(self.info.mcdc_bitmap_bytes, end);
+    }
+
+    /// Updates `mcdc_num_condition_bitmaps` to the maximum encountered decision depth plus 1.
+    fn update_mcdc_num_condition_bitmaps(&mut self, decision_depth: u16) {
+        let num_condition_bitmaps = u32::from(decision_depth) + 1;
+        self.info.mcdc_num_condition_bitmaps =
+            std::cmp::max(self.info.mcdc_num_condition_bitmaps, num_condition_bitmaps);
+    }
+
     fn update_from_expression_operand(&mut self, operand: Operand) {
         match operand {
             Operand::Counter(id) => self.update_num_counters(id),
@@ -85,12 +102,27 @@ fn visit_coverage(&mut self, coverage: &Coverage) {
                     self.update_from_expression_operand(lhs);
                     self.update_from_expression_operand(rhs);
                 }
+                CoverageKind::Branch { true_term, false_term, .. } => {
+                    self.update_from_expression_operand(true_term);
+                    self.update_from_expression_operand(false_term);
+                }
                 _ => {}
             }
         } else {
             match coverage.kind {
-                CoverageKind::Counter { id, .. } => self.update_num_counters(id),
+                CoverageKind::Counter { function_source_hash, id } => {
+                    self.info.function_source_hash = function_source_hash;
+                    self.update_num_counters(id)
+                }
                 CoverageKind::Expression { id, .. } => self.update_num_expressions(id),
+                CoverageKind::MCDCDecision { bitmap_idx, num_conditions } => {
+                    self.update_mcdc_bitmap_bytes(bitmap_idx, num_conditions)
+                }
+                CoverageKind::CondBitmapReset { decision_depth }
+                | CoverageKind::CondBitmapUpdate { decision_depth, .. }
+                | CoverageKind::TestVectorBitmapUpdate { decision_depth, .. } => {
+                    self.update_mcdc_num_condition_bitmaps(decision_depth)
+                }
                 _ => {}
             }
         }
@@ -101,7 +133,13 @@ fn coverageinfo<'tcx>(tcx: TyCtxt<'tcx>, instance_def: ty::InstanceDef<'tcx>) ->
     let mir_body = tcx.instance_mir(instance_def);
 
     let mut coverage_visitor = CoverageVisitor {
-        info: CoverageInfo { num_counters: 0, num_expressions: 0 },
+        info: CoverageInfo {
+            num_counters: 0,
+            num_expressions: 0,
+            mcdc_bitmap_bytes: 0,
+            mcdc_num_condition_bitmaps: 0,
+            function_source_hash: 0,
+        },
         add_missing_operands: false,
     };
 
@@ -120,7 +158,13 @@ fn covered_code_regions(tcx: TyCtxt<'_>, def_id: DefId) -> Vec<&CodeRegion> {
         .flat_map(|data| {
             data.statements.iter().filter_map(|statement| match statement.kind {
                 StatementKind::Coverage(box ref coverage) => {
-                    if is_inlined(body, statement) {
+                    // Unused functions only report their code as not covered, not the branch
+                    // and MC/DC decision mappings.
+                    let is_branch_mapping = matches!(
+                        coverage.kind,
+                        CoverageKind::Branch { .. } | CoverageKind::MCDCDecision { .. }
+                    );
+                    if is_inlined(body, statement) || is_branch_mapping {
                         None
                     } else {
                         coverage.code_region.as_ref() // may be None
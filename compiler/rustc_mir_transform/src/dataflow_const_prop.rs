@@ -17,6 +17,7 @@
 use rustc_span::DUMMY_SP;
 use rustc_target::abi::{Align, FieldIdx, VariantIdx};
 
+use crate::const_prop::throw_machine_stop_str;
 use crate::MirPass;
 
 // These constants are somewhat random guesses and have not been optimized.
@@ -506,7 +507,9 @@ fn visit_operand(&mut self, operand: &Operand<'tcx>, location: Location) {
     }
 }
 
-struct DummyMachine;
+/// A machine that can only evaluate pure, side-effect free operations on constants. It is shared
+/// by the optimizations that need to fold values without ever pushing a stack frame.
+pub(crate) struct DummyMachine;
 
 impl<'mir, 'tcx: 'mir> rustc_const_eval::interpret::Machine<'mir, 'tcx> for DummyMachine {
     rustc_const_eval::interpret::compile_time_machine!(<'mir, 'tcx>);
@@ -514,11 +517,18 @@ impl<'mir, 'tcx: 'mir> rustc_const_eval::interpret::Machine<'mir, 'tcx> for Dumm
     const PANIC_ON_ALLOC_FAIL: bool = true;
 
     fn enforce_alignment(_ecx: &InterpCx<'mir, 'tcx, Self>) -> CheckAlignment {
-        unimplemented!()
+        // We do not check for alignment to avoid having to carry an `Align`
+        // in `ConstValue::ByRef`.
+        CheckAlignment::No
     }
 
     fn enforce_validity(_ecx: &InterpCx<'mir, 'tcx, Self>, _layout: TyAndLayout<'tcx>) -> bool {
-        unimplemented!()
+        false // for now, we don't enforce validity
+    }
+
+    fn ub_checks(_ecx: &InterpCx<'mir, 'tcx, Self>) -> interpret::InterpResult<'tcx, bool> {
+        // The value depends on the crate that the function is codegened in.
+        throw_machine_stop_str!("`UbChecks` can't be evaluated in ConstProp")
     }
     fn alignment_check_failed(
         _ecx: &InterpCx<'mir, 'tcx, Self>,
@@ -597,7 +607,8 @@ fn stack<'a>(
         _ecx: &'a InterpCx<'mir, 'tcx, Self>,
     ) -> &'a [rustc_const_eval::interpret::Frame<'mir, 'tcx, Self::Provenance, Self::FrameExtra>]
     {
-        unimplemented!()
+        // Constants may be evaluated through this machine, and those query the current span.
+        &[]
     }
 
     fn stack_mut<'a>(
@@ -206,7 +206,7 @@ fn replace_base<'tcx>(place: &mut Place<'tcx>, new_base: Place<'tcx>, tcx: TyCtx
 /// Number of variants to reserve in generator state. Corresponds to
 /// `UNRESUMED` (beginning of a generator) and `RETURNED`/`POISONED`
 /// (end of a generator) states.
-const RESERVED_VARIANTS: usize = 3;
+const RESERVED_VARIANTS: usize = GeneratorArgs::RESERVED_VARIANTS;
 
 /// A `yield` point in the generator.
 struct SuspensionPoint<'tcx> {
@@ -0,0 +1,824 @@
+//! Global value numbering.
+//!
+//! MIR may contain repeated and/or redundant computations. The objective of this pass is to detect
+//! such redundancies and re-use the already-computed result when possible.
+//!
+//! We traverse the body in reverse postorder, so that each SSA local is assigned before its uses.
+//! For each assignment and operand, we compute a symbolic representation of the value it produces.
+//! This symbolic representation is defined by the `Value` enum. Each produced instance of `Value`
+//! is interned as a `VnIndex`, which allows us to cheaply compare identical values.
+//!
+//! When an SSA local is assigned, we remember the `VnIndex` it holds, and record the local as an
+//! available storage for that value. For each other rvalue and operand:
+//! - if its `VnIndex` can be evaluated to a constant, we replace it by that constant;
+//! - otherwise, if there is an SSA local `y` associated to this `VnIndex`, and if its definition
+//!   location strictly dominates the current location, we replace the rvalue by a copy of `y`.
+//!
+//! By opportunity, this pass simplifies some `Rvalue`s based on the accumulated knowledge:
+//! projections of known aggregates are replaced by the projected field, the discriminant of a
+//! known enum aggregate and the length of an array are replaced by constants, and indexing by a
+//! known constant is turned into a `ConstantIndex` projection. The length of a slice behind a
+//! pointer only depends on the pointer, so repeated length reads through it are deduplicated.
+//!
+//! # Operational semantic
+//!
+//! Operationally, this pass attempts to prove bitwise equality between locals. Given this MIR:
+//! ```ignore (MIR)
+//! _a = some value // has VnIndex i
+//! // some MIR
+//! _b = some other value // also has VnIndex i
+//! ```
+//!
+//! We consider it to be replacable by:
+//! ```ignore (MIR)
+//! _a = some value // has VnIndex i
+//! // some MIR
+//! _c = some other value // also has VnIndex i
+//! _b = _a
+//! ```
+//!
+//! # Handling of references
+//!
+//! We handle references by assigning a different "provenance" index to each `Ref`/`AddressOf`
+//! rvalue. This ensure that we do not spuriously merge borrows that should not be merged. We do
+//! not track the pointees either: each dereference produces an opaque value, as the pointed-to
+//! memory may be modified between two reads.
+//!
+//! # Handling of moves
+//!
+//! When we replace an operand by a copy of `_a`, `_a` may already have been moved out of, possibly
+//! into a call that is allowed to overwrite the moved-from memory. To avoid this, every move out of
+//! a re-used local is turned into a copy. We also remove the storage statements of re-used locals,
+//! as the copies we introduce may extend their live range.
+
+use rustc_const_eval::interpret::{
+    ConstValue, ImmTy, Immediate, InterpCx, MemoryKind, OpTy, Projectable, Scalar,
+};
+use rustc_data_structures::fx::FxIndexSet;
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::IndexVec;
+use rustc_middle::mir::visit::*;
+use rustc_middle::mir::*;
+use rustc_middle::ty::layout::LayoutOf;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::DUMMY_SP;
+use rustc_target::abi::{self, Abi, Size, VariantIdx, FIRST_VARIANT};
+
+use crate::dataflow_const_prop::DummyMachine;
+use crate::ssa::SsaLocals;
+use crate::MirPass;
+
+pub struct GVN;
+
+impl<'tcx> MirPass<'tcx> for GVN {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 4
+    }
+
+    #[instrument(level = "trace", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+        propagate_ssa(tcx, body);
+    }
+}
+
+fn propagate_ssa<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+    let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+    let ssa = SsaLocals::new(body);
+    // Clone dominators as we need them while mutating the body.
+    let dominators = body.basic_blocks.dominators().clone();
+
+    let mut state = VnState::new(tcx, param_env, &ssa, &dominators, &body.local_decls);
+    for arg in body.args_iter() {
+        if ssa.is_ssa(arg) {
+            let value = state.new_opaque();
+            state.assign(arg, value);
+        }
+    }
+
+    let reverse_postorder = body.basic_blocks.reverse_postorder().to_vec();
+    for bb in reverse_postorder {
+        let data = &mut body.basic_blocks.as_mut_preserves_cfg()[bb];
+        state.visit_basic_block_data(bb, data);
+    }
+    let any_replacement = state.any_replacement;
+
+    // For each local that is reused (`_a` above), we remove its storage statements to avoid any
+    // difficulty. Those locals are SSA, so should be easy to optimize by LLVM without storage
+    // statements.
+    StorageRemover { tcx, reused_locals: state.reused_locals }.visit_body_preserves_cfg(body);
+
+    if any_replacement {
+        crate::simplify::remove_unused_definitions(body);
+    }
+}
+
+rustc_index::newtype_index! {
+    struct VnIndex {}
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Value<'tcx> {
+    // Root values.
+    /// Used to represent values we know nothing about.
+    /// The `usize` is a counter incremented by `new_opaque`.
+    Opaque(usize),
+    /// Evaluated or unevaluated constant value.
+    Constant {
+        value: ConstantKind<'tcx>,
+        /// Some constants do not have a deterministic value. To avoid merging two instances of the
+        /// same `ConstantKind`, we assign them an additional integer index.
+        disambiguator: usize,
+    },
+    /// An aggregate value, either tuple/closure/struct/enum.
+    /// This does not contain unions, as we cannot reason with the value.
+    Aggregate(Ty<'tcx>, VariantIdx, Vec<VnIndex>),
+    /// This corresponds to a `[value; count]` expression.
+    Repeat(VnIndex, ty::Const<'tcx>),
+    /// The address of a place. Each borrow is given a fresh `provenance`.
+    Address {
+        place: Place<'tcx>,
+        provenance: usize,
+    },
+
+    // Extractions.
+    /// This is the *value* obtained by projecting another value.
+    Projection(VnIndex, ProjectionElem<VnIndex, Ty<'tcx>>),
+    /// Discriminant of the given value.
+    Discriminant(VnIndex),
+    /// Length of an array or slice, or of the slice behind the given pointer.
+    Len(VnIndex),
+
+    // Operations.
+    NullaryOp(NullOp<'tcx>, Ty<'tcx>),
+    UnaryOp(UnOp, VnIndex),
+    BinaryOp(BinOp, VnIndex, VnIndex),
+    CheckedBinaryOp(BinOp, VnIndex, VnIndex),
+    Cast {
+        kind: CastKind,
+        value: VnIndex,
+        from: Ty<'tcx>,
+        to: Ty<'tcx>,
+    },
+}
+
+struct VnState<'body, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    ecx: InterpCx<'tcx, 'tcx, DummyMachine>,
+    param_env: ty::ParamEnv<'tcx>,
+    local_decls: &'body LocalDecls<'tcx>,
+    /// Value stored in each local.
+    locals: IndexVec<Local, Option<VnIndex>>,
+    /// Locals that are assigned each value.
+    rev_locals: IndexVec<VnIndex, Vec<Local>>,
+    values: FxIndexSet<Value<'tcx>>,
+    /// Values evaluated as constants if possible.
+    evaluated: IndexVec<VnIndex, Option<OpTy<'tcx>>>,
+    /// Counter to generate different values.
+    next_opaque: usize,
+    ssa: &'body SsaLocals,
+    dominators: &'body Dominators<BasicBlock>,
+    reused_locals: BitSet<Local>,
+    any_replacement: bool,
+}
+
+impl<'body, 'tcx> VnState<'body, 'tcx> {
+    fn new(
+        tcx: TyCtxt<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+        ssa: &'body SsaLocals,
+        dominators: &'body Dominators<BasicBlock>,
+        local_decls: &'body LocalDecls<'tcx>,
+    ) -> Self {
+        VnState {
+            tcx,
+            ecx: InterpCx::new(tcx, DUMMY_SP, param_env, DummyMachine),
+            param_env,
+            local_decls,
+            locals: IndexVec::from_elem(None, local_decls),
+            rev_locals: IndexVec::default(),
+            values: FxIndexSet::default(),
+            evaluated: IndexVec::new(),
+            // Disambiguator 0 is reserved for deterministic constants.
+            next_opaque: 1,
+            ssa,
+            dominators,
+            reused_locals: BitSet::new_empty(local_decls.len()),
+            any_replacement: false,
+        }
+    }
+
+    #[instrument(level = "trace", skip(self), ret)]
+    fn insert(&mut self, value: Value<'tcx>) -> VnIndex {
+        let (index, new) = self.values.insert_full(value);
+        let index = VnIndex::from_usize(index);
+        if new {
+            let evaluated = self.eval_to_const(index);
+            let _index = self.evaluated.push(evaluated);
+            debug_assert_eq!(index, _index);
+            let _index = self.rev_locals.push(Vec::new());
+            debug_assert_eq!(index, _index);
+        }
+        index
+    }
+
+    /// Create a new `Value` for which we have no information at all, except that it is distinct
+    /// from all the others.
+    #[instrument(level = "trace", skip(self), ret)]
+    fn new_opaque(&mut self) -> VnIndex {
+        let next_opaque = self.next_opaque;
+        self.next_opaque += 1;
+        self.insert(Value::Opaque(next_opaque))
+    }
+
+    /// Create a new `Value::Address` distinct from all the others.
+    #[instrument(level = "trace", skip(self), ret)]
+    fn new_pointer(&mut self, place: Place<'tcx>) -> VnIndex {
+        let provenance = self.next_opaque;
+        self.next_opaque += 1;
+        self.insert(Value::Address { place, provenance })
+    }
+
+    /// Insert a constant, taking care not to merge constants whose value may differ between uses.
+    fn insert_constant(&mut self, value: ConstantKind<'tcx>) -> VnIndex {
+        let disambiguator = if is_deterministic(&value) {
+            0
+        } else {
+            let next_opaque = self.next_opaque;
+            self.next_opaque += 1;
+            next_opaque
+        };
+        self.insert(Value::Constant { value, disambiguator })
+    }
+
+    fn get(&self, index: VnIndex) -> &Value<'tcx> {
+        self.values.get_index(index.as_usize()).unwrap()
+    }
+
+    /// Record that `local` is assigned `value`. `local` must be SSA.
+    #[instrument(level = "trace", skip(self))]
+    fn assign(&mut self, local: Local, value: VnIndex) {
+        self.locals[local] = Some(value);
+        self.rev_locals[value].push(local);
+    }
+
+    #[instrument(level = "trace", skip(self), ret)]
+    fn eval_to_const(&mut self, value: VnIndex) -> Option<OpTy<'tcx>> {
+        use Value::*;
+        let op = match *self.get(value) {
+            Opaque(_) => return None,
+            // Do not bother evaluating repeat expressions. This would uselessly consume memory.
+            Repeat(..) => return None,
+            // The address of a place is only known at runtime.
+            Address { .. } => return None,
+
+            Constant { ref value, disambiguator: _ } => {
+                self.ecx.eval_mir_constant(value, None, None).ok()?
+            }
+            Aggregate(ty, variant, ref fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|&f| self.evaluated[f].clone())
+                    .collect::<Option<Vec<_>>>()?;
+                let variant = if ty.is_enum() { Some(variant) } else { None };
+                let ty = self.ecx.layout_of(ty).ok()?;
+                if ty.is_zst() {
+                    ImmTy::uninit(ty).into()
+                } else if matches!(ty.abi, Abi::Scalar(..) | Abi::ScalarPair(..)) {
+                    let dest = self.ecx.allocate(ty, MemoryKind::Stack).ok()?;
+                    let variant_dest = if let Some(variant) = variant {
+                        self.ecx.project_downcast(&dest, variant).ok()?
+                    } else {
+                        dest.clone()
+                    };
+                    for (field_index, op) in fields.into_iter().enumerate() {
+                        let field_dest = self.ecx.project_field(&variant_dest, field_index).ok()?;
+                        self.ecx.copy_op(&op, &field_dest, /*allow_transmute*/ false).ok()?;
+                    }
+                    self.ecx.write_discriminant(variant.unwrap_or(FIRST_VARIANT), &dest).ok()?;
+                    self.ecx.alloc_mark_immutable(dest.ptr().provenance.unwrap()).ok()?;
+                    dest.into()
+                } else {
+                    return None;
+                }
+            }
+
+            Projection(base, elem) => {
+                let value = self.evaluated[base].as_ref()?;
+                let elem = match elem {
+                    ProjectionElem::Deref => return None,
+                    ProjectionElem::Downcast(name, read_variant) => {
+                        // Only project into the variant that is actually stored, and avoid
+                        // offsetting immediates into a smaller variant layout.
+                        if value.as_mplace_or_imm().is_right()
+                            || self.ecx.read_discriminant(value).ok()? != read_variant
+                        {
+                            return None;
+                        }
+                        ProjectionElem::Downcast(name, read_variant)
+                    }
+                    ProjectionElem::Field(f, ty) => ProjectionElem::Field(f, ty),
+                    ProjectionElem::Index(idx) => {
+                        let idx = self.evaluated[idx].as_ref()?;
+                        let offset = self.ecx.read_target_usize(idx).ok()?;
+                        ProjectionElem::ConstantIndex {
+                            offset,
+                            min_length: offset + 1,
+                            from_end: false,
+                        }
+                    }
+                    ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                        ProjectionElem::ConstantIndex { offset, min_length, from_end }
+                    }
+                    ProjectionElem::Subslice { from, to, from_end } => {
+                        ProjectionElem::Subslice { from, to, from_end }
+                    }
+                    ProjectionElem::OpaqueCast(ty) => ProjectionElem::OpaqueCast(ty),
+                };
+                self.ecx.project(value, elem).ok()?
+            }
+            Discriminant(base) => {
+                let base = self.evaluated[base].as_ref()?;
+                let variant = self.ecx.read_discriminant(base).ok()?;
+                let discr_value = self.ecx.discriminant_for_variant(base.layout, variant).ok()?;
+                let discr_layout =
+                    self.ecx.layout_of(base.layout.ty.discriminant_ty(self.tcx)).ok()?;
+                ImmTy::from_scalar(discr_value, discr_layout).into()
+            }
+            Len(slice) => {
+                let slice = self.evaluated[slice].as_ref()?;
+                // We do not evaluate the pointee of a pointer.
+                if slice.layout.ty.is_any_ptr() {
+                    return None;
+                }
+                let usize_layout = self.ecx.layout_of(self.tcx.types.usize).unwrap();
+                let len = slice.len(&self.ecx).ok()?;
+                ImmTy::from_uint(len, usize_layout).into()
+            }
+            NullaryOp(ref null_op, ty) => {
+                let layout = self.ecx.layout_of(ty).ok()?;
+                if let NullOp::SizeOf | NullOp::AlignOf = null_op
+                    && layout.is_unsized()
+                {
+                    return None;
+                }
+                let val = match null_op {
+                    NullOp::SizeOf => layout.size.bytes(),
+                    NullOp::AlignOf => layout.align.abi.bytes(),
+                    NullOp::OffsetOf(fields) => layout
+                        .offset_of_subfield(&self.ecx, fields.iter().map(|f| f.index()))
+                        .bytes(),
+                    // The result depends on the crate that codegens this body.
+                    NullOp::UbChecks => return None,
+                };
+                let usize_layout = self.ecx.layout_of(self.tcx.types.usize).unwrap();
+                ImmTy::from_uint(val, usize_layout).into()
+            }
+            UnaryOp(un_op, operand) => {
+                let operand = self.evaluated[operand].as_ref()?;
+                let operand = self.ecx.read_immediate(operand).ok()?;
+                let (val, _, ty) = self.ecx.overflowing_unary_op(un_op, &operand).ok()?;
+                ImmTy::from_scalar(val, self.ecx.layout_of(ty).ok()?).into()
+            }
+            BinaryOp(bin_op, lhs, rhs) => {
+                let lhs = self.evaluated[lhs].as_ref()?;
+                let lhs = self.ecx.read_immediate(lhs).ok()?;
+                let rhs = self.evaluated[rhs].as_ref()?;
+                let rhs = self.ecx.read_immediate(rhs).ok()?;
+                let (val, _, ty) = self.ecx.overflowing_binary_op(bin_op, &lhs, &rhs).ok()?;
+                ImmTy::from_scalar(val, self.ecx.layout_of(ty).ok()?).into()
+            }
+            CheckedBinaryOp(bin_op, lhs, rhs) => {
+                let lhs = self.evaluated[lhs].as_ref()?;
+                let lhs = self.ecx.read_immediate(lhs).ok()?;
+                let rhs = self.evaluated[rhs].as_ref()?;
+                let rhs = self.ecx.read_immediate(rhs).ok()?;
+                let (val, overflowed, ty) =
+                    self.ecx.overflowing_binary_op(bin_op, &lhs, &rhs).ok()?;
+                let tuple = Ty::new_tup(self.tcx, &[ty, self.tcx.types.bool]);
+                let tuple = self.ecx.layout_of(tuple).ok()?;
+                ImmTy::from_immediate(
+                    Immediate::ScalarPair(val, Scalar::from_bool(overflowed)),
+                    tuple,
+                )
+                .into()
+            }
+            Cast { kind, value, from: _, to } => match kind {
+                CastKind::IntToInt | CastKind::IntToFloat => {
+                    let value = self.evaluated[value].as_ref()?;
+                    let value = self.ecx.read_immediate(value).ok()?;
+                    let to = self.ecx.layout_of(to).ok()?;
+                    let res = self.ecx.int_to_int_or_float(&value, to.ty).ok()?;
+                    ImmTy::from_immediate(res, to).into()
+                }
+                CastKind::FloatToFloat | CastKind::FloatToInt => {
+                    let value = self.evaluated[value].as_ref()?;
+                    let value = self.ecx.read_immediate(value).ok()?;
+                    let to = self.ecx.layout_of(to).ok()?;
+                    let res = self.ecx.float_to_float_or_int(&value, to.ty).ok()?;
+                    ImmTy::from_immediate(res, to).into()
+                }
+                CastKind::Transmute => {
+                    let value = self.evaluated[value].as_ref()?;
+                    let to = self.ecx.layout_of(to).ok()?;
+                    // `offset` for immediates only supports scalar/scalar-pair ABIs,
+                    // so bail out if the target is not one.
+                    if value.as_mplace_or_imm().is_right() {
+                        match (value.layout.abi, to.abi) {
+                            (Abi::Scalar(..), Abi::Scalar(..)) => {}
+                            (Abi::ScalarPair(..), Abi::ScalarPair(..)) => {}
+                            _ => return None,
+                        }
+                    }
+                    value.transmute(to, &self.ecx).ok()?
+                }
+                _ => return None,
+            },
+        };
+        Some(op)
+    }
+
+    /// Represent the *value* which would be read from `place`, and point `place` to a preexisting
+    /// place with the same value (if that already exists).
+    #[instrument(level = "trace", skip(self), ret)]
+    fn simplify_place_value(
+        &mut self,
+        place: &mut Place<'tcx>,
+        location: Location,
+    ) -> Option<VnIndex> {
+        let mut value = self.locals[place.local]?;
+        let mut place_ty = PlaceTy::from_ty(self.local_decls[place.local].ty);
+        let mut new_projection = None;
+
+        for (index, proj) in place.projection.iter().enumerate() {
+            let elem = match proj {
+                // We do not track the pointee, which may be modified behind our back.
+                ProjectionElem::Deref => return None,
+                ProjectionElem::Field(f, ty) => ProjectionElem::Field(f, ty),
+                ProjectionElem::Index(idx) => {
+                    let idx = self.locals[idx]?;
+                    if let Some(offset) = self.evaluated[idx]
+                        .as_ref()
+                        .and_then(|idx| self.ecx.read_target_usize(idx).ok())
+                    {
+                        // The bounds check has already been performed, so we can use the
+                        // constant index directly.
+                        let min_length = offset + 1;
+                        new_projection.get_or_insert_with(|| place.projection.to_vec())[index] =
+                            ProjectionElem::ConstantIndex { offset, min_length, from_end: false };
+                        ProjectionElem::ConstantIndex { offset, min_length, from_end: false }
+                    } else {
+                        ProjectionElem::Index(idx)
+                    }
+                }
+                ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                    ProjectionElem::ConstantIndex { offset, min_length, from_end }
+                }
+                ProjectionElem::Subslice { from, to, from_end } => {
+                    ProjectionElem::Subslice { from, to, from_end }
+                }
+                ProjectionElem::Downcast(name, variant) => ProjectionElem::Downcast(name, variant),
+                ProjectionElem::OpaqueCast(ty) => ProjectionElem::OpaqueCast(ty),
+            };
+            value = self.project(value, elem)?;
+            place_ty = place_ty.projection_ty(self.tcx, proj);
+        }
+
+        if let Some(new_projection) = new_projection {
+            place.projection = self.tcx.mk_place_elems(&new_projection);
+            self.any_replacement = true;
+        }
+
+        if place_ty.variant_index.is_none()
+            && let Some(local) = self.try_as_local(value, place_ty.ty, location)
+            && (local != place.local || !place.projection.is_empty())
+        {
+            *place = local.into();
+            self.reused_locals.insert(local);
+            self.any_replacement = true;
+        }
+
+        Some(value)
+    }
+
+    /// Compute the value of projecting `value` by `elem`, using the knowledge of aggregates.
+    fn project(
+        &mut self,
+        value: VnIndex,
+        elem: ProjectionElem<VnIndex, Ty<'tcx>>,
+    ) -> Option<VnIndex> {
+        match elem {
+            ProjectionElem::Field(f, _) => {
+                if let Value::Aggregate(_, _, fields) = self.get(value) {
+                    return Some(fields[f.as_usize()]);
+                } else if let Value::Projection(outer, ProjectionElem::Downcast(_, read_variant)) =
+                    self.get(value)
+                    && let Value::Aggregate(_, written_variant, fields) = self.get(*outer)
+                    && written_variant == read_variant
+                {
+                    return Some(fields[f.as_usize()]);
+                }
+            }
+            ProjectionElem::Index(_) => {
+                if let Value::Repeat(inner, _) = self.get(value) {
+                    return Some(*inner);
+                }
+            }
+            ProjectionElem::ConstantIndex { offset, from_end, .. } => match self.get(value) {
+                Value::Repeat(inner, _) => return Some(*inner),
+                Value::Aggregate(ty, _, fields) if ty.is_array() => {
+                    let offset = offset as usize;
+                    let index = if from_end { fields.len().checked_sub(offset)? } else { offset };
+                    return fields.get(index).copied();
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+
+        Some(self.insert(Value::Projection(value, elem)))
+    }
+
+    #[instrument(level = "trace", skip(self), ret)]
+    fn simplify_operand(
+        &mut self,
+        operand: &mut Operand<'tcx>,
+        location: Location,
+    ) -> Option<VnIndex> {
+        match *operand {
+            Operand::Constant(ref constant) => Some(self.insert_constant(constant.literal)),
+            Operand::Copy(ref mut place) | Operand::Move(ref mut place) => {
+                let value = self.simplify_place_value(place, location)?;
+                if let Some(const_) = self.try_as_constant(value) {
+                    *operand = Operand::Constant(Box::new(const_));
+                    self.any_replacement = true;
+                }
+                Some(value)
+            }
+        }
+    }
+
+    #[instrument(level = "trace", skip(self), ret)]
+    fn simplify_rvalue(
+        &mut self,
+        rvalue: &mut Rvalue<'tcx>,
+        location: Location,
+    ) -> Option<VnIndex> {
+        let ty = rvalue.ty(self.local_decls, self.tcx);
+        let value = match *rvalue {
+            // Forward values.
+            Rvalue::Use(ref mut operand) => return self.simplify_operand(operand, location),
+            Rvalue::CopyForDeref(place) => {
+                let mut operand = Operand::Copy(place);
+                let val = self.simplify_operand(&mut operand, location);
+                *rvalue = Rvalue::Use(operand);
+                return val;
+            }
+
+            // Roots.
+            Rvalue::Repeat(ref mut op, amount) => {
+                let op = self.simplify_operand(op, location)?;
+                Value::Repeat(op, amount)
+            }
+            Rvalue::NullaryOp(ref op, ty) => Value::NullaryOp(op.clone(), ty),
+            Rvalue::Aggregate(box ref kind, ref mut fields) => {
+                let variant_index = match *kind {
+                    AggregateKind::Array(..)
+                    | AggregateKind::Tuple
+                    | AggregateKind::Closure(..) => FIRST_VARIANT,
+                    AggregateKind::Adt(_, variant_index, _, _, None) => variant_index,
+                    // Do not track unions and generators.
+                    AggregateKind::Adt(_, _, _, _, Some(_)) | AggregateKind::Generator(..) => {
+                        return None;
+                    }
+                };
+                let fields: Vec<_> = fields
+                    .iter_mut()
+                    .map(|op| {
+                        self.simplify_operand(op, location).unwrap_or_else(|| self.new_opaque())
+                    })
+                    .collect();
+                Value::Aggregate(ty, variant_index, fields)
+            }
+            // Each borrow is distinct, even shared ones: the address of a local may be observed,
+            // and unsafe code may mutate behind a shared borrow of an `UnsafeCell`.
+            Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) => {
+                return Some(self.new_pointer(place));
+            }
+
+            // Operations.
+            Rvalue::Len(ref mut place) => {
+                let place_ty = place.ty(self.local_decls, self.tcx).ty;
+                if let ty::Array(_, len) = place_ty.kind() {
+                    return Some(self.insert_constant(ConstantKind::from_const(*len, self.tcx)));
+                }
+                // The length of a slice is stored in the metadata of the pointer to it, so it
+                // cannot be modified through that pointer.
+                if let [ProjectionElem::Deref] = place.projection[..]
+                    && let Some(pointer) = self.locals[place.local]
+                {
+                    Value::Len(pointer)
+                } else {
+                    let place = self.simplify_place_value(place, location)?;
+                    Value::Len(place)
+                }
+            }
+            Rvalue::Cast(kind, ref mut value, to) => {
+                let from = value.ty(self.local_decls, self.tcx);
+                let value = self.simplify_operand(value, location)?;
+                Value::Cast { kind, value, from, to }
+            }
+            Rvalue::BinaryOp(op, box (ref mut lhs, ref mut rhs)) => {
+                let lhs = self.simplify_operand(lhs, location);
+                let rhs = self.simplify_operand(rhs, location);
+                Value::BinaryOp(op, lhs?, rhs?)
+            }
+            Rvalue::CheckedBinaryOp(op, box (ref mut lhs, ref mut rhs)) => {
+                let lhs = self.simplify_operand(lhs, location);
+                let rhs = self.simplify_operand(rhs, location);
+                Value::CheckedBinaryOp(op, lhs?, rhs?)
+            }
+            Rvalue::UnaryOp(op, ref mut arg) => {
+                let arg = self.simplify_operand(arg, location)?;
+                Value::UnaryOp(op, arg)
+            }
+            Rvalue::Discriminant(ref mut place) => {
+                let place = self.simplify_place_value(place, location)?;
+                if let Some(discr) = self.simplify_discriminant(place) {
+                    return Some(discr);
+                }
+                Value::Discriminant(place)
+            }
+
+            // Unsupported values.
+            Rvalue::ThreadLocalRef(..) | Rvalue::ShallowInitBox(..) => return None,
+        };
+        debug!(?value);
+        Some(self.insert(value))
+    }
+
+    /// The discriminant of a known enum aggregate is the discriminant of its variant.
+    fn simplify_discriminant(&mut self, place: VnIndex) -> Option<VnIndex> {
+        if let Value::Aggregate(enum_ty, variant, _) = *self.get(place)
+            && enum_ty.is_enum()
+        {
+            let discr = enum_ty.discriminant_for_variant(self.tcx, variant)?;
+            let literal =
+                ConstantKind::from_bits(self.tcx, discr.val, self.param_env.and(discr.ty));
+            return Some(self.insert_constant(literal));
+        }
+
+        None
+    }
+}
+
+/// Whether two uses of this constant are guaranteed to produce the same value. Constants that
+/// contain pointers may be codegened to distinct allocations, so each use gets its own value.
+fn is_deterministic(literal: &ConstantKind<'_>) -> bool {
+    match *literal {
+        ConstantKind::Ty(c) => c.ty().is_primitive(),
+        ConstantKind::Unevaluated(_, ty) => ty.is_primitive(),
+        ConstantKind::Val(ConstValue::Scalar(Scalar::Int(_)) | ConstValue::ZeroSized, _) => true,
+        ConstantKind::Val(..) => false,
+    }
+}
+
+fn op_to_prop_const<'tcx>(
+    ecx: &mut InterpCx<'_, 'tcx, DummyMachine>,
+    op: &OpTy<'tcx>,
+) -> Option<ConstValue<'tcx>> {
+    // Do not attempt to propagate unsized locals.
+    if op.layout.is_unsized() {
+        return None;
+    }
+
+    // This constant is a ZST, just return an empty value.
+    if op.layout.is_zst() {
+        return Some(ConstValue::ZeroSized);
+    }
+
+    // If this constant has scalar ABI, return it as a `ConstValue::Scalar`.
+    if let Abi::Scalar(abi::Scalar::Initialized { .. }) = op.layout.abi
+        && let Ok(scalar) = ecx.read_scalar(op)
+    {
+        // Do not propagate pointers, their address may differ between uses.
+        return scalar.try_to_int().is_ok().then_some(ConstValue::Scalar(scalar));
+    }
+
+    // Only synthesize scalar pairs, larger constants would just be copied from memory by codegen.
+    if !matches!(op.layout.abi, Abi::ScalarPair(..)) {
+        return None;
+    }
+
+    let alloc =
+        ecx.intern_with_temp_alloc(op.layout, |ecx, dest| ecx.copy_op(op, dest, false)).ok()?;
+    if !alloc.inner().provenance().ptrs().is_empty() {
+        return None;
+    }
+    Some(ConstValue::ByRef { alloc, offset: Size::ZERO })
+}
+
+impl<'tcx> VnState<'_, 'tcx> {
+    /// If `index` is a `Value::Constant`, return the `Constant` to be put in the MIR.
+    fn try_as_constant(&mut self, index: VnIndex) -> Option<Constant<'tcx>> {
+        if let Value::Constant { value, disambiguator: 0 } = *self.get(index) {
+            return Some(Constant { span: DUMMY_SP, user_ty: None, literal: value });
+        }
+
+        let op = self.evaluated[index].clone()?;
+        let value = op_to_prop_const(&mut self.ecx, &op)?;
+        let literal = ConstantKind::Val(value, op.layout.ty);
+        Some(Constant { span: DUMMY_SP, user_ty: None, literal })
+    }
+
+    /// If there is a local which is assigned `index`, has type `ty`, and whose assignment
+    /// dominates `loc`, return it.
+    fn try_as_local(&self, index: VnIndex, ty: Ty<'tcx>, loc: Location) -> Option<Local> {
+        let other = self.rev_locals.get(index)?;
+        other.iter().copied().find(|&other| {
+            // FIXME(#112651): `rvalue` may have a subtype of the type of `other`.
+            self.local_decls[other].ty == ty
+                && self.ssa.assignment_dominates(self.dominators, other, loc)
+        })
+    }
+}
+
+impl<'tcx> MutVisitor<'tcx> for VnState<'_, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        self.simplify_operand(operand, location);
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement<'tcx>, location: Location) {
+        let StatementKind::Assign(box (ref lhs, ref mut rvalue)) = stmt.kind else {
+            return self.super_statement(stmt, location);
+        };
+
+        let ty = rvalue.ty(self.local_decls, self.tcx);
+        let value = self.simplify_rvalue(rvalue, location);
+
+        if let Some(local) = lhs.as_local()
+            && self.ssa.is_ssa(local)
+            // FIXME(#112651) `rvalue` may have a subtype to `local`. We can only mark `local` as
+            // reusable if we have an exact type match.
+            && self.local_decls[local].ty == ty
+        {
+            let value = value.unwrap_or_else(|| self.new_opaque());
+            self.assign(local, value);
+        }
+
+        let Some(value) = value else { return };
+        // Do not try to simplify a constant, it's already in canonical shape.
+        if matches!(rvalue, Rvalue::Use(Operand::Constant(_))) {
+            return;
+        }
+
+        if let Some(const_) = self.try_as_constant(value) {
+            *rvalue = Rvalue::Use(Operand::Constant(Box::new(const_)));
+            self.any_replacement = true;
+        } else if let Some(local) = self.try_as_local(value, ty, location)
+            && *rvalue != Rvalue::Use(Operand::Move(local.into()))
+            && *rvalue != Rvalue::Use(Operand::Copy(local.into()))
+        {
+            *rvalue = Rvalue::Use(Operand::Copy(local.into()));
+            self.reused_locals.insert(local);
+            self.any_replacement = true;
+        }
+    }
+}
+
+struct StorageRemover<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    reused_locals: BitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for StorageRemover<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, _: Location) {
+        if let Operand::Move(place) = *operand
+            && !place.is_indirect_first_projection()
+            && self.reused_locals.contains(place.local)
+        {
+            *operand = Operand::Copy(place);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement<'tcx>, loc: Location) {
+        match stmt.kind {
+            // When removing storage statements, we need to remove both (#107511).
+            StatementKind::StorageLive(l) | StatementKind::StorageDead(l)
+                if self.reused_locals.contains(l) =>
+            {
+                stmt.make_nop()
+            }
+            _ => self.super_statement(stmt, loc),
+        }
+    }
+}
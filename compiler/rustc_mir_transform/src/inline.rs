@@ -1,4 +1,5 @@
 //! Inlining pass for MIR functions
+use crate::cost_checker::CostChecker;
 use crate::deref_separator::deref_finder;
 use rustc_attr::InlineAttr;
 use rustc_const_eval::transform::validate::validate_types;
@@ -10,23 +11,21 @@
 use rustc_middle::mir::*;
 use rustc_middle::ty::TypeVisitableExt;
 use rustc_middle::ty::{self, Instance, InstanceDef, ParamEnv, Ty, TyCtxt};
+use rustc_session::code_stats::MirInliningInfo;
 use rustc_session::config::OptLevel;
+use rustc_span::Span;
 use rustc_target::abi::FieldIdx;
 use rustc_target::spec::abi::Abi;
 
 use crate::simplify::{remove_dead_blocks, CfgSimplifier};
 use crate::util;
 use crate::MirPass;
+use std::cell::Cell;
 use std::iter;
 use std::ops::{Range, RangeFrom};
 
 pub(crate) mod cycle;
 
-const INSTR_COST: usize = 5;
-const CALL_PENALTY: usize = 25;
-const LANDINGPAD_PENALTY: usize = 50;
-const RESUME_PENALTY: usize = 45;
-
 const TOP_DOWN_DEPTH_LIMIT: usize = 5;
 
 pub struct Inline;
@@ -94,6 +93,7 @@ fn inline<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) -> bool {
         codegen_fn_attrs: tcx.codegen_fn_attrs(def_id),
         history: Vec::new(),
         changed: false,
+        callee_cost: Cell::new(None),
     };
     let blocks = START_BLOCK..body.basic_blocks.next_index();
     this.process_blocks(body, blocks);
@@ -113,6 +113,9 @@ struct Inliner<'tcx> {
     history: Vec<DefId>,
     /// Indicates that the caller body has been modified.
     changed: bool,
+    /// The cost of the last callee body checked by `check_mir_body`, and the threshold it was
+    /// compared against. Only used for `-Z print-mir-inlining`.
+    callee_cost: Cell<Option<(usize, usize)>>,
 }
 
 impl<'tcx> Inliner<'tcx> {
@@ -138,7 +141,17 @@ fn process_blocks(&mut self, caller_body: &mut Body<'tcx>, blocks: Range<BasicBl
             let span = trace_span!("process_blocks", %callsite.callee, ?bb);
             let _guard = span.enter();
 
-            match self.try_inlining(caller_body, &callsite) {
+            let result = self.try_inlining(caller_body, &callsite);
+            let cost = self.callee_cost.take();
+            self.record_decision(
+                caller_body,
+                || callsite.callee.to_string(),
+                callsite.source_info.span,
+                cost,
+                result.as_ref().err().copied(),
+            );
+
+            match result {
                 Err(reason) => {
                     debug!("not-inlined {} [{}]", callsite.callee, reason);
                     continue;
@@ -355,15 +368,33 @@ fn resolve_callsite(
             let func_ty = func.ty(caller_body, self.tcx);
             if let ty::FnDef(def_id, args) = *func_ty.kind() {
                 // To resolve an instance its args have to be fully normalized.
-                let args = self.tcx.try_normalize_erasing_regions(self.param_env, args).ok()?;
-                let callee =
-                    Instance::resolve(self.tcx, self.param_env, def_id, args).ok().flatten()?;
+                let normalized = self.tcx.try_normalize_erasing_regions(self.param_env, args).ok();
+                let callee = normalized.and_then(|args| {
+                    Instance::resolve(self.tcx, self.param_env, def_id, args).ok().flatten()
+                });
+                let (Some(args), Some(callee)) = (normalized, callee) else {
+                    self.record_decision(
+                        caller_body,
+                        || self.tcx.def_path_str_with_args(def_id, args),
+                        fn_span,
+                        None,
+                        Some("unresolved instance"),
+                    );
+                    return None;
+                };
 
                 if let InstanceDef::Virtual(..) | InstanceDef::Intrinsic(_) = callee.def {
                     return None;
                 }
 
                 if self.history.contains(&callee.def_id()) {
+                    self.record_decision(
+                        caller_body,
+                        || callee.to_string(),
+                        fn_span,
+                        None,
+                        Some("recursion through inlined callees"),
+                    );
                     return None;
                 }
 
@@ -436,6 +467,16 @@ fn check_mir_body(
     ) -> Result<(), &'static str> {
         let tcx = self.tcx;
 
+        // The MC/DC condition bitmaps of a function are allocated in its own stack frame, so its
+        // MC/DC instrumentation can't be moved into other functions.
+        if callee_body
+            .coverage_branch_info
+            .as_ref()
+            .is_some_and(|branch_info| !branch_info.mcdc_decision_spans.is_empty())
+        {
+            return Err("callee has MC/DC coverage instrumentation");
+        }
+
         let mut threshold = if callee_attrs.requests_inline() {
             self.tcx.sess.opts.unstable_opts.inline_mir_hint_threshold.unwrap_or(100)
         } else {
@@ -452,13 +493,8 @@ fn check_mir_body(
 
         // FIXME: Give a bonus to functions with only a single caller
 
-        let mut checker = CostChecker {
-            tcx: self.tcx,
-            param_env: self.param_env,
-            instance: callsite.callee,
-            callee_body,
-            cost: 0,
-        };
+        let mut checker =
+            CostChecker::new(self.tcx, self.param_env, Some(callsite.callee), callee_body);
 
         // Traverse the MIR manually so we can account for the effects of inlining on the CFG.
         let mut work_list = vec![START_BLOCK];
@@ -500,7 +536,8 @@ fn check_mir_body(
         // That attribute is often applied to very large functions that exceed LLVM's (very
         // generous) inlining threshold. Such functions are very poor MIR inlining candidates.
         // Always inlining #[inline(always)] functions in MIR, on net, slows down the compiler.
-        let cost = checker.cost;
+        let cost = checker.cost();
+        self.callee_cost.set(Some((cost, threshold)));
         if cost <= threshold {
             debug!("INLINING {:?} [cost={} <= threshold={}]", callsite, cost, threshold);
             Ok(())
@@ -510,6 +547,29 @@ fn check_mir_body(
         }
     }
 
+    /// Records whether a callsite was inlined, and why not, for `-Z print-mir-inlining`.
+    fn record_decision(
+        &self,
+        caller_body: &Body<'tcx>,
+        callee: impl FnOnce() -> String,
+        span: Span,
+        cost: Option<(usize, usize)>,
+        rejection: Option<&'static str>,
+    ) {
+        let sess = self.tcx.sess;
+        if sess.opts.unstable_opts.print_mir_inlining.is_none() {
+            return;
+        }
+        sess.code_stats.record_mir_inlining(MirInliningInfo {
+            caller: self.tcx.def_path_str(caller_body.source.def_id()),
+            callee: callee(),
+            span: sess.source_map().span_to_embeddable_string(span),
+            depth: self.history.len(),
+            cost,
+            rejection,
+        });
+    }
+
     fn inline_call(
         &self,
         caller_body: &mut Body<'tcx>,
@@ -777,77 +837,6 @@ fn new_call_temp(
     }
 }
 
-/// Verify that the callee body is compatible with the caller.
-///
-/// This visitor mostly computes the inlining cost,
-/// but also needs to verify that types match because of normalization failure.
-struct CostChecker<'b, 'tcx> {
-    tcx: TyCtxt<'tcx>,
-    param_env: ParamEnv<'tcx>,
-    cost: usize,
-    callee_body: &'b Body<'tcx>,
-    instance: ty::Instance<'tcx>,
-}
-
-impl<'tcx> Visitor<'tcx> for CostChecker<'_, 'tcx> {
-    fn visit_statement(&mut self, statement: &Statement<'tcx>, _: Location) {
-        // Don't count StorageLive/StorageDead in the inlining cost.
-        match statement.kind {
-            StatementKind::StorageLive(_)
-            | StatementKind::StorageDead(_)
-            | StatementKind::Deinit(_)
-            | StatementKind::Nop => {}
-            _ => self.cost += INSTR_COST,
-        }
-    }
-
-    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, _: Location) {
-        let tcx = self.tcx;
-        match terminator.kind {
-            TerminatorKind::Drop { ref place, unwind, .. } => {
-                // If the place doesn't actually need dropping, treat it like a regular goto.
-                let ty = self
-                    .instance
-                    .subst_mir(tcx, ty::EarlyBinder::bind(&place.ty(self.callee_body, tcx).ty));
-                if ty.needs_drop(tcx, self.param_env) {
-                    self.cost += CALL_PENALTY;
-                    if let UnwindAction::Cleanup(_) = unwind {
-                        self.cost += LANDINGPAD_PENALTY;
-                    }
-                } else {
-                    self.cost += INSTR_COST;
-                }
-            }
-            TerminatorKind::Call { func: Operand::Constant(ref f), unwind, .. } => {
-                let fn_ty = self.instance.subst_mir(tcx, ty::EarlyBinder::bind(&f.literal.ty()));
-                self.cost += if let ty::FnDef(def_id, _) = *fn_ty.kind() && tcx.is_intrinsic(def_id) {
-                    // Don't give intrinsics the extra penalty for calls
-                    INSTR_COST
-                } else {
-                    CALL_PENALTY
-                };
-                if let UnwindAction::Cleanup(_) = unwind {
-                    self.cost += LANDINGPAD_PENALTY;
-                }
-            }
-            TerminatorKind::Assert { unwind, .. } => {
-                self.cost += CALL_PENALTY;
-                if let UnwindAction::Cleanup(_) = unwind {
-                    self.cost += LANDINGPAD_PENALTY;
-                }
-            }
-            TerminatorKind::UnwindResume => self.cost += RESUME_PENALTY,
-            TerminatorKind::InlineAsm { unwind, .. } => {
-                self.cost += INSTR_COST;
-                if let UnwindAction::Cleanup(_) = unwind {
-                    self.cost += LANDINGPAD_PENALTY;
-                }
-            }
-            _ => self.cost += INSTR_COST,
-        }
-    }
-}
-
 /**
  * Integrator.
  *
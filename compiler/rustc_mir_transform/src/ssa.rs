@@ -146,6 +146,27 @@ pub fn assignments<'a, 'tcx>(
         })
     }
 
+    /// Whether the assignment to `local` strictly dominates `location`, ie. `local` is guaranteed
+    /// to hold its SSA value when `location` is reached.
+    pub fn assignment_dominates(
+        &self,
+        dominators: &Dominators<BasicBlock>,
+        local: Local,
+        location: Location,
+    ) -> bool {
+        match self.assignments[local] {
+            Set1::One(LocationExtended::Arg) => true,
+            Set1::One(LocationExtended::Plain(assign)) => {
+                if assign.block == location.block {
+                    assign.statement_index < location.statement_index
+                } else {
+                    dominators.dominates(assign.block, location.block)
+                }
+            }
+            Set1::Empty | Set1::Many => false,
+        }
+    }
+
     /// Compute the equivalence classes for locals, based on copy statements.
     ///
     /// The returned vector maps each local to the one it copies. In the following case:
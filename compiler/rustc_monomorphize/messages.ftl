@@ -1,6 +1,9 @@
 monomorphize_consider_type_length_limit =
     consider adding a `#![type_length_limit="{$type_length}"]` attribute to your crate
 
+monomorphize_couldnt_dump_mono_provenance =
+    unexpected error occurred while dumping monomorphization provenance: {$error}
+
 monomorphize_couldnt_dump_mono_stats =
     unexpected error occurred while dumping monomorphization stats: {$error}
 
@@ -18,6 +21,24 @@ monomorphize_no_optimized_mir =
     missing optimized MIR for an item in the crate `{$crate_name}`
     .note = missing optimized MIR for this item (was the crate `{$crate_name}` compiled with `--emit=metadata`?)
 
+monomorphize_reachable_panic =
+    panic in `{$item}` reachable from `{$entry_point}`
+    .label = {$reason}
+
+monomorphize_reachable_panic_step =
+    `{$user}` {$kind ->
+        [call] calls
+        [drop-glue] drops a value with
+        [fn-pointer] takes a function pointer to
+        [vtable] creates a vtable with
+        *[other] refers to
+    } `{$used}`
+
+monomorphize_reachable_unknown_callee =
+    unknown function `{$callee}` called in `{$item}` reachable from `{$entry_point}`
+    .label = not checked for panics
+    .note = only the functions instantiated in this crate are searched for panics
+
 monomorphize_recursion_limit =
     reached the recursion limit while instantiating `{$shrunk}`
     .note = `{$def_path_str}` defined here
@@ -209,23 +209,87 @@ pub struct UsageMap<'tcx> {
 
     // Maps every mono item to the mono items that use it.
     user_map: FxHashMap<MonoItem<'tcx>, Vec<MonoItem<'tcx>>>,
+
+    // Maps every mono item to how it is used by each of its users, only
+    // recorded for `-Z print-mono-items-provenance` and `-Z report-reachable-panics`.
+    usages: Option<FxHashMap<MonoItem<'tcx>, Vec<Usage<'tcx>>>>,
+
+    // The roots the collection started from, only recorded for
+    // `-Z print-mono-items-provenance` and `-Z report-reachable-panics`.
+    roots: Vec<MonoItem<'tcx>>,
+}
+
+/// How a mono item uses another one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UsageKind {
+    /// Not a use: the item is a root of the mono item graph, e.g. a function
+    /// exported from the crate.
+    Root,
+    /// A direct call.
+    Call,
+    /// Dropping a value, which directly calls its drop glue.
+    Drop,
+    /// Reifying a function or a closure to a function pointer.
+    FnPointer,
+    /// A method or the drop glue in the vtable of an unsizing cast.
+    Vtable,
+    /// Any other reference, e.g. to a static, or to a function from a constant
+    /// or a `sym` operand of inline assembly.
+    Reference,
 }
 
-type MonoItems<'tcx> = Vec<Spanned<MonoItem<'tcx>>>;
+impl UsageKind {
+    fn is_direct_call(self) -> bool {
+        matches!(self, UsageKind::Call | UsageKind::Drop)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UsageKind::Root => "root",
+            UsageKind::Call => "call",
+            UsageKind::Drop => "drop-glue",
+            UsageKind::FnPointer => "fn-pointer",
+            UsageKind::Vtable => "vtable",
+            UsageKind::Reference => "reference",
+        }
+    }
+}
+
+/// A use of a mono item by `user`, at `span` in the body of `user`.
+#[derive(Clone, Copy, Debug)]
+pub struct Usage<'tcx> {
+    pub user: MonoItem<'tcx>,
+    pub span: Span,
+    pub kind: UsageKind,
+}
+
+type MonoItems<'tcx> = Vec<(Spanned<MonoItem<'tcx>>, UsageKind)>;
 
 impl<'tcx> UsageMap<'tcx> {
-    fn new() -> UsageMap<'tcx> {
-        UsageMap { used_map: FxHashMap::default(), user_map: FxHashMap::default() }
+    /// Creates a usage map that records the uses of each item if `roots` is given.
+    fn new(roots: Option<Vec<MonoItem<'tcx>>>) -> UsageMap<'tcx> {
+        UsageMap {
+            used_map: FxHashMap::default(),
+            user_map: FxHashMap::default(),
+            usages: roots.is_some().then(FxHashMap::default),
+            roots: roots.unwrap_or_default(),
+        }
     }
 
     fn record_used<'a>(
         &mut self,
         user_item: MonoItem<'tcx>,
-        used_items: &'a [Spanned<MonoItem<'tcx>>],
+        used_items: &'a [(Spanned<MonoItem<'tcx>>, UsageKind)],
     ) where
         'tcx: 'a,
     {
-        let used_items: Vec<_> = used_items.iter().map(|item| item.node).collect();
+        if let Some(usages) = &mut self.usages {
+            for &(Spanned { node: used_item, span }, kind) in used_items.iter() {
+                usages.entry(used_item).or_default().push(Usage { user: user_item, span, kind });
+            }
+        }
+
+        let used_items: Vec<_> = used_items.iter().map(|(item, _)| item.node).collect();
         for &used_item in used_items.iter() {
             self.user_map.entry(used_item).or_default().push(user_item);
         }
@@ -233,6 +297,19 @@ fn record_used<'a>(
         assert!(self.used_map.insert(user_item, used_items).is_none());
     }
 
+    /// The uses of `item` by other mono items, if `-Z print-mono-items-provenance`
+    /// or `-Z report-reachable-panics` is enabled.
+    pub fn get_usages(&self, item: MonoItem<'tcx>) -> Option<&[Usage<'tcx>]> {
+        let usages = self.usages.as_ref()?;
+        Some(usages.get(&item).map(|usages| usages.as_slice()).unwrap_or(&[]))
+    }
+
+    /// The roots of the mono item graph, if `-Z print-mono-items-provenance` or
+    /// `-Z report-reachable-panics` is enabled.
+    pub fn roots(&self) -> &[MonoItem<'tcx>] {
+        &self.roots
+    }
+
     pub fn get_user_items(&self, item: MonoItem<'tcx>) -> &[MonoItem<'tcx>] {
         self.user_map.get(&item).map(|items| items.as_slice()).unwrap_or(&[])
     }
@@ -265,7 +342,9 @@ pub fn collect_crate_mono_items(
     debug!("building mono item graph, beginning at roots");
 
     let mut visited = MTLock::new(FxHashSet::default());
-    let mut usage_map = MTLock::new(UsageMap::new());
+    let record_usages = tcx.sess.opts.unstable_opts.print_mono_items_provenance.enabled()
+        || tcx.sess.opts.unstable_opts.report_reachable_panics.is_some();
+    let mut usage_map = MTLock::new(UsageMap::new(record_usages.then(|| roots.clone())));
     let recursion_limit = tcx.recursion_limit();
 
     {
@@ -322,7 +401,7 @@ fn collect_roots(tcx: TyCtxt<'_>, mode: MonoItemCollectionMode) -> Vec<MonoItem<
     // can't actually be used, so we can just skip codegenning them.
     roots
         .into_iter()
-        .filter_map(|Spanned { node: mono_item, .. }| {
+        .filter_map(|(Spanned { node: mono_item, .. }, _)| {
             mono_item.is_instantiable(tcx).then_some(mono_item)
         })
         .collect()
@@ -380,7 +459,7 @@ fn collect_items_rec<'tcx>(
             debug_assert!(should_codegen_locally(tcx, &instance));
 
             let ty = instance.ty(tcx, ty::ParamEnv::reveal_all());
-            visit_drop_use(tcx, ty, true, starting_item.span, &mut used_items);
+            visit_drop_use(tcx, ty, UsageKind::Drop, starting_item.span, &mut used_items);
 
             recursion_depth_reset = None;
 
@@ -391,12 +470,15 @@ fn collect_items_rec<'tcx>(
             }
 
             if tcx.needs_thread_local_shim(def_id) {
-                used_items.push(respan(
-                    starting_item.span,
-                    MonoItem::Fn(Instance {
-                        def: InstanceDef::ThreadLocalShim(def_id),
-                        args: GenericArgs::empty(),
-                    }),
+                used_items.push((
+                    respan(
+                        starting_item.span,
+                        MonoItem::Fn(Instance {
+                            def: InstanceDef::ThreadLocalShim(def_id),
+                            args: GenericArgs::empty(),
+                        }),
+                    ),
+                    UsageKind::Reference,
                 ));
             }
         }
@@ -433,13 +515,23 @@ fn collect_items_rec<'tcx>(
                         hir::InlineAsmOperand::SymFn { anon_const } => {
                             let fn_ty =
                                 tcx.typeck_body(anon_const.body).node_type(anon_const.hir_id);
-                            visit_fn_use(tcx, fn_ty, false, *op_sp, &mut used_items, &[]);
+                            visit_fn_use(
+                                tcx,
+                                fn_ty,
+                                UsageKind::Reference,
+                                *op_sp,
+                                &mut used_items,
+                                &[],
+                            );
                         }
                         hir::InlineAsmOperand::SymStatic { path: _, def_id } => {
                             let instance = Instance::mono(tcx, *def_id);
                             if should_codegen_locally(tcx, &instance) {
                                 trace!("collecting static {:?}", def_id);
-                                used_items.push(dummy_spanned(MonoItem::Static(*def_id)));
+                                used_items.push((
+                                    dummy_spanned(MonoItem::Static(*def_id)),
+                                    UsageKind::Reference,
+                                ));
                             }
                         }
                         hir::InlineAsmOperand::In { .. }
@@ -470,7 +562,7 @@ fn collect_items_rec<'tcx>(
     }
     usage_map.lock_mut().record_used(starting_item.node, &used_items);
 
-    for used_item in used_items {
+    for (used_item, _) in used_items {
         collect_items_rec(tcx, used_item, visited, recursion_depths, recursion_limit, usage_map);
     }
 
@@ -700,7 +792,7 @@ fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
                 visit_fn_use(
                     self.tcx,
                     fn_ty,
-                    false,
+                    UsageKind::FnPointer,
                     span,
                     &mut self.output,
                     &self.skip_move_check_fns,
@@ -723,7 +815,10 @@ fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
                         )
                         .expect("failed to normalize and resolve closure during codegen");
                         if should_codegen_locally(self.tcx, &instance) {
-                            self.output.push(create_fn_mono_item(self.tcx, instance, span));
+                            self.output.push((
+                                create_fn_mono_item(self.tcx, instance, span),
+                                UsageKind::FnPointer,
+                            ));
                         }
                     }
                     _ => bug!(),
@@ -734,7 +829,8 @@ fn visit_rvalue(&mut self, rvalue: &mir::Rvalue<'tcx>, location: Location) {
                 let instance = Instance::mono(self.tcx, def_id);
                 if should_codegen_locally(self.tcx, &instance) {
                     trace!("collecting thread-local static {:?}", def_id);
-                    self.output.push(respan(span, MonoItem::Static(def_id)));
+                    self.output
+                        .push((respan(span, MonoItem::Static(def_id)), UsageKind::Reference));
                 }
             }
             _ => { /* not interesting */ }
@@ -795,7 +891,7 @@ fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Loc
         let push_mono_lang_item = |this: &mut Self, lang_item: LangItem| {
             let instance = Instance::mono(tcx, tcx.require_lang_item(lang_item, Some(source)));
             if should_codegen_locally(tcx, &instance) {
-                this.output.push(create_fn_mono_item(tcx, instance, source));
+                this.output.push((create_fn_mono_item(tcx, instance, source), UsageKind::Call));
             }
         };
 
@@ -806,7 +902,7 @@ fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Loc
                 self.skip_move_size_check = visit_fn_use(
                     self.tcx,
                     callee_ty,
-                    true,
+                    UsageKind::Call,
                     source,
                     &mut self.output,
                     &self.skip_move_check_fns,
@@ -815,20 +911,30 @@ fn visit_terminator(&mut self, terminator: &mir::Terminator<'tcx>, location: Loc
             mir::TerminatorKind::Drop { ref place, .. } => {
                 let ty = place.ty(self.body, self.tcx).ty;
                 let ty = self.monomorphize(ty);
-                visit_drop_use(self.tcx, ty, true, source, self.output);
+                visit_drop_use(self.tcx, ty, UsageKind::Drop, source, self.output);
             }
             mir::TerminatorKind::InlineAsm { ref operands, .. } => {
                 for op in operands {
                     match *op {
                         mir::InlineAsmOperand::SymFn { ref value } => {
                             let fn_ty = self.monomorphize(value.literal.ty());
-                            visit_fn_use(self.tcx, fn_ty, false, source, &mut self.output, &[]);
+                            visit_fn_use(
+                                self.tcx,
+                                fn_ty,
+                                UsageKind::Reference,
+                                source,
+                                &mut self.output,
+                                &[],
+                            );
                         }
                         mir::InlineAsmOperand::SymStatic { def_id } => {
                             let instance = Instance::mono(self.tcx, def_id);
                             if should_codegen_locally(self.tcx, &instance) {
                                 trace!("collecting asm sym static {:?}", def_id);
-                                self.output.push(respan(source, MonoItem::Static(def_id)));
+                                self.output.push((
+                                    respan(source, MonoItem::Static(def_id)),
+                                    UsageKind::Reference,
+                                ));
                             }
                         }
                         _ => {}
@@ -884,18 +990,18 @@ fn visit_local(
 fn visit_drop_use<'tcx>(
     tcx: TyCtxt<'tcx>,
     ty: Ty<'tcx>,
-    is_direct_call: bool,
+    kind: UsageKind,
     source: Span,
     output: &mut MonoItems<'tcx>,
 ) {
     let instance = Instance::resolve_drop_in_place(tcx, ty);
-    visit_instance_use(tcx, instance, is_direct_call, source, output);
+    visit_instance_use(tcx, instance, kind, source, output);
 }
 
 fn visit_fn_use<'tcx>(
     tcx: TyCtxt<'tcx>,
     ty: Ty<'tcx>,
-    is_direct_call: bool,
+    kind: UsageKind,
     source: Span,
     output: &mut MonoItems<'tcx>,
     skip_move_check_fns: &[DefId],
@@ -903,7 +1009,7 @@ fn visit_fn_use<'tcx>(
     let mut skip_move_size_check = false;
     if let ty::FnDef(def_id, args) = *ty.kind() {
         skip_move_size_check = skip_move_check_fns.contains(&def_id);
-        let instance = if is_direct_call {
+        let instance = if kind.is_direct_call() {
             ty::Instance::expect_resolve(tcx, ty::ParamEnv::reveal_all(), def_id, args)
         } else {
             match ty::Instance::resolve_for_fn_ptr(tcx, ty::ParamEnv::reveal_all(), def_id, args) {
@@ -911,7 +1017,7 @@ fn visit_fn_use<'tcx>(
                 _ => bug!("failed to resolve instance for {ty}"),
             }
         };
-        visit_instance_use(tcx, instance, is_direct_call, source, output);
+        visit_instance_use(tcx, instance, kind, source, output);
     }
     skip_move_size_check
 }
@@ -919,15 +1025,16 @@ fn visit_fn_use<'tcx>(
 fn visit_instance_use<'tcx>(
     tcx: TyCtxt<'tcx>,
     instance: ty::Instance<'tcx>,
-    is_direct_call: bool,
+    kind: UsageKind,
     source: Span,
     output: &mut MonoItems<'tcx>,
 ) {
-    debug!("visit_item_use({:?}, is_direct_call={:?})", instance, is_direct_call);
+    debug!("visit_item_use({:?}, kind={:?})", instance, kind);
     if !should_codegen_locally(tcx, &instance) {
         return;
     }
 
+    let is_direct_call = kind.is_direct_call();
     match instance.def {
         ty::InstanceDef::Virtual(..) | ty::InstanceDef::Intrinsic(_) => {
             if !is_direct_call {
@@ -940,7 +1047,7 @@ fn visit_instance_use<'tcx>(
         ty::InstanceDef::DropGlue(_, None) => {
             // Don't need to emit noop drop glue if we are calling directly.
             if !is_direct_call {
-                output.push(create_fn_mono_item(tcx, instance, source));
+                output.push((create_fn_mono_item(tcx, instance, source), kind));
             }
         }
         ty::InstanceDef::DropGlue(_, Some(_))
@@ -951,7 +1058,7 @@ fn visit_instance_use<'tcx>(
         | ty::InstanceDef::FnPtrShim(..)
         | ty::InstanceDef::CloneShim(..)
         | ty::InstanceDef::FnPtrAddrShim(..) => {
-            output.push(create_fn_mono_item(tcx, instance, source));
+            output.push((create_fn_mono_item(tcx, instance, source), kind));
         }
     }
 }
@@ -1148,12 +1255,12 @@ fn create_mono_items_for_vtable_methods<'tcx>(
                         Some(*instance).filter(|instance| should_codegen_locally(tcx, instance))
                     }
                 })
-                .map(|item| create_fn_mono_item(tcx, item, source));
+                .map(|item| (create_fn_mono_item(tcx, item, source), UsageKind::Vtable));
             output.extend(methods);
         }
 
         // Also add the destructor.
-        visit_drop_use(tcx, impl_ty, false, source, output);
+        visit_drop_use(tcx, impl_ty, UsageKind::Vtable, source, output);
     }
 }
 
@@ -1178,7 +1285,7 @@ fn process_item(&mut self, id: hir::ItemId) {
                     debug!("RootCollector: ADT drop-glue for `{id:?}`",);
 
                     let ty = self.tcx.type_of(id.owner_id.to_def_id()).no_bound_vars().unwrap();
-                    visit_drop_use(self.tcx, ty, true, DUMMY_SP, self.output);
+                    visit_drop_use(self.tcx, ty, UsageKind::Drop, DUMMY_SP, self.output);
                 }
             }
             DefKind::GlobalAsm => {
@@ -1186,12 +1293,12 @@ fn process_item(&mut self, id: hir::ItemId) {
                     "RootCollector: ItemKind::GlobalAsm({})",
                     self.tcx.def_path_str(id.owner_id)
                 );
-                self.output.push(dummy_spanned(MonoItem::GlobalAsm(id)));
+                self.output.push((dummy_spanned(MonoItem::GlobalAsm(id)), UsageKind::Root));
             }
             DefKind::Static(..) => {
                 let def_id = id.owner_id.to_def_id();
                 debug!("RootCollector: ItemKind::Static({})", self.tcx.def_path_str(def_id));
-                self.output.push(dummy_spanned(MonoItem::Static(def_id)));
+                self.output.push((dummy_spanned(MonoItem::Static(def_id)), UsageKind::Root));
             }
             DefKind::Const => {
                 // const items only generate mono items if they are
@@ -1244,7 +1351,7 @@ fn push_if_root(&mut self, def_id: LocalDefId) {
             debug!("found root");
 
             let instance = Instance::mono(self.tcx, def_id.to_def_id());
-            self.output.push(create_fn_mono_item(self.tcx, instance, DUMMY_SP));
+            self.output.push((create_fn_mono_item(self.tcx, instance, DUMMY_SP), UsageKind::Root));
         }
     }
 
@@ -1280,7 +1387,8 @@ fn push_extra_entry_roots(&mut self) {
         .unwrap()
         .unwrap();
 
-        self.output.push(create_fn_mono_item(self.tcx, start_instance, DUMMY_SP));
+        self.output
+            .push((create_fn_mono_item(self.tcx, start_instance, DUMMY_SP), UsageKind::Root));
     }
 }
 
@@ -1357,7 +1465,7 @@ fn create_mono_items_for_default_impls<'tcx>(
 
         let mono_item = create_fn_mono_item(tcx, instance, DUMMY_SP);
         if mono_item.node.is_instantiable(tcx) && should_codegen_locally(tcx, &instance) {
-            output.push(mono_item);
+            output.push((mono_item, UsageKind::Root));
         }
     }
 }
@@ -1370,7 +1478,7 @@ fn collect_alloc<'tcx>(tcx: TyCtxt<'tcx>, alloc_id: AllocId, output: &mut MonoIt
             let instance = Instance::mono(tcx, def_id);
             if should_codegen_locally(tcx, &instance) {
                 trace!("collecting static {:?}", def_id);
-                output.push(dummy_spanned(MonoItem::Static(def_id)));
+                output.push((dummy_spanned(MonoItem::Static(def_id)), UsageKind::Reference));
             }
         }
         GlobalAlloc::Memory(alloc) => {
@@ -1384,7 +1492,8 @@ fn collect_alloc<'tcx>(tcx: TyCtxt<'tcx>, alloc_id: AllocId, output: &mut MonoIt
         GlobalAlloc::Function(fn_instance) => {
             if should_codegen_locally(tcx, &fn_instance) {
                 trace!("collecting {:?} with {:#?}", alloc_id, fn_instance);
-                output.push(create_fn_mono_item(tcx, fn_instance, DUMMY_SP));
+                output
+                    .push((create_fn_mono_item(tcx, fn_instance, DUMMY_SP), UsageKind::Reference));
             }
         }
         GlobalAlloc::VTable(ty, trait_ref) => {
@@ -3,7 +3,7 @@
 use crate::fluent_generated as fluent;
 use rustc_errors::ErrorGuaranteed;
 use rustc_errors::IntoDiagnostic;
-use rustc_macros::{Diagnostic, LintDiagnostic};
+use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_span::{Span, Symbol};
 
 #[derive(Diagnostic)]
@@ -93,6 +93,49 @@ pub struct CouldntDumpMonoStats {
     pub error: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(monomorphize_couldnt_dump_mono_provenance)]
+pub struct CouldntDumpMonoProvenance {
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(monomorphize_reachable_panic)]
+pub struct ReachablePanic {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+    pub entry_point: String,
+    pub item: String,
+    pub reason: String,
+    #[subdiagnostic]
+    pub chain: Vec<ReachablePanicStep>,
+}
+
+#[derive(Diagnostic)]
+#[diag(monomorphize_reachable_unknown_callee)]
+#[note]
+pub struct ReachableUnknownCallee {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+    pub entry_point: String,
+    pub item: String,
+    pub callee: String,
+    #[subdiagnostic]
+    pub chain: Vec<ReachablePanicStep>,
+}
+
+#[derive(Subdiagnostic)]
+#[note(monomorphize_reachable_panic_step)]
+pub struct ReachablePanicStep {
+    #[primary_span]
+    pub span: Span,
+    pub user: String,
+    pub used: String,
+    pub kind: &'static str,
+}
+
 #[derive(Diagnostic)]
 #[diag(monomorphize_encountered_error_while_instantiating)]
 pub struct EncounteredErrorWhileInstantiating {
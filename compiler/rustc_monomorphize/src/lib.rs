@@ -22,6 +22,8 @@
 mod errors;
 mod partitioning;
 mod polymorphize;
+mod provenance;
+mod reachable_panics;
 mod util;
 
 fluent_messages! { "../messages.ftl" }
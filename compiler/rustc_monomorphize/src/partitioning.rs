@@ -119,7 +119,11 @@
 
 use crate::collector::UsageMap;
 use crate::collector::{self, MonoItemCollectionMode};
-use crate::errors::{CouldntDumpMonoStats, SymbolAlreadyDefined, UnknownCguCollectionMode};
+use crate::errors::{
+    CouldntDumpMonoProvenance, CouldntDumpMonoStats, SymbolAlreadyDefined, UnknownCguCollectionMode,
+};
+use crate::provenance;
+use crate::reachable_panics;
 
 struct PartitioningCx<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
@@ -1107,6 +1111,16 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
 
     tcx.sess.abort_if_errors();
 
+    if let Some(mode) = tcx.sess.opts.unstable_opts.report_reachable_panics {
+        tcx.sess.time("report_reachable_panics", || {
+            reachable_panics::report_reachable_panics(tcx, &items, &usage_map, mode)
+        });
+    }
+
+    if tcx.sess.opts.unstable_opts.item_time_report.enabled() {
+        record_mono_item_requesters(tcx, &items, &usage_map);
+    }
+
     let (codegen_units, _) = tcx.sess.time("partition_and_assert_distinct_symbols", || {
         sync::join(
             || {
@@ -1147,6 +1161,14 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
         }
     }
 
+    if let SwitchWithOptPath::Enabled(ref path) =
+        tcx.sess.opts.unstable_opts.print_mono_items_provenance
+    {
+        if let Err(err) = provenance::dump_mono_item_provenance(tcx, &items, &usage_map, path) {
+            tcx.sess.emit_fatal(CouldntDumpMonoProvenance { error: err.to_string() });
+        }
+    }
+
     if tcx.sess.opts.unstable_opts.print_mono_items.is_some() {
         let mut item_to_cgus: FxHashMap<_, Vec<_>> = Default::default();
 
@@ -1201,6 +1223,56 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
     (tcx.arena.alloc(mono_items), codegen_units)
 }
 
+/// Records the crates that requested each generic function instantiated in this crate, for
+/// `-Z item-time-report`.
+///
+/// An instantiation is requested by the crates of the items that use it. Uses from the crate
+/// defining the generic function itself are followed back to their own users, so that each
+/// instantiation is attributed to a crate downstream of the defining one, e.g. an instance of a
+/// private helper of `alloc` to the crate calling the public function of `alloc` that uses it.
+/// Instantiations that no other mono item uses are roots, requested by the local crate.
+fn record_mono_item_requesters<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    usage_map: &UsageMap<'tcx>,
+) {
+    for &item in items {
+        let MonoItem::Fn(instance) = item else { continue };
+        let defining_crate = instance.def_id().krate;
+        if !tcx.generics_of(instance.def_id()).requires_monomorphization(tcx) {
+            continue;
+        }
+
+        let mut requesters = Vec::new();
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![item];
+        while let Some(item) = stack.pop() {
+            if !visited.insert(item) {
+                continue;
+            }
+            let users = usage_map.get_user_items(item);
+            if users.is_empty() {
+                requesters.push(LOCAL_CRATE);
+            }
+            for &user in users {
+                let krate = user.def_id().krate;
+                if krate == defining_crate && krate != LOCAL_CRATE {
+                    stack.push(user);
+                } else {
+                    requesters.push(krate);
+                }
+            }
+        }
+
+        let mut requesters: Vec<_> =
+            requesters.into_iter().map(|krate| tcx.crate_name(krate).to_string()).collect();
+        requesters.sort_unstable();
+        requesters.dedup();
+        let symbol_name = item.symbol_name(tcx).name.to_string();
+        tcx.sess.code_stats.record_mono_item_requesters(symbol_name, requesters);
+    }
+}
+
 /// Outputs stats about instantiation counts and estimated size, per `MonoItem`'s
 /// def, to a file in the given output directory.
 fn dump_mono_items_stats<'tcx>(
@@ -0,0 +1,235 @@
+//! Output of `-Z print-mono-items-provenance`, which explains why each mono item was collected.
+//!
+//! While collecting, the collector records every use of a mono item: the user, the span of the
+//! use in the body of the user, and the [`UsageKind`]. Here the uses are written out as a graph,
+//! both as JSON and as DOT, together with the shortest chain of uses from a root of the
+//! collection to each item. With `-Z print-mono-items-filter`, only the items with the given
+//! path and their transitive users are written.
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+
+use crate::collector::{UsageKind, UsageMap};
+
+struct Edge {
+    user: usize,
+    used: usize,
+    span: Span,
+    kind: UsageKind,
+}
+
+/// Writes the provenance graph of `items` to `<crate>.mono_provenance.json` and
+/// `<crate>.mono_provenance.dot` in the given output directory.
+pub(crate) fn dump_mono_item_provenance<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    usage_map: &UsageMap<'tcx>,
+    output_directory: &Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output_directory = if let Some(ref directory) = output_directory {
+        fs::create_dir_all(directory)?;
+        directory
+    } else {
+        Path::new(".")
+    };
+
+    // Number the items in the order of their names, to make the output deterministic. Different
+    // items can have the same name, so the nodes are identified by their number, and the symbol
+    // names, which are unique, break ties.
+    let mut items: Vec<_> = items
+        .iter()
+        .map(|&item| {
+            let name = with_no_trimmed_paths!(item.to_string());
+            (name, item.symbol_name(tcx).name, item)
+        })
+        .collect();
+    items.sort_by(|(a, a_symbol, _), (b, b_symbol, _)| (a, a_symbol).cmp(&(b, b_symbol)));
+    let indices: FxHashMap<_, _> =
+        items.iter().enumerate().map(|(index, &(_, _, item))| (item, index)).collect();
+    let def_paths: Vec<_> = items
+        .iter()
+        .map(|&(_, _, item)| with_no_trimmed_paths!(tcx.def_path_str(item.def_id())))
+        .collect();
+
+    let mut edges = Vec::new();
+    for (used, &(_, _, item)) in items.iter().enumerate() {
+        for usage in usage_map.get_usages(item).unwrap_or_default() {
+            // Only collected items can use other items.
+            let user = indices[&usage.user];
+            edges.push(Edge { user, used, span: usage.span, kind: usage.kind });
+        }
+    }
+    // Stable, so that multiple uses by the same user stay in the order of the MIR.
+    edges.sort_by_key(|edge| (edge.user, edge.used));
+
+    let mut roots: Vec<_> = usage_map.roots().iter().map(|root| indices[root]).collect();
+    roots.sort_unstable();
+    roots.dedup();
+    let mut is_root = vec![false; items.len()];
+    for &root in &roots {
+        is_root[root] = true;
+    }
+
+    // The edge through which each item is first reached by a breadth-first search from the
+    // roots. Following these back from an item gives the shortest chain from a root to it.
+    let mut outgoing = vec![Vec::new(); items.len()];
+    let mut incoming = vec![Vec::new(); items.len()];
+    for (index, edge) in edges.iter().enumerate() {
+        outgoing[edge.user].push(index);
+        incoming[edge.used].push(index);
+    }
+    let mut reached_by: Vec<Option<usize>> = vec![None; items.len()];
+    let mut reached = is_root.clone();
+    let mut queue: VecDeque<_> = roots.iter().copied().collect();
+    while let Some(item) = queue.pop_front() {
+        for &edge in &outgoing[item] {
+            let used = edges[edge].used;
+            if !reached[used] {
+                reached[used] = true;
+                reached_by[used] = Some(edge);
+                queue.push_back(used);
+            }
+        }
+    }
+
+    let filter = tcx.sess.opts.unstable_opts.print_mono_items_filter.as_deref();
+    let selected: Vec<_> = (0..items.len())
+        .filter(|&index| filter.map_or(true, |f| items[index].0 == f || def_paths[index] == f))
+        .collect();
+
+    // The selected items and everything that transitively uses them.
+    let mut in_graph = vec![filter.is_none(); items.len()];
+    let mut stack = Vec::new();
+    for &index in &selected {
+        if !in_graph[index] {
+            in_graph[index] = true;
+            stack.push(index);
+        }
+    }
+    while let Some(item) = stack.pop() {
+        for &edge in &incoming[item] {
+            let user = edges[edge].user;
+            if !in_graph[user] {
+                in_graph[user] = true;
+                stack.push(user);
+            }
+        }
+    }
+
+    let mut chain_edges = FxHashSet::default();
+    let chains: Vec<_> = selected
+        .iter()
+        .map(|&index| {
+            let mut chain = Vec::new();
+            let mut item = index;
+            while let Some(edge) = reached_by[item] {
+                chain.push(edge);
+                item = edges[edge].user;
+            }
+            chain.reverse();
+            chain_edges.extend(chain.iter().copied());
+            (index, chain)
+        })
+        .collect();
+
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let span_to_string = |span: Span| {
+        (!span.is_dummy()).then(|| tcx.sess.source_map().span_to_embeddable_string(span))
+    };
+    let edge_to_json = |edge: &Edge| {
+        serde_json::json!({
+            "user": edge.user,
+            "used": edge.used,
+            "kind": edge.kind.as_str(),
+            "span": span_to_string(edge.span),
+        })
+    };
+
+    let nodes: Vec<_> = (0..items.len())
+        .filter(|&index| in_graph[index])
+        .map(|index| {
+            serde_json::json!({
+                "id": index,
+                "name": items[index].0,
+                "symbol": items[index].1,
+                "def_path": def_paths[index],
+                "root": is_root[index],
+            })
+        })
+        .collect();
+    let graph_edges: Vec<_> = edges
+        .iter()
+        .filter(|edge| in_graph[edge.user] && in_graph[edge.used])
+        .map(edge_to_json)
+        .collect();
+    let chains_json: Vec<_> = chains
+        .iter()
+        .map(|(index, chain)| {
+            serde_json::json!({
+                "item": index,
+                "chain": chain.iter().map(|&edge| edge_to_json(&edges[edge])).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let json = serde_json::json!({
+        "crate": crate_name.as_str(),
+        "filter": filter,
+        "nodes": nodes,
+        "edges": graph_edges,
+        "chains": chains_json,
+    });
+    let file = File::create(output_directory.join(format!("{crate_name}.mono_provenance.json")))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &json)?;
+
+    let file = File::create(output_directory.join(format!("{crate_name}.mono_provenance.dot")))?;
+    let mut file = BufWriter::new(file);
+    writeln!(file, "digraph mono_items {{")?;
+    writeln!(file, r#"    node [shape="box", fontname="monospace"];"#)?;
+    let is_selected = |index: usize| filter.is_some() && selected.binary_search(&index).is_ok();
+    for index in (0..items.len()).filter(|&index| in_graph[index]) {
+        let mut attrs = format!(r#"label="{}""#, escape_dot(&items[index].0));
+        if is_root[index] {
+            attrs.push_str(r#", peripheries="2""#);
+        }
+        if is_selected(index) {
+            attrs.push_str(r#", style="filled", fillcolor="lightgrey""#);
+        }
+        writeln!(file, "    n{index} [{attrs}];")?;
+    }
+    // Multiple uses of the same kind between the same items only get a single edge.
+    let mut written = FxHashSet::default();
+    for (index, edge) in edges.iter().enumerate() {
+        if !in_graph[edge.user] || !in_graph[edge.used] {
+            continue;
+        }
+        if !written.insert((edge.user, edge.used, edge.kind)) {
+            continue;
+        }
+        let on_chain = filter.is_some() && chain_edges.contains(&index);
+        let color = if on_chain { r#", color="red""# } else { "" };
+        writeln!(
+            file,
+            r#"    n{} -> n{} [label="{}"{color}];"#,
+            edge.user,
+            edge.used,
+            edge.kind.as_str()
+        )?;
+    }
+    writeln!(file, "}}")?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
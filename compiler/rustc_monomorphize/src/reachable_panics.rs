@@ -0,0 +1,244 @@
+//! The `-Z report-reachable-panics` check, which reports every panic that can be reached from the
+//! entry points of the crate.
+//!
+//! The entry points are the local functions the mono item collection starts from: `main`, and the
+//! functions exported from the crate, such as the `#[no_mangle]` ones. The MIR of every function
+//! the collector reached from them is searched for the calls of panic functions, which are
+//!
+//! - the functions of `core::panicking` and `std::panicking`, which also cover the failed bounds
+//!   and overflow checks, and
+//! - the diverging `#[track_caller]` functions of other crates, like the one `Result::unwrap`
+//!   calls.
+//!
+//! Every call is reported with the shortest chain of uses from an entry point that leads to it,
+//! as recorded in the [`UsageMap`] of the collector.
+//!
+//! The non-generic functions of other crates and foreign functions are only linked to, so the
+//! collector doesn't reach them and their bodies aren't searched. The calls of those functions
+//! are reported as well, as unknown callees that weren't checked.
+
+use std::collections::VecDeque;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::IntoDiagnostic;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::mir::{self, mono::MonoItem};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_session::config::ReachablePanics;
+use rustc_span::symbol::sym;
+use rustc_span::Span;
+
+use crate::collector::{Usage, UsageMap};
+use crate::errors::{ReachablePanic, ReachablePanicStep, ReachableUnknownCallee};
+
+/// Whether calling `def_id` panics.
+fn is_panic_fn(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    if def_id.is_local() {
+        return false;
+    }
+    let krate = tcx.crate_name(def_id.krate);
+    if (krate == sym::core || krate == sym::std)
+        && tcx.opt_parent(def_id).and_then(|parent| tcx.opt_item_name(parent))
+            == Some(sym::panicking)
+    {
+        return true;
+    }
+    // Functions like `core::result::unwrap_failed`, which only exist to panic.
+    matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        && tcx.codegen_fn_attrs(def_id).flags.contains(CodegenFnAttrFlags::TRACK_CALLER)
+        && tcx.fn_sig(def_id).skip_binder().output().skip_binder().is_never()
+}
+
+/// The span of `source_info` in the function the MIR belongs to: for code that was inlined from
+/// another function, the span of the outermost call that was inlined.
+fn outermost_span(body: &mir::Body<'_>, source_info: mir::SourceInfo) -> Span {
+    let mut span = source_info.span;
+    let mut scope = Some(source_info.scope);
+    while let Some(current) = scope {
+        let data = &body.source_scopes[current];
+        if let Some((_, call_span)) = data.inlined {
+            span = call_span;
+        }
+        scope = data.parent_scope;
+    }
+    span
+}
+
+/// What a call or an assertion in a reachable function does.
+enum Finding {
+    /// It panics, for the given reason.
+    Panic(String),
+    /// It calls the given function, whose body isn't searched.
+    UnknownCallee(String),
+}
+
+/// The panics and unknown callees in the body of `instance`, with their spans.
+fn findings_in<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+) -> Vec<(Span, Finding)> {
+    let body = tcx.instance_mir(instance.def);
+    let mut findings = Vec::new();
+    for block in body.basic_blocks.iter() {
+        let terminator = block.terminator();
+        let finding = match &terminator.kind {
+            mir::TerminatorKind::Call { func, .. } => {
+                let callee_ty = instance.subst_mir_and_normalize_erasing_regions(
+                    tcx,
+                    ty::ParamEnv::reveal_all(),
+                    ty::EarlyBinder::bind(func.ty(body, tcx)),
+                );
+                let ty::FnDef(def_id, substs) = *callee_ty.kind() else { continue };
+                let path = |def_id: DefId| {
+                    let path = tcx.def_path(def_id).to_string_no_crate_verbose();
+                    format!("{}{path}", tcx.crate_name(def_id.krate))
+                };
+                if is_panic_fn(tcx, def_id) {
+                    Finding::Panic(format!("`{}` is called here", path(def_id)))
+                } else if let Ok(Some(callee)) =
+                    Instance::resolve(tcx, ty::ParamEnv::reveal_all(), def_id, substs)
+                    && let InstanceDef::Item(callee_def_id) = callee.def
+                    && !items.contains(&MonoItem::Fn(callee))
+                {
+                    Finding::UnknownCallee(path(callee_def_id))
+                } else {
+                    continue;
+                }
+            }
+            mir::TerminatorKind::Assert { msg, .. } => Finding::Panic(match &**msg {
+                mir::AssertKind::BoundsCheck { .. } => "index out of bounds".to_owned(),
+                mir::AssertKind::MisalignedPointerDereference { .. } => {
+                    "misaligned pointer dereference".to_owned()
+                }
+                msg => msg.description().to_owned(),
+            }),
+            _ => continue,
+        };
+        findings.push((outermost_span(body, terminator.source_info), finding));
+    }
+    findings
+}
+
+/// Emits `diag` as a warning or as an error, depending on `mode`.
+fn emit<'a>(
+    tcx: TyCtxt<'a>,
+    mode: ReachablePanics,
+    diag: impl IntoDiagnostic<'a> + IntoDiagnostic<'a, ()>,
+) {
+    match mode {
+        ReachablePanics::Warn => tcx.sess.emit_warning(diag),
+        ReachablePanics::Deny => {
+            tcx.sess.emit_err(diag);
+        }
+    }
+}
+
+/// Reports the panics reachable from the entry points of the crate.
+pub(crate) fn report_reachable_panics<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    items: &FxHashSet<MonoItem<'tcx>>,
+    usage_map: &UsageMap<'tcx>,
+    mode: ReachablePanics,
+) {
+    // Visit the items in the order of their names, to make the output deterministic.
+    let mut sorted_items: Vec<_> = items
+        .iter()
+        .map(|&item| {
+            let name = match item {
+                MonoItem::Fn(instance) => with_no_trimmed_paths!(instance.to_string()),
+                _ => with_no_trimmed_paths!(item.to_string()),
+            };
+            (name, item)
+        })
+        .collect();
+    sorted_items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let names: FxHashMap<_, _> =
+        sorted_items.iter().map(|(name, item)| (*item, name.as_str())).collect();
+
+    let mut uses: FxHashMap<MonoItem<'tcx>, Vec<(MonoItem<'tcx>, Usage<'tcx>)>> =
+        FxHashMap::default();
+    for &(_, item) in &sorted_items {
+        for &usage in usage_map.get_usages(item).unwrap_or_default() {
+            uses.entry(usage.user).or_default().push((item, usage));
+        }
+    }
+
+    let mut entry_points: Vec<_> = usage_map
+        .roots()
+        .iter()
+        .copied()
+        .filter(|root| match root {
+            MonoItem::Fn(instance) => {
+                matches!(instance.def, InstanceDef::Item(_)) && instance.def_id().is_local()
+            }
+            MonoItem::Static(_) | MonoItem::GlobalAsm(_) => false,
+        })
+        .collect();
+    entry_points.sort_by_key(|item| names[item]);
+    entry_points.dedup();
+
+    // The use through which each item is first reached by a breadth-first search from the entry
+    // points, which gives the shortest chains. The panic functions themselves are not searched.
+    let mut reached_by: FxHashMap<MonoItem<'tcx>, Option<Usage<'tcx>>> =
+        entry_points.iter().map(|&item| (item, None)).collect();
+    let mut queue: VecDeque<_> = entry_points.iter().copied().collect();
+    while let Some(item) = queue.pop_front() {
+        for &(used, usage) in uses.get(&item).map(|uses| uses.as_slice()).unwrap_or_default() {
+            if let MonoItem::Fn(instance) = used
+                && is_panic_fn(tcx, instance.def_id())
+            {
+                continue;
+            }
+            if !reached_by.contains_key(&used) {
+                reached_by.insert(used, Some(usage));
+                queue.push_back(used);
+            }
+        }
+    }
+
+    let mut reported = FxHashSet::default();
+    for &(ref name, item) in &sorted_items {
+        let MonoItem::Fn(instance) = item else { continue };
+        if !reached_by.contains_key(&item) || is_panic_fn(tcx, instance.def_id()) {
+            continue;
+        }
+        for (span, finding) in findings_in(tcx, instance, items) {
+            // The instances of a generic function all panic at the same places.
+            let key = match &finding {
+                Finding::Panic(reason) | Finding::UnknownCallee(reason) => reason.clone(),
+            };
+            if !reported.insert((span, key)) {
+                continue;
+            }
+            let mut chain = Vec::new();
+            let mut current = item;
+            while let Some(&Some(usage)) = reached_by.get(&current) {
+                chain.push(ReachablePanicStep {
+                    span: usage.span,
+                    user: names[&usage.user].to_owned(),
+                    used: names[&current].to_owned(),
+                    kind: usage.kind.as_str(),
+                });
+                current = usage.user;
+            }
+            chain.reverse();
+            let entry_point = names[&current].to_owned();
+            match finding {
+                Finding::Panic(reason) => emit(
+                    tcx,
+                    mode,
+                    ReachablePanic { span, entry_point, item: name.clone(), reason, chain },
+                ),
+                Finding::UnknownCallee(callee) => emit(
+                    tcx,
+                    mode,
+                    ReachableUnknownCallee { span, entry_point, item: name.clone(), callee, chain },
+                ),
+            }
+        }
+    }
+}
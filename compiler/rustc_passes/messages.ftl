@@ -402,6 +402,16 @@ passes_lang_item_on_incorrect_target =
     `{$name}` language item must be applied to a {$expected_target}
     .label = attribute should be applied to a {$expected_target}, not a {$actual_target}
 
+passes_layering_spec_invalid =
+    invalid layering rule on line {$line} of `{$path}`: {$reason}
+
+passes_layering_spec_read =
+    failed to read layering rules from `{$path}`: {$error}
+
+passes_layering_violation =
+    `{$module}` must not depend on `{$path}`
+    .note = denied by `{$rule}` on line {$line} of `{$spec}`
+
 passes_layout =
     layout error: {$layout_error}
 
@@ -742,6 +752,9 @@ passes_unused_capture_maybe_capture_ref = value captured by `{$name}` is never r
 passes_unused_default_method_body_const_note =
     `default_method_body_is_const` has been replaced with `#[const_trait]` on traits
 
+passes_unused_dependency =
+    `{$krate}` depends on `{$dependency}`, but doesn't use any of its items
+
 passes_unused_duplicate =
     unused attribute
     .suggestion = remove this attribute
@@ -759,6 +772,17 @@ passes_unused_multiple =
 passes_unused_no_lints_note =
     attribute `{$name}` without any lints has no effect
 
+passes_unused_pub_item = `{$path}` is not used
+
+passes_unused_pub_items =
+    {$count ->
+        [one] a public item
+        *[other] {$count} public items
+    } of `{$krate}` {$count ->
+        [one] is
+        *[other] are
+    } not used by any crate of the dependency graph
+
 passes_unused_var_assigned_only = variable `{$name}` is assigned to, but never used
     .note = consider using `_{$name}` instead
 
@@ -1766,3 +1766,53 @@ pub struct AttrCrateLevelOnlySugg {
     #[primary_span]
     pub attr: Span,
 }
+
+#[derive(Diagnostic)]
+#[diag(passes_layering_spec_read)]
+pub struct LayeringSpecRead {
+    pub path: PathBuf,
+    pub error: Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_layering_spec_invalid)]
+pub struct LayeringSpecInvalid {
+    pub path: PathBuf,
+    pub line: usize,
+    pub reason: &'static str,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(passes_layering_violation)]
+#[note]
+pub struct LayeringViolation {
+    pub module: String,
+    pub path: String,
+    pub rule: String,
+    pub line: usize,
+    pub spec: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_unused_pub_items)]
+pub struct UnusedPubItems {
+    pub krate: Symbol,
+    pub count: usize,
+    #[subdiagnostic]
+    pub items: Vec<UnusedPubItem>,
+}
+
+#[derive(Subdiagnostic)]
+#[note(passes_unused_pub_item)]
+pub struct UnusedPubItem {
+    #[primary_span]
+    pub span: Span,
+    pub path: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_unused_dependency)]
+pub struct UnusedDependency {
+    pub krate: Symbol,
+    pub dependency: Symbol,
+}
@@ -0,0 +1,199 @@
+//! Checks the paths used in the crate against the layering rules given with
+//! `-Z dependency-layering`, and reports the [`LAYERING_VIOLATIONS`] lint for
+//! every resolved path that a rule denies.
+//!
+//! Each line of the rules file is either empty, a comment starting with `#`, or
+//! a rule of the form
+//!
+//! ```text
+//! deny crate::domain -> crate::infra
+//! allow crate::domain -> crate::infra::ids
+//! ```
+//!
+//! which denies (or allows) paths in the modules under `crate::domain` that
+//! resolve to items under `crate::infra`. Paths start with `crate` or the name
+//! of the current crate for its own items, or with the name of another crate;
+//! `*` matches everything. Paths are compared with the path where an item is
+//! *defined*, so re-exports can't be used to sidestep a rule. If several rules
+//! apply to a path, the one with the longest module path wins, then the one with
+//! the longest item path, then the later one. Paths no rule applies to are allowed.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{DefId, LocalModDefId, LOCAL_CRATE};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_hir::HirId;
+use rustc_middle::hir::nested_filter;
+use rustc_middle::lint::in_external_macro;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::lint::builtin::LAYERING_VIOLATIONS;
+use rustc_span::symbol::{kw, Symbol};
+use std::path::Path;
+
+use crate::errors::{LayeringSpecInvalid, LayeringSpecRead, LayeringViolation};
+
+struct Rule {
+    allow: bool,
+    /// The modules the rule applies to, empty for all modules.
+    from: Vec<Symbol>,
+    /// The items the rule applies to, empty for all items.
+    to: Vec<Symbol>,
+    /// The text of the rule, for diagnostics.
+    text: String,
+    line: usize,
+}
+
+fn parse_path(s: &str, local_crate: Symbol) -> Result<Vec<Symbol>, &'static str> {
+    if s == "*" {
+        return Ok(Vec::new());
+    }
+    let mut segments = Vec::new();
+    for (index, segment) in s.split("::").enumerate() {
+        if segment.is_empty() || !segment.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err("expected a path like `crate::module` or `*`");
+        }
+        let segment = Symbol::intern(segment);
+        // Normalize the paths to the current crate.
+        if index == 0 && segment == local_crate {
+            segments.push(kw::Crate);
+        } else {
+            segments.push(segment);
+        }
+    }
+    Ok(segments)
+}
+
+fn parse_rules(spec: &str, local_crate: Symbol) -> Result<Vec<Rule>, (usize, &'static str)> {
+    let mut rules = Vec::new();
+    for (index, line) in spec.lines().enumerate() {
+        let line_number = index + 1;
+        let text = line.split('#').next().unwrap().trim();
+        if text.is_empty() {
+            continue;
+        }
+        let (allow, rest) = if let Some(rest) = text.strip_prefix("allow ") {
+            (true, rest)
+        } else if let Some(rest) = text.strip_prefix("deny ") {
+            (false, rest)
+        } else {
+            return Err((line_number, "expected a rule starting with `allow` or `deny`"));
+        };
+        let Some((from, to)) = rest.split_once("->") else {
+            return Err((line_number, "expected `->` between the module and the item path"));
+        };
+        let from = parse_path(from.trim(), local_crate).map_err(|msg| (line_number, msg))?;
+        if from.first().is_some_and(|&krate| krate != kw::Crate) {
+            return Err((line_number, "rules can only apply to modules of the current crate"));
+        }
+        let to = parse_path(to.trim(), local_crate).map_err(|msg| (line_number, msg))?;
+        rules.push(Rule { allow, from, to, text: text.to_owned(), line: line_number });
+    }
+    Ok(rules)
+}
+
+/// The path of `def_id` as used by the rules, starting with `crate` for local items.
+fn def_path_segments(tcx: TyCtxt<'_>, def_id: DefId) -> Vec<Symbol> {
+    let krate = if def_id.is_local() { kw::Crate } else { tcx.crate_name(def_id.krate) };
+    std::iter::once(krate)
+        .chain(tcx.def_path(def_id).data.iter().filter_map(|data| data.data.get_opt_name()))
+        .collect()
+}
+
+fn path_to_string(segments: &[Symbol]) -> String {
+    segments.iter().map(|segment| segment.as_str()).collect::<Vec<_>>().join("::")
+}
+
+struct LayeringVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    spec_path: &'a Path,
+    rules: &'a [Rule],
+    module_paths: FxHashMap<LocalModDefId, Vec<Symbol>>,
+}
+
+impl<'tcx> LayeringVisitor<'_, 'tcx> {
+    fn check_path(&mut self, path: &hir::Path<'tcx>, hir_id: HirId) {
+        let Res::Def(kind, def_id) = path.res else { return };
+        if matches!(
+            kind,
+            DefKind::TyParam | DefKind::ConstParam | DefKind::LifetimeParam | DefKind::Field
+        ) {
+            return;
+        }
+        // Uses expanded from macros of other crates are their responsibility.
+        if in_external_macro(self.tcx.sess, path.span) {
+            return;
+        }
+
+        let tcx = self.tcx;
+        let module = tcx.parent_module(hir_id);
+        let module_path = self
+            .module_paths
+            .entry(module)
+            .or_insert_with(|| def_path_segments(tcx, module.to_def_id()));
+        let item_path = def_path_segments(tcx, def_id);
+
+        let rule = self
+            .rules
+            .iter()
+            .filter(|rule| module_path.starts_with(&rule.from) && item_path.starts_with(&rule.to))
+            .max_by_key(|rule| (rule.from.len(), rule.to.len(), rule.line));
+        if let Some(rule) = rule
+            && !rule.allow
+        {
+            tcx.emit_spanned_lint(
+                LAYERING_VIOLATIONS,
+                hir_id,
+                path.span,
+                LayeringViolation {
+                    module: path_to_string(module_path),
+                    path: path_to_string(&item_path),
+                    rule: rule.text.clone(),
+                    line: rule.line,
+                    spec: self.spec_path.display().to_string(),
+                },
+            );
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for LayeringVisitor<'_, 'tcx> {
+    type NestedFilter = nested_filter::All;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_path(&mut self, path: &hir::Path<'tcx>, hir_id: HirId) {
+        self.check_path(path, hir_id);
+        intravisit::walk_path(self, path);
+    }
+}
+
+/// Checks the crate against the rules of `-Z dependency-layering`, if given.
+pub fn check_crate(tcx: TyCtxt<'_>) {
+    let Some(spec_path) = &tcx.sess.opts.unstable_opts.dependency_layering else {
+        return;
+    };
+    let spec = match std::fs::read_to_string(spec_path) {
+        Ok(spec) => spec,
+        Err(error) => {
+            tcx.sess.emit_err(LayeringSpecRead { path: spec_path.clone(), error });
+            return;
+        }
+    };
+    let rules = match parse_rules(&spec, tcx.crate_name(LOCAL_CRATE)) {
+        Ok(rules) => rules,
+        Err((line, reason)) => {
+            tcx.sess.emit_err(LayeringSpecInvalid { path: spec_path.clone(), line, reason });
+            return;
+        }
+    };
+    if rules.iter().all(|rule| rule.allow) {
+        return;
+    }
+
+    let mut visitor =
+        LayeringVisitor { tcx, spec_path, rules: &rules, module_paths: Default::default() };
+    tcx.hir().walk_toplevel_module(&mut visitor);
+}
@@ -35,6 +35,7 @@
 pub mod hir_id_validator;
 pub mod hir_stats;
 mod lang_items;
+pub mod layering;
 pub mod layout_test;
 mod lib_features;
 mod liveness;
@@ -43,6 +44,7 @@
 mod reachable;
 pub mod stability;
 mod upvars;
+pub mod used_items;
 mod weak_lang_items;
 
 fluent_messages! { "../messages.ftl" }
@@ -62,4 +64,5 @@ pub fn provide(providers: &mut Providers) {
     reachable::provide(providers);
     stability::provide(providers);
     upvars::provide(providers);
+    used_items::provide(providers);
 }
@@ -0,0 +1,243 @@
+//! Records which items of other crates a crate uses, for `-Z record-used-items`, and reports the
+//! unused public API of a crate graph for `-Z report-unused-pub-items`.
+//!
+//! With `-Z record-used-items`, the items of other crates that are named by a path, called as a
+//! method or appear in the type of an expression are stored in the metadata of the crate,
+//! together with the `--extern` dependencies none of whose items are used. A crate compiled with
+//! `-Z report-unused-pub-items` loads these records for all crates in its dependency graph and
+//! reports the public items of the recording crates that no recording crate uses, as well as the
+//! unused dependencies. This is meant to be passed to the last crate of a workspace, such as the
+//! binary, once all libraries have been compiled with `-Z record-used-items`.
+
+use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
+use rustc_hir as hir;
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_hir::HirId;
+use rustc_middle::hir::nested_filter;
+use rustc_middle::middle::used_items::UsedItems;
+use rustc_middle::query::{LocalCrate, Providers};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, GenericArgKind, Ty, TyCtxt};
+use rustc_session::config::ExternLocation;
+use rustc_span::Symbol;
+use std::iter;
+
+use crate::errors::{UnusedDependency, UnusedPubItem, UnusedPubItems};
+
+struct UsedItemsVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    maybe_typeck_results: Option<&'tcx ty::TypeckResults<'tcx>>,
+    items: FxIndexSet<DefId>,
+}
+
+impl<'tcx> UsedItemsVisitor<'tcx> {
+    fn record(&mut self, def_id: DefId) {
+        if def_id.is_local() || !self.items.insert(def_id) {
+            return;
+        }
+        let tcx = self.tcx;
+        match tcx.def_kind(def_id) {
+            // Using a part of an item uses the item itself.
+            DefKind::Variant
+            | DefKind::Field
+            | DefKind::Ctor(..)
+            | DefKind::AssocFn
+            | DefKind::AssocConst
+            | DefKind::AssocTy => {
+                let parent = tcx.parent(def_id);
+                if let DefKind::Impl { .. } = tcx.def_kind(parent) {
+                    if let Some(trait_def_id) = tcx.trait_id_of_impl(parent) {
+                        self.record(trait_def_id);
+                    }
+                    if let Some(adt) = tcx.type_of(parent).instantiate_identity().ty_adt_def() {
+                        self.record(adt.did());
+                    }
+                } else {
+                    self.record(parent);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Records the items that `ty` refers to, for the uses of types that aren't named.
+    fn record_ty(&mut self, ty: Ty<'tcx>) {
+        for arg in ty.walk() {
+            let GenericArgKind::Type(ty) = arg.unpack() else { continue };
+            match *ty.kind() {
+                ty::Adt(adt, _) => self.record(adt.did()),
+                ty::Foreign(def_id) | ty::FnDef(def_id, _) => self.record(def_id),
+                ty::Dynamic(predicates, ..) => {
+                    if let Some(def_id) = predicates.principal_def_id() {
+                        self.record(def_id);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for UsedItemsVisitor<'tcx> {
+    type NestedFilter = nested_filter::All;
+
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_nested_body(&mut self, body: hir::BodyId) {
+        let old_maybe_typeck_results =
+            self.maybe_typeck_results.replace(self.tcx.typeck_body(body));
+        let body = self.tcx.hir().body(body);
+        self.visit_body(body);
+        self.maybe_typeck_results = old_maybe_typeck_results;
+    }
+
+    fn visit_path(&mut self, path: &hir::Path<'tcx>, _: HirId) {
+        if let Res::Def(_, def_id) = path.res {
+            self.record(def_id);
+        }
+        intravisit::walk_path(self, path);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        if let Some(typeck_results) = self.maybe_typeck_results {
+            if let Some(def_id) = typeck_results.type_dependent_def_id(expr.hir_id) {
+                self.record(def_id);
+            }
+            if let Some(ty) = typeck_results.expr_ty_adjusted_opt(expr) {
+                self.record_ty(ty);
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &'tcx hir::Pat<'tcx>) {
+        if let Some(typeck_results) = self.maybe_typeck_results
+            && let Some(def_id) = typeck_results.type_dependent_def_id(pat.hir_id)
+        {
+            self.record(def_id);
+        }
+        intravisit::walk_pat(self, pat);
+    }
+}
+
+fn used_items(tcx: TyCtxt<'_>, _: LocalCrate) -> Option<UsedItems> {
+    let opts = &tcx.sess.opts.unstable_opts;
+    if !opts.record_used_items && !opts.report_unused_pub_items {
+        return None;
+    }
+
+    let mut visitor =
+        UsedItemsVisitor { tcx, maybe_typeck_results: None, items: Default::default() };
+    tcx.hir().walk_toplevel_module(&mut visitor);
+
+    let used_crates: FxHashSet<CrateNum> =
+        visitor.items.iter().map(|def_id| def_id.krate).collect();
+    let mut unused_dependencies = Vec::new();
+    for (name, entry) in tcx.sess.opts.externs.iter() {
+        // Like `unused_crate_dependencies`, ignore pathless `--extern`s of sysroot crates and
+        // the ones that are explicitly allowed to be unused.
+        let ExternLocation::ExactPaths(paths) = &entry.location else { continue };
+        if entry.nounused_dep || entry.force {
+            continue;
+        }
+        // Crates that are never used aren't even loaded.
+        let used = tcx.crates(()).iter().any(|&cnum| {
+            used_crates.contains(&cnum)
+                && tcx
+                    .used_crate_source(cnum)
+                    .paths()
+                    .any(|source| paths.iter().any(|path| path.canonicalized() == source))
+        });
+        if !used {
+            unused_dependencies.push(Symbol::intern(name));
+        }
+    }
+
+    Some(UsedItems { items: visitor.items.into_iter().collect(), unused_dependencies })
+}
+
+/// The public items of `krate` that other crates can use: those reachable through public
+/// modules, and the public associated items of their inherent impls.
+fn public_items(tcx: TyCtxt<'_>, krate: CrateNum) -> FxIndexSet<DefId> {
+    let mut items = FxIndexSet::default();
+    let mut modules = vec![krate.as_def_id()];
+    let mut seen_modules = FxHashSet::default();
+    while let Some(module) = modules.pop() {
+        for child in tcx.module_children(module) {
+            let Res::Def(kind, def_id) = child.res else { continue };
+            // Re-exports of the items of other crates are part of the API of those crates.
+            if !child.vis.is_public() || def_id.krate != krate {
+                continue;
+            }
+            match kind {
+                DefKind::Mod => {
+                    if seen_modules.insert(def_id) {
+                        modules.push(def_id);
+                    }
+                }
+                // The uses of macros aren't recorded, and constructors count as their struct or
+                // variant.
+                DefKind::Macro(_) | DefKind::Ctor(..) => {}
+                DefKind::Struct | DefKind::Enum | DefKind::Union => {
+                    if items.insert(def_id) {
+                        for &impl_def_id in tcx.inherent_impls(def_id) {
+                            items.extend(
+                                tcx.associated_item_def_ids(impl_def_id)
+                                    .iter()
+                                    .copied()
+                                    .filter(|&item| tcx.visibility(item).is_public()),
+                            );
+                        }
+                    }
+                }
+                _ => {
+                    items.insert(def_id);
+                }
+            }
+        }
+    }
+    items
+}
+
+/// Reports the public items of the upstream crates compiled with `-Z record-used-items` that no
+/// crate of the dependency graph uses, and the unused dependencies of these crates.
+pub fn report_unused_pub_items(tcx: TyCtxt<'_>) {
+    if !tcx.sess.opts.unstable_opts.report_unused_pub_items {
+        return;
+    }
+
+    let recorded: Vec<_> = iter::once(LOCAL_CRATE)
+        .chain(tcx.crates(()).iter().copied())
+        .filter_map(|cnum| Some((cnum, tcx.used_items(cnum).as_ref()?)))
+        .collect();
+    let used: FxHashSet<DefId> =
+        recorded.iter().flat_map(|(_, used_items)| used_items.items.iter().copied()).collect();
+
+    for &(cnum, used_items) in &recorded {
+        let krate = tcx.crate_name(cnum);
+        if cnum != LOCAL_CRATE {
+            let unused: Vec<_> = public_items(tcx, cnum)
+                .into_iter()
+                .filter(|def_id| !used.contains(def_id))
+                .map(|def_id| UnusedPubItem {
+                    span: tcx.def_span(def_id),
+                    path: with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+                })
+                .collect();
+            if !unused.is_empty() {
+                tcx.sess.emit_warning(UnusedPubItems { krate, count: unused.len(), items: unused });
+            }
+        }
+        for &dependency in &used_items.unused_dependencies {
+            tcx.sess.emit_warning(UnusedDependency { krate, dependency });
+        }
+    }
+}
+
+pub fn provide(providers: &mut Providers) {
+    providers.used_items = used_items;
+}
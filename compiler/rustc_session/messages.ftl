@@ -31,6 +31,16 @@ session_file_is_not_writeable = output file {$file} is not writeable -- check it
 
 session_file_write_fail = failed to write `{$path}` due to error `{$err}`
 
+session_fix_suggestions_file_changed = `{$path}` changed during compilation, not applying suggestions to it
+
+session_fix_suggestions_io = failed to apply suggestions to `{$path}`: {$err}
+
+session_fix_suggestions_skipped = skipped {$count} machine-applicable {$count ->
+    [one] suggestion
+    *[other] suggestions
+    }
+    .note = suggestions are skipped when they overlap an already applied suggestion or point into macro-generated code; run again to apply the remaining ones
+
 session_hexadecimal_float_literal_not_supported = hexadecimal float literal is not supported
 
 session_incompatible_linker_flavor = linker flavor `{$flavor}` is incompatible with the current target
@@ -73,6 +83,8 @@ session_invalid_num_literal_suffix = invalid suffix `{$suffix}` for number liter
 
 session_linker_plugin_lto_windows_not_supported = linker plugin based LTO is not supported together with `-C prefer-dynamic` when targeting Windows-like targets
 
+session_lint_baseline_write = failed to write lint baseline file `{$path}`: {$err}
+
 session_not_circumvent_feature = `-Zunleash-the-miri-inside-of-you` may not be used to circumvent feature gates, except when testing error paths in the CTFE engine
 
 session_not_supported = not supported
@@ -85,10 +85,85 @@ pub struct VTableSizeInfo {
     pub upcasting_cost_percent: f64,
 }
 
+/// A local of an `async fn` or block that is saved in its future across an await point.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SavedLocalInfo {
+    pub name: Symbol,
+    /// Where the local is declared.
+    pub span: String,
+    pub size: u64,
+    /// Whether the local is itself a future, like the one being awaited.
+    pub is_future: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AwaitPointInfo {
+    pub span: String,
+    /// The size of the future while it is suspended at this await point.
+    pub size: u64,
+    /// The locals saved across the await point, largest first.
+    pub locals: Vec<SavedLocalInfo>,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct FutureLayoutInfo {
+    pub type_description: String,
+    pub overall_size: u64,
+    pub await_points: Vec<AwaitPointInfo>,
+}
+
+/// A callsite considered by the MIR inliner.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct MirInliningInfo {
+    /// The function whose MIR contains the call.
+    pub caller: String,
+    /// The resolved callee instance, or the called function if it could not be resolved.
+    pub callee: String,
+    pub span: String,
+    /// The number of inlined bodies the call is nested in.
+    pub depth: usize,
+    /// The cost of the callee body and the threshold it was compared against, if the inliner
+    /// got as far as computing them.
+    pub cost: Option<(usize, usize)>,
+    /// Why the callee was not inlined, or `None` if it was.
+    pub rejection: Option<&'static str>,
+}
+
+/// The work done by the const evaluator, for `-Z const-eval-profile`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ConstEvalStats {
+    /// The number of MIR statements that were executed.
+    pub statements: u64,
+    /// The number of steps counted towards the `long_running_const_eval` lint.
+    pub steps: u64,
+    /// The number of function calls.
+    pub calls: u64,
+    /// The number of bytes of memory that were allocated. Locals only count once they are moved to
+    /// memory, e.g. because a reference to them is taken.
+    pub allocated_bytes: u64,
+}
+
+impl ConstEvalStats {
+    pub fn add(&mut self, other: &ConstEvalStats) {
+        self.statements += other.statements;
+        self.steps += other.steps;
+        self.calls += other.calls;
+        self.allocated_bytes += other.allocated_bytes;
+    }
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    future_layouts: Lock<FxHashSet<FutureLayoutInfo>>,
     vtable_sizes: Lock<FxHashMap<DefId, VTableSizeInfo>>,
+    mir_inlining: Lock<Vec<MirInliningInfo>>,
+    /// The work done to evaluate each const, static and promoted, and the work done in each
+    /// function called during these evaluations.
+    const_eval_items: Lock<FxHashMap<String, ConstEvalStats>>,
+    const_eval_functions: Lock<FxHashMap<String, ConstEvalStats>>,
+    /// The crates that requested each generic mono item, by symbol name.
+    mono_item_requesters: Lock<FxHashMap<String, Vec<String>>>,
 }
 
 impl CodeStats {
@@ -122,6 +197,40 @@ pub fn record_type_size<S: ToString>(
         self.type_sizes.borrow_mut().insert(info);
     }
 
+    pub fn record_future_layout(&self, info: FutureLayoutInfo) {
+        self.future_layouts.borrow_mut().insert(info);
+    }
+
+    pub fn record_mir_inlining(&self, info: MirInliningInfo) {
+        self.mir_inlining.borrow_mut().push(info);
+    }
+
+    /// Records the work done to evaluate `item`, and the part of it done in each called function.
+    pub fn record_const_eval(
+        &self,
+        item: String,
+        total: ConstEvalStats,
+        functions: impl IntoIterator<Item = (String, ConstEvalStats)>,
+    ) {
+        // An item can be evaluated more than once, e.g. for different `ParamEnv`s.
+        self.const_eval_items.borrow_mut().entry(item).or_default().add(&total);
+        let mut const_eval_functions = self.const_eval_functions.borrow_mut();
+        for (function, stats) in functions {
+            const_eval_functions.entry(function).or_default().add(&stats);
+        }
+    }
+
+    /// Records the crates that requested the generic mono item with the given symbol name, for
+    /// `-Z item-time-report`.
+    pub fn record_mono_item_requesters(&self, symbol_name: String, crates: Vec<String>) {
+        self.mono_item_requesters.borrow_mut().insert(symbol_name, crates);
+    }
+
+    /// The crates that requested the generic mono item with the given symbol name, if recorded.
+    pub fn mono_item_requesters(&self, symbol_name: &str) -> Option<Vec<String>> {
+        self.mono_item_requesters.borrow().get(symbol_name).cloned()
+    }
+
     pub fn record_vtable_size(&self, trait_did: DefId, trait_name: &str, info: VTableSizeInfo) {
         let prev = self.vtable_sizes.lock().insert(trait_did, info);
         assert!(
@@ -226,6 +335,55 @@ pub fn print_type_sizes(&self) {
         }
     }
 
+    pub fn print_future_layouts(&self) {
+        let future_layouts = self.future_layouts.borrow();
+        let mut sorted: Vec<_> = future_layouts.iter().collect();
+
+        // Primary sort: large-to-small.
+        // Secondary sort: description (dictionary order)
+        sorted.sort_by_key(|info| (cmp::Reverse(info.overall_size), &info.type_description));
+
+        for info in sorted {
+            let FutureLayoutInfo { type_description, overall_size, await_points } = info;
+            println!("print-future-layout future: `{type_description}`: {overall_size} bytes");
+            for AwaitPointInfo { span, size, locals } in await_points {
+                println!("print-future-layout     await point at {span}: {size} bytes");
+                for SavedLocalInfo { name, span, size, is_future } in locals {
+                    let kind = if *is_future { "future" } else { "local" };
+                    println!(
+                        "print-future-layout         {kind} `.{name}`: {size} bytes, \
+                              declared at {span}"
+                    );
+                }
+            }
+
+            // The same future can be live across several await points, so only count it once.
+            let mut nested: Vec<_> = await_points
+                .iter()
+                .flat_map(|point| &point.locals)
+                .filter(|local| local.is_future)
+                .collect();
+            nested.sort_by(|a, b| {
+                b.size
+                    .cmp(&a.size)
+                    .then_with(|| a.span.cmp(&b.span))
+                    .then_with(|| a.name.as_str().cmp(b.name.as_str()))
+            });
+            nested.dedup_by(|a, b| a.span == b.span && a.name == b.name);
+            if nested.is_empty() {
+                continue;
+            }
+            println!("print-future-layout     largest nested futures:");
+            for SavedLocalInfo { name, span, size, .. } in nested {
+                let percent = if *overall_size == 0 { 0 } else { size * 100 / overall_size };
+                println!(
+                    "print-future-layout         `.{name}`: {size} bytes \
+                          ({percent}% of the future), declared at {span}"
+                );
+            }
+        }
+    }
+
     pub fn print_vtable_sizes(&self, crate_name: &str) {
         let mut infos =
             std::mem::take(&mut *self.vtable_sizes.lock()).into_values().collect::<Vec<_>>();
@@ -252,4 +410,54 @@ pub fn print_vtable_sizes(&self, crate_name: &str) {
             );
         }
     }
+
+    /// Returns the callsites recorded by the MIR inliner, grouped by caller. The callsites of
+    /// each caller are kept in the order the inliner considered them.
+    pub fn mir_inlining(&self) -> Vec<MirInliningInfo> {
+        let mut infos = self.mir_inlining.borrow().clone();
+        // Bodies are optimized in an unspecified order, so sort by caller for stable output.
+        infos.sort_by(|a, b| a.caller.cmp(&b.caller));
+        infos
+    }
+
+    pub fn print_mir_inlining(&self) {
+        let mut current_caller = None;
+        for MirInliningInfo { caller, callee, span, depth, cost, rejection } in self.mir_inlining()
+        {
+            if current_caller.as_ref() != Some(&caller) {
+                println!("print-mir-inlining caller: `{caller}`");
+                current_caller = Some(caller);
+            }
+            let indent = "    ".repeat(depth + 1);
+            let cost = match cost {
+                Some((cost, threshold)) => format!(" (cost {cost}, threshold {threshold})"),
+                None => String::new(),
+            };
+            match rejection {
+                None => println!("print-mir-inlining {indent}inlined `{callee}` at {span}{cost}"),
+                Some(reason) => println!(
+                    "print-mir-inlining {indent}not inlined `{callee}` at {span}: {reason}{cost}"
+                ),
+            }
+        }
+    }
+
+    pub fn print_const_eval_profile(&self) {
+        fn print_sorted(kind: &str, stats: &FxHashMap<String, ConstEvalStats>) {
+            let mut sorted: Vec<_> = stats.iter().collect();
+            // Primary sort: most statements first.
+            // Secondary sort: name (dictionary order)
+            sorted.sort_by_key(|(name, stats)| (cmp::Reverse(stats.statements), *name));
+            for (name, stats) in sorted {
+                let ConstEvalStats { statements, steps, calls, allocated_bytes } = stats;
+                println!(
+                    "const-eval-profile {kind}: `{name}`: {statements} statements, {steps} steps, \
+                    {calls} calls, {allocated_bytes} bytes allocated"
+                );
+            }
+        }
+
+        print_sorted("item", &self.const_eval_items.borrow());
+        print_sorted("function", &self.const_eval_functions.borrow());
+    }
 }
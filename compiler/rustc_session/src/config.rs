@@ -176,6 +176,16 @@ pub enum InstrumentCoverage {
     Off,
 }
 
+/// Individual flag values controlled by `-Z coverage-options`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CoverageOptions {
+    /// `-Z coverage-options=branch`, add branch mappings for `if`, `match`, `&&` and `||`
+    pub branch: bool,
+    /// `-Z coverage-options=mcdc`, add MC/DC mappings for compound boolean decisions.
+    /// Implies `branch`.
+    pub mcdc: bool,
+}
+
 /// Settings for `-Z instrument-xray` flag.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct InstrumentXRay {
@@ -419,6 +429,7 @@ pub enum OutputType {
     Assembly,
     LlvmAssembly,
     Mir,
+    StableMir,
     Metadata,
     Object,
     Exe,
@@ -446,6 +457,7 @@ fn is_compatible_with_codegen_units_and_single_output_file(&self) -> bool {
             | OutputType::Assembly
             | OutputType::LlvmAssembly
             | OutputType::Mir
+            | OutputType::StableMir
             | OutputType::Object => false,
         }
     }
@@ -456,6 +468,7 @@ pub fn shorthand(&self) -> &'static str {
             OutputType::Assembly => "asm",
             OutputType::LlvmAssembly => "llvm-ir",
             OutputType::Mir => "mir",
+            OutputType::StableMir => "stable-mir",
             OutputType::Object => "obj",
             OutputType::Metadata => "metadata",
             OutputType::Exe => "link",
@@ -468,6 +481,7 @@ fn from_shorthand(shorthand: &str) -> Option<Self> {
             "asm" => OutputType::Assembly,
             "llvm-ir" => OutputType::LlvmAssembly,
             "mir" => OutputType::Mir,
+            "stable-mir" => OutputType::StableMir,
             "llvm-bc" => OutputType::Bitcode,
             "obj" => OutputType::Object,
             "metadata" => OutputType::Metadata,
@@ -484,6 +498,7 @@ fn shorthands_display() -> String {
             OutputType::Assembly.shorthand(),
             OutputType::LlvmAssembly.shorthand(),
             OutputType::Mir.shorthand(),
+            OutputType::StableMir.shorthand(),
             OutputType::Object.shorthand(),
             OutputType::Metadata.shorthand(),
             OutputType::Exe.shorthand(),
@@ -497,6 +512,7 @@ pub fn extension(&self) -> &'static str {
             OutputType::Assembly => "s",
             OutputType::LlvmAssembly => "ll",
             OutputType::Mir => "mir",
+            OutputType::StableMir => "smir.json",
             OutputType::Object => "o",
             OutputType::Metadata => "rmeta",
             OutputType::DepInfo => "d",
@@ -509,6 +525,7 @@ pub fn is_text_output(&self) -> bool {
             OutputType::Assembly
             | OutputType::LlvmAssembly
             | OutputType::Mir
+            | OutputType::StableMir
             | OutputType::DepInfo => true,
             OutputType::Bitcode | OutputType::Object | OutputType::Metadata | OutputType::Exe => {
                 false
@@ -530,6 +547,8 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF log per compilation, consumed by code-scanning tools.
+    Sarif,
 }
 
 impl Default for ErrorOutputType {
@@ -607,7 +626,7 @@ pub fn should_codegen(&self) -> bool {
             | OutputType::Mir
             | OutputType::Object
             | OutputType::Exe => true,
-            OutputType::Metadata | OutputType::DepInfo => false,
+            OutputType::StableMir | OutputType::Metadata | OutputType::DepInfo => false,
         })
     }
 
@@ -618,6 +637,7 @@ pub fn should_link(&self) -> bool {
             | OutputType::Assembly
             | OutputType::LlvmAssembly
             | OutputType::Mir
+            | OutputType::StableMir
             | OutputType::Metadata
             | OutputType::Object
             | OutputType::DepInfo => false,
@@ -716,6 +736,7 @@ pub fn files(&self) -> Option<impl Iterator<Item = &CanonicalizedPath>> {
 pub struct PrintRequest {
     pub kind: PrintKind,
     pub out: OutFileName,
+    pub format: PrintFormat,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -741,6 +762,53 @@ pub enum PrintKind {
     DeploymentTarget,
 }
 
+const PRINT_KINDS: &[(&str, PrintKind)] = &[
+    ("crate-name", PrintKind::CrateName),
+    ("file-names", PrintKind::FileNames),
+    ("sysroot", PrintKind::Sysroot),
+    ("target-libdir", PrintKind::TargetLibdir),
+    ("cfg", PrintKind::Cfg),
+    ("calling-conventions", PrintKind::CallingConventions),
+    ("target-list", PrintKind::TargetList),
+    ("target-cpus", PrintKind::TargetCPUs),
+    ("target-features", PrintKind::TargetFeatures),
+    ("relocation-models", PrintKind::RelocationModels),
+    ("code-models", PrintKind::CodeModels),
+    ("tls-models", PrintKind::TlsModels),
+    ("native-static-libs", PrintKind::NativeStaticLibs),
+    ("stack-protector-strategies", PrintKind::StackProtectorStrategies),
+    ("target-spec-json", PrintKind::TargetSpec),
+    ("all-target-specs-json", PrintKind::AllTargetSpecs),
+    ("link-args", PrintKind::LinkArgs),
+    ("split-debuginfo", PrintKind::SplitDebuginfo),
+    ("deployment-target", PrintKind::DeploymentTarget),
+];
+
+impl PrintKind {
+    /// The name of this print request on the command line, as in `--print <name>`.
+    pub fn name(self) -> &'static str {
+        PRINT_KINDS.iter().find(|&&(_, kind)| kind == self).unwrap().0
+    }
+}
+
+/// The format used for all `--print` requests, selected with `--print-format`.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum PrintFormat {
+    /// The traditional, human-readable output.
+    #[default]
+    Text,
+    /// One JSON document per request, see [`PRINT_JSON_VERSION`].
+    Json,
+}
+
+/// The version of the documents produced by `--print-format=json`.
+///
+/// Every document is an object of the form
+/// `{"version": PRINT_JSON_VERSION, "kind": "<print request>", "data": ...}`.
+/// Adding fields to `data` is a compatible change; removing or changing the meaning of
+/// existing fields requires bumping this version.
+pub const PRINT_JSON_VERSION: u32 = 1;
+
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
 pub enum TraitSolver {
     /// Classic trait solver in `rustc_trait_selection::traits::select`
@@ -759,6 +827,25 @@ pub enum DumpSolverProofTree {
     Never,
 }
 
+/// The format of the explanations added by `-Z explain-trait-errors`.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum ExplainTraitErrors {
+    /// Only add the explanation as a note to the error.
+    Text,
+    /// Also print the explanation as a JSON document to stdout.
+    Json,
+}
+
+/// The borrow conflict explained by `-Z borrowck-explain`.
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub enum BorrowckExplain {
+    /// The n-th error of the compilation session, starting at 1.
+    Index(usize),
+    /// The error whose primary span starts at this position of a file whose path ends with `file`.
+    /// Lines and columns start at 1, and any column matches if none is given.
+    Location { file: String, line: usize, col: Option<usize> },
+}
+
 pub enum Input {
     /// Load source code from a file.
     File(PathBuf),
@@ -1102,6 +1189,7 @@ pub fn diagnostic_handler_flags(&self, can_emit_warnings: bool) -> HandlerFlags
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
             track_diagnostics: self.track_diagnostics,
+            collect_machine_applicable_suggestions: self.fix_suggestions.is_some(),
         }
     }
 }
@@ -1700,7 +1788,7 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
             "emit",
             "Comma separated list of types of output for \
              the compiler to emit",
-            "[asm|llvm-bc|llvm-ir|obj|metadata|link|dep-info|mir]",
+            "[asm|llvm-bc|llvm-ir|obj|metadata|link|dep-info|mir|stable-mir]",
         ),
         opt::multi_s(
             "",
@@ -1771,6 +1859,7 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "human|json|short",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
+        opt::opt("", "print-format", "The format of `--print` output", "text|json"),
         opt::opt_s(
             "",
             "color",
@@ -1941,14 +2030,15 @@ pub fn parse_error_format(
             Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
             Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
             Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
+            Some("sarif") => ErrorOutputType::Sarif,
 
             Some(arg) => {
                 handler.abort_if_error_and_set_error_format(ErrorOutputType::HumanReadable(
                     HumanReadableErrorType::Default(color),
                 ));
                 handler.early_error(format!(
-                    "argument for `--error-format` must be `human`, `json` or \
-                     `short` (instead was `{arg}`)"
+                    "argument for `--error-format` must be `human`, `json`, \
+                     `short` or `sarif` (instead was `{arg}`)"
                 ))
             }
         }
@@ -2021,6 +2111,13 @@ fn check_error_format_stability(
             });
             handler.early_error("`--error-format=human-annotate-rs` is unstable");
         }
+        if let ErrorOutputType::Sarif = error_format {
+            handler.abort_if_error_and_set_error_format(ErrorOutputType::Json {
+                pretty: false,
+                json_rendered,
+            });
+            handler.early_error("`--error-format=sarif` is unstable");
+        }
     }
 }
 
@@ -2040,6 +2137,12 @@ fn parse_output_types(
                         display = OutputType::shorthands_display(),
                     ))
                 });
+                if output_type == OutputType::StableMir && !unstable_opts.unstable_options {
+                    handler.early_error(
+                        "the `-Z unstable-options` flag must also be passed to enable \
+                        `--emit=stable-mir`",
+                    );
+                }
                 output_types.insert(output_type, path);
             }
         }
@@ -2119,38 +2222,28 @@ fn collect_print_requests(
     unstable_opts: &mut UnstableOptions,
     matches: &getopts::Matches,
 ) -> Vec<PrintRequest> {
+    let format = match matches.opt_str("print-format").as_deref() {
+        None | Some("text") => PrintFormat::Text,
+        Some("json") => PrintFormat::Json,
+        Some(arg) => handler.early_error(format!(
+            "argument for `--print-format` must be `text` or `json` (instead was `{arg}`)"
+        )),
+    };
+
     let mut prints = Vec::<PrintRequest>::new();
     if cg.target_cpu.as_ref().is_some_and(|s| s == "help") {
-        prints.push(PrintRequest { kind: PrintKind::TargetCPUs, out: OutFileName::Stdout });
+        prints.push(PrintRequest { kind: PrintKind::TargetCPUs, out: OutFileName::Stdout, format });
         cg.target_cpu = None;
     };
     if cg.target_feature == "help" {
-        prints.push(PrintRequest { kind: PrintKind::TargetFeatures, out: OutFileName::Stdout });
+        prints.push(PrintRequest {
+            kind: PrintKind::TargetFeatures,
+            out: OutFileName::Stdout,
+            format,
+        });
         cg.target_feature = String::new();
     }
 
-    const PRINT_KINDS: &[(&str, PrintKind)] = &[
-        ("crate-name", PrintKind::CrateName),
-        ("file-names", PrintKind::FileNames),
-        ("sysroot", PrintKind::Sysroot),
-        ("target-libdir", PrintKind::TargetLibdir),
-        ("cfg", PrintKind::Cfg),
-        ("calling-conventions", PrintKind::CallingConventions),
-        ("target-list", PrintKind::TargetList),
-        ("target-cpus", PrintKind::TargetCPUs),
-        ("target-features", PrintKind::TargetFeatures),
-        ("relocation-models", PrintKind::RelocationModels),
-        ("code-models", PrintKind::CodeModels),
-        ("tls-models", PrintKind::TlsModels),
-        ("native-static-libs", PrintKind::NativeStaticLibs),
-        ("stack-protector-strategies", PrintKind::StackProtectorStrategies),
-        ("target-spec-json", PrintKind::TargetSpec),
-        ("all-target-specs-json", PrintKind::AllTargetSpecs),
-        ("link-args", PrintKind::LinkArgs),
-        ("split-debuginfo", PrintKind::SplitDebuginfo),
-        ("deployment-target", PrintKind::DeploymentTarget),
-    ];
-
     // We disallow reusing the same path in multiple prints, such as `--print
     // cfg=output.txt --print link-args=output.txt`, because outputs are printed
     // by disparate pieces of the compiler, and keeping track of which files
@@ -2208,7 +2301,7 @@ fn collect_print_requests(
             }
         }
 
-        PrintRequest { kind, out }
+        PrintRequest { kind, out, format }
     }));
 
     prints
@@ -2626,6 +2719,10 @@ pub fn build_session_options(
         }
     }
 
+    if unstable_opts.export_dep_graph.enabled() && incremental.is_none() {
+        handler.early_error("can't export the dependency graph without `-C incremental`");
+    }
+
     if cg.profile_generate.enabled() && cg.profile_use.is_some() {
         handler.early_error("options `-C profile-generate` and `-C profile-use` are exclusive");
     }
@@ -2684,6 +2781,10 @@ pub fn build_session_options(
         _ => {}
     }
 
+    if unstable_opts.update_lint_baseline && unstable_opts.lint_baseline.is_none() {
+        handler.early_error("`-Z update-lint-baseline` requires `-Z lint-baseline`");
+    }
+
     if cg.instrument_coverage.is_some() && cg.instrument_coverage != Some(InstrumentCoverage::Off) {
         if cg.profile_generate.enabled() || cg.profile_use.is_some() {
             handler.early_error(
@@ -3119,11 +3220,11 @@ pub fn needs_analysis(&self) -> bool {
 /// how the hash should be calculated when adding a new command-line argument.
 pub(crate) mod dep_tracking {
     use super::{
-        BranchProtection, CFGuard, CFProtection, CrateType, DebugInfo, ErrorOutputType,
-        InstrumentCoverage, InstrumentXRay, LdImpl, LinkerPluginLto, LocationDetail, LtoCli,
-        OomStrategy, OptLevel, OutFileName, OutputType, OutputTypes, Passes, ResolveDocLinks,
-        SourceFileHashAlgorithm, SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion,
-        TraitSolver, TrimmedDefPaths,
+        BranchProtection, CFGuard, CFProtection, CoverageOptions, CrateType, DebugInfo,
+        ErrorOutputType, InstrumentCoverage, InstrumentXRay, LdImpl, LinkerPluginLto,
+        LocationDetail, LtoCli, MirInliningFormat, OomStrategy, OptLevel, OutFileName, OutputType,
+        OutputTypes, Passes, ReachablePanics, ResolveDocLinks, SourceFileHashAlgorithm,
+        SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion, TraitSolver, TrimmedDefPaths,
     };
     use crate::lint;
     use crate::options::WasiExecModel;
@@ -3192,6 +3293,7 @@ fn hash(
         CodeModel,
         TlsModel,
         InstrumentCoverage,
+        CoverageOptions,
         InstrumentXRay,
         CrateType,
         MergeFunctions,
@@ -3200,6 +3302,8 @@ fn hash(
         Passes,
         OptLevel,
         LtoCli,
+        ReachablePanics,
+        MirInliningFormat,
         DebugInfo,
         UnstableFeatures,
         NativeLib,
@@ -3365,3 +3469,30 @@ pub fn extension(self) -> &'static str {
         }
     }
 }
+
+/// What to do with the machine-applicable suggestions collected by `-Z fix-suggestions`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum FixSuggestions {
+    /// Rewrite the affected source files in place.
+    Write,
+    /// Print a unified diff of the changes to stdout, leaving the sources untouched.
+    Diff,
+}
+
+/// How to report the panics found by `-Z report-reachable-panics`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum ReachablePanics {
+    /// Emit a warning for every reachable panic.
+    Warn,
+    /// Emit an error for every reachable panic, failing the compilation.
+    Deny,
+}
+
+/// The format of the report printed by `-Z print-mir-inlining`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum MirInliningFormat {
+    /// One line of text per callsite.
+    Text,
+    /// A single JSON document listing every callsite.
+    Json,
+}
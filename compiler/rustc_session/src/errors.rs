@@ -1,4 +1,5 @@
 use std::num::NonZeroU32;
+use std::path::Path;
 
 use crate::cgu_reuse_tracker::CguReuse;
 use crate::parse::ParseSess;
@@ -455,3 +456,30 @@ pub struct IncompatibleLinkerFlavor {
     pub flavor: &'static str,
     pub compatible_list: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(session_fix_suggestions_io)]
+pub struct FixSuggestionsIo<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_fix_suggestions_file_changed)]
+pub struct FixSuggestionsFileChanged<'a> {
+    pub path: &'a Path,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_fix_suggestions_skipped)]
+#[note]
+pub struct FixSuggestionsSkipped {
+    pub count: usize,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_lint_baseline_write)]
+pub struct LintBaselineWrite<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
@@ -381,8 +381,12 @@ mod desc {
     pub const parse_optimization_fuel: &str = "crate=integer";
     pub const parse_mir_spanview: &str = "`statement` (default), `terminator`, or `block`";
     pub const parse_dump_mono_stats: &str = "`markdown` (default) or `json`";
+    pub const parse_fix_suggestions: &str = "`write` (default) or `diff`";
+    pub const parse_reachable_panics: &str = "`warn` (default) or `deny`";
+    pub const parse_mir_inlining_format: &str = "`text` (default) or `json`";
     pub const parse_instrument_coverage: &str =
         "`all` (default), `except-unused-generics`, `except-unused-functions`, or `off`";
+    pub const parse_coverage_options: &str = "a comma separated list of `branch` or `mcdc`";
     pub const parse_instrument_xray: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), or a comma separated list of settings: `always` or `never` (mutually exclusive), `ignore-loops`, `instruction-threshold=N`, `skip-entry`, `skip-exit`";
     pub const parse_unpretty: &str = "`string` or `string=string`";
     pub const parse_treat_err_as_bug: &str = "either no value or a number bigger than 0";
@@ -420,6 +424,9 @@ mod desc {
     pub const parse_proc_macro_execution_strategy: &str =
         "one of supported execution strategies (`same-thread`, or `cross-thread`)";
     pub const parse_dump_solver_proof_tree: &str = "one of: `always`, `on-request`, `on-error`";
+    pub const parse_explain_trait_errors: &str = "`text` (default) or `json`";
+    pub const parse_borrowck_explain: &str =
+        "the index of an error (starting at 1), or a `file:line[:col]` position";
 }
 
 mod parse {
@@ -876,6 +883,42 @@ pub(crate) fn parse_dump_mono_stats(slot: &mut DumpMonoStatsFormat, v: Option<&s
         }
     }
 
+    pub(crate) fn parse_fix_suggestions(
+        slot: &mut Option<FixSuggestions>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            None | Some("write") => Some(FixSuggestions::Write),
+            Some("diff") => Some(FixSuggestions::Diff),
+            Some(_) => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_reachable_panics(
+        slot: &mut Option<ReachablePanics>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            None | Some("warn") => Some(ReachablePanics::Warn),
+            Some("deny") => Some(ReachablePanics::Deny),
+            Some(_) => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_mir_inlining_format(
+        slot: &mut Option<MirInliningFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            None | Some("text") => Some(MirInliningFormat::Text),
+            Some("json") => Some(MirInliningFormat::Json),
+            Some(_) => return false,
+        };
+        true
+    }
+
     pub(crate) fn parse_instrument_coverage(
         slot: &mut Option<InstrumentCoverage>,
         v: Option<&str>,
@@ -907,6 +950,21 @@ pub(crate) fn parse_instrument_coverage(
         true
     }
 
+    pub(crate) fn parse_coverage_options(slot: &mut CoverageOptions, v: Option<&str>) -> bool {
+        let Some(v) = v else { return false };
+        for option in v.split(',') {
+            match option {
+                "branch" => slot.branch = true,
+                "mcdc" => {
+                    slot.branch = true;
+                    slot.mcdc = true;
+                }
+                _ => return false,
+            }
+        }
+        true
+    }
+
     pub(crate) fn parse_instrument_xray(
         slot: &mut Option<InstrumentXRay>,
         v: Option<&str>,
@@ -1252,6 +1310,50 @@ pub(crate) fn parse_dump_solver_proof_tree(
         };
         true
     }
+
+    pub(crate) fn parse_explain_trait_errors(
+        slot: &mut Option<ExplainTraitErrors>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = match v {
+            None | Some("text") => Some(ExplainTraitErrors::Text),
+            Some("json") => Some(ExplainTraitErrors::Json),
+            Some(_) => return false,
+        };
+        true
+    }
+
+    pub(crate) fn parse_borrowck_explain(
+        slot: &mut Option<BorrowckExplain>,
+        v: Option<&str>,
+    ) -> bool {
+        let Some(v) = v else { return false };
+        if let Ok(index) = v.parse::<usize>() {
+            if index == 0 {
+                return false;
+            }
+            *slot = Some(BorrowckExplain::Index(index));
+            return true;
+        }
+        // The file name itself may contain `:`, so split the position off from the end.
+        let mut parts = v.rsplitn(3, ':');
+        let (Some(last), Some(middle)) = (parts.next(), parts.next()) else { return false };
+        let (file, line, col) = match parts.next() {
+            Some(file) => match (middle.parse(), last.parse()) {
+                (Ok(line), Ok(col)) => (file, line, Some(col)),
+                _ => return false,
+            },
+            None => match last.parse() {
+                Ok(line) => (middle, line, None),
+                Err(_) => return false,
+            },
+        };
+        if file.is_empty() {
+            return false;
+        }
+        *slot = Some(BorrowckExplain::Location { file: file.to_string(), line, col });
+        true
+    }
 }
 
 options! {
@@ -1408,6 +1510,11 @@ pub(crate) fn parse_dump_solver_proof_tree(
     binary_dep_depinfo: bool = (false, parse_bool, [TRACKED],
         "include artifacts (sysroot, crate dependencies) used during compilation in dep-info \
         (default: no)"),
+    borrowck_explain: Option<BorrowckExplain> = (None, parse_borrowck_explain, [UNTRACKED],
+        "explain a borrow conflict error, given by its index or by the `file:line[:col]` \
+        position of its primary span, with the path of region constraints that keeps the \
+        conflicting loan live, as annotated source and as a DOT graph written to \
+        `<crate>.borrowck_explain.dot` in the output directory"),
     box_noalias: bool = (true, parse_bool, [TRACKED],
         "emit noalias metadata for box (default: yes)"),
     branch_protection: Option<BranchProtection> = (None, parse_branch_protection, [TRACKED],
@@ -1418,6 +1525,13 @@ pub(crate) fn parse_dump_solver_proof_tree(
         "the backend to use"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
+    const_eval_profile: bool = (false, parse_bool, [TRACKED],
+        "print the MIR statements, calls and bytes of memory each const and static item, and \
+        each function called during their evaluation, took to evaluate (default: no)"),
+    coverage_options: CoverageOptions = (CoverageOptions::default(),
+        parse_coverage_options, [TRACKED],
+        "control details of coverage instrumentation: `branch` adds branch mappings, \
+        `mcdc` adds MC/DC mappings for compound boolean decisions (and implies `branch`)"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
         "inject the given attribute in the crate"),
     debug_info_for_profiling: bool = (false, parse_bool, [TRACKED],
@@ -1432,6 +1546,8 @@ pub(crate) fn parse_dump_solver_proof_tree(
     dep_tasks: bool = (false, parse_bool, [UNTRACKED],
         "print tasks that execute and the color their dep node gets (requires debug build) \
         (default: no)"),
+    dependency_layering: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "check the paths used in the crate against the layering rules in the given file"),
     dont_buffer_diagnostics: bool = (false, parse_bool, [UNTRACKED],
         "emit diagnostics rather than buffering (breaks NLL error downgrading, sorting) \
         (default: no)"),
@@ -1488,6 +1604,15 @@ pub(crate) fn parse_dump_solver_proof_tree(
         "emit a section containing stack size metadata (default: no)"),
     emit_thin_lto: bool = (true, parse_bool, [TRACKED],
         "emit the bc module with thin LTO info (default: yes)"),
+    explain_trait_errors: Option<ExplainTraitErrors> = (None,
+        parse_explain_trait_errors, [UNTRACKED],
+        "explain why unsatisfied trait bounds do not hold, based on the proof tree of the new \
+        trait solver, as `text` notes or also as `json` (default: no)"),
+    export_dep_graph: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "write the fingerprints of the upstream items and source files the crate depends on, \
+        and of the items it provides, as `<crate>.dep_graph.json` to the given directory \
+        (default: the current directory); requires `-C incremental`"),
     export_executable_symbols: bool = (false, parse_bool, [TRACKED],
         "export symbols from executables, as if they were dynamic libraries"),
     extra_const_ub_checks: bool = (false, parse_bool, [TRACKED],
@@ -1496,6 +1621,13 @@ pub(crate) fn parse_dump_solver_proof_tree(
     fewer_names: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "reduce memory use by retaining fewer names within compilation artifacts (LLVM-IR) \
         (default: no)"),
+    fix_suggestions: Option<FixSuggestions> = (None, parse_fix_suggestions, [UNTRACKED],
+        "apply the machine-applicable suggestions of all emitted lints at the end of the \
+        session, either by rewriting the sources (`write`, the default) or by printing a \
+        unified diff to stdout (`diff`)"),
+    fix_suggestions_lints: Option<Vec<String>> = (None, parse_opt_comma_list, [UNTRACKED],
+        "only apply `-Z fix-suggestions` to lints in this comma-separated list of lints \
+        and lint groups (default: all lints)"),
     flatten_format_args: bool = (true, parse_bool, [TRACKED],
         "flatten nested format_args!() and literals into a simplified format_args!() call \
         (default: yes)"),
@@ -1507,6 +1639,8 @@ pub(crate) fn parse_dump_solver_proof_tree(
         "whether each function should go in its own section"),
     future_incompat_test: bool = (false, parse_bool, [UNTRACKED],
         "forces all lints to be future incompatible, used for internal testing (default: no)"),
+    future_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "the size in bytes at which the `oversized_futures` lint starts to be emitted"),
     gcc_ld: Option<LdImpl> = (None, parse_gcc_ld, [TRACKED], "implementation of ld used by cc"),
     graphviz_dark_mode: bool = (false, parse_bool, [UNTRACKED],
         "use dark-themed colors in graphviz output (default: no)"),
@@ -1562,6 +1696,10 @@ pub(crate) fn parse_dump_solver_proof_tree(
          `=skip-entry`
          `=skip-exit`
          Multiple options can be combined with commas."),
+    item_time_report: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "write a report of the compile time spent on each item to the given directory \
+        (default: the current directory)"),
     keep_hygiene_data: bool = (false, parse_bool, [UNTRACKED],
         "keep hygiene data after analysis (default: no)"),
     layout_seed: Option<u64> = (None, parse_opt_number, [TRACKED],
@@ -1572,6 +1710,8 @@ pub(crate) fn parse_dump_solver_proof_tree(
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
+    lint_baseline: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "only report lints that are not recorded in this baseline file"),
     llvm_plugins: Vec<String> = (Vec::new(), parse_list, [TRACKED],
         "a list LLVM plugins to enable (space separated)"),
     llvm_time_trace: bool = (false, parse_bool, [UNTRACKED],
@@ -1672,10 +1812,23 @@ pub(crate) fn parse_dump_solver_proof_tree(
         "print codegen statistics (default: no)"),
     print_fuel: Option<String> = (None, parse_opt_string, [TRACKED],
         "make rustc print the total optimization fuel used by a crate"),
+    print_future_layouts: bool = (false, parse_bool, [UNTRACKED],
+        "print the locals saved across each await point of the futures of `async` functions \
+        and blocks, and the largest futures they contain (default: no)"),
     print_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
         "print the LLVM optimization passes being run (default: no)"),
+    print_mir_inlining: Option<MirInliningFormat> = (None, parse_mir_inlining_format, [TRACKED],
+        "print every callsite considered by the MIR inliner, with the callee, its cost and \
+        threshold, and why it was not inlined, as `text` or `json` (default: no)"),
     print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "print the result of the monomorphization collection pass"),
+    print_mono_items_filter: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "only trace the provenance of the mono items with the given path, or the given name \
+        as printed by `-Z print-mono-items`"),
+    print_mono_items_provenance: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "write the uses that led to the collection of each mono item as a graph to the given \
+        directory (default: the current directory)"),
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
         "print layout information for each type encountered (default: no)"),
     print_vtable_sizes: bool = (false, parse_bool, [UNTRACKED],
@@ -1700,6 +1853,9 @@ pub(crate) fn parse_dump_solver_proof_tree(
         "enable queries of the dependency graph for regression testing (default: no)"),
     randomize_layout: bool = (false, parse_bool, [TRACKED],
         "randomize the layout of types (default: no)"),
+    record_used_items: bool = (false, parse_bool, [TRACKED],
+        "record the items of other crates that the crate uses in its metadata, for \
+        `-Z report-unused-pub-items` (default: no)"),
     relax_elf_relocations: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "whether ELF relocations can be relaxed"),
     relro_level: Option<RelroLevel> = (None, parse_relro_level, [TRACKED],
@@ -1711,6 +1867,14 @@ pub(crate) fn parse_dump_solver_proof_tree(
 written to standard error output)"),
     report_delayed_bugs: bool = (false, parse_bool, [TRACKED],
         "immediately print bugs registered with `delay_span_bug` (default: no)"),
+    report_reachable_panics: Option<ReachablePanics> = (None, parse_reachable_panics, [TRACKED],
+        "report every call of a panic function that is reachable from `main` or the functions \
+        exported from the crate, with the chain of calls leading to it; `deny` makes these \
+        errors (default: no)"),
+    report_unused_pub_items: bool = (false, parse_bool, [TRACKED],
+        "report the public items of upstream crates compiled with `-Z record-used-items` that \
+        no crate in the dependency graph uses, and dependencies that are never used \
+        (default: no)"),
     sanitizer: SanitizerSet = (SanitizerSet::empty(), parse_sanitizers, [TRACKED],
         "use a sanitizer"),
     sanitizer_cfi_canonical_jump_tables: Option<bool> = (Some(true), parse_opt_bool, [TRACKED],
@@ -1748,6 +1912,10 @@ pub(crate) fn parse_dump_solver_proof_tree(
     simulate_remapped_rust_src_base: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
         "simulate the effect of remap-debuginfo = true at bootstrapping by remapping path \
         to rust's source base directory. only meant for testing purposes"),
+    size_report: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "write a report of the code and data bytes of each mono item, module and crate to the \
+        given directory (default: the current directory)"),
     span_debug: bool = (false, parse_bool, [UNTRACKED],
         "forward proc_macro::Span's `Debug` impl to `Span`"),
     /// o/w tests have closure@path
@@ -1771,6 +1939,11 @@ pub(crate) fn parse_dump_solver_proof_tree(
     #[rustc_lint_opt_deny_field_access("use `Session::stack_protector` instead of this field")]
     stack_protector: StackProtector = (StackProtector::None, parse_stack_protector, [TRACKED],
         "control stack smash protection strategy (`rustc --print stack-protector-strategies` for details)"),
+    stack_report: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "write a report of the worst-case stack depth of `main` and the functions exported from \
+        the crate to the given directory (default: the current directory). implies \
+        `-Z emit-stack-sizes`"),
     staticlib_allow_rdylib_deps: bool = (false, parse_bool, [TRACKED],
         "allow staticlibs to have rust dylib dependencies"),
     staticlib_prefer_dynamic: bool = (false, parse_bool, [TRACKED],
@@ -1837,6 +2010,9 @@ pub(crate) fn parse_dump_solver_proof_tree(
         "in diagnostics, use heuristics to shorten paths referring to items"),
     tune_cpu: Option<String> = (None, parse_opt_string, [TRACKED],
         "select processor to schedule for (`rustc --print target-cpus` for details)"),
+    ub_checks: Option<bool> = (None, parse_opt_bool, [TRACKED],
+        "emit runtime checks for the preconditions of unsafe library functions, whose violation \
+        is Undefined Behavior (default: `-C debug-assertions`)"),
     ui_testing: bool = (false, parse_bool, [UNTRACKED],
         "emit compiler diagnostics in a form suitable for UI testing (default: no)"),
     uninit_const_chunk_threshold: usize = (16, parse_number, [TRACKED],
@@ -1862,6 +2038,9 @@ pub(crate) fn parse_dump_solver_proof_tree(
     #[rustc_lint_opt_deny_field_access("use `Session::unstable_options` instead of this field")]
     unstable_options: bool = (false, parse_bool, [UNTRACKED],
         "adds unstable command line options to rustc interface (default: no)"),
+    update_lint_baseline: bool = (false, parse_bool, [UNTRACKED],
+        "record all lints emitted by the crate in the `-Z lint-baseline` file instead of \
+        reporting them (default: no)"),
     use_ctors_section: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "use legacy .ctors section for initializers rather than .init_array"),
     validate_mir: bool = (false, parse_bool, [UNTRACKED],
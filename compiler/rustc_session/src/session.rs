@@ -13,7 +13,7 @@
 pub use rustc_ast::attr::MarkedAttrs;
 pub use rustc_ast::Attribute;
 use rustc_data_structures::flock;
-use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexSet};
 use rustc_data_structures::jobserver::{self, Client};
 use rustc_data_structures::profiling::{duration_to_secs_str, SelfProfiler, SelfProfilerRef};
 use rustc_data_structures::sync::{
@@ -21,12 +21,15 @@
 };
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
 use rustc_errors::emitter::{DynEmitter, EmitterWriter, HumanReadableErrorType};
+use rustc_errors::fix_suggestions::resolve_suggestions;
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::lint_baseline::LintBaseline;
 use rustc_errors::registry::Registry;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::{
-    error_code, fallback_fluent_bundle, DiagnosticBuilder, DiagnosticId, DiagnosticMessage,
-    ErrorGuaranteed, FluentBundle, Handler, IntoDiagnostic, LazyFallbackBundle, MultiSpan, Noted,
-    TerminalUrl,
+    error_code, fallback_fluent_bundle, ColorConfig, DiagnosticBuilder, DiagnosticId,
+    DiagnosticMessage, ErrorGuaranteed, FluentBundle, Handler, IntoDiagnostic, LazyFallbackBundle,
+    MultiSpan, Noted, TerminalUrl,
 };
 use rustc_macros::HashStable_Generic;
 pub use rustc_span::def_id::StableCrateId;
@@ -193,6 +196,11 @@ pub struct Session {
     /// drown everything else in noise.
     miri_unleashed_features: Lock<Vec<(Span, Option<Symbol>)>>,
 
+    /// The lints whose suggestions `-Z fix-suggestions` applies, with lint groups
+    /// from `-Z fix-suggestions-lints` already expanded. `None` means all lints.
+    /// Set once the lint store has been created.
+    pub fix_suggestions_lints: Lock<Option<FxHashSet<String>>>,
+
     /// Architecture to use for interpreting asm!.
     pub asm_arch: Option<InlineAsmArch>,
 
@@ -284,6 +292,8 @@ fn check_miri_unleashed_features(&self) {
     /// Invoked all the way at the end to finish off diagnostics printing.
     pub fn finish_diagnostics(&self, registry: &Registry) {
         self.check_miri_unleashed_features();
+        self.apply_fix_suggestions();
+        self.write_lint_baseline();
         self.diagnostic().print_error_count(registry);
         self.emit_future_breakage();
     }
@@ -300,6 +310,59 @@ fn emit_future_breakage(&self) {
         self.parse_sess.span_diagnostic.emit_future_breakage_report(diags);
     }
 
+    fn apply_fix_suggestions(&self) {
+        let Some(mode) = self.opts.unstable_opts.fix_suggestions else {
+            return;
+        };
+
+        let mut suggestions = self.diagnostic().take_machine_applicable_suggestions();
+        if let Some(lints) = &*self.fix_suggestions_lints.lock() {
+            suggestions.retain(|sugg| lints.contains(&sugg.lint_name));
+        }
+        let resolved = resolve_suggestions(self.source_map(), &suggestions);
+
+        for file in &resolved.files {
+            let src = match std::fs::read_to_string(&file.path) {
+                Ok(src) if file.src_hash.matches(&src) => src,
+                Ok(_) => {
+                    self.emit_err(errors::FixSuggestionsFileChanged { path: &file.path });
+                    continue;
+                }
+                Err(err) => {
+                    self.emit_err(errors::FixSuggestionsIo { path: &file.path, err });
+                    continue;
+                }
+            };
+            match mode {
+                config::FixSuggestions::Write => {
+                    if let Err(err) = std::fs::write(&file.path, file.apply(&src)) {
+                        self.emit_err(errors::FixSuggestionsIo { path: &file.path, err });
+                    }
+                }
+                config::FixSuggestions::Diff => print!("{}", file.unified_diff(&src)),
+            }
+        }
+
+        if resolved.skipped > 0 {
+            self.emit_note(errors::FixSuggestionsSkipped { count: resolved.skipped });
+        }
+    }
+
+    fn write_lint_baseline(&self) {
+        if !self.opts.unstable_opts.update_lint_baseline {
+            return;
+        }
+        let (Some(path), Some(baseline)) = (
+            self.opts.unstable_opts.lint_baseline.as_deref(),
+            self.diagnostic().serialize_lint_baseline(),
+        ) else {
+            return;
+        };
+        if let Err(err) = std::fs::write(path, baseline) {
+            self.emit_err(errors::LintBaselineWrite { path, err });
+        }
+    }
+
     /// Returns true if the crate is a testing one.
     pub fn is_test_crate(&self) -> bool {
         self.opts.test
@@ -637,6 +700,7 @@ pub fn delay_good_path_bug(&self, msg: impl Into<DiagnosticMessage>) {
             || self.opts.unstable_opts.dump_mir.is_some()
             || self.opts.unstable_opts.unpretty.is_some()
             || self.opts.output_types.contains_key(&OutputType::Mir)
+            || self.opts.output_types.contains_key(&OutputType::StableMir)
             || std::env::var_os("RUSTC_LOG").is_some()
         {
             return;
@@ -703,6 +767,14 @@ pub fn instrument_coverage_except_unused_functions(&self) -> bool {
         self.opts.cg.instrument_coverage() == InstrumentCoverage::ExceptUnusedFunctions
     }
 
+    pub fn instrument_coverage_branch(&self) -> bool {
+        self.instrument_coverage() && self.opts.unstable_opts.coverage_options.branch
+    }
+
+    pub fn instrument_coverage_mcdc(&self) -> bool {
+        self.instrument_coverage() && self.opts.unstable_opts.coverage_options.mcdc
+    }
+
     pub fn is_sanitizer_cfi_enabled(&self) -> bool {
         self.opts.unstable_opts.sanitizer.contains(SanitizerSet::CFI)
     }
@@ -1102,6 +1174,10 @@ pub fn overflow_checks(&self) -> bool {
         self.opts.cg.overflow_checks.unwrap_or(self.opts.debug_assertions)
     }
 
+    pub fn ub_checks(&self) -> bool {
+        self.opts.unstable_opts.ub_checks.unwrap_or(self.opts.debug_assertions)
+    }
+
     pub fn relocation_model(&self) -> RelocModel {
         self.opts.cg.relocation_model.unwrap_or(self.target.relocation_model)
     }
@@ -1308,6 +1384,10 @@ fn default_emitter(
             )
             .ui_testing(sopts.unstable_opts.ui_testing),
         ),
+        config::ErrorOutputType::Sarif => Box::new(
+            SarifEmitter::stderr(Some(registry), source_map, bundle, fallback_bundle)
+                .ui_testing(sopts.unstable_opts.ui_testing),
+        ),
     }
 }
 
@@ -1375,6 +1455,26 @@ pub fn build_session(
     if let Some(ice_file) = ice_file {
         span_diagnostic = span_diagnostic.with_ice_file(ice_file);
     }
+    if let Some(path) = &sopts.unstable_opts.lint_baseline {
+        let baseline = if sopts.unstable_opts.update_lint_baseline {
+            // The previous contents are irrelevant, the file is rewritten from scratch.
+            LintBaseline::for_update()
+        } else {
+            let src = std::fs::read_to_string(path).unwrap_or_else(|e| {
+                handler.early_error(format!(
+                    "failed to read lint baseline file `{}`: {e}",
+                    path.display()
+                ))
+            });
+            LintBaseline::parse(&src).unwrap_or_else(|line| {
+                handler.early_error(format!(
+                    "malformed lint baseline file `{}`: invalid entry on line {line}",
+                    path.display()
+                ))
+            })
+        };
+        span_diagnostic = span_diagnostic.with_lint_baseline(baseline);
+    }
 
     let self_profiler = if let SwitchWithOptPath::Enabled(ref d) = sopts.unstable_opts.self_profile
     {
@@ -1400,6 +1500,12 @@ pub fn build_session(
 
     let mut parse_sess = ParseSess::with_span_handler(span_diagnostic, source_map);
     parse_sess.assume_incomplete_release = sopts.unstable_opts.assume_incomplete_release;
+    if let Some(path) = &sopts.unstable_opts.lint_baseline
+        && !sopts.unstable_opts.update_lint_baseline
+    {
+        // Changing the baseline changes which lints are reported.
+        parse_sess.file_depinfo.get_mut().insert(Symbol::intern(&path.to_string_lossy()));
+    }
 
     let host_triple = config::host_triple();
     let target_triple = sopts.target_triple.triple();
@@ -1427,6 +1533,7 @@ pub fn build_session(
     let prof = SelfProfilerRef::new(
         self_profiler,
         sopts.unstable_opts.time_passes.then(|| sopts.unstable_opts.time_passes_format),
+        sopts.unstable_opts.item_time_report.enabled().then(Default::default),
     );
 
     let ctfe_backtrace = Lock::new(match env::var("RUSTC_CTFE_BACKTRACE") {
@@ -1463,6 +1570,7 @@ pub fn build_session(
         driver_lint_caps,
         ctfe_backtrace,
         miri_unleashed_features: Lock::new(Default::default()),
+        fix_suggestions_lints: Lock::new(None),
         asm_arch,
         target_features: Default::default(),
         unstable_target_features: Default::default(),
@@ -1737,6 +1845,12 @@ fn mk_emitter(output: ErrorOutputType) -> Box<DynEmitter> {
             false,
             TerminalUrl::No,
         )),
+        // The SARIF log is written once per session, so early (command-line)
+        // errors are reported in human-readable form instead of producing a
+        // separate, mostly empty log.
+        config::ErrorOutputType::Sarif => {
+            Box::new(EmitterWriter::stderr(ColorConfig::Never, fallback_bundle))
+        }
     };
     emitter
 }
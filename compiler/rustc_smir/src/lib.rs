@@ -17,6 +17,8 @@
 
 // Declare extern rustc_* crates to enable building this crate separately from the compiler.
 #[cfg(not(feature = "default"))]
+extern crate rustc_const_eval;
+#[cfg(not(feature = "default"))]
 extern crate rustc_hir;
 #[cfg(not(feature = "default"))]
 extern crate rustc_middle;
@@ -0,0 +1,845 @@
+//! Translation of stable MIR back into internal MIR.
+//!
+//! This is the inverse of the [`Stable`](crate::rustc_smir::Stable) implementations, and is used
+//! to hand bodies that were rewritten through stable MIR back to the compiler. Everything stable
+//! MIR does not represent, such as debug info, is taken from the body that was rewritten.
+
+use rustc_hir as hir;
+use rustc_middle::mir;
+use rustc_middle::mir::interpret::{
+    alloc_range, read_target_uint, Allocation, ConstAllocation, ConstValue, Pointer, Scalar,
+};
+use rustc_middle::ty::{self, Ty};
+use rustc_span::Symbol;
+use rustc_target::abi::{Align, FieldIdx, Size, VariantIdx};
+
+use super::opaque;
+use crate::rustc_smir::{MaybeStable, Tables};
+use crate::stable_mir::mir::{
+    AggregateKind, AssertMessage, BasicBlock, BinOp, Body, BorrowKind, CastKind,
+    CopyNonOverlapping, Local, MutBorrowKind, Mutability, NonDivergingIntrinsic, NullOp, Operand,
+    Place, PointerCoercion, ProjectionElem, RetagKind, Rvalue, Safety, Statement, StatementKind,
+    Terminator, TerminatorKind, UnOp, UnwindAction,
+};
+use crate::stable_mir::ty::{
+    Allocation as StableAllocation, Const, ConstantKind, FloatTy, GenericArgKind, GenericArgs,
+    IntTy, Movability, RigidTy, Span, Ty as StableTy, TyKind, UintTy,
+};
+use crate::stable_mir::{self, Error};
+
+type Result<T> = std::result::Result<T, Error>;
+
+fn unsupported(what: &str) -> Error {
+    opaque(&format_args!("{what} cannot be translated back to internal MIR"))
+}
+
+/// Trait used to convert a Stable MIR type back to the equivalent internal MIR type.
+pub(crate) trait RustcInternal<'tcx> {
+    /// The internal representation of the type implementing RustcInternal.
+    type T;
+    /// Converts an object to the equivalent internal MIR representation, if there is one.
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T>;
+}
+
+/// Translates `body`, which was obtained from `original` and then rewritten, back into internal
+/// MIR. The locals of `original` may not be removed and their types may not be changed.
+pub(super) fn body<'tcx>(
+    tables: &mut Tables<'tcx>,
+    original: &mir::Body<'tcx>,
+    body: &Body,
+) -> Result<mir::Body<'tcx>> {
+    let mut new_body = original.clone();
+    if body.locals.len() < original.local_decls.len() {
+        return Err(opaque(&format_args!("locals cannot be removed from a body")));
+    }
+    for (local, ty) in body.locals.iter().enumerate() {
+        let ty = ty.internal(tables)?;
+        match original.local_decls.get(mir::Local::from_usize(local)) {
+            Some(decl) if decl.ty != ty => {
+                return Err(opaque(&format_args!("the type of local _{local} cannot be changed")));
+            }
+            Some(_) => {}
+            None => {
+                new_body.local_decls.push(mir::LocalDecl::new(ty, original.span));
+            }
+        }
+    }
+
+    let cleanup = cleanup_blocks(body);
+    let mut cx = BodyCx { tables, original, body };
+    let blocks = body
+        .blocks
+        .iter()
+        .zip(cleanup)
+        .map(|(block, is_cleanup)| cx.basic_block(block, is_cleanup))
+        .collect::<Result<Vec<_>>>()?;
+    *new_body.basic_blocks_mut() = blocks.into_iter().collect();
+    Ok(new_body)
+}
+
+/// Stable MIR does not record which blocks are cleanup blocks, so recompute it: cleanup blocks are
+/// the blocks that are reachable from the block an unwinding terminator unwinds to.
+fn cleanup_blocks(body: &Body) -> Vec<bool> {
+    let mut cleanup = vec![false; body.blocks.len()];
+    let mut worklist: Vec<usize> = body
+        .blocks
+        .iter()
+        .filter_map(|block| match block.terminator.kind.unwind() {
+            Some(UnwindAction::Cleanup(target)) => Some(*target),
+            _ => None,
+        })
+        .collect();
+    while let Some(block) = worklist.pop() {
+        if block < cleanup.len() && !cleanup[block] {
+            cleanup[block] = true;
+            worklist.extend(body.blocks[block].terminator.kind.successors());
+        }
+    }
+    cleanup
+}
+
+struct BodyCx<'a, 'tcx> {
+    tables: &'a mut Tables<'tcx>,
+    original: &'a mir::Body<'tcx>,
+    body: &'a Body,
+}
+
+impl<'a, 'tcx> BodyCx<'a, 'tcx> {
+    fn local(&self, local: Local) -> Result<mir::Local> {
+        if local < self.body.locals.len() {
+            Ok(mir::Local::from_usize(local))
+        } else {
+            Err(opaque(&format_args!("local _{local} does not exist")))
+        }
+    }
+
+    fn block(&self, block: usize) -> Result<mir::BasicBlock> {
+        if block < self.body.blocks.len() {
+            Ok(mir::BasicBlock::from_usize(block))
+        } else {
+            Err(opaque(&format_args!("block bb{block} does not exist")))
+        }
+    }
+
+    fn span(&self, span: Span) -> Result<rustc_span::Span> {
+        match self.tables.spans.get(span.0) {
+            Some((span, _)) => Ok(*span),
+            None => Err(opaque(&format_args!("span {} does not exist", span.0))),
+        }
+    }
+
+    /// Statements keep their source scope if they come from the body being rewritten, and are
+    /// put in the outermost scope otherwise.
+    fn source_info(&self, span: Span) -> Result<mir::SourceInfo> {
+        let scope = match self.tables.spans.get(span.0) {
+            Some((_, Some((def_id, scope)))) if *def_id == self.original.source.def_id() => *scope,
+            _ => mir::OUTERMOST_SOURCE_SCOPE,
+        };
+        Ok(mir::SourceInfo { span: self.span(span)?, scope })
+    }
+
+    fn user_ty(&self, user_ty: Option<usize>) -> Option<ty::UserTypeAnnotationIndex> {
+        user_ty
+            .filter(|user_ty| *user_ty < self.original.user_type_annotations.len())
+            .map(ty::UserTypeAnnotationIndex::from_usize)
+    }
+
+    fn basic_block(
+        &mut self,
+        block: &BasicBlock,
+        is_cleanup: bool,
+    ) -> Result<mir::BasicBlockData<'tcx>> {
+        let statements = block
+            .statements
+            .iter()
+            .map(|statement| self.statement(statement))
+            .collect::<Result<_>>()?;
+        let terminator = self.terminator(&block.terminator, is_cleanup)?;
+        Ok(mir::BasicBlockData { statements, terminator: Some(terminator), is_cleanup })
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Result<mir::Statement<'tcx>> {
+        let kind = match &statement.kind {
+            StatementKind::Assign(place, rvalue) => {
+                mir::StatementKind::Assign(Box::new((self.place(place)?, self.rvalue(rvalue)?)))
+            }
+            StatementKind::SetDiscriminant { place, variant_index } => {
+                mir::StatementKind::SetDiscriminant {
+                    place: Box::new(self.place(place)?),
+                    variant_index: VariantIdx::from_usize(*variant_index),
+                }
+            }
+            StatementKind::Deinit(place) => {
+                mir::StatementKind::Deinit(Box::new(self.place(place)?))
+            }
+            StatementKind::StorageLive(local) => {
+                mir::StatementKind::StorageLive(self.local(*local)?)
+            }
+            StatementKind::StorageDead(local) => {
+                mir::StatementKind::StorageDead(self.local(*local)?)
+            }
+            StatementKind::Retag(kind, place) => {
+                mir::StatementKind::Retag(kind.internal(self.tables)?, Box::new(self.place(place)?))
+            }
+            StatementKind::PlaceMention(place) => {
+                mir::StatementKind::PlaceMention(Box::new(self.place(place)?))
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::Assume(operand)) => {
+                mir::StatementKind::Intrinsic(Box::new(mir::NonDivergingIntrinsic::Assume(
+                    self.operand(operand)?,
+                )))
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::CopyNonOverlapping(
+                CopyNonOverlapping { src, dst, count },
+            )) => mir::StatementKind::Intrinsic(Box::new(
+                mir::NonDivergingIntrinsic::CopyNonOverlapping(mir::CopyNonOverlapping {
+                    src: self.operand(src)?,
+                    dst: self.operand(dst)?,
+                    count: self.operand(count)?,
+                }),
+            )),
+            StatementKind::ConstEvalCounter => mir::StatementKind::ConstEvalCounter,
+            StatementKind::Nop => mir::StatementKind::Nop,
+            // These only exist before borrowck, or their contents are not part of stable MIR.
+            StatementKind::FakeRead(..) => return Err(unsupported("`FakeRead` statements")),
+            StatementKind::AscribeUserType { .. } => {
+                return Err(unsupported("`AscribeUserType` statements"));
+            }
+            StatementKind::Coverage(_) => return Err(unsupported("coverage statements")),
+        };
+        Ok(mir::Statement { source_info: self.source_info(statement.span)?, kind })
+    }
+
+    fn terminator(
+        &mut self,
+        terminator: &Terminator,
+        is_cleanup: bool,
+    ) -> Result<mir::Terminator<'tcx>> {
+        let source_info = self.source_info(terminator.span)?;
+        let kind = match &terminator.kind {
+            TerminatorKind::Goto { target } => {
+                mir::TerminatorKind::Goto { target: self.block(*target)? }
+            }
+            TerminatorKind::SwitchInt { discr, targets, otherwise } => {
+                let targets = targets
+                    .iter()
+                    .map(|target| Ok((target.value, self.block(target.target)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                mir::TerminatorKind::SwitchInt {
+                    discr: self.operand(discr)?,
+                    targets: mir::SwitchTargets::new(targets.into_iter(), self.block(*otherwise)?),
+                }
+            }
+            TerminatorKind::Resume => mir::TerminatorKind::UnwindResume,
+            TerminatorKind::Abort => {
+                mir::TerminatorKind::UnwindTerminate(terminate_reason(is_cleanup))
+            }
+            TerminatorKind::Return => mir::TerminatorKind::Return,
+            TerminatorKind::Unreachable => mir::TerminatorKind::Unreachable,
+            TerminatorKind::Drop { place, target, unwind } => mir::TerminatorKind::Drop {
+                place: self.place(place)?,
+                target: self.block(*target)?,
+                unwind: self.unwind(unwind, is_cleanup)?,
+                replace: false,
+            },
+            TerminatorKind::Call { func, args, destination, target, unwind } => {
+                mir::TerminatorKind::Call {
+                    func: self.operand(func)?,
+                    args: args.iter().map(|arg| self.operand(arg)).collect::<Result<_>>()?,
+                    destination: self.place(destination)?,
+                    target: target.map(|target| self.block(target)).transpose()?,
+                    unwind: self.unwind(unwind, is_cleanup)?,
+                    call_source: mir::CallSource::Normal,
+                    fn_span: source_info.span,
+                }
+            }
+            TerminatorKind::Assert { cond, expected, msg, target, unwind } => {
+                mir::TerminatorKind::Assert {
+                    cond: self.operand(cond)?,
+                    expected: *expected,
+                    msg: Box::new(self.assert_message(msg)?),
+                    target: self.block(*target)?,
+                    unwind: self.unwind(unwind, is_cleanup)?,
+                }
+            }
+            TerminatorKind::GeneratorDrop => mir::TerminatorKind::GeneratorDrop,
+            // Stable MIR only has a debug representation of the template and operands.
+            TerminatorKind::InlineAsm { .. } => return Err(unsupported("inline assembly")),
+        };
+        Ok(mir::Terminator { source_info, kind })
+    }
+
+    fn unwind(&self, unwind: &UnwindAction, is_cleanup: bool) -> Result<mir::UnwindAction> {
+        Ok(match unwind {
+            UnwindAction::Continue => mir::UnwindAction::Continue,
+            UnwindAction::Unreachable => mir::UnwindAction::Unreachable,
+            UnwindAction::Terminate => mir::UnwindAction::Terminate(terminate_reason(is_cleanup)),
+            UnwindAction::Cleanup(block) => mir::UnwindAction::Cleanup(self.block(*block)?),
+        })
+    }
+
+    fn assert_message(&mut self, msg: &AssertMessage) -> Result<mir::AssertMessage<'tcx>> {
+        Ok(match msg {
+            AssertMessage::BoundsCheck { len, index } => mir::AssertKind::BoundsCheck {
+                len: self.operand(len)?,
+                index: self.operand(index)?,
+            },
+            AssertMessage::Overflow(bin_op, lhs, rhs) => mir::AssertKind::Overflow(
+                bin_op.internal(self.tables)?,
+                self.operand(lhs)?,
+                self.operand(rhs)?,
+            ),
+            AssertMessage::OverflowNeg(operand) => {
+                mir::AssertKind::OverflowNeg(self.operand(operand)?)
+            }
+            AssertMessage::DivisionByZero(operand) => {
+                mir::AssertKind::DivisionByZero(self.operand(operand)?)
+            }
+            AssertMessage::RemainderByZero(operand) => {
+                mir::AssertKind::RemainderByZero(self.operand(operand)?)
+            }
+            AssertMessage::ResumedAfterReturn(generator) => {
+                mir::AssertKind::ResumedAfterReturn(generator.internal(self.tables)?)
+            }
+            AssertMessage::ResumedAfterPanic(generator) => {
+                mir::AssertKind::ResumedAfterPanic(generator.internal(self.tables)?)
+            }
+            AssertMessage::MisalignedPointerDereference { required, found } => {
+                mir::AssertKind::MisalignedPointerDereference {
+                    required: self.operand(required)?,
+                    found: self.operand(found)?,
+                }
+            }
+        })
+    }
+
+    fn rvalue(&mut self, rvalue: &Rvalue) -> Result<mir::Rvalue<'tcx>> {
+        Ok(match rvalue {
+            Rvalue::AddressOf(mutability, place) => {
+                mir::Rvalue::AddressOf(mutability.internal(self.tables)?, self.place(place)?)
+            }
+            Rvalue::Aggregate(kind, operands) => {
+                let operands = operands
+                    .iter()
+                    .map(|operand| self.operand(operand))
+                    .collect::<Result<Vec<_>>>()?;
+                mir::Rvalue::Aggregate(
+                    Box::new(self.aggregate_kind(kind)?),
+                    operands.into_iter().collect(),
+                )
+            }
+            Rvalue::BinaryOp(bin_op, lhs, rhs) => mir::Rvalue::BinaryOp(
+                bin_op.internal(self.tables)?,
+                Box::new((self.operand(lhs)?, self.operand(rhs)?)),
+            ),
+            Rvalue::Cast(kind, operand, ty) => mir::Rvalue::Cast(
+                kind.internal(self.tables)?,
+                self.operand(operand)?,
+                ty.internal(self.tables)?,
+            ),
+            Rvalue::CheckedBinaryOp(bin_op, lhs, rhs) => mir::Rvalue::CheckedBinaryOp(
+                bin_op.internal(self.tables)?,
+                Box::new((self.operand(lhs)?, self.operand(rhs)?)),
+            ),
+            Rvalue::CopyForDeref(place) => mir::Rvalue::CopyForDeref(self.place(place)?),
+            Rvalue::Discriminant(place) => mir::Rvalue::Discriminant(self.place(place)?),
+            Rvalue::Len(place) => mir::Rvalue::Len(self.place(place)?),
+            // Regions are erased in optimized MIR.
+            Rvalue::Ref(_, kind, place) => mir::Rvalue::Ref(
+                self.tables.tcx.lifetimes.re_erased,
+                kind.internal(self.tables)?,
+                self.place(place)?,
+            ),
+            Rvalue::Repeat(operand, count) => {
+                mir::Rvalue::Repeat(self.operand(operand)?, ty_const(count, self.tables)?)
+            }
+            Rvalue::ShallowInitBox(operand, ty) => {
+                mir::Rvalue::ShallowInitBox(self.operand(operand)?, ty.internal(self.tables)?)
+            }
+            Rvalue::ThreadLocalRef(item) => mir::Rvalue::ThreadLocalRef(self.tables[item.0]),
+            Rvalue::NullaryOp(null_op, ty) => {
+                mir::Rvalue::NullaryOp(null_op.internal(self.tables)?, ty.internal(self.tables)?)
+            }
+            Rvalue::UnaryOp(un_op, operand) => {
+                mir::Rvalue::UnaryOp(un_op.internal(self.tables)?, self.operand(operand)?)
+            }
+            Rvalue::Use(operand) => mir::Rvalue::Use(self.operand(operand)?),
+        })
+    }
+
+    fn aggregate_kind(&mut self, kind: &AggregateKind) -> Result<mir::AggregateKind<'tcx>> {
+        Ok(match kind {
+            AggregateKind::Array(ty) => mir::AggregateKind::Array(ty.internal(self.tables)?),
+            AggregateKind::Tuple => mir::AggregateKind::Tuple,
+            AggregateKind::Adt(def, variant, args, user_ty, field) => mir::AggregateKind::Adt(
+                self.tables[def.0],
+                VariantIdx::from_usize(*variant),
+                args.internal(self.tables)?,
+                self.user_ty(*user_ty),
+                field.map(FieldIdx::from_usize),
+            ),
+            AggregateKind::Closure(def, args) => {
+                mir::AggregateKind::Closure(self.tables[def.0], args.internal(self.tables)?)
+            }
+            AggregateKind::Generator(def, args, movability) => mir::AggregateKind::Generator(
+                self.tables[def.0],
+                args.internal(self.tables)?,
+                movability.internal(self.tables)?,
+            ),
+        })
+    }
+
+    fn operand(&mut self, operand: &Operand) -> Result<mir::Operand<'tcx>> {
+        Ok(match operand {
+            Operand::Copy(place) => mir::Operand::Copy(self.place(place)?),
+            Operand::Move(place) => mir::Operand::Move(self.place(place)?),
+            Operand::Constant(constant) => mir::Operand::Constant(Box::new(mir::Constant {
+                span: self.span(constant.span)?,
+                user_ty: self.user_ty(constant.user_ty),
+                literal: constant.literal.internal(self.tables)?,
+            })),
+        })
+    }
+
+    fn place(&mut self, place: &Place) -> Result<mir::Place<'tcx>> {
+        let projection = place
+            .projection
+            .iter()
+            .map(|elem| self.place_elem(elem))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(mir::Place {
+            local: self.local(place.local)?,
+            projection: self.tables.tcx.mk_place_elems(&projection),
+        })
+    }
+
+    fn place_elem(&mut self, elem: &ProjectionElem) -> Result<mir::PlaceElem<'tcx>> {
+        Ok(match *elem {
+            ProjectionElem::Deref => mir::ProjectionElem::Deref,
+            ProjectionElem::Field(idx, ty) => {
+                mir::ProjectionElem::Field(FieldIdx::from_usize(idx), ty.internal(self.tables)?)
+            }
+            ProjectionElem::Index(local) => mir::ProjectionElem::Index(self.local(local)?),
+            ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                mir::ProjectionElem::ConstantIndex { offset, min_length, from_end }
+            }
+            ProjectionElem::Subslice { from, to, from_end } => {
+                mir::ProjectionElem::Subslice { from, to, from_end }
+            }
+            ProjectionElem::Downcast(idx) => {
+                mir::ProjectionElem::Downcast(None, VariantIdx::from_usize(idx))
+            }
+            ProjectionElem::OpaqueCast(ty) => {
+                mir::ProjectionElem::OpaqueCast(ty.internal(self.tables)?)
+            }
+        })
+    }
+}
+
+fn terminate_reason(is_cleanup: bool) -> mir::UnwindTerminateReason {
+    if is_cleanup {
+        mir::UnwindTerminateReason::InCleanup
+    } else {
+        mir::UnwindTerminateReason::Abi
+    }
+}
+
+/// Converts a constant used in a type, e.g. as the length of an array. Only integers and
+/// similar scalar values are supported.
+fn ty_const<'tcx>(constant: &Const, tables: &mut Tables<'tcx>) -> Result<ty::Const<'tcx>> {
+    let tcx = tables.tcx;
+    let ty = constant.ty.internal(tables)?;
+    match &constant.literal {
+        ConstantKind::Allocated(alloc) => {
+            let bytes = alloc.bytes.iter().copied().collect::<Option<Vec<u8>>>();
+            let int = match bytes {
+                Some(bytes) if alloc.provenance.ptrs.is_empty() => {
+                    read_target_uint(tcx.data_layout.endian, &bytes).ok().and_then(|value| {
+                        ty::ScalarInt::try_from_uint(value, Size::from_bytes(bytes.len()))
+                    })
+                }
+                _ => None,
+            };
+            let Some(int) = int else {
+                return Err(unsupported("a constant in a type that is not an integer"));
+            };
+            Ok(ty::Const::new_value(tcx, ty::ValTree::from_scalar_int(int), ty))
+        }
+        ConstantKind::Unevaluated(unevaluated) => {
+            if unevaluated.promoted.is_some() {
+                return Err(unsupported("a promoted constant in a type"));
+            }
+            let unevaluated = ty::UnevaluatedConst {
+                def: tables[unevaluated.def.0],
+                args: unevaluated.args.internal(tables)?,
+            };
+            Ok(ty::Const::new_unevaluated(tcx, unevaluated, ty))
+        }
+        ConstantKind::Param(param) => {
+            let param = ty::ParamConst::new(param.index, Symbol::intern(&param.name));
+            Ok(ty::Const::new_param(tcx, param, ty))
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Const {
+    type T = mir::ConstantKind<'tcx>;
+
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T> {
+        let ty = self.ty.internal(tables)?;
+        match &self.literal {
+            ConstantKind::Allocated(alloc) => {
+                // Generic types have no layout, their constants are checked once instantiated.
+                if let Ok(layout) = tables.tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)) {
+                    if layout.size.bytes_usize() != alloc.bytes.len() {
+                        return Err(opaque(&format_args!(
+                            "a constant of type `{ty}` must have {} bytes, but has {}",
+                            layout.size.bytes(),
+                            alloc.bytes.len()
+                        )));
+                    }
+                }
+                let value = if alloc.bytes.is_empty() {
+                    ConstValue::ZeroSized
+                } else {
+                    ConstValue::ByRef { alloc: alloc.internal(tables)?, offset: Size::ZERO }
+                };
+                Ok(mir::ConstantKind::Val(value, ty))
+            }
+            ConstantKind::Unevaluated(unevaluated) => {
+                let unevaluated = mir::UnevaluatedConst {
+                    def: tables[unevaluated.def.0],
+                    args: unevaluated.args.internal(tables)?,
+                    promoted: unevaluated.promoted.map(mir::Promoted::from_u32),
+                };
+                Ok(mir::ConstantKind::Unevaluated(unevaluated, ty))
+            }
+            ConstantKind::Param(_) => Ok(mir::ConstantKind::Ty(ty_const(self, tables)?)),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for StableAllocation {
+    type T = ConstAllocation<'tcx>;
+
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T> {
+        let tcx = tables.tcx;
+        let bytes: Vec<u8> = self.bytes.iter().map(|byte| byte.unwrap_or(0)).collect();
+        let align = Align::from_bytes(self.align).map_err(|err| opaque(&err))?;
+        let mut alloc: Allocation = Allocation::from_bytes(&bytes[..], align, mir::Mutability::Mut);
+        for (offset, byte) in self.bytes.iter().enumerate() {
+            if byte.is_none() {
+                let range = alloc_range(Size::from_bytes(offset), Size::from_bytes(1));
+                alloc.write_uninit(&tcx, range).map_err(|err| opaque(&err))?;
+            }
+        }
+        // The bytes of a pointer are the offset into the allocation it points to.
+        let pointer_size = tcx.data_layout.pointer_size;
+        for (offset, prov) in &self.provenance.ptrs {
+            let range = alloc_range(Size::from_bytes(*offset), pointer_size);
+            let Some(offset_bytes) = bytes.get(*offset..*offset + pointer_size.bytes_usize())
+            else {
+                return Err(opaque(&format_args!("pointer at offset {offset} is out of bounds")));
+            };
+            let Some(alloc_id) = tables.alloc_ids.get(prov.0.0) else {
+                return Err(opaque(&format_args!("allocation {} does not exist", prov.0.0)));
+            };
+            let target_offset = read_target_uint(tcx.data_layout.endian, offset_bytes)
+                .map_err(|err| opaque(&err))?;
+            let ptr = Pointer::new(*alloc_id, Size::from_bytes(target_offset));
+            alloc
+                .write_scalar(&tcx, range, Scalar::from_pointer(ptr, &tcx))
+                .map_err(|err| opaque(&err))?;
+        }
+        alloc.mutability = self.mutability.internal(tables)?;
+        Ok(tcx.mk_const_alloc(alloc))
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for StableTy {
+    type T = Ty<'tcx>;
+
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T> {
+        match tables.types.get(self.0).cloned() {
+            Some(MaybeStable::Rustc(ty)) => Ok(ty),
+            Some(MaybeStable::Stable(kind)) => kind.internal(tables),
+            None => Err(opaque(&format_args!("type {} does not exist", self.0))),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for TyKind {
+    type T = Ty<'tcx>;
+
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T> {
+        match self {
+            TyKind::RigidTy(rigid_ty) => rigid_ty.internal(tables),
+            TyKind::Param(param) => {
+                Ok(Ty::new_param(tables.tcx, param.index, Symbol::intern(&param.name)))
+            }
+            TyKind::Alias(..) => Err(unsupported("an alias type created from a `TyKind`")),
+            TyKind::Bound(..) => Err(unsupported("a bound type created from a `TyKind`")),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for RigidTy {
+    type T = Ty<'tcx>;
+
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T> {
+        let tcx = tables.tcx;
+        Ok(match self {
+            RigidTy::Bool => tcx.types.bool,
+            RigidTy::Char => tcx.types.char,
+            RigidTy::Int(int_ty) => match int_ty {
+                IntTy::Isize => tcx.types.isize,
+                IntTy::I8 => tcx.types.i8,
+                IntTy::I16 => tcx.types.i16,
+                IntTy::I32 => tcx.types.i32,
+                IntTy::I64 => tcx.types.i64,
+                IntTy::I128 => tcx.types.i128,
+            },
+            RigidTy::Uint(uint_ty) => match uint_ty {
+                UintTy::Usize => tcx.types.usize,
+                UintTy::U8 => tcx.types.u8,
+                UintTy::U16 => tcx.types.u16,
+                UintTy::U32 => tcx.types.u32,
+                UintTy::U64 => tcx.types.u64,
+                UintTy::U128 => tcx.types.u128,
+            },
+            RigidTy::Float(FloatTy::F32) => tcx.types.f32,
+            RigidTy::Float(FloatTy::F64) => tcx.types.f64,
+            RigidTy::Adt(def, args) => {
+                let adt_def = tcx.adt_def(tables[def.0]);
+                Ty::new_adt(tcx, adt_def, args.internal(tables)?)
+            }
+            RigidTy::Foreign(def) => Ty::new_foreign(tcx, tables[def.0]),
+            RigidTy::Str => tcx.types.str_,
+            RigidTy::Array(ty, len) => {
+                Ty::new_array_with_const_len(tcx, ty.internal(tables)?, ty_const(len, tables)?)
+            }
+            RigidTy::Slice(ty) => Ty::new_slice(tcx, ty.internal(tables)?),
+            RigidTy::RawPtr(ty, mutability) => Ty::new_ptr(
+                tcx,
+                ty::TypeAndMut { ty: ty.internal(tables)?, mutbl: mutability.internal(tables)? },
+            ),
+            // Regions are erased in optimized MIR.
+            RigidTy::Ref(_, ty, mutability) => Ty::new_ref(
+                tcx,
+                tcx.lifetimes.re_erased,
+                ty::TypeAndMut { ty: ty.internal(tables)?, mutbl: mutability.internal(tables)? },
+            ),
+            RigidTy::FnDef(def, args) => Ty::new_fn_def(tcx, tables[def.0], args.internal(tables)?),
+            RigidTy::Closure(def, args) => {
+                Ty::new_closure(tcx, tables[def.0], args.internal(tables)?)
+            }
+            RigidTy::Generator(def, args, movability) => Ty::new_generator(
+                tcx,
+                tables[def.0],
+                args.internal(tables)?,
+                movability.internal(tables)?,
+            ),
+            RigidTy::Never => tcx.types.never,
+            RigidTy::Tuple(tys) => {
+                let tys = tys.iter().map(|ty| ty.internal(tables)).collect::<Result<Vec<_>>>()?;
+                Ty::new_tup(tcx, &tys)
+            }
+            RigidTy::FnPtr(_) => {
+                return Err(unsupported("a function pointer type created from a `TyKind`"));
+            }
+            RigidTy::Dynamic(..) => {
+                return Err(unsupported("a trait object type created from a `TyKind`"));
+            }
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for GenericArgs {
+    type T = ty::GenericArgsRef<'tcx>;
+
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T> {
+        let args = self.0.iter().map(|arg| arg.internal(tables)).collect::<Result<Vec<_>>>()?;
+        Ok(tables.tcx.mk_args(&args))
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for GenericArgKind {
+    type T = ty::GenericArg<'tcx>;
+
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T> {
+        Ok(match self {
+            // Regions are erased in optimized MIR.
+            GenericArgKind::Lifetime(_) => tables.tcx.lifetimes.re_erased.into(),
+            GenericArgKind::Type(ty) => ty.internal(tables)?.into(),
+            GenericArgKind::Const(constant) => ty_const(constant, tables)?.into(),
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Mutability {
+    type T = mir::Mutability;
+
+    fn internal(&self, _: &mut Tables<'tcx>) -> Result<Self::T> {
+        Ok(match self {
+            Mutability::Not => mir::Mutability::Not,
+            Mutability::Mut => mir::Mutability::Mut,
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Movability {
+    type T = hir::Movability;
+
+    fn internal(&self, _: &mut Tables<'tcx>) -> Result<Self::T> {
+        Ok(match self {
+            Movability::Static => hir::Movability::Static,
+            Movability::Movable => hir::Movability::Movable,
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for stable_mir::mir::GeneratorKind {
+    type T = hir::GeneratorKind;
+
+    fn internal(&self, _: &mut Tables<'tcx>) -> Result<Self::T> {
+        use stable_mir::mir::{AsyncGeneratorKind, GeneratorKind};
+        Ok(match self {
+            GeneratorKind::Async(async_gen) => hir::GeneratorKind::Async(match async_gen {
+                AsyncGeneratorKind::Block => hir::AsyncGeneratorKind::Block,
+                AsyncGeneratorKind::Closure => hir::AsyncGeneratorKind::Closure,
+                AsyncGeneratorKind::Fn => hir::AsyncGeneratorKind::Fn,
+            }),
+            GeneratorKind::Gen => hir::GeneratorKind::Gen,
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for RetagKind {
+    type T = mir::RetagKind;
+
+    fn internal(&self, _: &mut Tables<'tcx>) -> Result<Self::T> {
+        Ok(match self {
+            RetagKind::FnEntry => mir::RetagKind::FnEntry,
+            RetagKind::TwoPhase => mir::RetagKind::TwoPhase,
+            RetagKind::Raw => mir::RetagKind::Raw,
+            RetagKind::Default => mir::RetagKind::Default,
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for BorrowKind {
+    type T = mir::BorrowKind;
+
+    fn internal(&self, _: &mut Tables<'tcx>) -> Result<Self::T> {
+        Ok(match self {
+            BorrowKind::Shared => mir::BorrowKind::Shared,
+            BorrowKind::Shallow => mir::BorrowKind::Shallow,
+            BorrowKind::Mut { kind } => mir::BorrowKind::Mut {
+                kind: match kind {
+                    MutBorrowKind::Default => mir::MutBorrowKind::Default,
+                    MutBorrowKind::TwoPhaseBorrow => mir::MutBorrowKind::TwoPhaseBorrow,
+                    MutBorrowKind::ClosureCapture => mir::MutBorrowKind::ClosureCapture,
+                },
+            },
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for NullOp {
+    type T = mir::NullOp<'tcx>;
+
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T> {
+        Ok(match self {
+            NullOp::SizeOf => mir::NullOp::SizeOf,
+            NullOp::AlignOf => mir::NullOp::AlignOf,
+            NullOp::OffsetOf(indices) => {
+                let indices: Vec<_> = indices.iter().copied().map(FieldIdx::from_usize).collect();
+                mir::NullOp::OffsetOf(tables.tcx.mk_fields(&indices))
+            }
+            NullOp::UbChecks => mir::NullOp::UbChecks,
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for CastKind {
+    type T = mir::CastKind;
+
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Result<Self::T> {
+        Ok(match self {
+            CastKind::PointerExposeAddress => mir::CastKind::PointerExposeAddress,
+            CastKind::PointerFromExposedAddress => mir::CastKind::PointerFromExposedAddress,
+            CastKind::PointerCoercion(coercion) => {
+                mir::CastKind::PointerCoercion(coercion.internal(tables)?)
+            }
+            CastKind::DynStar => mir::CastKind::DynStar,
+            CastKind::IntToInt => mir::CastKind::IntToInt,
+            CastKind::FloatToInt => mir::CastKind::FloatToInt,
+            CastKind::FloatToFloat => mir::CastKind::FloatToFloat,
+            CastKind::IntToFloat => mir::CastKind::IntToFloat,
+            CastKind::PtrToPtr => mir::CastKind::PtrToPtr,
+            CastKind::FnPtrToPtr => mir::CastKind::FnPtrToPtr,
+            CastKind::Transmute => mir::CastKind::Transmute,
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for PointerCoercion {
+    type T = ty::adjustment::PointerCoercion;
+
+    fn internal(&self, _: &mut Tables<'tcx>) -> Result<Self::T> {
+        use ty::adjustment::PointerCoercion as Internal;
+        Ok(match self {
+            PointerCoercion::ReifyFnPointer => Internal::ReifyFnPointer,
+            PointerCoercion::UnsafeFnPointer => Internal::UnsafeFnPointer,
+            PointerCoercion::ClosureFnPointer(safety) => Internal::ClosureFnPointer(match safety {
+                Safety::Unsafe => hir::Unsafety::Unsafe,
+                Safety::Normal => hir::Unsafety::Normal,
+            }),
+            PointerCoercion::MutToConstPointer => Internal::MutToConstPointer,
+            PointerCoercion::ArrayToPointer => Internal::ArrayToPointer,
+            PointerCoercion::Unsize => Internal::Unsize,
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for BinOp {
+    type T = mir::BinOp;
+
+    fn internal(&self, _: &mut Tables<'tcx>) -> Result<Self::T> {
+        Ok(match self {
+            BinOp::Add => mir::BinOp::Add,
+            BinOp::AddUnchecked => mir::BinOp::AddUnchecked,
+            BinOp::Sub => mir::BinOp::Sub,
+            BinOp::SubUnchecked => mir::BinOp::SubUnchecked,
+            BinOp::Mul => mir::BinOp::Mul,
+            BinOp::MulUnchecked => mir::BinOp::MulUnchecked,
+            BinOp::Div => mir::BinOp::Div,
+            BinOp::Rem => mir::BinOp::Rem,
+            BinOp::BitXor => mir::BinOp::BitXor,
+            BinOp::BitAnd => mir::BinOp::BitAnd,
+            BinOp::BitOr => mir::BinOp::BitOr,
+            BinOp::Shl => mir::BinOp::Shl,
+            BinOp::ShlUnchecked => mir::BinOp::ShlUnchecked,
+            BinOp::Shr => mir::BinOp::Shr,
+            BinOp::ShrUnchecked => mir::BinOp::ShrUnchecked,
+            BinOp::Eq => mir::BinOp::Eq,
+            BinOp::Lt => mir::BinOp::Lt,
+            BinOp::Le => mir::BinOp::Le,
+            BinOp::Ne => mir::BinOp::Ne,
+            BinOp::Ge => mir::BinOp::Ge,
+            BinOp::Gt => mir::BinOp::Gt,
+            BinOp::Offset => mir::BinOp::Offset,
+        })
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for UnOp {
+    type T = mir::UnOp;
+
+    fn internal(&self, _: &mut Tables<'tcx>) -> Result<Self::T> {
+        Ok(match self {
+            UnOp::Not => mir::UnOp::Not,
+            UnOp::Neg => mir::UnOp::Neg,
+        })
+    }
+}
@@ -0,0 +1,145 @@
+//! Serialization of the stable MIR of the local crate to a versioned JSON document.
+//!
+//! This is what `--emit=stable-mir` produces, so that analysis tools written in other languages
+//! can consume MIR without linking to rustc. The format is described in the unstable book, and
+//! any change to the shape of the document must bump [`FORMAT_VERSION`].
+//!
+//! Values that stable MIR does not model yet are written as the debug representation of the
+//! internal compiler value (see [`Opaque`](super::Opaque)). Their contents are unstable and are
+//! not covered by [`FORMAT_VERSION`].
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use rustc_hir::ConstContext;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::{OutFileName, OutputType};
+use serde::Serialize;
+
+use super::{run, with_tables};
+use crate::stable_mir::mir::Body;
+use crate::stable_mir::ty::{ImplDef, ImplTrait, Span, Ty, TyKind};
+use crate::stable_mir::{self, with, DefId, Symbol};
+
+/// The version of the JSON document written by [`write_json`].
+pub const FORMAT_VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct CrateMir {
+    format_version: u32,
+    crate_name: Symbol,
+    items: Vec<ItemMir>,
+    trait_impls: Vec<TraitImplMir>,
+    /// The kinds of all types referred to by id in the rest of the document.
+    types: Vec<TypeEntry>,
+    /// The paths of all definitions referred to by id in the rest of the document.
+    def_ids: Vec<DefIdEntry>,
+    /// The locations of all spans referred to by id in the rest of the document.
+    spans: Vec<SpanEntry>,
+}
+
+#[derive(Serialize)]
+struct ItemMir {
+    def_id: DefId,
+    name: String,
+    /// `None` for constants and statics, whose MIR only exists for const evaluation.
+    body: Option<Body>,
+}
+
+#[derive(Serialize)]
+struct TraitImplMir {
+    impl_def: ImplDef,
+    trait_ref: ImplTrait,
+}
+
+#[derive(Serialize)]
+struct TypeEntry {
+    id: usize,
+    kind: TyKind,
+}
+
+#[derive(Serialize)]
+struct DefIdEntry {
+    id: usize,
+    name: String,
+}
+
+#[derive(Serialize)]
+struct SpanEntry {
+    id: usize,
+    location: String,
+}
+
+/// Collects the document for the local crate. Must be called from within [`run`].
+fn crate_mir() -> CrateMir {
+    let crate_name = stable_mir::local_crate().name;
+    let items = stable_mir::all_local_items()
+        .into_iter()
+        .map(|item| {
+            let def_id = item.0;
+            let name = with(|cx| cx.name_of_def_id(def_id));
+            let is_const = with_tables(|t| {
+                let def_id = t[def_id].expect_local();
+                matches!(
+                    t.tcx.hir().body_const_context(def_id),
+                    Some(ConstContext::Const | ConstContext::Static(_))
+                )
+            });
+            ItemMir { def_id, name, body: (!is_const).then(|| item.body()) }
+        })
+        .collect();
+    let trait_impls = stable_mir::all_trait_impls()
+        .into_iter()
+        .map(|impl_def| TraitImplMir { trait_ref: stable_mir::trait_impl(&impl_def), impl_def })
+        .collect();
+
+    // Computing the kind of a type may intern new types, so keep going until the table stops
+    // growing. Only then are all the definitions known.
+    let mut types = Vec::new();
+    while types.len() < with_tables(|t| t.types.len()) {
+        let ty = Ty(types.len());
+        types.push(TypeEntry { id: ty.0, kind: ty.kind() });
+    }
+    let def_ids = (0..with_tables(|t| t.def_ids.len()))
+        .map(|id| DefIdEntry { id, name: with(|cx| cx.name_of_def_id(DefId(id))) })
+        .collect();
+    let spans = (0..with_tables(|t| t.spans.len()))
+        .map(|id| SpanEntry { id, location: with(|cx| cx.span_to_string(Span(id))) })
+        .collect();
+
+    CrateMir {
+        format_version: FORMAT_VERSION,
+        crate_name,
+        items,
+        trait_impls,
+        types,
+        def_ids,
+        spans,
+    }
+}
+
+/// Writes the stable MIR of the local crate to `w` as a JSON document.
+pub fn write_json(tcx: TyCtxt<'_>, w: &mut dyn Write) -> io::Result<()> {
+    let mut krate = None;
+    run(tcx, || krate = Some(crate_mir()));
+    serde_json::to_writer(&mut *w, &krate.unwrap())?;
+    writeln!(w)
+}
+
+/// Writes the output of `--emit=stable-mir`.
+fn emit_stable_mir(tcx: TyCtxt<'_>) -> io::Result<()> {
+    match tcx.output_filenames(()).path(OutputType::StableMir) {
+        OutFileName::Stdout => write_json(tcx, &mut io::stdout().lock()),
+        OutFileName::Real(path) => {
+            let mut f = BufWriter::new(File::create(&path)?);
+            write_json(tcx, &mut f)?;
+            f.flush()
+        }
+    }
+}
+
+/// Makes `--emit=stable-mir` use [`write_json`]. The compiler itself cannot depend on this
+/// crate, so this must be called before running it.
+pub fn install_stable_mir_emitter() {
+    rustc_interface::EMIT_STABLE_MIR.swap(&(emit_stable_mir as _));
+}
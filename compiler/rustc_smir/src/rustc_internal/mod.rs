@@ -5,19 +5,26 @@
 
 use std::fmt::Debug;
 use std::ops::{ControlFlow, Index};
+use std::sync::Mutex;
 
 use crate::rustc_internal;
 use crate::stable_mir::CompilerError;
 use crate::{
-    rustc_smir::Tables,
+    rustc_smir::{Stable, Tables},
     stable_mir::{self, with},
 };
+use rustc_const_eval::transform::validate::Validator;
 use rustc_driver::{Callbacks, Compilation, RunCompiler};
 use rustc_interface::{interface, Queries};
 use rustc_middle::mir::interpret::AllocId;
+use rustc_middle::mir::{self, MirPass, MirPhase, RuntimePhase};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::EarlyErrorHandler;
 pub use rustc_span::def_id::{CrateNum, DefId};
+use serde::{Serialize, Serializer};
+
+mod internal;
+pub mod json;
 
 fn with_tables<R>(mut f: impl FnMut(&mut Tables<'_>) -> R) -> R {
     let mut ret = None;
@@ -73,6 +80,10 @@ pub fn impl_def(did: DefId) -> stable_mir::ty::ImplDef {
     with_tables(|t| t.impl_def(did))
 }
 
+pub fn static_def(did: DefId) -> stable_mir::ty::StaticDef {
+    with_tables(|t| t.static_def(did))
+}
+
 impl<'tcx> Index<stable_mir::DefId> for Tables<'tcx> {
     type Output = DefId;
 
@@ -135,6 +146,10 @@ pub fn impl_def(&mut self, did: DefId) -> stable_mir::ty::ImplDef {
         stable_mir::ty::ImplDef(self.create_def_id(did))
     }
 
+    pub fn static_def(&mut self, did: DefId) -> stable_mir::ty::StaticDef {
+        stable_mir::ty::StaticDef(self.create_def_id(did))
+    }
+
     pub fn prov(&mut self, aid: AllocId) -> stable_mir::ty::Prov {
         stable_mir::ty::Prov(self.create_alloc_id(aid))
     }
@@ -156,7 +171,7 @@ fn create_alloc_id(&mut self, aid: AllocId) -> stable_mir::AllocId {
         if let Some(i) = self.alloc_ids.iter().position(|a| *a == aid) {
             return stable_mir::AllocId(i);
         };
-        let id = self.def_ids.len();
+        let id = self.alloc_ids.len();
         self.alloc_ids.push(aid);
         stable_mir::AllocId(id)
     }
@@ -167,7 +182,56 @@ pub fn crate_num(item: &stable_mir::Crate) -> CrateNum {
 }
 
 pub fn run(tcx: TyCtxt<'_>, f: impl FnOnce()) {
-    crate::stable_mir::run(Tables { tcx, def_ids: vec![], alloc_ids: vec![], types: vec![] }, f);
+    crate::stable_mir::run(&mut Tables::new(tcx), f);
+}
+
+/// A function that rewrites the stable MIR body of a local item, and returns whether it changed
+/// the body. See [`instrument`].
+pub type BodyRewriter = fn(&stable_mir::CrateItem, &mut stable_mir::mir::Body) -> bool;
+
+/// Passes the stable MIR of `body` to `rewriter`, and returns the internal MIR of the rewritten
+/// body if `rewriter` changed it. Meant to be called from an `optimized_mir` query provider.
+///
+/// If the rewritten body cannot be translated back or is not valid optimized MIR, an error is
+/// reported and `body` is returned.
+pub fn rewrite_optimized_mir<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &'tcx mir::Body<'tcx>,
+    rewriter: BodyRewriter,
+) -> &'tcx mir::Body<'tcx> {
+    let mut tables = Tables::new(tcx);
+    let item = tables.crate_item(body.source.def_id());
+    let mut stable_body = body.stable(&mut tables);
+    let mut changed = false;
+    crate::stable_mir::run(&mut tables, || changed = rewriter(&item, &mut stable_body));
+    if !changed {
+        return body;
+    }
+    let path = tcx.def_path_str(body.source.def_id());
+    let mut rewritten = match internal::body(&mut tables, body, &stable_body) {
+        Ok(rewritten) => rewritten,
+        Err(err) => {
+            tcx.sess.span_err(
+                body.span,
+                format!("cannot translate the rewritten MIR of `{path}` back: {err}"),
+            );
+            return body;
+        }
+    };
+
+    // The validator reports broken MIR as delayed bugs, which only turn into an ICE if no error
+    // is emitted. Report an error instead, as the rewriter is the one to blame.
+    let had_errors = tcx.sess.has_errors_or_delayed_span_bugs().is_some();
+    let validator = Validator {
+        when: "after rewriting it through stable MIR".to_owned(),
+        mir_phase: MirPhase::Runtime(RuntimePhase::Optimized),
+    };
+    validator.run_pass(tcx, &mut rewritten);
+    if !had_errors && tcx.sess.has_errors_or_delayed_span_bugs().is_some() {
+        tcx.sess.span_err(body.span, format!("the rewritten MIR of `{path}` is invalid"));
+        return body;
+    }
+    tcx.arena.alloc(rewritten)
 }
 
 /// A type that provides internal information but that can still be used for debug purpose.
@@ -186,6 +250,14 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
+/// Serializes as the debug representation of the internal value, which is not stable: it may
+/// change between compiler versions without a bump of [`json::FORMAT_VERSION`].
+impl Serialize for Opaque {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
 pub(crate) fn opaque<T: Debug>(value: &T) -> Opaque {
     Opaque(format!("{value:?}"))
 }
@@ -249,3 +321,40 @@ fn after_analysis<'tcx>(
         })
     }
 }
+
+/// The rewriter passed to [`instrument`]. Query providers are plain function pointers, so they
+/// cannot capture it.
+static REWRITER: Mutex<Option<BodyRewriter>> = Mutex::new(None);
+
+struct Instrument;
+
+impl Callbacks for Instrument {
+    fn config(&mut self, config: &mut interface::Config) {
+        config.override_queries = Some(|_session, providers, _extern_providers| {
+            providers.optimized_mir = |tcx, def_id| {
+                let body = (rustc_interface::DEFAULT_QUERY_PROVIDERS.optimized_mir)(tcx, def_id);
+                let rewriter = REWRITER.lock().unwrap().expect("no rewriter was installed");
+                rewrite_optimized_mir(tcx, body, rewriter)
+            };
+        });
+    }
+}
+
+/// Instantiates and runs the compiler with the provided arguments, letting `rewriter` change the
+/// stable MIR body of every local item that has one before it is code generated.
+///
+/// `rewriter` is called at most once per item, whenever the compiler computes the optimized MIR
+/// of the item, so the order of calls is unspecified. It must not request the body of another
+/// item. Rewriting a body that contains inline assembly, coverage statements or types built from
+/// a `TyKind` that cannot be translated back is reported as an error, and so is producing a body
+/// that does not pass MIR validation.
+pub fn instrument(args: Vec<String>, rewriter: BodyRewriter) -> Result<(), CompilerError<()>> {
+    *REWRITER.lock().unwrap() = Some(rewriter);
+    let compiler_result =
+        rustc_driver::catch_fatal_errors(|| RunCompiler::new(&args, &mut Instrument).run());
+    match compiler_result {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(_)) => Err(CompilerError::CompilationFailed),
+        Err(_) => Err(CompilerError::ICE),
+    }
+}
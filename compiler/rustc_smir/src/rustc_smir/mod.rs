@@ -7,11 +7,12 @@
 //!
 //! For now, we are developing everything inside `rustc`, thus, we keep this module private.
 
-use crate::rustc_internal::{self, opaque};
+use crate::rustc_internal::opaque;
 use crate::stable_mir::mir::{CopyNonOverlapping, UserTypeProjection, VariantIdx};
 use crate::stable_mir::ty::{FloatTy, GenericParamDef, IntTy, Movability, RigidTy, TyKind, UintTy};
 use crate::stable_mir::{self, CompilerError, Context};
 use rustc_hir as hir;
+use rustc_hir::def::DefKind;
 use rustc_middle::mir::interpret::{alloc_range, AllocId};
 use rustc_middle::mir::{self, ConstantKind};
 use rustc_middle::ty::{self, Ty, TyCtxt, Variance};
@@ -42,6 +43,10 @@ fn name_of_def_id(&self, def_id: stable_mir::DefId) -> String {
         self.tcx.def_path_str(self[def_id])
     }
 
+    fn span_to_string(&self, span: stable_mir::ty::Span) -> String {
+        self.tcx.sess.source_map().span_to_diagnostic_string(self.spans[span.0].0)
+    }
+
     fn all_local_items(&mut self) -> stable_mir::CrateItems {
         self.tcx.mir_keys(()).iter().map(|item| self.crate_item(item.to_def_id())).collect()
     }
@@ -80,22 +85,7 @@ fn trait_impl(&mut self, impl_def: &stable_mir::ty::ImplDef) -> stable_mir::ty::
 
     fn mir_body(&mut self, item: stable_mir::DefId) -> stable_mir::mir::Body {
         let def_id = self[item];
-        let mir = self.tcx.optimized_mir(def_id);
-        stable_mir::mir::Body {
-            blocks: mir
-                .basic_blocks
-                .iter()
-                .map(|block| stable_mir::mir::BasicBlock {
-                    terminator: block.terminator().stable(self),
-                    statements: block
-                        .statements
-                        .iter()
-                        .map(|statement| statement.stable(self))
-                        .collect(),
-                })
-                .collect(),
-            locals: mir.local_decls.iter().map(|decl| self.intern_ty(decl.ty)).collect(),
-        }
+        self.tcx.optimized_mir(def_id).stable(self)
     }
 
     fn rustc_tables(&mut self, f: &mut dyn FnMut(&mut Tables<'_>)) {
@@ -112,6 +102,63 @@ fn mk_ty(&mut self, kind: TyKind) -> stable_mir::ty::Ty {
         stable_mir::ty::Ty(n)
     }
 
+    fn ty_layout(
+        &mut self,
+        ty: stable_mir::ty::Ty,
+    ) -> Result<stable_mir::ty::Layout, stable_mir::Error> {
+        let ty = match self.types[ty.0] {
+            MaybeStable::Rustc(ty) => ty,
+            MaybeStable::Stable(_) => {
+                return Err(opaque(&format_args!(
+                    "cannot compute the layout of a type created from a `TyKind`"
+                )));
+            }
+        };
+        match self.tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)) {
+            Ok(layout) => Ok(layout.layout.0.stable(self)),
+            Err(err) => Err(opaque(err)),
+        }
+    }
+
+    fn as_static(&mut self, item: stable_mir::DefId) -> Option<stable_mir::ty::StaticDef> {
+        let def_id = self[item];
+        matches!(self.tcx.def_kind(def_id), DefKind::Static(_)).then(|| self.static_def(def_id))
+    }
+
+    fn as_fn_def(&mut self, item: stable_mir::DefId) -> Option<stable_mir::ty::FnDef> {
+        let def_id = self[item];
+        matches!(self.tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+            .then(|| self.fn_def(def_id))
+    }
+
+    fn eval_const_initializer(
+        &mut self,
+        def: stable_mir::ty::ConstDef,
+    ) -> Result<stable_mir::ty::Allocation, stable_mir::Error> {
+        let def_id = self[def.0];
+        let ty = self.tcx.type_of(def_id).instantiate_identity();
+        match self.tcx.const_eval_poly(def_id) {
+            Ok(value) => Ok(alloc::new_allocation(ty, value, self)),
+            Err(err) => Err(opaque(&err)),
+        }
+    }
+
+    fn eval_static_initializer(
+        &mut self,
+        def: stable_mir::ty::StaticDef,
+    ) -> Result<stable_mir::ty::Allocation, stable_mir::Error> {
+        let def_id = self[def.0];
+        match self.tcx.eval_static_initializer(def_id) {
+            Ok(alloc) => Ok(alloc.inner().stable(self)),
+            Err(err) => Err(opaque(&err)),
+        }
+    }
+
+    fn global_alloc(&mut self, alloc_id: stable_mir::AllocId) -> stable_mir::ty::GlobalAlloc {
+        let alloc_id = self.alloc_ids[alloc_id.0];
+        self.tcx.global_alloc(alloc_id).stable(self)
+    }
+
     fn generics_of(&mut self, def_id: stable_mir::DefId) -> stable_mir::ty::Generics {
         let def_id = self[def_id];
         let generics = self.tcx.generics_of(def_id);
@@ -165,9 +212,26 @@ pub struct Tables<'tcx> {
     pub def_ids: Vec<DefId>,
     pub alloc_ids: Vec<AllocId>,
     pub types: Vec<MaybeStable<stable_mir::ty::TyKind, Ty<'tcx>>>,
+    /// Spans are not deduplicated. Spans of MIR statements and terminators also remember the body
+    /// and source scope they come from, so that the scope can be restored when the body is
+    /// translated back.
+    pub spans: Vec<(rustc_span::Span, Option<(DefId, mir::SourceScope)>)>,
 }
 
 impl<'tcx> Tables<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> Self {
+        Tables { tcx, def_ids: vec![], alloc_ids: vec![], types: vec![], spans: vec![] }
+    }
+
+    fn create_span(
+        &mut self,
+        span: rustc_span::Span,
+        scope: Option<(DefId, mir::SourceScope)>,
+    ) -> stable_mir::ty::Span {
+        self.spans.push((span, scope));
+        stable_mir::ty::Span(self.spans.len() - 1)
+    }
+
     fn intern_ty(&mut self, ty: Ty<'tcx>) -> stable_mir::ty::Ty {
         if let Some(id) = self.types.iter().position(|t| *t == ty) {
             return stable_mir::ty::Ty(id);
@@ -194,44 +258,81 @@ pub(crate) trait Stable<'tcx> {
     fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T;
 }
 
-impl<'tcx> Stable<'tcx> for mir::Statement<'tcx> {
-    type T = stable_mir::mir::Statement;
+impl<'tcx> Stable<'tcx> for mir::Body<'tcx> {
+    type T = stable_mir::mir::Body;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let def_id = self.source.def_id();
+        let span = |tables: &mut Tables<'tcx>, source_info: mir::SourceInfo| {
+            tables.create_span(source_info.span, Some((def_id, source_info.scope)))
+        };
+        stable_mir::mir::Body {
+            blocks: self
+                .basic_blocks
+                .iter()
+                .map(|block| {
+                    let terminator = block.terminator();
+                    stable_mir::mir::BasicBlock {
+                        statements: block
+                            .statements
+                            .iter()
+                            .map(|statement| stable_mir::mir::Statement {
+                                kind: statement.kind.stable(tables),
+                                span: span(tables, statement.source_info),
+                            })
+                            .collect(),
+                        terminator: stable_mir::mir::Terminator {
+                            kind: terminator.kind.stable(tables),
+                            span: span(tables, terminator.source_info),
+                        },
+                    }
+                })
+                .collect(),
+            locals: self.local_decls.iter().map(|decl| tables.intern_ty(decl.ty)).collect(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::StatementKind<'tcx> {
+    type T = stable_mir::mir::StatementKind;
     fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
         use rustc_middle::mir::StatementKind::*;
-        match &self.kind {
-            Assign(assign) => {
-                stable_mir::mir::Statement::Assign(assign.0.stable(tables), assign.1.stable(tables))
-            }
-            FakeRead(fake_read_place) => stable_mir::mir::Statement::FakeRead(
+        match self {
+            Assign(assign) => stable_mir::mir::StatementKind::Assign(
+                assign.0.stable(tables),
+                assign.1.stable(tables),
+            ),
+            FakeRead(fake_read_place) => stable_mir::mir::StatementKind::FakeRead(
                 fake_read_place.0.stable(tables),
                 fake_read_place.1.stable(tables),
             ),
             SetDiscriminant { place: plc, variant_index: idx } => {
-                stable_mir::mir::Statement::SetDiscriminant {
+                stable_mir::mir::StatementKind::SetDiscriminant {
                     place: plc.as_ref().stable(tables),
                     variant_index: idx.stable(tables),
                 }
             }
-            Deinit(place) => stable_mir::mir::Statement::Deinit(place.stable(tables)),
-            StorageLive(place) => stable_mir::mir::Statement::StorageLive(place.stable(tables)),
-            StorageDead(place) => stable_mir::mir::Statement::StorageDead(place.stable(tables)),
+            Deinit(place) => stable_mir::mir::StatementKind::Deinit(place.stable(tables)),
+            StorageLive(place) => stable_mir::mir::StatementKind::StorageLive(place.stable(tables)),
+            StorageDead(place) => stable_mir::mir::StatementKind::StorageDead(place.stable(tables)),
             Retag(retag, place) => {
-                stable_mir::mir::Statement::Retag(retag.stable(tables), place.stable(tables))
+                stable_mir::mir::StatementKind::Retag(retag.stable(tables), place.stable(tables))
+            }
+            PlaceMention(place) => {
+                stable_mir::mir::StatementKind::PlaceMention(place.stable(tables))
             }
-            PlaceMention(place) => stable_mir::mir::Statement::PlaceMention(place.stable(tables)),
             AscribeUserType(place_projection, variance) => {
-                stable_mir::mir::Statement::AscribeUserType {
+                stable_mir::mir::StatementKind::AscribeUserType {
                     place: place_projection.as_ref().0.stable(tables),
                     projections: place_projection.as_ref().1.stable(tables),
                     variance: variance.stable(tables),
                 }
             }
-            Coverage(coverage) => stable_mir::mir::Statement::Coverage(opaque(coverage)),
+            Coverage(coverage) => stable_mir::mir::StatementKind::Coverage(opaque(coverage)),
             Intrinsic(intrinstic) => {
-                stable_mir::mir::Statement::Intrinsic(intrinstic.stable(tables))
+                stable_mir::mir::StatementKind::Intrinsic(intrinstic.stable(tables))
             }
-            ConstEvalCounter => stable_mir::mir::Statement::ConstEvalCounter,
-            Nop => stable_mir::mir::Statement::Nop,
+            ConstEvalCounter => stable_mir::mir::StatementKind::ConstEvalCounter,
+            Nop => stable_mir::mir::StatementKind::Nop,
         }
     }
 }
@@ -252,7 +353,7 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
                 place.stable(tables),
             ),
             ThreadLocalRef(def_id) => {
-                stable_mir::mir::Rvalue::ThreadLocalRef(rustc_internal::crate_item(*def_id))
+                stable_mir::mir::Rvalue::ThreadLocalRef(tables.crate_item(*def_id))
             }
             AddressOf(mutability, place) => {
                 stable_mir::mir::Rvalue::AddressOf(mutability.stable(tables), place.stable(tables))
@@ -337,6 +438,7 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
             OffsetOf(indices) => stable_mir::mir::NullOp::OffsetOf(
                 indices.iter().map(|idx| idx.stable(tables)).collect(),
             ),
+            UbChecks => stable_mir::mir::NullOp::UbChecks,
         }
     }
 }
@@ -529,10 +631,29 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
 
 impl<'tcx> Stable<'tcx> for mir::Place<'tcx> {
     type T = stable_mir::mir::Place;
-    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
         stable_mir::mir::Place {
             local: self.local.as_usize(),
-            projection: format!("{:?}", self.projection),
+            projection: self.projection.iter().map(|elem| elem.stable(tables)).collect(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::PlaceElem<'tcx> {
+    type T = stable_mir::mir::ProjectionElem;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::ProjectionElem::*;
+        use stable_mir::mir::ProjectionElem;
+        match *self {
+            Deref => ProjectionElem::Deref,
+            Field(idx, ty) => ProjectionElem::Field(idx.stable(tables), tables.intern_ty(ty)),
+            Index(local) => ProjectionElem::Index(local.stable(tables)),
+            ConstantIndex { offset, min_length, from_end } => {
+                ProjectionElem::ConstantIndex { offset, min_length, from_end }
+            }
+            Subslice { from, to, from_end } => ProjectionElem::Subslice { from, to, from_end },
+            Downcast(_, idx) => ProjectionElem::Downcast(idx.stable(tables)),
+            OpaqueCast(ty) => ProjectionElem::OpaqueCast(tables.intern_ty(ty)),
         }
     }
 }
@@ -715,7 +836,7 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
             mir::AggregateKind::Tuple => stable_mir::mir::AggregateKind::Tuple,
             mir::AggregateKind::Adt(def_id, var_idx, generic_arg, user_ty_index, field_idx) => {
                 stable_mir::mir::AggregateKind::Adt(
-                    rustc_internal::adt_def(*def_id),
+                    tables.adt_def(*def_id),
                     var_idx.index(),
                     generic_arg.stable(tables),
                     user_ty_index.map(|idx| idx.index()),
@@ -724,13 +845,13 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
             }
             mir::AggregateKind::Closure(def_id, generic_arg) => {
                 stable_mir::mir::AggregateKind::Closure(
-                    rustc_internal::closure_def(*def_id),
+                    tables.closure_def(*def_id),
                     generic_arg.stable(tables),
                 )
             }
             mir::AggregateKind::Generator(def_id, generic_arg, movability) => {
                 stable_mir::mir::AggregateKind::Generator(
-                    rustc_internal::generator_def(*def_id),
+                    tables.generator_def(*def_id),
                     generic_arg.stable(tables),
                     movability.stable(tables),
                 )
@@ -777,14 +898,14 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
     }
 }
 
-impl<'tcx> Stable<'tcx> for mir::Terminator<'tcx> {
-    type T = stable_mir::mir::Terminator;
+impl<'tcx> Stable<'tcx> for mir::TerminatorKind<'tcx> {
+    type T = stable_mir::mir::TerminatorKind;
     fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
         use rustc_middle::mir::TerminatorKind::*;
-        use stable_mir::mir::Terminator;
-        match &self.kind {
-            Goto { target } => Terminator::Goto { target: target.as_usize() },
-            SwitchInt { discr, targets } => Terminator::SwitchInt {
+        use stable_mir::mir::TerminatorKind;
+        match self {
+            Goto { target } => TerminatorKind::Goto { target: target.as_usize() },
+            SwitchInt { discr, targets } => TerminatorKind::SwitchInt {
                 discr: discr.stable(tables),
                 targets: targets
                     .iter()
@@ -795,17 +916,17 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
                     .collect(),
                 otherwise: targets.otherwise().as_usize(),
             },
-            UnwindResume => Terminator::Resume,
-            UnwindTerminate(_) => Terminator::Abort,
-            Return => Terminator::Return,
-            Unreachable => Terminator::Unreachable,
-            Drop { place, target, unwind, replace: _ } => Terminator::Drop {
+            UnwindResume => TerminatorKind::Resume,
+            UnwindTerminate(_) => TerminatorKind::Abort,
+            Return => TerminatorKind::Return,
+            Unreachable => TerminatorKind::Unreachable,
+            Drop { place, target, unwind, replace: _ } => TerminatorKind::Drop {
                 place: place.stable(tables),
                 target: target.as_usize(),
                 unwind: unwind.stable(tables),
             },
             Call { func, args, destination, target, unwind, call_source: _, fn_span: _ } => {
-                Terminator::Call {
+                TerminatorKind::Call {
                     func: func.stable(tables),
                     args: args.iter().map(|arg| arg.stable(tables)).collect(),
                     destination: destination.stable(tables),
@@ -813,7 +934,7 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
                     unwind: unwind.stable(tables),
                 }
             }
-            Assert { cond, expected, msg, target, unwind } => Terminator::Assert {
+            Assert { cond, expected, msg, target, unwind } => TerminatorKind::Assert {
                 cond: cond.stable(tables),
                 expected: *expected,
                 msg: msg.stable(tables),
@@ -821,7 +942,7 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
                 unwind: unwind.stable(tables),
             },
             InlineAsm { template, operands, options, line_spans, destination, unwind } => {
-                Terminator::InlineAsm {
+                TerminatorKind::InlineAsm {
                     template: format!("{template:?}"),
                     operands: operands.iter().map(|operand| operand.stable(tables)).collect(),
                     options: format!("{options:?}"),
@@ -940,13 +1061,13 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
 impl<'tcx> Stable<'tcx> for ty::BoundTyKind {
     type T = stable_mir::ty::BoundTyKind;
 
-    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
         use stable_mir::ty::BoundTyKind;
 
         match self {
             ty::BoundTyKind::Anon => BoundTyKind::Anon,
             ty::BoundTyKind::Param(def_id, symbol) => {
-                BoundTyKind::Param(rustc_internal::param_def(*def_id), symbol.to_string())
+                BoundTyKind::Param(tables.param_def(*def_id), symbol.to_string())
             }
         }
     }
@@ -963,7 +1084,7 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
                 BoundRegionKind::BrAnon(option_span.map(|span| span.stable(tables)))
             }
             ty::BoundRegionKind::BrNamed(def_id, symbol) => {
-                BoundRegionKind::BrNamed(rustc_internal::br_named_def(*def_id), symbol.to_string())
+                BoundRegionKind::BrNamed(tables.br_named_def(*def_id), symbol.to_string())
             }
             ty::BoundRegionKind::BrEnv => BoundRegionKind::BrEnv,
         }
@@ -1050,11 +1171,11 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
             ty::Uint(uint_ty) => TyKind::RigidTy(RigidTy::Uint(uint_ty.stable(tables))),
             ty::Float(float_ty) => TyKind::RigidTy(RigidTy::Float(float_ty.stable(tables))),
             ty::Adt(adt_def, generic_args) => TyKind::RigidTy(RigidTy::Adt(
-                rustc_internal::adt_def(adt_def.did()),
+                tables.adt_def(adt_def.did()),
                 generic_args.stable(tables),
             )),
             ty::Foreign(def_id) => {
-                TyKind::RigidTy(RigidTy::Foreign(rustc_internal::foreign_def(*def_id)))
+                TyKind::RigidTy(RigidTy::Foreign(tables.foreign_def(*def_id)))
             }
             ty::Str => TyKind::RigidTy(RigidTy::Str),
             ty::Array(ty, constant) => {
@@ -1070,7 +1191,7 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
                 mutbl.stable(tables),
             )),
             ty::FnDef(def_id, generic_args) => TyKind::RigidTy(RigidTy::FnDef(
-                rustc_internal::fn_def(*def_id),
+                tables.fn_def(*def_id),
                 generic_args.stable(tables),
             )),
             ty::FnPtr(poly_fn_sig) => TyKind::RigidTy(RigidTy::FnPtr(poly_fn_sig.stable(tables))),
@@ -1085,11 +1206,11 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
                 ))
             }
             ty::Closure(def_id, generic_args) => TyKind::RigidTy(RigidTy::Closure(
-                rustc_internal::closure_def(*def_id),
+                tables.closure_def(*def_id),
                 generic_args.stable(tables),
             )),
             ty::Generator(def_id, generic_args, movability) => TyKind::RigidTy(RigidTy::Generator(
-                rustc_internal::generator_def(*def_id),
+                tables.generator_def(*def_id),
                 generic_args.stable(tables),
                 movability.stable(tables),
             )),
@@ -1184,6 +1305,155 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
     }
 }
 
+impl<'tcx> Stable<'tcx> for mir::interpret::GlobalAlloc<'tcx> {
+    type T = stable_mir::ty::GlobalAlloc;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::interpret::GlobalAlloc;
+        match self {
+            GlobalAlloc::Function(instance) => stable_mir::ty::GlobalAlloc::Function(
+                tables.fn_def(instance.def_id()),
+                instance.args.stable(tables),
+            ),
+            GlobalAlloc::VTable(ty, trait_ref) => stable_mir::ty::GlobalAlloc::VTable(
+                tables.intern_ty(*ty),
+                trait_ref.map(|trait_ref| trait_ref.stable(tables)),
+            ),
+            GlobalAlloc::Static(def_id) => {
+                stable_mir::ty::GlobalAlloc::Static(tables.static_def(*def_id))
+            }
+            GlobalAlloc::Memory(alloc) => {
+                stable_mir::ty::GlobalAlloc::Memory(alloc.inner().stable(tables))
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_target::abi::LayoutS {
+    type T = stable_mir::ty::Layout;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::ty::Layout {
+            size: self.size.bytes_usize(),
+            align: self.align.abi.bytes(),
+            fields: self.fields.stable(tables),
+            variants: self.variants.stable(tables),
+            largest_niche: self.largest_niche.map(|niche| niche.stable(tables)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_target::abi::FieldsShape {
+    type T = stable_mir::ty::FieldsShape;
+
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use rustc_target::abi::FieldsShape;
+        match self {
+            FieldsShape::Primitive => stable_mir::ty::FieldsShape::Primitive,
+            FieldsShape::Union(count) => stable_mir::ty::FieldsShape::Union(count.get()),
+            FieldsShape::Array { stride, count } => {
+                stable_mir::ty::FieldsShape::Array { stride: stride.bytes_usize(), count: *count }
+            }
+            FieldsShape::Arbitrary { offsets, .. } => stable_mir::ty::FieldsShape::Arbitrary {
+                offsets: offsets.iter().map(|offset| offset.bytes_usize()).collect(),
+            },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_target::abi::Variants {
+    type T = stable_mir::ty::VariantsShape;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use rustc_target::abi::Variants;
+        match self {
+            Variants::Single { index } => {
+                stable_mir::ty::VariantsShape::Single { index: index.stable(tables) }
+            }
+            Variants::Multiple { tag, tag_encoding, tag_field, variants } => {
+                stable_mir::ty::VariantsShape::Multiple {
+                    tag: tag.stable(tables),
+                    tag_encoding: tag_encoding.stable(tables),
+                    tag_field: *tag_field,
+                    variants: variants.iter().map(|variant| variant.stable(tables)).collect(),
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_target::abi::TagEncoding {
+    type T = stable_mir::ty::TagEncoding;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use rustc_target::abi::TagEncoding;
+        match self {
+            TagEncoding::Direct => stable_mir::ty::TagEncoding::Direct,
+            TagEncoding::Niche { untagged_variant, niche_variants, niche_start } => {
+                stable_mir::ty::TagEncoding::Niche {
+                    untagged_variant: untagged_variant.stable(tables),
+                    niche_variants: niche_variants.start().stable(tables)
+                        ..=niche_variants.end().stable(tables),
+                    niche_start: *niche_start,
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_target::abi::Scalar {
+    type T = stable_mir::ty::Scalar;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use rustc_target::abi::Scalar;
+        match self {
+            Scalar::Initialized { value, valid_range } => stable_mir::ty::Scalar::Initialized {
+                value: value.stable(tables),
+                valid_range: valid_range.stable(tables),
+            },
+            Scalar::Union { value } => {
+                stable_mir::ty::Scalar::Union { value: value.stable(tables) }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_target::abi::Primitive {
+    type T = stable_mir::ty::Primitive;
+
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use rustc_target::abi::Primitive;
+        match *self {
+            Primitive::Int(integer, signed) => {
+                stable_mir::ty::Primitive::Int { size: integer.size().bytes_usize(), signed }
+            }
+            Primitive::F32 => stable_mir::ty::Primitive::F32,
+            Primitive::F64 => stable_mir::ty::Primitive::F64,
+            Primitive::Pointer(_) => stable_mir::ty::Primitive::Pointer,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_target::abi::WrappingRange {
+    type T = stable_mir::ty::WrappingRange;
+
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::ty::WrappingRange { start: self.start, end: self.end }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_target::abi::Niche {
+    type T = stable_mir::ty::Niche;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::ty::Niche {
+            offset: self.offset.bytes_usize(),
+            value: self.value.stable(tables),
+            valid_range: self.valid_range.stable(tables),
+        }
+    }
+}
+
 impl<'tcx> Stable<'tcx> for ty::trait_def::TraitSpecializationKind {
     type T = stable_mir::ty::TraitSpecializationKind;
     fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
@@ -1205,7 +1475,7 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
         use stable_mir::ty::TraitDecl;
 
         TraitDecl {
-            def_id: rustc_internal::trait_def(self.def_id),
+            def_id: tables.trait_def(self.def_id),
             unsafety: self.unsafety.stable(tables),
             paren_sugar: self.paren_sugar,
             has_auto_impl: self.has_auto_impl,
@@ -1254,7 +1524,7 @@ impl<'tcx> Stable<'tcx> for ty::TraitRef<'tcx> {
     fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
         use stable_mir::ty::TraitRef;
 
-        TraitRef { def_id: rustc_internal::trait_def(self.def_id), args: self.args.stable(tables) }
+        TraitRef { def_id: tables.trait_def(self.def_id), args: self.args.stable(tables) }
     }
 }
 
@@ -1493,9 +1763,8 @@ fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
 impl<'tcx> Stable<'tcx> for rustc_span::Span {
     type T = stable_mir::ty::Span;
 
-    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
-        // FIXME: add a real implementation of stable spans
-        opaque(self)
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        tables.create_span(*self, None)
     }
 }
 
@@ -1,3 +1,4 @@
 mod body;
+pub mod visit;
 
 pub use body::*;
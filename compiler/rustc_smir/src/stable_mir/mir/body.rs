@@ -1,23 +1,45 @@
 use crate::rustc_internal::Opaque;
 use crate::stable_mir::ty::{
-    AdtDef, ClosureDef, Const, GeneratorDef, GenericArgs, Movability, Region,
+    AdtDef, Allocation, ClosureDef, Const, ConstantKind, FnDef, GeneratorDef, GenericArgs,
+    Movability, ProvenanceMap, Region, RigidTy, TyKind,
 };
 use crate::stable_mir::{self, ty::Ty, Span};
+use serde::Serialize;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Body {
     pub blocks: Vec<BasicBlock>,
     pub locals: Vec<Ty>,
 }
 
-#[derive(Clone, Debug)]
+impl Body {
+    /// Adds a new local of type `ty` to this body and returns it.
+    pub fn add_local(&mut self, ty: Ty) -> Local {
+        self.locals.push(ty);
+        self.locals.len() - 1
+    }
+
+    /// Adds a new basic block to this body and returns its index.
+    pub fn add_block(&mut self, block: BasicBlock) -> usize {
+        self.blocks.push(block);
+        self.blocks.len() - 1
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub struct BasicBlock {
     pub statements: Vec<Statement>,
     pub terminator: Terminator,
 }
 
-#[derive(Clone, Debug)]
-pub enum Terminator {
+#[derive(Clone, Debug, Serialize)]
+pub struct Terminator {
+    pub kind: TerminatorKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub enum TerminatorKind {
     Goto {
         target: usize,
     },
@@ -60,7 +82,51 @@ pub enum Terminator {
     },
 }
 
-#[derive(Clone, Debug)]
+impl TerminatorKind {
+    /// Returns the blocks this terminator may continue with, including the block it unwinds to.
+    pub fn successors(&self) -> Vec<usize> {
+        use self::TerminatorKind::*;
+        let (mut targets, unwind) = match self {
+            Goto { target } => (vec![*target], None),
+            SwitchInt { targets, otherwise, .. } => {
+                let mut targets: Vec<_> = targets.iter().map(|target| target.target).collect();
+                targets.push(*otherwise);
+                (targets, None)
+            }
+            Drop { target, unwind, .. } | Assert { target, unwind, .. } => {
+                (vec![*target], Some(unwind))
+            }
+            Call { target, unwind, .. } => (target.iter().copied().collect(), Some(unwind)),
+            InlineAsm { destination, unwind, .. } => {
+                (destination.iter().copied().collect(), Some(unwind))
+            }
+            Resume | Abort | Return | Unreachable | GeneratorDrop => (vec![], None),
+        };
+        if let Some(UnwindAction::Cleanup(cleanup)) = unwind {
+            targets.push(*cleanup);
+        }
+        targets
+    }
+
+    /// Returns what happens if this terminator unwinds, if it can unwind.
+    pub fn unwind(&self) -> Option<&UnwindAction> {
+        match self {
+            TerminatorKind::Drop { unwind, .. }
+            | TerminatorKind::Call { unwind, .. }
+            | TerminatorKind::Assert { unwind, .. }
+            | TerminatorKind::InlineAsm { unwind, .. } => Some(unwind),
+            TerminatorKind::Goto { .. }
+            | TerminatorKind::SwitchInt { .. }
+            | TerminatorKind::Resume
+            | TerminatorKind::Abort
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::GeneratorDrop => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub struct InlineAsmOperand {
     pub in_value: Option<Operand>,
     pub out_place: Option<Place>,
@@ -69,7 +135,7 @@ pub struct InlineAsmOperand {
     pub raw_rpr: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum UnwindAction {
     Continue,
     Unreachable,
@@ -77,7 +143,7 @@ pub enum UnwindAction {
     Cleanup(usize),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum AssertMessage {
     BoundsCheck { len: Operand, index: Operand },
     Overflow(BinOp, Operand, Operand),
@@ -89,7 +155,7 @@ pub enum AssertMessage {
     MisalignedPointerDereference { required: Operand, found: Operand },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum BinOp {
     Add,
     AddUnchecked,
@@ -115,19 +181,19 @@ pub enum BinOp {
     Offset,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum UnOp {
     Not,
     Neg,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum GeneratorKind {
     Async(AsyncGeneratorKind),
     Gen,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum AsyncGeneratorKind {
     Block,
     Closure,
@@ -141,7 +207,7 @@ pub enum AsyncGeneratorKind {
 pub(crate) type Coverage = Opaque;
 
 /// The FakeReadCause describes the type of pattern why a FakeRead statement exists.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum FakeReadCause {
     ForMatchGuard,
     ForMatchedPlace(LocalDefId),
@@ -151,7 +217,7 @@ pub enum FakeReadCause {
 }
 
 /// Describes what kind of retag is to be performed
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum RetagKind {
     FnEntry,
     TwoPhase,
@@ -159,7 +225,7 @@ pub enum RetagKind {
     Default,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum Variance {
     Covariant,
     Invariant,
@@ -167,21 +233,27 @@ pub enum Variance {
     Bivariant,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct CopyNonOverlapping {
     pub src: Operand,
     pub dst: Operand,
     pub count: Operand,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum NonDivergingIntrinsic {
     Assume(Operand),
     CopyNonOverlapping(CopyNonOverlapping),
 }
 
-#[derive(Clone, Debug)]
-pub enum Statement {
+#[derive(Clone, Debug, Serialize)]
+pub struct Statement {
+    pub kind: StatementKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub enum StatementKind {
     Assign(Place, Rvalue),
     FakeRead(FakeReadCause, Place),
     SetDiscriminant { place: Place, variant_index: VariantIdx },
@@ -197,7 +269,7 @@ pub enum Statement {
     Nop,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum Rvalue {
     /// Creates a pointer with the indicated mutability to the place.
     ///
@@ -311,7 +383,7 @@ pub enum Rvalue {
     Use(Operand),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum AggregateKind {
     Array(Ty),
     Tuple,
@@ -320,20 +392,69 @@ pub enum AggregateKind {
     Generator(GeneratorDef, GenericArgs, Movability),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum Operand {
     Copy(Place),
     Move(Place),
     Constant(Constant),
 }
 
-#[derive(Clone, Debug)]
+impl Operand {
+    /// Creates an operand that refers to the function `def` instantiated with `args`, e.g. to be
+    /// used as the callee of a [`TerminatorKind::Call`].
+    pub fn function(def: FnDef, args: GenericArgs, span: Span) -> Operand {
+        // Function items are zero-sized, so their value is an empty allocation.
+        let literal = ConstantKind::Allocated(Allocation {
+            bytes: Vec::new(),
+            provenance: ProvenanceMap { ptrs: Vec::new() },
+            align: 1,
+            mutability: Mutability::Not,
+        });
+        let ty = Ty::from(TyKind::RigidTy(RigidTy::FnDef(def, args)));
+        Operand::Constant(Constant { span, user_ty: None, literal: Const { literal, ty } })
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub struct Place {
     pub local: Local,
-    pub projection: String,
+    pub projection: Vec<ProjectionElem>,
+}
+
+impl From<Local> for Place {
+    fn from(local: Local) -> Self {
+        Place { local, projection: Vec::new() }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub enum ProjectionElem {
+    /// Dereference the place.
+    Deref,
+
+    /// A field of a struct, tuple, closure or generator, or of an enum variant selected with
+    /// `Downcast`, with the type of the field.
+    Field(FieldIdx, Ty),
+
+    /// Index into a slice or an array, where the index is the value of the given local.
+    Index(Local),
+
+    /// Index into a slice or an array with a constant index, counting from the end of the slice
+    /// if `from_end` is true. Used for slice patterns, where `min_length` is the minimum length of
+    /// the slice required for the pattern to match.
+    ConstantIndex { offset: u64, min_length: u64, from_end: bool },
+
+    /// The subslice `[from..to]`, or `[from..len - to]` if `from_end` is true.
+    Subslice { from: u64, to: u64, from_end: bool },
+
+    /// Treat the enum as the given variant, so that the fields of the variant can be projected.
+    Downcast(VariantIdx),
+
+    /// Cast an opaque type to its hidden type.
+    OpaqueCast(Ty),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct UserTypeProjection {
     pub base: UserTypeAnnotationIndex,
     pub projection: String,
@@ -348,20 +469,20 @@ pub struct UserTypeProjection {
 
 type UserTypeAnnotationIndex = usize;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Constant {
     pub span: Span,
     pub user_ty: Option<UserTypeAnnotationIndex>,
     pub literal: Const,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct SwitchTarget {
     pub value: u128,
     pub target: usize,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum BorrowKind {
     /// Data must be immutable and is aliasable.
     Shared,
@@ -378,26 +499,26 @@ pub enum BorrowKind {
     },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum MutBorrowKind {
     Default,
     TwoPhaseBorrow,
     ClosureCapture,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum Mutability {
     Not,
     Mut,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize)]
 pub enum Safety {
     Unsafe,
     Normal,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum PointerCoercion {
     /// Go from a fn-item type to a fn-pointer type.
     ReifyFnPointer,
@@ -424,7 +545,7 @@ pub enum PointerCoercion {
     Unsize,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum CastKind {
     PointerExposeAddress,
     PointerFromExposedAddress,
@@ -439,7 +560,7 @@ pub enum CastKind {
     Transmute,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum NullOp {
     /// Returns the size of a value of that type.
     SizeOf,
@@ -447,4 +568,6 @@ pub enum NullOp {
     AlignOf,
     /// Returns the offset of a field.
     OffsetOf(Vec<FieldIdx>),
+    /// Returns whether runtime checks for Undefined Behavior should be performed.
+    UbChecks,
 }
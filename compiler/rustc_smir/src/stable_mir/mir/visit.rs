@@ -0,0 +1,213 @@
+//! A visitor that rewrites the MIR of a body in place.
+//!
+//! Implementors override the `visit_*` methods for the parts of the body they are interested in,
+//! and call the corresponding `super_*` method to keep walking into them. Types and constants are
+//! only visited as a whole; use [`Folder`](crate::stable_mir::fold::Folder) to rewrite their
+//! components.
+//!
+//! Rewritten bodies of local items can be handed back to the compiler with
+//! [`instrument`](crate::rustc_internal::instrument).
+
+use crate::stable_mir::ty::Ty;
+
+use super::{
+    AggregateKind, AssertMessage, BasicBlock, Body, Constant, CopyNonOverlapping, InlineAsmOperand,
+    Local, NonDivergingIntrinsic, Operand, Place, ProjectionElem, Rvalue, Statement, StatementKind,
+    Terminator, TerminatorKind,
+};
+
+/// A position in a body: the statement with index `statement_index` in block `block`, or the
+/// terminator of that block if `statement_index` is the number of statements in the block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub block: usize,
+    pub statement_index: usize,
+}
+
+pub trait MutVisitor {
+    fn visit_body(&mut self, body: &mut Body) {
+        self.super_body(body)
+    }
+
+    fn visit_basic_block(&mut self, block: &mut BasicBlock, index: usize) {
+        self.super_basic_block(block, index)
+    }
+
+    fn visit_statement(&mut self, statement: &mut Statement, location: Location) {
+        self.super_statement(statement, location)
+    }
+
+    fn visit_terminator(&mut self, terminator: &mut Terminator, location: Location) {
+        self.super_terminator(terminator, location)
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mut Rvalue, location: Location) {
+        self.super_rvalue(rvalue, location)
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand, location: Location) {
+        self.super_operand(operand, location)
+    }
+
+    fn visit_place(&mut self, place: &mut Place, location: Location) {
+        self.super_place(place, location)
+    }
+
+    fn visit_local(&mut self, _local: &mut Local, _location: Location) {}
+
+    fn visit_constant(&mut self, _constant: &mut Constant, _location: Location) {}
+
+    /// Visits the types of locals, casts, projections and aggregates. `location` is `None` for
+    /// the types of locals.
+    fn visit_ty(&mut self, _ty: &mut Ty, _location: Option<Location>) {}
+
+    fn super_body(&mut self, body: &mut Body) {
+        for (index, block) in body.blocks.iter_mut().enumerate() {
+            self.visit_basic_block(block, index);
+        }
+        for ty in &mut body.locals {
+            self.visit_ty(ty, None);
+        }
+    }
+
+    fn super_basic_block(&mut self, block: &mut BasicBlock, index: usize) {
+        for (statement_index, statement) in block.statements.iter_mut().enumerate() {
+            self.visit_statement(statement, Location { block: index, statement_index });
+        }
+        let location = Location { block: index, statement_index: block.statements.len() };
+        self.visit_terminator(&mut block.terminator, location);
+    }
+
+    fn super_statement(&mut self, statement: &mut Statement, location: Location) {
+        match &mut statement.kind {
+            StatementKind::Assign(place, rvalue) => {
+                self.visit_place(place, location);
+                self.visit_rvalue(rvalue, location);
+            }
+            StatementKind::FakeRead(_, place)
+            | StatementKind::SetDiscriminant { place, .. }
+            | StatementKind::Deinit(place)
+            | StatementKind::Retag(_, place)
+            | StatementKind::PlaceMention(place)
+            | StatementKind::AscribeUserType { place, .. } => self.visit_place(place, location),
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                self.visit_local(local, location)
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::Assume(operand)) => {
+                self.visit_operand(operand, location)
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::CopyNonOverlapping(
+                CopyNonOverlapping { src, dst, count },
+            )) => {
+                self.visit_operand(src, location);
+                self.visit_operand(dst, location);
+                self.visit_operand(count, location);
+            }
+            StatementKind::Coverage(_) | StatementKind::ConstEvalCounter | StatementKind::Nop => {}
+        }
+    }
+
+    fn super_terminator(&mut self, terminator: &mut Terminator, location: Location) {
+        match &mut terminator.kind {
+            TerminatorKind::SwitchInt { discr, .. } => self.visit_operand(discr, location),
+            TerminatorKind::Drop { place, .. } => self.visit_place(place, location),
+            TerminatorKind::Call { func, args, destination, .. } => {
+                self.visit_operand(func, location);
+                for arg in args {
+                    self.visit_operand(arg, location);
+                }
+                self.visit_place(destination, location);
+            }
+            TerminatorKind::Assert { cond, msg, .. } => {
+                self.visit_operand(cond, location);
+                match msg {
+                    AssertMessage::BoundsCheck { len: first, index: second }
+                    | AssertMessage::Overflow(_, first, second)
+                    | AssertMessage::MisalignedPointerDereference {
+                        required: first,
+                        found: second,
+                    } => {
+                        self.visit_operand(first, location);
+                        self.visit_operand(second, location);
+                    }
+                    AssertMessage::OverflowNeg(operand)
+                    | AssertMessage::DivisionByZero(operand)
+                    | AssertMessage::RemainderByZero(operand) => {
+                        self.visit_operand(operand, location)
+                    }
+                    AssertMessage::ResumedAfterReturn(_) | AssertMessage::ResumedAfterPanic(_) => {}
+                }
+            }
+            TerminatorKind::InlineAsm { operands, .. } => {
+                for InlineAsmOperand { in_value, out_place, .. } in operands {
+                    if let Some(in_value) = in_value {
+                        self.visit_operand(in_value, location);
+                    }
+                    if let Some(out_place) = out_place {
+                        self.visit_place(out_place, location);
+                    }
+                }
+            }
+            TerminatorKind::Goto { .. }
+            | TerminatorKind::Resume
+            | TerminatorKind::Abort
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::GeneratorDrop => {}
+        }
+    }
+
+    fn super_rvalue(&mut self, rvalue: &mut Rvalue, location: Location) {
+        match rvalue {
+            Rvalue::AddressOf(_, place)
+            | Rvalue::CopyForDeref(place)
+            | Rvalue::Discriminant(place)
+            | Rvalue::Len(place)
+            | Rvalue::Ref(_, _, place) => self.visit_place(place, location),
+            Rvalue::Aggregate(kind, operands) => {
+                if let AggregateKind::Array(ty) = kind {
+                    self.visit_ty(ty, Some(location));
+                }
+                for operand in operands {
+                    self.visit_operand(operand, location);
+                }
+            }
+            Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+                self.visit_operand(lhs, location);
+                self.visit_operand(rhs, location);
+            }
+            Rvalue::Cast(_, operand, ty) | Rvalue::ShallowInitBox(operand, ty) => {
+                self.visit_operand(operand, location);
+                self.visit_ty(ty, Some(location));
+            }
+            Rvalue::Repeat(operand, _) | Rvalue::UnaryOp(_, operand) | Rvalue::Use(operand) => {
+                self.visit_operand(operand, location)
+            }
+            Rvalue::NullaryOp(_, ty) => self.visit_ty(ty, Some(location)),
+            Rvalue::ThreadLocalRef(_) => {}
+        }
+    }
+
+    fn super_operand(&mut self, operand: &mut Operand, location: Location) {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => self.visit_place(place, location),
+            Operand::Constant(constant) => self.visit_constant(constant, location),
+        }
+    }
+
+    fn super_place(&mut self, place: &mut Place, location: Location) {
+        self.visit_local(&mut place.local, location);
+        for elem in &mut place.projection {
+            match elem {
+                ProjectionElem::Field(_, ty) | ProjectionElem::OpaqueCast(ty) => {
+                    self.visit_ty(ty, Some(location))
+                }
+                ProjectionElem::Index(local) => self.visit_local(local, location),
+                ProjectionElem::Deref
+                | ProjectionElem::ConstantIndex { .. }
+                | ProjectionElem::Subslice { .. }
+                | ProjectionElem::Downcast(_) => {}
+            }
+        }
+    }
+}
@@ -15,9 +15,13 @@
 use std::fmt;
 use std::fmt::Debug;
 
+use serde::Serialize;
+
 use self::ty::{
-    GenericPredicates, Generics, ImplDef, ImplTrait, Span, TraitDecl, TraitDef, Ty, TyKind,
+    Allocation, ConstDef, FnDef, GenericPredicates, Generics, GlobalAlloc, ImplDef, ImplTrait,
+    Layout, Span, StaticDef, TraitDecl, TraitDef, Ty, TyKind,
 };
+use crate::rustc_internal::Opaque;
 use crate::rustc_smir::Tables;
 
 pub mod fold;
@@ -32,7 +36,7 @@
 pub type CrateNum = usize;
 
 /// A unique identification number for each item accessible for the current compilation unit.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct DefId(pub(crate) usize);
 
 impl Debug for DefId {
@@ -45,7 +49,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 }
 
 /// A unique identification number for each provenance
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct AllocId(pub(crate) usize);
 
 /// A list of crate items.
@@ -71,8 +75,12 @@ pub enum CompilerError<T> {
     Skipped,
 }
 
+/// An error reported by the compiler while computing the information requested through stable
+/// MIR, e.g. when the layout of a type is not known.
+pub type Error = Opaque;
+
 /// Holds information about a crate.
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
 pub struct Crate {
     pub(crate) id: CrateNum,
     pub name: Symbol,
@@ -82,13 +90,29 @@ pub struct Crate {
 /// Holds information about an item in the crate.
 /// For now, it only stores the item DefId. Use functions inside `rustc_internal` module to
 /// use this item.
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
 pub struct CrateItem(pub(crate) DefId);
 
 impl CrateItem {
     pub fn body(&self) -> mir::Body {
         with(|cx| cx.mir_body(self.0))
     }
+
+    /// Returns the path of this item.
+    pub fn name(&self) -> Symbol {
+        with(|cx| cx.name_of_def_id(self.0))
+    }
+
+    /// Returns the static defined by this item, if it is a `static` item.
+    pub fn as_static(&self) -> Option<StaticDef> {
+        with(|cx| cx.as_static(self.0))
+    }
+
+    /// Returns the function defined by this item, if it is a function or an associated function.
+    /// This can be used to call the function from a rewritten body.
+    pub fn as_fn_def(&self) -> Option<FnDef> {
+        with(|cx| cx.as_fn_def(self.0))
+    }
 }
 
 /// Return the function where execution starts if the current
@@ -156,12 +180,33 @@ pub trait Context {
     /// Prints the name of given `DefId`
     fn name_of_def_id(&self, def_id: DefId) -> String;
 
+    /// Prints the location of the given `Span`.
+    fn span_to_string(&self, span: Span) -> String;
+
     /// Obtain the representation of a type.
     fn ty_kind(&mut self, ty: Ty) -> TyKind;
 
     /// Create a new `Ty` from scratch without information from rustc.
     fn mk_ty(&mut self, kind: TyKind) -> Ty;
 
+    /// Compute the layout of a type for the current target.
+    fn ty_layout(&mut self, ty: Ty) -> Result<Layout, Error>;
+
+    /// Retrieve the static defined by an item, if any.
+    fn as_static(&mut self, item: DefId) -> Option<StaticDef>;
+
+    /// Retrieve the function defined by an item, if any.
+    fn as_fn_def(&mut self, item: DefId) -> Option<FnDef>;
+
+    /// Evaluate the initializer of a constant.
+    fn eval_const_initializer(&mut self, def: ConstDef) -> Result<Allocation, Error>;
+
+    /// Evaluate the initializer of a static.
+    fn eval_static_initializer(&mut self, def: StaticDef) -> Result<Allocation, Error>;
+
+    /// Retrieve the allocation an `AllocId` refers to.
+    fn global_alloc(&mut self, alloc_id: AllocId) -> GlobalAlloc;
+
     /// HACK: Until we have fully stable consumers, we need an escape hatch
     /// to get `DefId`s out of `CrateItem`s.
     fn rustc_tables(&mut self, f: &mut dyn FnMut(&mut Tables<'_>));
@@ -171,15 +216,13 @@ pub trait Context {
 // datastructures and stable MIR datastructures
 scoped_thread_local! (static TLV: Cell<*mut ()>);
 
-pub fn run(mut context: impl Context, f: impl FnOnce()) {
+/// Makes `context` available to the stable MIR APIs while `f` runs.
+pub fn run(mut context: &mut dyn Context, f: impl FnOnce()) {
     assert!(!TLV.is_set());
-    fn g<'a>(mut context: &mut (dyn Context + 'a), f: impl FnOnce()) {
-        let ptr: *mut () = &mut context as *mut &mut _ as _;
-        TLV.set(&Cell::new(ptr), || {
-            f();
-        });
-    }
-    g(&mut context, f);
+    let ptr: *mut () = &mut context as *mut &mut _ as _;
+    TLV.set(&Cell::new(ptr), || {
+        f();
+    });
 }
 
 /// Loads the current context and calls a function with it.
@@ -1,12 +1,14 @@
 use super::{
     mir::Safety,
-    mir::{Body, Mutability},
-    with, AllocId, DefId,
+    mir::{Body, Mutability, VariantIdx},
+    with, AllocId, DefId, Error,
 };
 use crate::rustc_internal::Opaque;
+use serde::Serialize;
 use std::fmt::{self, Debug, Formatter};
+use std::ops::RangeInclusive;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize)]
 pub struct Ty(pub usize);
 
 impl Debug for Ty {
@@ -19,6 +21,12 @@ impl Ty {
     pub fn kind(&self) -> TyKind {
         with(|context| context.ty_kind(*self))
     }
+
+    /// Computes the layout of this type for the current target. This fails if the type is
+    /// generic, or if its layout cannot be computed, e.g. because it is too big.
+    pub fn layout(&self) -> Result<Layout, Error> {
+        with(|context| context.ty_layout(*self))
+    }
 }
 
 impl From<TyKind> for Ty {
@@ -27,7 +35,7 @@ fn from(value: TyKind) -> Self {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Const {
     pub literal: ConstantKind,
     pub ty: Ty,
@@ -35,9 +43,22 @@ pub struct Const {
 
 type Ident = Opaque;
 pub(crate) type Region = Opaque;
-pub(crate) type Span = Opaque;
 
-#[derive(Clone, Debug)]
+/// A location in the source code. Spans can be printed, and spans of MIR statements are mapped
+/// back to their original location when a rewritten body is handed back to the compiler.
+#[derive(Clone, Copy, Serialize)]
+pub struct Span(pub(crate) usize);
+
+impl Debug for Span {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Span")
+            .field("id", &self.0)
+            .field("repr", &with(|cx| cx.span_to_string(*self)))
+            .finish()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub enum TyKind {
     RigidTy(RigidTy),
     Alias(AliasKind, AliasTy),
@@ -45,7 +66,7 @@ pub enum TyKind {
     Bound(usize, BoundTy),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum RigidTy {
     Bool,
     Char,
@@ -68,7 +89,7 @@ pub enum RigidTy {
     Tuple(Vec<Ty>),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum IntTy {
     Isize,
     I8,
@@ -78,7 +99,7 @@ pub enum IntTy {
     I128,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum UintTy {
     Usize,
     U8,
@@ -88,22 +109,22 @@ pub enum UintTy {
     U128,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum FloatTy {
     F32,
     F64,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum Movability {
     Static,
     Movable,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct ForeignDef(pub(crate) DefId);
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct FnDef(pub(crate) DefId);
 
 impl FnDef {
@@ -112,37 +133,56 @@ pub fn body(&self) -> Body {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct ClosureDef(pub(crate) DefId);
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct GeneratorDef(pub(crate) DefId);
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct ParamDef(pub(crate) DefId);
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct BrNamedDef(pub(crate) DefId);
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct AdtDef(pub(crate) DefId);
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct AliasDef(pub(crate) DefId);
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct TraitDef(pub(crate) DefId);
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct GenericDef(pub(crate) DefId);
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct ConstDef(pub(crate) DefId);
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+impl ConstDef {
+    /// Evaluates the initializer of this constant. This fails for constants whose value depends
+    /// on generic parameters.
+    pub fn eval_initializer(&self) -> Result<Allocation, Error> {
+        with(|context| context.eval_const_initializer(*self))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+pub struct StaticDef(pub(crate) DefId);
+
+impl StaticDef {
+    /// Evaluates the initializer of this static, i.e. the contents of its memory before the
+    /// program starts.
+    pub fn eval_initializer(&self) -> Result<Allocation, Error> {
+        with(|context| context.eval_static_initializer(*self))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
 pub struct ImplDef(pub(crate) DefId);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct GenericArgs(pub Vec<GenericArgKind>);
 
 impl std::ops::Index<ParamTy> for GenericArgs {
@@ -161,7 +201,7 @@ fn index(&self, index: ParamConst) -> &Self::Output {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum GenericArgKind {
     Lifetime(Region),
     Type(Ty),
@@ -190,13 +230,13 @@ pub fn expect_const(&self) -> &Const {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum TermKind {
     Type(Ty),
     Const(Const),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum AliasKind {
     Projection,
     Inherent,
@@ -204,7 +244,7 @@ pub enum AliasKind {
     Weak,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct AliasTy {
     pub def_id: AliasDef,
     pub args: GenericArgs,
@@ -212,7 +252,7 @@ pub struct AliasTy {
 
 pub type PolyFnSig = Binder<FnSig>;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct FnSig {
     pub inputs_and_output: Vec<Ty>,
     pub c_variadic: bool,
@@ -220,7 +260,7 @@ pub struct FnSig {
     pub abi: Abi,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
 pub enum Abi {
     Rust,
     C { unwind: bool },
@@ -251,70 +291,70 @@ pub enum Abi {
     RiscvInterruptS,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Binder<T> {
     pub value: T,
     pub bound_vars: Vec<BoundVariableKind>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct EarlyBinder<T> {
     pub value: T,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum BoundVariableKind {
     Ty(BoundTyKind),
     Region(BoundRegionKind),
     Const,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
 pub enum BoundTyKind {
     Anon,
     Param(ParamDef, String),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum BoundRegionKind {
     BrAnon(Option<Span>),
     BrNamed(BrNamedDef, String),
     BrEnv,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum DynKind {
     Dyn,
     DynStar,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum ExistentialPredicate {
     Trait(ExistentialTraitRef),
     Projection(ExistentialProjection),
     AutoTrait(TraitDef),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ExistentialTraitRef {
     pub def_id: TraitDef,
     pub generic_args: GenericArgs,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ExistentialProjection {
     pub def_id: TraitDef,
     pub generic_args: GenericArgs,
     pub term: TermKind,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ParamTy {
     pub index: u32,
     pub name: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct BoundTy {
     pub var: usize,
     pub kind: BoundTyKind,
@@ -323,21 +363,29 @@ pub struct BoundTy {
 pub type Bytes = Vec<Option<u8>>;
 pub type Size = usize;
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
 pub struct Prov(pub(crate) AllocId);
+
+impl Prov {
+    /// Returns the allocation that pointers with this provenance point into.
+    pub fn global_alloc(&self) -> GlobalAlloc {
+        with(|context| context.global_alloc(self.0))
+    }
+}
+
 pub type Align = u64;
 pub type Promoted = u32;
 pub type InitMaskMaterialized = Vec<u64>;
 
 /// Stores the provenance information of pointers stored in memory.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ProvenanceMap {
     /// Provenance in this map applies from the given offset for an entire pointer-size worth of
     /// bytes. Two entries in this map are always at least a pointer size apart.
     pub ptrs: Vec<(Size, Prov)>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Allocation {
     pub bytes: Bytes,
     pub provenance: ProvenanceMap,
@@ -345,34 +393,48 @@ pub struct Allocation {
     pub mutability: Mutability,
 }
 
-#[derive(Clone, Debug)]
+/// The allocations that pointers can point into.
+#[derive(Clone, Debug, Serialize)]
+pub enum GlobalAlloc {
+    /// A function, for function pointers. Shims, such as drop glue, refer to the function they
+    /// were generated for.
+    Function(FnDef, GenericArgs),
+    /// The vtable of a type, for a trait object of the given trait or of auto traits only.
+    VTable(Ty, Option<Binder<ExistentialTraitRef>>),
+    /// A static, whose contents can be obtained with [`StaticDef::eval_initializer`].
+    Static(StaticDef),
+    /// Any other memory, e.g. a promoted constant or a string literal.
+    Memory(Allocation),
+}
+
+#[derive(Clone, Debug, Serialize)]
 pub enum ConstantKind {
     Allocated(Allocation),
     Unevaluated(UnevaluatedConst),
     Param(ParamConst),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ParamConst {
     pub index: u32,
     pub name: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct UnevaluatedConst {
     pub def: ConstDef,
     pub args: GenericArgs,
     pub promoted: Option<Promoted>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum TraitSpecializationKind {
     None,
     Marker,
     AlwaysApplicable,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct TraitDecl {
     pub def_id: TraitDef,
     pub unsafety: Safety,
@@ -399,13 +461,13 @@ pub fn predicates_of(&self) -> GenericPredicates {
 
 pub type ImplTrait = EarlyBinder<TraitRef>;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct TraitRef {
     pub def_id: TraitDef,
     pub args: GenericArgs,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Generics {
     pub parent: Option<GenericDef>,
     pub parent_count: usize,
@@ -416,14 +478,14 @@ pub struct Generics {
     pub host_effect_index: Option<usize>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum GenericParamDefKind {
     Lifetime,
     Type { has_default: bool, synthetic: bool },
     Const { has_default: bool },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct GenericParamDef {
     pub name: super::Symbol,
     pub def_id: GenericDef,
@@ -437,7 +499,7 @@ pub struct GenericPredicates {
     pub predicates: Vec<(PredicateKind, Span)>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum PredicateKind {
     Clause(ClauseKind),
     ObjectSafe(TraitDef),
@@ -449,7 +511,7 @@ pub enum PredicateKind {
     AliasRelate(TermKind, TermKind, AliasRelationDirection),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum ClauseKind {
     Trait(TraitPredicate),
     RegionOutlives(RegionOutlivesPredicate),
@@ -460,52 +522,135 @@ pub enum ClauseKind {
     ConstEvaluatable(Const),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum ClosureKind {
     Fn,
     FnMut,
     FnOnce,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct SubtypePredicate {
     pub a: Ty,
     pub b: Ty,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct CoercePredicate {
     pub a: Ty,
     pub b: Ty,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum AliasRelationDirection {
     Equate,
     Subtype,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct TraitPredicate {
     pub trait_ref: TraitRef,
     pub polarity: ImplPolarity,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct OutlivesPredicate<A, B>(pub A, pub B);
 
 pub type RegionOutlivesPredicate = OutlivesPredicate<Region, Region>;
 pub type TypeOutlivesPredicate = OutlivesPredicate<Ty, Region>;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct ProjectionPredicate {
     pub projection_ty: AliasTy,
     pub term: TermKind,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub enum ImplPolarity {
     Positive,
     Negative,
     Reservation,
 }
+
+/// The memory layout of a type for the current target. Sizes and offsets are in bytes.
+#[derive(Clone, Debug, Serialize)]
+pub struct Layout {
+    pub size: Size,
+    pub align: Align,
+    /// Where the fields are located within the layout.
+    pub fields: FieldsShape,
+    /// The layouts of the variants, for enums and generators with more than one variant.
+    pub variants: VariantsShape,
+    /// The leaf scalar with the largest number of invalid values, if there is one. Enclosing
+    /// types can use those values to encode their discriminant.
+    pub largest_niche: Option<Niche>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub enum FieldsShape {
+    /// Scalars and `!`, which have no fields.
+    Primitive,
+    /// All the fields start at offset zero. The `usize` is the number of fields.
+    Union(usize),
+    /// Arrays and SIMD vectors, whose fields all have the same type.
+    Array { stride: Size, count: u64 },
+    /// Structs, tuples and the variants of enums, with the offset of each field in source order.
+    Arbitrary { offsets: Vec<Size> },
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub enum VariantsShape {
+    /// Structs, tuples, unions, enums with a single variant and all non-ADT types.
+    Single { index: VariantIdx },
+    /// Enums and generators with more than one variant. The discriminant is stored in the field
+    /// `tag_field`, as described by `tag_encoding`.
+    Multiple { tag: Scalar, tag_encoding: TagEncoding, tag_field: usize, variants: Vec<Layout> },
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub enum TagEncoding {
+    /// The tag directly stores the discriminant.
+    Direct,
+    /// The tag is a niche of the untagged variant. Variant `v` in `niche_variants` is encoded as
+    /// `(v - niche_variants.start()).wrapping_add(niche_start)`, and any other value means
+    /// `untagged_variant`.
+    Niche {
+        untagged_variant: VariantIdx,
+        niche_variants: RangeInclusive<VariantIdx>,
+        niche_start: u128,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum Scalar {
+    Initialized {
+        value: Primitive,
+        valid_range: WrappingRange,
+    },
+    /// A scalar in a union, which may hold any value, including uninitialized memory.
+    Union {
+        value: Primitive,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum Primitive {
+    Int { size: Size, signed: bool },
+    F32,
+    F64,
+    Pointer,
+}
+
+/// An inclusive range of valid values, which wraps around if `start > end`.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct WrappingRange {
+    pub start: u128,
+    pub end: u128,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Niche {
+    pub offset: Size,
+    pub value: Primitive,
+    pub valid_range: WrappingRange,
+}
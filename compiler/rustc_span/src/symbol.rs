@@ -1605,6 +1605,7 @@
         u32,
         u64,
         u8,
+        ub_checks,
         unaligned_volatile_load,
         unaligned_volatile_store,
         unboxed_closures,
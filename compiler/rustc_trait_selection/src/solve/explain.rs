@@ -0,0 +1,269 @@
+//! Explanations of why a goal does not hold, for `-Z explain-trait-errors`.
+//!
+//! Proof trees of the new solver record every candidate and nested goal that was tried, which
+//! makes them too verbose to show to users. This module walks the proof tree of a failed goal and
+//! only keeps the nested goals that failed, together with the candidate that required them.
+use rustc_middle::traits::query::NoSolution;
+use rustc_middle::traits::solve::inspect::{
+    CacheHit, CandidateKind, GoalCandidate, GoalEvaluation, GoalEvaluationKind,
+};
+use rustc_middle::traits::solve::Certainty;
+use rustc_middle::ty::fold::FnMutDelegate;
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::Symbol;
+use std::fmt::{self, Write};
+
+/// Nested goals deeper than this are not explained, to keep explanations concise.
+const MAX_DEPTH: usize = 8;
+
+/// Why a goal does not hold.
+#[derive(Debug)]
+pub struct Explanation<'tcx> {
+    /// The goal. Variables the solver did not infer are printed as `_`.
+    pub predicate: ty::Predicate<'tcx>,
+    /// Whether the solver could not decide whether the goal holds, rather than proving that it
+    /// does not hold.
+    pub ambiguous: bool,
+    /// The candidate of the parent goal that required this goal.
+    pub via: Option<String>,
+    /// The field of the self type of the parent goal whose type this goal is about.
+    pub field: Option<Symbol>,
+    /// The nested goals that do not hold.
+    pub causes: Vec<Explanation<'tcx>>,
+    /// Why the goal does not hold, if no candidate got as far as failing nested goals.
+    pub reason: Option<&'static str>,
+}
+
+/// Explains why the root goal of `tree` does not hold. Returns `None` if it holds.
+pub fn explain_goal_evaluation<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    tree: &GoalEvaluation<'tcx>,
+) -> Option<Explanation<'tcx>> {
+    explain(tcx, tree, 0)
+}
+
+fn explain<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    goal: &GoalEvaluation<'tcx>,
+    depth: usize,
+) -> Option<Explanation<'tcx>> {
+    let ambiguous = match goal.result {
+        Err(NoSolution) => false,
+        Ok(response) => match response.value.certainty {
+            Certainty::Yes => return None,
+            Certainty::Maybe(_) => true,
+        },
+    };
+    // The canonical goal has all the inference variables that were already constrained
+    // replaced with their values, which is not the case for `uncanonicalized_goal`.
+    let predicate = tcx.replace_escaping_bound_vars_uncached(
+        goal.canonicalized_goal.value.goal.predicate,
+        FnMutDelegate {
+            regions: &mut |_| tcx.lifetimes.re_erased,
+            types: &mut |_| Ty::new_var(tcx, ty::TyVid::from_u32(0)),
+            consts: &mut |_, ty| ty::Const::new_infer(tcx, ty::InferConst::Fresh(0), ty),
+        },
+    );
+    let mut explanation =
+        Explanation { predicate, ambiguous, via: None, field: None, causes: vec![], reason: None };
+
+    match &goal.kind {
+        GoalEvaluationKind::CacheHit(CacheHit::Provisional) => {
+            explanation.reason = Some("it depends on itself");
+        }
+        GoalEvaluationKind::CacheHit(CacheHit::Global) => {}
+        GoalEvaluationKind::Uncached { revisions } => {
+            // Only the last revision is relevant for the final result.
+            if let Some(step) = revisions.last()
+                && depth < MAX_DEPTH
+            {
+                let mut nested = vec![];
+                for added_goals in &step.nested_goal_evaluations {
+                    collect_nested_goals(added_goals.evaluations.as_slice(), &mut nested);
+                }
+                explanation.add_causes(tcx, None, &nested, depth, false);
+                for candidate in &step.candidates {
+                    explanation.explain_candidate(tcx, candidate, depth);
+                }
+            }
+        }
+    }
+
+    if explanation.causes.is_empty() && explanation.reason.is_none() {
+        explanation.reason = Some(leaf_reason(tcx, predicate, ambiguous));
+    }
+    Some(explanation)
+}
+
+/// Collects the goals that were evaluated while evaluating added goals. Goals are evaluated
+/// repeatedly until they hold or fail, so apart from failed goals, only the goals of the last
+/// iteration are interesting.
+fn collect_nested_goals<'a, 'tcx>(
+    iterations: &'a [Vec<GoalEvaluation<'tcx>>],
+    nested: &mut Vec<&'a GoalEvaluation<'tcx>>,
+) {
+    for (i, iteration) in iterations.iter().enumerate() {
+        let is_last = i + 1 == iterations.len();
+        nested.extend(iteration.iter().filter(|goal| is_last || goal.result.is_err()));
+    }
+}
+
+impl<'tcx> Explanation<'tcx> {
+    fn explain_candidate(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        candidate: &GoalCandidate<'tcx>,
+        depth: usize,
+    ) {
+        match &candidate.kind {
+            CandidateKind::Candidate { name, result } => {
+                if let Ok(response) = result
+                    && response.value.certainty == Certainty::Yes
+                {
+                    return;
+                }
+                let mut nested = vec![];
+                collect_candidate_goals(candidate, &mut nested);
+                let via = self.describe_candidate(tcx, name);
+                // Only the nested goals of structural candidates are about fields.
+                let structural = name == "constituent tys";
+                self.add_causes(tcx, Some(via), &nested, depth, structural);
+            }
+            // These probes are not candidates themselves, but contain the actual candidates.
+            CandidateKind::NormalizedSelfTyAssembly
+            | CandidateKind::UnsizeAssembly
+            | CandidateKind::UpcastProbe => {
+                for candidate in &candidate.candidates {
+                    self.explain_candidate(tcx, candidate, depth);
+                }
+            }
+        }
+    }
+
+    fn add_causes(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        via: Option<String>,
+        nested: &[&GoalEvaluation<'tcx>],
+        depth: usize,
+        structural: bool,
+    ) {
+        for goal in nested {
+            if let Some(mut cause) = explain(tcx, goal, depth + 1) {
+                if structural {
+                    cause.field = self.field_of(tcx, cause.predicate);
+                }
+                cause.via = via.clone();
+                self.causes.push(cause);
+            }
+        }
+    }
+
+    /// Describes the candidate `name` of this goal, as recorded by `probe_candidate`.
+    fn describe_candidate(&self, tcx: TyCtxt<'tcx>, name: &str) -> String {
+        match name {
+            "impl" => "an impl".to_string(),
+            "assumption" => "a where-clause".to_string(),
+            "constituent tys" => match self.predicate.to_opt_poly_trait_pred() {
+                Some(pred) if tcx.trait_is_auto(pred.def_id()) => "the auto-trait impl".to_string(),
+                _ => "the builtin impl".to_string(),
+            },
+            _ => format!("the {name} candidate"),
+        }
+    }
+
+    /// Returns the name of the field of the self type of this goal that has the self type of
+    /// `nested` as its type, if any.
+    fn field_of(&self, tcx: TyCtxt<'tcx>, nested: ty::Predicate<'tcx>) -> Option<Symbol> {
+        let self_ty = self.predicate.to_opt_poly_trait_pred()?.skip_binder().self_ty();
+        let nested_self_ty = nested.to_opt_poly_trait_pred()?.skip_binder().self_ty();
+        let ty::Adt(def, args) = *self_ty.kind() else { return None };
+        def.all_fields().find(|field| field.ty(tcx, args) == nested_self_ty).map(|field| field.name)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "goal": self.predicate.to_string(),
+            "ambiguous": self.ambiguous,
+            "via": self.via,
+            "field": self.field.map(|field| field.to_string()),
+            "reason": self.reason,
+            "causes": self.causes.iter().map(Explanation::to_json).collect::<Vec<_>>(),
+        })
+    }
+
+    fn write_indented(&self, f: &mut dyn Write, indent: usize) -> fmt::Result {
+        match (self.field, self.predicate.to_opt_poly_trait_pred()) {
+            (Some(field), Some(pred)) => {
+                let pred = pred.skip_binder();
+                let is = if self.ambiguous { "may not be" } else { "is not" };
+                write!(
+                    f,
+                    "field `{field}: {}` {is} `{}`",
+                    pred.self_ty(),
+                    pred.trait_ref.print_only_trait_path()
+                )?;
+            }
+            _ => {
+                let holds = if self.ambiguous { "may not hold" } else { "does not hold" };
+                write!(f, "`{}` {holds}", self.predicate)?;
+            }
+        }
+        if let Some(via) = &self.via {
+            write!(f, ", required by {via}")?;
+        }
+        if let Some(reason) = self.reason {
+            write!(f, ": {reason}")?;
+        } else if !self.causes.is_empty() {
+            write!(f, " because")?;
+        }
+        for cause in &self.causes {
+            write!(f, "\n{:indent$}- ", "", indent = indent)?;
+            cause.write_indented(f, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects the nested goals of a candidate, including the ones of the probes it contains.
+fn collect_candidate_goals<'a, 'tcx>(
+    candidate: &'a GoalCandidate<'tcx>,
+    nested: &mut Vec<&'a GoalEvaluation<'tcx>>,
+) {
+    for added_goals in &candidate.nested_goal_evaluations {
+        collect_nested_goals(added_goals.evaluations.as_slice(), nested);
+    }
+    for probe in &candidate.candidates {
+        collect_candidate_goals(probe, nested);
+    }
+}
+
+/// Why `predicate` does not hold if it has no failing nested goals.
+fn leaf_reason<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    predicate: ty::Predicate<'tcx>,
+    ambiguous: bool,
+) -> &'static str {
+    if ambiguous {
+        return "the types involved are not known yet";
+    }
+    let Some(pred) = predicate.to_opt_poly_trait_pred() else {
+        return "it could not be proven";
+    };
+    let pred = pred.skip_binder();
+    let mut negative = false;
+    tcx.for_each_relevant_impl(pred.def_id(), pred.self_ty(), |impl_def_id| {
+        negative |= tcx.impl_polarity(impl_def_id) == ty::ImplPolarity::Negative;
+    });
+    if negative {
+        "it is explicitly not implemented"
+    } else {
+        "no impl or where-clause applies"
+    }
+}
+
+impl fmt::Display for Explanation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
@@ -30,6 +30,7 @@
 mod assembly;
 mod canonicalize;
 mod eval_ctxt;
+pub mod explain;
 mod fulfill;
 mod inherent_projection;
 pub mod inspect;
@@ -11,6 +11,7 @@
 use crate::infer::error_reporting::{TyCategory, TypeAnnotationNeeded as ErrorCode};
 use crate::infer::type_variable::{TypeVariableOrigin, TypeVariableOriginKind};
 use crate::infer::{self, InferCtxt};
+use crate::solve::explain::explain_goal_evaluation;
 use crate::solve::{GenerateProofTree, InferCtxtEvalExt, UseGlobalCache};
 use crate::traits::query::evaluate_obligation::InferCtxtExt as _;
 use crate::traits::specialize::to_pretty_impl_header;
@@ -39,7 +40,7 @@
     self, SubtypePredicate, ToPolyTraitRef, ToPredicate, TraitRef, Ty, TyCtxt, TypeFoldable,
     TypeVisitable, TypeVisitableExt,
 };
-use rustc_session::config::{DumpSolverProofTree, TraitSolver};
+use rustc_session::config::{DumpSolverProofTree, ExplainTraitErrors, TraitSolver};
 use rustc_session::Limit;
 use rustc_span::def_id::LOCAL_CRATE;
 use rustc_span::symbol::sym;
@@ -791,6 +792,10 @@ fn report_selection_error(
                             err.note("See <https://github.com/rust-lang/rust/pull/94901> for more details");
                         }
 
+                        if let Some(format) = tcx.sess.opts.unstable_opts.explain_trait_errors {
+                            note_trait_error_explanation(&mut err, &obligation, self.infcx, format);
+                        }
+
                         let explanation = get_explanation_based_on_obligation(
                             &obligation,
                             trait_ref,
@@ -3544,3 +3549,35 @@ pub fn dump_proof_tree<'tcx>(o: &Obligation<'tcx, ty::Predicate<'tcx>>, infcx: &
         let _ = lock.flush();
     });
 }
+
+/// Adds a note explaining why `o` does not hold to `err`, based on the proof tree of the new
+/// solver, for `-Z explain-trait-errors`.
+fn note_trait_error_explanation<'tcx>(
+    err: &mut Diagnostic,
+    o: &Obligation<'tcx, ty::Predicate<'tcx>>,
+    infcx: &InferCtxt<'tcx>,
+    format: ExplainTraitErrors,
+) {
+    // The old solver does not generate proof trees.
+    if !infcx.next_trait_solver() {
+        return;
+    }
+    infcx.probe(|_| {
+        let goal = Goal { predicate: o.predicate, param_env: o.param_env };
+        let (_, tree) = infcx.evaluate_root_goal(goal, GenerateProofTree::Yes(UseGlobalCache::No));
+        let Some(tree) = tree else {
+            return;
+        };
+        let Some(explanation) = explain_goal_evaluation(infcx.tcx, &tree) else {
+            return;
+        };
+        err.note(explanation.to_string());
+        if format == ExplainTraitErrors::Json {
+            let span = infcx.tcx.sess.source_map().span_to_diagnostic_string(o.cause.span);
+            let json = serde_json::json!({ "span": span, "explanation": explanation.to_json() });
+            let mut lock = std::io::stdout().lock();
+            let _ = writeln!(lock, "{json}");
+            let _ = lock.flush();
+        }
+    });
+}
@@ -52,6 +52,16 @@ ty_utils_non_primitive_simd_type = monomorphising SIMD type `{$ty}` with a non-p
 
 ty_utils_operation_not_supported = unsupported operation in generic constants
 
+ty_utils_oversized_future = this future is {$size} bytes, which is more than the limit of {$limit} bytes
+    .help = consider moving large values to the heap, or boxing the large futures it awaits with `Box::pin`
+
+ty_utils_oversized_future_await_point = the future is {$await_size} bytes while it is suspended at this await point
+
+ty_utils_oversized_future_local = the largest value saved across it is {$local ->
+        [__awaitee] the awaited future
+        *[other] `{$local}`
+    }, with {$local_size} bytes
+
 ty_utils_oversized_simd_type = monomorphising SIMD type `{$ty}` of length greater than {$max_lanes}
 
 ty_utils_pointer_not_supported = pointer casts are not allowed in generic constants
@@ -1,8 +1,8 @@
 //! Errors emitted by ty_utils
 
-use rustc_macros::{Diagnostic, Subdiagnostic};
+use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_middle::ty::{GenericArg, Ty};
-use rustc_span::Span;
+use rustc_span::{Span, Symbol};
 
 #[derive(Diagnostic)]
 #[diag(ty_utils_needs_drop_overflow)]
@@ -122,3 +122,18 @@ pub struct NotParam<'tcx> {
     #[note]
     pub opaque_span: Span,
 }
+
+#[derive(LintDiagnostic)]
+#[diag(ty_utils_oversized_future)]
+#[help]
+pub struct OversizedFuture {
+    pub size: u64,
+    pub limit: u64,
+    pub await_size: u64,
+    #[note(ty_utils_oversized_future_await_point)]
+    pub await_span: Option<Span>,
+    pub local: Symbol,
+    pub local_size: u64,
+    #[note(ty_utils_oversized_future_local)]
+    pub local_span: Option<Span>,
+}
@@ -0,0 +1,65 @@
+//! The `oversized_futures` lint, which is enabled with `-Z future-size-limit`.
+
+use rustc_hir as hir;
+use rustc_hir::def::DefKind;
+use rustc_middle::ty::layout::{LayoutCx, LayoutOf};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::lint::builtin::OVERSIZED_FUTURES;
+use rustc_span::symbol::kw;
+use rustc_target::abi::Variants;
+
+use crate::errors::OversizedFuture;
+use crate::layout::future_await_points;
+
+/// Lints the futures of the `async` functions and blocks of the crate which are larger than
+/// `-Z future-size-limit`.
+///
+/// The size of a generic future depends on its arguments, so only the futures of non-generic
+/// functions and blocks are checked.
+pub fn check_future_sizes(tcx: TyCtxt<'_>) {
+    let Some(limit) = tcx.sess.opts.unstable_opts.future_size_limit else { return };
+    let limit = limit as u64;
+    let cx = LayoutCx { tcx, param_env: ty::ParamEnv::reveal_all() };
+    for def_id in tcx.hir().body_owners() {
+        if tcx.def_kind(def_id) != DefKind::Generator
+            || !tcx.generator_is_async(def_id.to_def_id())
+            || tcx
+                .generics_of(tcx.typeck_root_def_id(def_id.to_def_id()))
+                .requires_monomorphization(tcx)
+        {
+            continue;
+        }
+        let ty = tcx.erase_regions(tcx.type_of(def_id).instantiate_identity());
+        // Layout errors are reported when the future is code generated.
+        let Ok(layout) = cx.layout_of(ty) else { continue };
+        if layout.size.bytes() <= limit {
+            continue;
+        }
+        let Variants::Multiple { .. } = layout.variants else { continue };
+
+        let await_points = future_await_points(&cx, layout);
+        let largest = await_points.iter().max_by_key(|point| point.size);
+        let largest_local = largest.and_then(|point| point.locals.first());
+        // Point at the signature of an `async fn` rather than at its whole body.
+        let span = match tcx.generator_kind(def_id) {
+            Some(hir::GeneratorKind::Async(hir::AsyncGeneratorKind::Fn)) => {
+                tcx.def_span(tcx.local_parent(def_id))
+            }
+            _ => tcx.def_span(def_id),
+        };
+        tcx.emit_spanned_lint(
+            OVERSIZED_FUTURES,
+            tcx.local_def_id_to_hir_id(def_id),
+            span,
+            OversizedFuture {
+                size: layout.size.bytes(),
+                limit,
+                await_size: largest.map_or(0, |point| point.size),
+                await_span: largest.map(|point| point.span),
+                local: largest_local.map_or(kw::Empty, |local| local.name),
+                local_size: largest_local.map_or(0, |local| local.size),
+                local_span: largest_local.map(|local| local.span),
+            },
+        );
+    }
+}
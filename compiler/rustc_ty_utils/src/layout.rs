@@ -10,9 +10,10 @@
 use rustc_middle::ty::{
     self, AdtDef, EarlyBinder, GenericArgsRef, ReprOptions, Ty, TyCtxt, TypeVisitableExt,
 };
+use rustc_session::code_stats::{AwaitPointInfo, FutureLayoutInfo, SavedLocalInfo};
 use rustc_session::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
-use rustc_span::symbol::Symbol;
-use rustc_span::DUMMY_SP;
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::{Span, DUMMY_SP};
 use rustc_target::abi::*;
 
 use std::fmt::Debug;
@@ -62,6 +63,7 @@ fn layout_of<'tcx>(
     let layout = TyAndLayout { ty, layout };
 
     record_layout_for_printing(&cx, layout);
+    record_future_layout(&cx, layout);
 
     sanity_check_layout(&cx, &layout);
 
@@ -166,29 +168,27 @@ fn layout_of_uncached<'tcx>(
                 // fall back to structurally deducing metadata.
                 && !pointee.references_error()
             {
-                let pointee_metadata = Ty::new_projection(tcx,metadata_def_id, [pointee]);
-                let metadata_ty = match tcx.try_normalize_erasing_regions(
-                    param_env,
-                    pointee_metadata,
-                ) {
-                    Ok(metadata_ty) => metadata_ty,
-                    Err(mut err) => {
-                        // Usually `<Ty as Pointee>::Metadata` can't be normalized because
-                        // its struct tail cannot be normalized either, so try to get a
-                        // more descriptive layout error here, which will lead to less confusing
-                        // diagnostics.
-                        match tcx.try_normalize_erasing_regions(
-                            param_env,
-                            tcx.struct_tail_without_normalization(pointee),
-                        ) {
-                            Ok(_) => {},
-                            Err(better_err) => {
-                                err = better_err;
+                let pointee_metadata = Ty::new_projection(tcx, metadata_def_id, [pointee]);
+                let metadata_ty =
+                    match tcx.try_normalize_erasing_regions(param_env, pointee_metadata) {
+                        Ok(metadata_ty) => metadata_ty,
+                        Err(mut err) => {
+                            // Usually `<Ty as Pointee>::Metadata` can't be normalized because
+                            // its struct tail cannot be normalized either, so try to get a
+                            // more descriptive layout error here, which will lead to less confusing
+                            // diagnostics.
+                            match tcx.try_normalize_erasing_regions(
+                                param_env,
+                                tcx.struct_tail_without_normalization(pointee),
+                            ) {
+                                Ok(_) => {}
+                                Err(better_err) => {
+                                    err = better_err;
+                                }
                             }
+                            return Err(error(cx, LayoutError::NormalizationFailure(pointee, err)));
                         }
-                        return Err(error(cx, LayoutError::NormalizationFailure(pointee, err)));
-                    },
-                };
+                    };
 
                 let metadata_layout = cx.layout_of(metadata_ty)?;
                 // If the metadata is a 1-zst, then the pointer is thin.
@@ -1163,3 +1163,124 @@ fn variant_info_for_generator<'tcx>(
         },
     )
 }
+
+/// This is invoked by the `layout_of` query to record the layout of the futures of `async`
+/// functions and blocks for `-Zprint-future-layouts`.
+#[inline(always)]
+fn record_future_layout<'tcx>(cx: &LayoutCx<'tcx, TyCtxt<'tcx>>, layout: TyAndLayout<'tcx>) {
+    if cx.tcx.sess.opts.unstable_opts.print_future_layouts {
+        record_future_layout_outlined(cx, layout)
+    }
+}
+
+fn record_future_layout_outlined<'tcx>(
+    cx: &LayoutCx<'tcx, TyCtxt<'tcx>>,
+    layout: TyAndLayout<'tcx>,
+) {
+    let tcx = cx.tcx;
+    let ty::Generator(def_id, ..) = *layout.ty.kind() else { return };
+    if !tcx.generator_is_async(def_id) {
+        return;
+    }
+    // Like for `-Zprint-type-sizes`, only the layouts of the final codegen session are
+    // interesting, as the size of a generic future depends on its arguments.
+    if layout.ty.has_non_region_param() || !cx.param_env.caller_bounds().is_empty() {
+        return;
+    }
+    let Variants::Multiple { .. } = layout.variants else { return };
+
+    let source_map = tcx.sess.source_map();
+    let span_to_string = |span: Span| source_map.span_to_embeddable_string(span);
+    let await_points = future_await_points(cx, layout)
+        .into_iter()
+        .map(|point| AwaitPointInfo {
+            span: span_to_string(point.span),
+            size: point.size,
+            locals: point
+                .locals
+                .into_iter()
+                .map(|local| SavedLocalInfo {
+                    name: local.name,
+                    span: span_to_string(local.span),
+                    size: local.size,
+                    is_future: local.is_future,
+                })
+                .collect(),
+        })
+        .collect();
+    tcx.sess.code_stats.record_future_layout(FutureLayoutInfo {
+        type_description: format!("{:?}", layout.ty),
+        overall_size: layout.size.bytes(),
+        await_points,
+    });
+}
+
+/// An await point of the future of an `async` function or block.
+pub(crate) struct AwaitPoint {
+    pub span: Span,
+    /// The size of the future while it is suspended at this await point.
+    pub size: u64,
+    /// The locals saved across the await point, largest first.
+    pub locals: Vec<SavedLocal>,
+}
+
+/// A local saved in a future across an await point.
+pub(crate) struct SavedLocal {
+    pub name: Symbol,
+    pub span: Span,
+    pub size: u64,
+    /// Whether the local is itself a future, like the one being awaited.
+    pub is_future: bool,
+}
+
+/// The await points of `layout`, which is the layout of the future of an `async` function or
+/// block.
+pub(crate) fn future_await_points<'tcx>(
+    cx: &LayoutCx<'tcx, TyCtxt<'tcx>>,
+    layout: TyAndLayout<'tcx>,
+) -> Vec<AwaitPoint> {
+    let tcx = cx.tcx;
+    let ty::Generator(def_id, args, _) = *layout.ty.kind() else {
+        bug!("`{}` is not a future", layout.ty)
+    };
+    let generator = tcx.optimized_mir(def_id).generator_layout().unwrap();
+    let is_future = |name, ty: Ty<'tcx>| {
+        name == Some(sym::__awaitee)
+            || matches!(*ty.kind(), ty::Generator(def_id, ..) if tcx.generator_is_async(def_id))
+    };
+
+    // Every suspension point of an `async` body is an await point. The locals stored in its
+    // variant are exactly the ones which are live across it.
+    generator
+        .variant_fields
+        .iter_enumerated()
+        .skip(ty::GeneratorArgs::RESERVED_VARIANTS)
+        .map(|(variant_idx, variant_def)| {
+            let mut locals: Vec<_> = variant_def
+                .iter()
+                .map(|&local| {
+                    let saved = &generator.field_tys[local];
+                    let ty = EarlyBinder::bind(saved.ty).instantiate(tcx, args);
+                    let ty = tcx.normalize_erasing_regions(cx.param_env, ty);
+                    let name = generator.field_names[local];
+                    SavedLocal {
+                        name: name.unwrap_or_else(|| {
+                            Symbol::intern(&format!(".generator_field{}", local.as_usize()))
+                        }),
+                        span: saved.source_info.span,
+                        size: cx.layout_of(ty).map_or(0, |layout| layout.size.bytes()),
+                        is_future: is_future(name, ty),
+                    }
+                })
+                .collect();
+            locals.sort_by(|a, b| {
+                b.size.cmp(&a.size).then_with(|| a.name.as_str().cmp(b.name.as_str()))
+            });
+            AwaitPoint {
+                span: generator.variant_source_info[variant_idx].span,
+                size: layout.for_variant(cx, variant_idx).size.bytes(),
+                locals,
+            }
+        })
+        .collect()
+}
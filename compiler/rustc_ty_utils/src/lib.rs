@@ -29,6 +29,7 @@
 mod common_traits;
 mod consts;
 mod errors;
+pub mod future_size;
 mod implied_bounds;
 pub mod instance;
 mod layout;
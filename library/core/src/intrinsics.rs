@@ -2491,6 +2491,36 @@ pub fn const_eval_select<ARG: Tuple, F, G, RET>(
     /// constructing an empty slice) is returned.
     #[rustc_nounwind]
     pub fn option_payload_ptr<T>(arg: *const Option<T>) -> *const T;
+
+    /// Returns whether the preconditions of unsafe library functions should be checked at
+    /// runtime, as done by `assert_unsafe_precondition!`.
+    ///
+    /// This is `-Zub-checks` (which defaults to `-Cdebug-assertions`) of the crate that the
+    /// calling function is codegened in, rather than of the crate that it is defined in. That way,
+    /// the checks in the inlined and generic functions of the standard library are enabled for
+    /// crates built with debug assertions, even though the standard library itself is not.
+    ///
+    /// At compile time this returns `true`, but the checks done by
+    /// `assert_unsafe_precondition!` only happen at runtime.
+    ///
+    /// Note that, unlike most intrinsics, this is safe to call;
+    /// it does not require an `unsafe` block.
+    /// Therefore, implementations must not require the user to uphold
+    /// any safety invariants.
+    #[cfg(not(bootstrap))]
+    #[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+    #[rustc_safe_intrinsic]
+    #[rustc_nounwind]
+    pub fn ub_checks() -> bool;
+}
+
+/// The bootstrap compiler doesn't know the `ub_checks` intrinsic, so the checks follow
+/// `debug_assertions` of the standard library itself.
+#[cfg(bootstrap)]
+#[rustc_const_unstable(feature = "const_ub_checks", issue = "none")]
+#[inline(always)]
+pub const fn ub_checks() -> bool {
+    cfg!(debug_assertions)
 }
 
 // Some functions are defined here because they accidentally got made
@@ -2498,7 +2528,7 @@ pub fn const_eval_select<ARG: Tuple, F, G, RET>(
 // (`transmute` also falls into this category, but it cannot be wrapped due to the
 // check that `T` and `U` have the same size.)
 
-/// Check that the preconditions of an unsafe function are followed, if debug_assertions are on,
+/// Check that the preconditions of an unsafe function are followed, if [`ub_checks`] is enabled,
 /// and only at runtime.
 ///
 /// This macro should be called as `assert_unsafe_precondition!([Generics](name: Type) => Expression)`
@@ -2511,17 +2541,19 @@ pub fn const_eval_select<ARG: Tuple, F, G, RET>(
 /// Invoking this macro is only sound if the following code is already UB when the passed
 /// expression evaluates to false.
 ///
-/// This macro expands to a check at runtime if debug_assertions is set. It has no effect at
+/// This macro expands to a check at runtime if [`ub_checks`] returns `true`, which is decided by
+/// `-Zub-checks` of the crate that the caller is codegened in. It has no effect at
 /// compile time, but the semantics of the contained `const_eval_select` must be the same at
 /// runtime and at compile time. Thus if the expression evaluates to false, this macro produces
 /// different behavior at compile time and at runtime, and invoking it is incorrect.
 ///
 /// So in a sense it is UB if this macro is useful, but we expect callers of `unsafe fn` to make
 /// the occasional mistake, and this check should help them figure things out.
-#[allow_internal_unstable(const_eval_select)] // permit this to be called in stably-const fn
+// permit this to be called in stably-const fn
+#[allow_internal_unstable(const_eval_select, const_ub_checks)]
 macro_rules! assert_unsafe_precondition {
     ($name:expr, $([$($tt:tt)*])?($($i:ident:$ty:ty),*$(,)?) => $e:expr) => {
-        if cfg!(debug_assertions) {
+        if ::core::intrinsics::ub_checks() {
             // allow non_snake_case to allow capturing const generics
             #[allow(non_snake_case)]
             #[inline(always)]
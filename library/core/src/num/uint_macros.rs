@@ -483,9 +483,16 @@ pub const fn checked_add(self, rhs: Self) -> Option<Self> {
         #[inline(always)]
         #[cfg_attr(miri, track_caller)] // even without panics, this helps for Miri backtraces
         pub const unsafe fn unchecked_add(self, rhs: Self) -> Self {
+            let lhs = self;
             // SAFETY: the caller must uphold the safety contract for
             // `unchecked_add`.
-            unsafe { intrinsics::unchecked_add(self, rhs) }
+            unsafe {
+                intrinsics::assert_unsafe_precondition!(
+                    concat!(stringify!($SelfT), "::unchecked_add cannot overflow"),
+                    (lhs: $SelfT, rhs: $SelfT) => !lhs.overflowing_add(rhs).1
+                );
+                intrinsics::unchecked_add(lhs, rhs)
+            }
         }
 
         /// Checked addition with a signed integer. Computes `self + rhs`,
@@ -599,9 +606,16 @@ pub const fn checked_mul(self, rhs: Self) -> Option<Self> {
         #[inline(always)]
         #[cfg_attr(miri, track_caller)] // even without panics, this helps for Miri backtraces
         pub const unsafe fn unchecked_mul(self, rhs: Self) -> Self {
+            let lhs = self;
             // SAFETY: the caller must uphold the safety contract for
             // `unchecked_mul`.
-            unsafe { intrinsics::unchecked_mul(self, rhs) }
+            unsafe {
+                intrinsics::assert_unsafe_precondition!(
+                    concat!(stringify!($SelfT), "::unchecked_mul cannot overflow"),
+                    (lhs: $SelfT, rhs: $SelfT) => !lhs.overflowing_mul(rhs).1
+                );
+                intrinsics::unchecked_mul(lhs, rhs)
+            }
         }
 
         /// Checked integer division. Computes `self / rhs`, returning `None`
@@ -3,6 +3,7 @@
 use rustc_data_structures::unord::UnordSet;
 use rustc_errors::emitter::{DynEmitter, EmitterWriter};
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::TerminalUrl;
 use rustc_feature::UnstableFeatures;
 use rustc_hir::def::Res;
@@ -121,8 +122,8 @@ pub(crate) fn as_local_hir_id(tcx: TyCtxt<'_>, item_id: ItemId) -> Option<HirId>
 
 /// Creates a new diagnostic `Handler` that can be used to emit warnings and errors.
 ///
-/// If the given `error_format` is `ErrorOutputType::Json` and no `SourceMap` is given, a new one
-/// will be created for the handler.
+/// If the given `error_format` is `ErrorOutputType::Json` or `ErrorOutputType::Sarif` and no
+/// `SourceMap` is given, a new one will be created for the handler.
 pub(crate) fn new_handler(
     error_format: ErrorOutputType,
     source_map: Option<Lrc<source_map::SourceMap>>,
@@ -166,6 +167,15 @@ pub(crate) fn new_handler(
                 .ui_testing(unstable_opts.ui_testing),
             )
         }
+        ErrorOutputType::Sarif => {
+            let source_map = source_map.unwrap_or_else(|| {
+                Lrc::new(source_map::SourceMap::new(source_map::FilePathMapping::empty()))
+            });
+            Box::new(
+                SarifEmitter::stderr(None, source_map, None, fallback_bundle)
+                    .ui_testing(unstable_opts.ui_testing),
+            )
+        }
     };
 
     rustc_errors::Handler::with_emitter(emitter)
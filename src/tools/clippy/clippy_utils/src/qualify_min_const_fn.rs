@@ -176,6 +176,7 @@ fn check_rvalue<'tcx>(
         Rvalue::NullaryOp(NullOp::SizeOf | NullOp::AlignOf | NullOp::OffsetOf(_), _) | Rvalue::ShallowInitBox(_, _) => {
             Ok(())
         },
+        Rvalue::NullaryOp(NullOp::UbChecks, _) => Ok(()),
         Rvalue::UnaryOp(_, operand) => {
             let ty = operand.ty(body, tcx);
             if ty.is_integral() || ty.is_bool() {
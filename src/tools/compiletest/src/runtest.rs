@@ -572,6 +572,8 @@ fn run_coverage_test(&self) {
             cmd.arg("--instr-profile");
             cmd.arg(&profdata_path);
 
+            cmd.args(&self.props.llvm_cov_flags);
+
             for bin in &bin_paths {
                 cmd.arg("--object");
                 cmd.arg(bin);
@@ -711,6 +713,7 @@ fn run_command_to_procres(&self, cmd: &mut Command) -> ProcRes {
     fn normalize_coverage_output(&self, coverage: &str) -> Result<String, String> {
         let normalized = self.normalize_output(coverage, &[]);
         let normalized = Self::anonymize_coverage_line_numbers(&normalized);
+        let normalized = Self::anonymize_coverage_branch_line_numbers(&normalized);
 
         let mut lines = normalized.lines().collect::<Vec<_>>();
 
@@ -736,6 +739,20 @@ fn anonymize_coverage_line_numbers(coverage: &str) -> Cow<'_, str> {
         LINE_NUMBER_RE.replace_all(coverage, "$prefix   LL|")
     }
 
+    /// `llvm-cov show --show-branches` and `--show-mcdc` print the line numbers
+    /// of branches and MC/DC decisions in their headings, such as
+    /// `Branch (12:8)` or `Decision Region (12:8) to (12:20)`, so replace those
+    /// line numbers with `LL` as well.
+    fn anonymize_coverage_branch_line_numbers(coverage: &str) -> String {
+        static BRANCH_LINE_NUMBER_RE: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"\b(?<heading>Branch|Decision Region) \([0-9]+:").unwrap());
+        static REGION_END_LINE_NUMBER_RE: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"(?<start>Decision Region \(LL:[0-9]+\) to) \([0-9]+:").unwrap()
+        });
+        let coverage = BRANCH_LINE_NUMBER_RE.replace_all(coverage, "$heading (LL:");
+        REGION_END_LINE_NUMBER_RE.replace_all(&coverage, "$start (LL:").into_owned()
+    }
+
     /// Coverage reports can describe multiple source files, separated by
     /// blank lines. The order of these files is unpredictable (since it
     /// depends on implementation details), so we need to sort the file
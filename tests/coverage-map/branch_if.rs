@@ -0,0 +1,9 @@
+// compile-flags: --edition=2021 -Zcoverage-options=branch
+
+fn main() {
+    let cond = std::env::args().len() == 1;
+    if cond {
+        println!("true");
+    }
+    println!("done");
+}
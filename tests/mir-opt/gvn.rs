@@ -0,0 +1,54 @@
+// EMIT_MIR_FOR_EACH_PANIC_STRATEGY
+// unit-test: GVN
+
+// EMIT_MIR gvn.subexpression_elimination.GVN.diff
+fn subexpression_elimination(x: u64, y: u64) -> u64 {
+    let a = x & y;
+    let b = x & y;
+    a ^ b
+}
+
+// EMIT_MIR gvn.aggregates.GVN.diff
+fn aggregates(x: u32) -> u32 {
+    let a = (x, 5u32);
+    let b = a.1;
+    let c = a.0;
+    c ^ b
+}
+
+// EMIT_MIR gvn.constant_aggregates.GVN.diff
+fn constant_aggregates() -> u32 {
+    let a = (4u32, 5u32);
+    let b = (a.1, a.0);
+    b.0 ^ b.1
+}
+
+// EMIT_MIR gvn.slices.GVN.diff
+fn slices(x: &[u32], i: usize) -> u32 {
+    // The second bounds check reuses the length and the comparison of the first one.
+    let a = x[i];
+    let b = x[i];
+    a ^ b
+}
+
+enum E {
+    V1(u32),
+    V2(u32),
+}
+
+// EMIT_MIR gvn.discriminants.GVN.diff
+fn discriminants(x: u32) -> u32 {
+    let e = E::V1(x);
+    match e {
+        E::V1(y) => y,
+        E::V2(y) => y,
+    }
+}
+
+fn main() {
+    subexpression_elimination(2, 4);
+    aggregates(7);
+    constant_aggregates();
+    slices(&[1, 2, 3], 1);
+    discriminants(5);
+}
@@ -0,0 +1,154 @@
+// unit-test: JumpThreading
+
+#![feature(custom_mir, core_intrinsics)]
+use std::convert::Infallible;
+use std::intrinsics::mir::*;
+use std::ops::ControlFlow;
+
+pub enum Foo {
+    A,
+    B,
+    C,
+}
+
+// EMIT_MIR jump_threading.custom_discr.JumpThreading.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+fn custom_discr(x: bool) -> u8 {
+    mir!(
+        let y: u8;
+        {
+            match x { true => bb1, _ => bb2 }
+        }
+        bb1 = {
+            y = 13;
+            Goto(bb3)
+        }
+        bb2 = {
+            y = 5;
+            Goto(bb3)
+        }
+        bb3 = {
+            match y { 13 => bb4, _ => bb5 }
+        }
+        bb4 = {
+            RET = 5;
+            Return()
+        }
+        bb5 = {
+            RET = 13;
+            Return()
+        }
+    )
+}
+
+// EMIT_MIR jump_threading.matches_bool.JumpThreading.diff
+fn matches_bool(x: Foo) -> u64 {
+    // The boolean produced by `matches!` is known in each arm, so the `if` is threaded.
+    if matches!(x, Foo::B | Foo::C) { 23 } else { 42 }
+}
+
+// EMIT_MIR jump_threading.question_mark.JumpThreading.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+fn question_mark(x: Option<u8>) -> Option<u8> {
+    // This is `Some(x?)` once `<Option<u8> as Try>::branch` has been inlined: the variant of
+    // the `ControlFlow` is known in each predecessor of the second `SwitchInt`.
+    mir!(
+        let d: isize;
+        let r: Option<Infallible>;
+        let cf: ControlFlow<Option<Infallible>, u8>;
+        let v: u8;
+        let d2: isize;
+        let w: u8;
+        {
+            d = Discriminant(x);
+            match d { 0 => bb1, 1 => bb2, _ => unreachable }
+        }
+        bb1 = {
+            r = None;
+            cf = ControlFlow::Break(r);
+            Goto(bb3)
+        }
+        bb2 = {
+            v = Field(Variant(x, 1), 0);
+            cf = ControlFlow::Continue(v);
+            Goto(bb3)
+        }
+        bb3 = {
+            d2 = Discriminant(cf);
+            match d2 { 0 => bb4, 1 => bb5, _ => unreachable }
+        }
+        bb4 = {
+            w = Field(Variant(cf, 0), 0);
+            RET = Some(w);
+            Return()
+        }
+        bb5 = {
+            RET = None;
+            Return()
+        }
+        unreachable = {
+            Unreachable()
+        }
+    )
+}
+
+// EMIT_MIR jump_threading.too_costly.JumpThreading.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+fn too_costly(x: bool) -> u8 {
+    // Same as `custom_discr`, but threading would duplicate `bb3`, which costs more than
+    // `MAX_COST`.
+    mir!(
+        let y: u8;
+        let z: u8;
+        {
+            match x { true => bb1, _ => bb2 }
+        }
+        bb1 = {
+            y = 13;
+            Goto(bb3)
+        }
+        bb2 = {
+            y = 5;
+            Goto(bb3)
+        }
+        bb3 = {
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            z = 0;
+            match y { 13 => bb4, _ => bb5 }
+        }
+        bb4 = {
+            RET = 5;
+            Return()
+        }
+        bb5 = {
+            RET = z;
+            Return()
+        }
+    )
+}
+
+fn main() {
+    custom_discr(false);
+    matches_bool(Foo::A);
+    question_mark(None);
+    too_costly(false);
+}
@@ -32,6 +32,11 @@ pub fn align_of<T>() -> usize {
     core::intrinsics::min_align_of::<T>()
 }
 
+// EMIT_MIR lower_intrinsics.ub_checks.LowerIntrinsics.diff
+pub fn ub_checks() -> bool {
+    core::intrinsics::ub_checks()
+}
+
 // EMIT_MIR lower_intrinsics.forget.LowerIntrinsics.diff
 pub fn forget<T>(t: T) {
     core::intrinsics::forget(t)
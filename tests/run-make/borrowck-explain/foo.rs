@@ -0,0 +1,18 @@
+fn shared_then_mutable(v: &mut Vec<u32>) {
+    let first = &v[0];
+    v.push(1);
+    println!("{first}");
+}
+
+fn mutable_twice(x: &mut u32) {
+    let a = &mut *x;
+    let b = a;
+    let c = &mut *x;
+    *b += 1;
+    *c += 1;
+}
+
+fn main() {
+    shared_then_mutable(&mut vec![0]);
+    mutable_twice(&mut 0);
+}
@@ -0,0 +1,33 @@
+#![crate_type = "lib"]
+
+const fn square(x: u32) -> u32 {
+    x * x
+}
+
+const fn build() -> [u32; 64] {
+    let mut table = [0; 64];
+    let mut i = 0;
+    while i < 64 {
+        table[i] = square(i as u32);
+        i += 1;
+    }
+    table
+}
+
+pub const TABLE: [u32; 64] = build();
+
+pub const SMALL: u32 = square(3);
+
+const fn count_up(n: u32) -> u32 {
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    i
+}
+
+const fn twice(n: u32) -> u32 {
+    count_up(n) + count_up(n)
+}
+
+pub const NESTED: u32 = twice(10);
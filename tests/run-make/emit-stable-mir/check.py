@@ -0,0 +1,32 @@
+import json
+import sys
+
+with open(sys.argv[1]) as f:
+    smir = json.load(f)
+
+assert smir["format_version"] == 2, smir["format_version"]
+assert smir["crate_name"] == "foo", smir["crate_name"]
+
+types = {ty["id"]: ty["kind"] for ty in smir["types"]}
+def_ids = {def_id["id"]: def_id["name"] for def_id in smir["def_ids"]}
+spans = {span["id"]: span["location"] for span in smir["spans"]}
+items = {item["name"]: item for item in smir["items"]}
+
+add = items["add"]
+assert def_ids[add["def_id"]] == "add", add
+body = add["body"]
+assert len(body["blocks"]) > 0, body
+# The return place and both arguments are `u32`.
+for local in body["locals"][:3]:
+    assert types[local] == {"RigidTy": {"Uint": "U32"}}, types[local]
+# Statements and terminators point back into the source file.
+[ret] = [block["terminator"] for block in body["blocks"] if block["terminator"]["kind"] == "Return"]
+assert spans[ret["span"]].startswith("foo.rs:"), spans[ret["span"]]
+
+# Constants only have MIR for const evaluation.
+assert items["LIMIT"]["body"] is None, items["LIMIT"]
+assert items["<Counter as std::default::Default>::default"]["body"] is not None
+
+[trait_impl] = smir["trait_impls"]
+trait_ref = trait_impl["trait_ref"]["value"]
+assert def_ids[trait_ref["def_id"]] == "std::default::Default", trait_ref
@@ -0,0 +1,15 @@
+#![crate_type = "lib"]
+
+pub const LIMIT: u32 = 10;
+
+pub fn add(x: u32, y: u32) -> u32 {
+    x.wrapping_add(y).min(LIMIT)
+}
+
+pub struct Counter(u32);
+
+impl Default for Counter {
+    fn default() -> Counter {
+        Counter(add(1, 2))
+    }
+}
@@ -0,0 +1,68 @@
+import json
+import sys
+
+# Decides whether `downstream` has to be rebuilt against a new build of `upstream`, following the
+# rule documented in `export-dep-graph.md`, and compares the decision with the expected one.
+
+downstream_path, upstream_path, expected = sys.argv[1:]
+with open(downstream_path) as f:
+    downstream = json.load(f)
+with open(upstream_path) as f:
+    upstream = json.load(f)
+
+for export in (downstream, upstream):
+    assert export["version"] == 2, export["version"]
+    assert any(file["path"].endswith(export["crate"] + ".rs") for file in export["source_files"])
+
+provides = {item["def_path_hash"]: item for item in upstream["provides"]}
+answer = [item for item in upstream["provides"] if item.get("path") == "upstream::answer"]
+assert len(answer) == 1 and answer[0]["reachable"], answer
+helper = [item for item in upstream["provides"] if item.get("path") == "upstream::helper"]
+assert len(helper) == 1 and not helper[0]["reachable"], helper
+
+used = [
+    item
+    for item in downstream["upstream"] + downstream["upstream_crates"]
+    if item.get("crate") == "upstream"
+]
+assert any(item.get("path") == "upstream::answer" for item in used), used
+assert not any(item.get("path") == "upstream::helper" for item in used), used
+assert not any("used_crate_source" in item["queries"] for item in used), used
+
+
+def decide(provides):
+    """Returns the queries that changed and the queries that are missing from `provides`."""
+    changed, missing = [], []
+    for item in used:
+        provided = provides.get(item["def_path_hash"], {"queries": {}})["queries"]
+        for query, fingerprint in item["queries"].items():
+            if query not in provided:
+                missing.append((item.get("path"), query))
+            elif provided[query] != fingerprint:
+                changed.append((item.get("path"), query))
+    return changed, missing
+
+
+changed, missing = decide(provides)
+
+answer_used = [item for item in used if item.get("path") == "upstream::answer"][0]
+assert "fn_sig" in answer_used["queries"], answer_used
+assert "fn_sig" in answer[0]["queries"], answer
+
+# A query that can't be compared forces a rebuild, even if everything else is unchanged.
+without_fn_sig = dict(provides)
+without_fn_sig[answer[0]["def_path_hash"]] = {
+    "queries": {q: f for q, f in answer[0]["queries"].items() if q != "fn_sig"}
+}
+assert ("upstream::answer", "fn_sig") in decide(without_fn_sig)[1]
+
+rebuild = bool(changed or missing)
+if expected == "unchanged":
+    # Nothing `downstream` depends on changed, so it can only have to be rebuilt because some
+    # query it read could not be compared.
+    assert not changed, changed
+else:
+    assert ("upstream::answer", "fn_sig") in changed, changed
+    assert rebuild
+
+print("rebuild" if rebuild else "skip", "changed:", changed, "missing:", missing)
@@ -0,0 +1,5 @@
+#![crate_type = "rlib"]
+
+pub fn print_answer() {
+    println!("{}", upstream::answer());
+}
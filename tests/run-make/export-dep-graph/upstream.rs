@@ -0,0 +1,21 @@
+#![crate_type = "rlib"]
+
+#[cfg(not(public_change))]
+pub fn answer() -> u32 {
+    helper()
+}
+
+#[cfg(public_change)]
+pub fn answer() -> u64 {
+    helper() as u64
+}
+
+#[cfg(not(private_change))]
+fn helper() -> u32 {
+    42
+}
+
+#[cfg(private_change)]
+fn helper() -> u32 {
+    6 * 7
+}
@@ -0,0 +1,8 @@
+#![crate_type = "lib"]
+#![warn(unused_parens)]
+
+pub fn foo() -> u32 {
+    let x = (1);
+    let y = 2;
+    x
+}
@@ -0,0 +1,9 @@
+extern crate upstream;
+
+pub fn generic<T: Default>() -> T {
+    T::default()
+}
+
+pub fn not_generic() -> u32 {
+    generic::<u32>() + generic::<u8>() as u32 + upstream::make::<u16>() as u32
+}
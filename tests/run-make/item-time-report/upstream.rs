@@ -0,0 +1,9 @@
+#![crate_type = "lib"]
+
+pub fn make<T: Default>() -> T {
+    helper::<T>()
+}
+
+fn helper<T: Default>() -> T {
+    T::default()
+}
@@ -0,0 +1,15 @@
+#![crate_type = "lib"]
+
+#[allow(dead_code)]
+struct legacy_type;
+
+pub fn legacy() {
+    let unused = 1;
+}
+
+pub mod inner {
+    pub fn legacy() -> u32 {
+        let unused = 2;
+        3
+    }
+}
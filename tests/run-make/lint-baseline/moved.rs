@@ -0,0 +1,18 @@
+#![crate_type = "lib"]
+
+// Moving code around does not invalidate the baseline.
+
+
+pub mod inner {
+    pub fn legacy() -> u32 {
+        let   unused = 2;
+        3
+    }
+}
+
+pub fn legacy() {
+    let unused = 1;
+}
+
+#[allow(dead_code)]
+struct legacy_type;
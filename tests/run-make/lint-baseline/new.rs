@@ -0,0 +1,21 @@
+#![crate_type = "lib"]
+
+#[allow(dead_code)]
+struct legacy_type;
+
+pub fn legacy() {
+    let unused = 1;
+    let fresh = 4;
+}
+
+pub mod inner {
+    pub fn legacy() -> u32 {
+        let unused = 2;
+        3
+    }
+}
+
+// The same code in another item is not covered by the baseline.
+pub fn copy() {
+    let unused = 1;
+}
@@ -0,0 +1,25 @@
+pub trait Speak {
+    fn speak(&self) -> u32;
+}
+
+pub struct Dog;
+
+impl Speak for Dog {
+    fn speak(&self) -> u32 {
+        1
+    }
+}
+
+fn leaf<T>(x: T) -> T {
+    x
+}
+
+fn middle() -> u32 {
+    leaf(1u32)
+}
+
+pub fn root() -> u32 {
+    let speaker: &dyn Speak = &Dog;
+    let f: fn() -> u32 = middle;
+    speaker.speak() + f() + leaf(2u32)
+}
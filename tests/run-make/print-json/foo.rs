@@ -0,0 +1,11 @@
+#[link(name = "systemd")]
+extern "C" {
+    fn init(p: *mut ());
+}
+
+#[no_mangle]
+pub extern "C" fn my_foo() {
+    unsafe {
+        init(std::ptr::null_mut());
+    }
+}
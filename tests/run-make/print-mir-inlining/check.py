@@ -0,0 +1,25 @@
+import json
+import sys
+
+with open(sys.argv[1]) as f:
+    report = json.load(f)
+
+callsites = {
+    callsite["callee"]: callsite
+    for callsite in report["callsites"]
+    if callsite["caller"] == "caller"
+}
+
+small = callsites["small"]
+assert small["inlined"] and small["reason"] is None, small
+assert small["cost"] <= small["threshold"], small
+assert small["depth"] == 0, small
+
+never = callsites["never"]
+assert not never["inlined"], never
+assert never["reason"] == "never inline hint", never
+assert never["cost"] is None, never
+
+not_inline = callsites["not_inline"]
+assert not not_inline["inlined"], not_inline
+assert not_inline["reason"] == "not exported", not_inline
@@ -0,0 +1,19 @@
+#![crate_type = "lib"]
+
+#[inline]
+pub fn small(x: u32) -> u32 {
+    x + 1
+}
+
+#[inline(never)]
+pub fn never(x: u32) -> u32 {
+    x.wrapping_mul(3)
+}
+
+pub fn not_inline(x: u32) -> u32 {
+    x.rotate_left(3)
+}
+
+pub fn caller(x: u32) -> u32 {
+    not_inline(never(small(x)))
+}
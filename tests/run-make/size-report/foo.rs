@@ -0,0 +1,9 @@
+pub static TABLE: [u32; 64] = [7; 64];
+
+pub fn use_vecs() -> usize {
+    let mut bytes = Vec::new();
+    bytes.push(1u8);
+    let mut words = Vec::new();
+    words.push(TABLE[0]);
+    bytes.len() + words.len()
+}
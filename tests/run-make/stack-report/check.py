@@ -0,0 +1,33 @@
+import json
+import sys
+
+with open(sys.argv[1]) as f:
+    report = json.load(f)
+
+assert report["crate"] == "foo", report["crate"]
+entry_points = {entry["name"]: entry for entry in report["entry_points"]}
+assert set(entry_points) == {"bounded", "recursive", "indirect"}, list(entry_points)
+
+
+def problem_kinds(entry):
+    return {problem["kind"] for problem in entry["problems"]}
+
+
+bounded = entry_points["bounded"]
+assert bounded["bounded"] and not bounded["problems"], bounded
+path = bounded["deepest_path"]
+assert [function["name"] for function in path] == ["bounded", "leaf"], path
+assert bounded["stack"] == sum(function["frame"] for function in path), bounded
+# `leaf` has an array of 16 `u64`s on its stack.
+assert path[1]["frame"] >= 128, path
+
+recursive = entry_points["recursive"]
+assert not recursive["bounded"], recursive
+assert problem_kinds(recursive) == {"recursion"}, recursive
+
+indirect = entry_points["indirect"]
+assert not indirect["bounded"], indirect
+assert problem_kinds(indirect) == {"indirect-call"}, indirect
+
+functions = {function["name"]: function for function in report["functions"]}
+assert functions["leaf"]["bounded"], functions["leaf"]
@@ -0,0 +1,20 @@
+fn leaf(x: u64) -> u64 {
+    let buf = [x; 16];
+    let [first, .., last] = buf;
+    first ^ last
+}
+
+#[no_mangle]
+pub extern "C" fn bounded(x: u64) -> u64 {
+    leaf(x) + 1
+}
+
+#[no_mangle]
+pub extern "C" fn recursive(n: u32) -> u32 {
+    if n == 0 { 0 } else { recursive(n - 1) }
+}
+
+#[no_mangle]
+pub extern "C" fn indirect(f: fn() -> u32) -> u32 {
+    f()
+}
@@ -0,0 +1,150 @@
+// run-pass
+// Test that users are able to rewrite the MIR of local items using the stable mir APIs, that
+// the rewritten bodies are the ones that get compiled, and that invalid bodies are rejected.
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_smir;
+
+use rustc_smir::rustc_internal;
+use rustc_smir::stable_mir::{
+    self,
+    mir::{
+        visit::{Location, MutVisitor},
+        BasicBlock, Body, Local, Operand, Place, Rvalue, Statement, StatementKind, Terminator,
+        TerminatorKind, UnwindAction,
+    },
+    ty::{FnDef, GenericArgs, RigidTy, Ty, TyKind},
+    CompilerError, CrateItem,
+};
+use std::io::Write;
+use std::process::Command;
+
+const CRATE_NAME: &str = "input";
+
+/// Redirects every edge of a `SwitchInt` to a new block that calls `trace` before jumping to the
+/// original target.
+struct TraceBranches {
+    trace: FnDef,
+    destination: Local,
+    next_block: usize,
+    new_blocks: Vec<BasicBlock>,
+}
+
+impl MutVisitor for TraceBranches {
+    fn visit_terminator(&mut self, terminator: &mut Terminator, location: Location) {
+        if let TerminatorKind::SwitchInt { targets, otherwise, .. } = &mut terminator.kind {
+            let targets = targets.iter_mut().map(|target| &mut target.target);
+            for target in targets.chain(std::iter::once(otherwise)) {
+                let call = TerminatorKind::Call {
+                    func: Operand::function(self.trace, GenericArgs(vec![]), terminator.span),
+                    args: vec![],
+                    destination: Place::from(self.destination),
+                    target: Some(*target),
+                    unwind: UnwindAction::Continue,
+                };
+                self.new_blocks.push(BasicBlock {
+                    statements: vec![],
+                    terminator: Terminator { kind: call, span: terminator.span },
+                });
+                *target = self.next_block;
+                self.next_block += 1;
+            }
+        }
+        self.super_terminator(terminator, location)
+    }
+}
+
+/// Instruments the branches of `classify` with calls to `trace`.
+fn rewrite(item: &CrateItem, body: &mut Body) -> bool {
+    if item.name() != "classify" {
+        return false;
+    }
+    let trace = stable_mir::all_local_items()
+        .into_iter()
+        .find(|item| item.name() == "trace")
+        .and_then(|item| item.as_fn_def())
+        .unwrap();
+    let unit = Ty::from(TyKind::RigidTy(RigidTy::Tuple(vec![])));
+    let mut visitor = TraceBranches {
+        trace,
+        destination: body.add_local(unit),
+        next_block: body.blocks.len(),
+        new_blocks: vec![],
+    };
+    visitor.visit_body(body);
+    assert_eq!(visitor.new_blocks.len(), 2);
+    for block in visitor.new_blocks {
+        body.add_block(block);
+    }
+    true
+}
+
+/// Assigns a `bool` to the `u32` return place of `classify`, which the MIR validator rejects.
+fn break_types(item: &CrateItem, body: &mut Body) -> bool {
+    if item.name() != "classify" {
+        return false;
+    }
+    let flag = body.add_local(Ty::from(TyKind::RigidTy(RigidTy::Bool)));
+    let block = &mut body.blocks[0];
+    let assign = StatementKind::Assign(Place::from(0), Rvalue::Use(Operand::Copy(flag.into())));
+    block.statements.push(Statement { kind: assign, span: block.terminator.span });
+    true
+}
+
+/// This test will generate a dummy binary crate, and compile it while instrumenting the body of
+/// `classify`. Then it will run the resulting executable, which checks that every branch taken in
+/// `classify` has been traced. Finally, it checks that compiling the crate with an ill-typed
+/// rewritten body fails with an error rather than an ICE.
+fn main() {
+    let path = "instrument_input.rs";
+    let output = "instrument_output";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=bin".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        "-o".to_string(),
+        output.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::instrument(args.clone(), rewrite).unwrap();
+    let status = Command::new(format!("./{output}")).status().unwrap();
+    assert!(status.success());
+    let result = rustc_internal::instrument(args, break_types);
+    assert!(matches!(result, Err(CompilerError::CompilationFailed)), "{result:?}");
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    static mut BRANCHES: u32 = 0;
+
+    pub fn trace() {{
+        unsafe {{ BRANCHES += 1 }};
+    }}
+
+    pub fn classify(x: u32) -> u32 {{
+        if x > 10 {{ 1 }} else {{ 2 }}
+    }}
+
+    fn main() {{
+        let mut sum = 0;
+        for i in 0..20 {{
+            sum += classify(i);
+        }}
+        assert_eq!(sum, 31);
+        assert_eq!(unsafe {{ BRANCHES }}, 20);
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,146 @@
+// run-pass
+// Test that users are able to retrieve type layouts, the initializers of statics and the
+// allocations their pointers point to using the stable mir APIs.
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+
+extern crate rustc_hir;
+extern crate rustc_middle;
+extern crate rustc_smir;
+
+use rustc_hir::def::DefKind;
+use rustc_hir::Mutability;
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::{
+    rustc_internal,
+    stable_mir::{
+        self,
+        ty::{Allocation, FieldsShape, GlobalAlloc, TagEncoding, VariantsShape},
+    },
+};
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::mem::size_of;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+
+    // The fields of `(u8, u32)` are reordered to avoid padding.
+    let pair = get_item(tcx, &items, (DefKind::Fn, "pair")).unwrap();
+    let layout = pair.body().locals[0].layout().unwrap();
+    assert_eq!(layout.size, 8);
+    assert_eq!(layout.align, 4);
+    assert_matches!(layout.fields, FieldsShape::Arbitrary { ref offsets } if offsets == &[4, 0]);
+    assert_matches!(layout.variants, VariantsShape::Single { index: 0 });
+
+    // `Option<&u8>` stores its discriminant in the niche of the reference.
+    let maybe_ref = get_item(tcx, &items, (DefKind::Fn, "maybe_ref")).unwrap();
+    let layout = maybe_ref.body().locals[0].layout().unwrap();
+    assert_eq!(layout.size, size_of::<usize>());
+    assert_matches!(
+        layout.variants,
+        VariantsShape::Multiple { tag_encoding: TagEncoding::Niche { niche_start: 0, .. }, .. }
+    );
+
+    // Generic types have no layout.
+    let generic = get_item(tcx, &items, (DefKind::Fn, "generic")).unwrap();
+    assert!(generic.body().locals[0].layout().is_err());
+
+    // The static is a pointer to the bytes of the string literal, followed by its length.
+    let greeting = get_item(tcx, &items, (DefKind::Static(Mutability::Not), "GREETING")).unwrap();
+    let alloc = greeting.as_static().unwrap().eval_initializer().unwrap();
+    assert_eq!(alloc.bytes.len(), 2 * size_of::<usize>());
+    assert_eq!(read_usize(&alloc, size_of::<usize>()), 5);
+    let [(0, prov)] = alloc.provenance.ptrs[..] else { panic!("{:?}", alloc.provenance) };
+    match prov.global_alloc() {
+        GlobalAlloc::Memory(literal) => assert_eq!(bytes(&literal), b"hello"),
+        other => panic!("{other:?}"),
+    }
+
+    // Pointers to other statics and to functions point to their definitions.
+    let callback = get_item(tcx, &items, (DefKind::Static(Mutability::Not), "CALLBACK")).unwrap();
+    let alloc = callback.as_static().unwrap().eval_initializer().unwrap();
+    let [(0, prov)] = alloc.provenance.ptrs[..] else { panic!("{:?}", alloc.provenance) };
+    assert_matches!(prov.global_alloc(), GlobalAlloc::Function(..));
+    let reference = get_item(tcx, &items, (DefKind::Static(Mutability::Not), "REFERENCE")).unwrap();
+    let alloc = reference.as_static().unwrap().eval_initializer().unwrap();
+    let [(0, prov)] = alloc.provenance.ptrs[..] else { panic!("{:?}", alloc.provenance) };
+    let GlobalAlloc::Static(greeting) = prov.global_alloc() else { panic!() };
+    assert_eq!(read_usize(&greeting.eval_initializer().unwrap(), size_of::<usize>()), 5);
+
+    assert!(pair.as_static().is_none());
+
+    ControlFlow::Continue(())
+}
+
+fn bytes(alloc: &Allocation) -> Vec<u8> {
+    alloc.bytes.iter().map(|byte| byte.unwrap()).collect()
+}
+
+fn read_usize(alloc: &Allocation, offset: usize) -> usize {
+    let bytes = bytes(alloc);
+    usize::from_ne_bytes(bytes[offset..offset + size_of::<usize>()].try_into().unwrap())
+}
+
+// Use internal API to find an item in a crate.
+fn get_item<'a>(
+    tcx: TyCtxt,
+    items: &'a stable_mir::CrateItems,
+    item: (DefKind, &str),
+) -> Option<&'a stable_mir::CrateItem> {
+    items.iter().find(|crate_item| {
+        let def_id = rustc_internal::item_def_id(crate_item);
+        tcx.def_kind(def_id) == item.0 && tcx.def_path_str(def_id) == item.1
+    })
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will run the compiler with custom arguments, and test the crate.
+fn main() {
+    let path = "layout_and_alloc_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir).run().unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub static GREETING: &str = "hello";
+    pub static CALLBACK: fn() -> (u8, u32) = pair;
+    pub static REFERENCE: &&str = &GREETING;
+
+    pub fn pair() -> (u8, u32) {{
+        (1, 2)
+    }}
+
+    pub fn maybe_ref(x: &u8) -> Option<&u8> {{
+        Some(x)
+    }}
+
+    pub fn generic<T: Default>() -> T {{
+        T::default()
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,19 @@
+// compile-flags: -Z print-future-layouts --crate-type lib
+// edition:2021
+// build-pass
+// ignore-pass
+
+#![allow(dropping_copy_types)]
+
+async fn wait() {}
+
+async fn big_fut(arg: [u8; 1024]) {
+    wait().await;
+    drop(arg);
+}
+
+pub async fn test() {
+    let buffer = [0u8; 256];
+    big_fut([0u8; 1024]).await;
+    drop(buffer);
+}
@@ -0,0 +1,34 @@
+// compile-flags: -Z future-size-limit=1024 --crate-type lib
+// edition:2021
+// check-fail
+
+#![deny(oversized_futures)]
+#![allow(dropping_copy_types)]
+
+async fn wait() {}
+
+pub async fn buffered() { //~ ERROR this future is 4098 bytes
+    let buffer = [0u8; 4096];
+    wait().await;
+    drop(buffer);
+}
+
+pub async fn small() {
+    let buffer = [0u8; 16];
+    wait().await;
+    drop(buffer);
+}
+
+pub fn block() -> impl std::future::Future<Output = ()> {
+    async { //~ ERROR this future is 4099 bytes
+        let fut = buffered();
+        fut.await;
+    }
+}
+
+// The size of a generic future depends on its arguments, so it isn't checked.
+pub async fn generic<T>(value: T) {
+    let buffer = [0u8; 4096];
+    wait().await;
+    drop((buffer, value));
+}
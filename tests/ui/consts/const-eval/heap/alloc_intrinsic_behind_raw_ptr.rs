@@ -0,0 +1,64 @@
+// run-pass
+// Const heap memory behind `*const` pointers that are themselves behind a shared reference is
+// interned as immutable memory.
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+#![feature(const_mut_refs)]
+use std::intrinsics;
+use std::mem::{align_of, size_of};
+use std::ptr;
+
+struct Node {
+    value: u8,
+    next: *const Node,
+}
+
+// Only the first node is reachable through a reference, the others only through raw pointers.
+const LIST: &Node = list();
+
+const fn list() -> &'static Node {
+    let mut next = ptr::null();
+    let mut value = 3;
+    while value > 0 {
+        unsafe {
+            let node = intrinsics::const_allocate(size_of::<Node>(), align_of::<Node>());
+            let node = node as *mut Node;
+            *node = Node { value, next };
+            next = node;
+        }
+        value -= 1;
+    }
+    unsafe { &*next }
+}
+
+struct Table {
+    len: usize,
+    data: *const u16,
+}
+
+const SQUARES: &Table = &Table { len: 16, data: squares(16) };
+
+const fn squares(len: usize) -> *const u16 {
+    unsafe {
+        let data = intrinsics::const_allocate(len * size_of::<u16>(), align_of::<u16>());
+        let data = data as *mut u16;
+        let mut i = 0;
+        while i < len {
+            *data.add(i) = (i * i) as u16;
+            i += 1;
+        }
+        data
+    }
+}
+
+fn main() {
+    assert_eq!(LIST.value, 1);
+    let second = unsafe { &*LIST.next };
+    assert_eq!(second.value, 2);
+    let third = unsafe { &*second.next };
+    assert_eq!(third.value, 3);
+    assert!(third.next.is_null());
+
+    let squares = unsafe { std::slice::from_raw_parts(SQUARES.data, SQUARES.len) };
+    assert_eq!(squares[15], 225);
+}
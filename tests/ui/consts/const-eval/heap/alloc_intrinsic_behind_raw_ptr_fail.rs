@@ -0,0 +1,26 @@
+#![feature(core_intrinsics)]
+#![feature(const_heap)]
+#![feature(const_mut_refs)]
+use std::intrinsics;
+use std::mem::{align_of, size_of};
+
+// Const heap memory behind a `*mut` pointer is rejected, even behind a shared reference.
+struct Counter {
+    count: *mut u32,
+}
+
+const COUNTER: &Counter = counter();
+//~^ ERROR unsupported untyped pointer in constant
+
+const fn counter() -> &'static Counter {
+    unsafe {
+        let count = intrinsics::const_allocate(4, 4) as *mut u32;
+        *count = 0;
+        let counter = intrinsics::const_allocate(size_of::<Counter>(), align_of::<Counter>());
+        let counter = counter as *mut Counter;
+        *counter = Counter { count };
+        &*counter
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// Checks the values accepted by `-Zcoverage-options`.
+//
+// revisions: branch both bad
+// compile-flags: -Cinstrument-coverage
+
+// [branch] check-pass
+// [branch] compile-flags: -Zcoverage-options=branch
+
+// [both] check-pass
+// [both] compile-flags: -Zcoverage-options=branch,branch
+
+// [bad] check-fail
+// [bad] compile-flags: -Zcoverage-options=bad
+
+fn main() {}
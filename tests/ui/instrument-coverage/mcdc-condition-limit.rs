@@ -0,0 +1,23 @@
+// Checks that MC/DC coverage is not recorded for decisions with more conditions than LLVM
+// supports, with a warning.
+//
+// min-llvm-version: 18
+// check-pass
+// compile-flags: -Cinstrument-coverage -Zcoverage-options=mcdc
+
+fn accept_6(a: bool, b: bool, c: bool, d: bool, e: bool, f: bool) {
+    if a && b && c && d && e && f {
+        core::hint::black_box("accepted");
+    }
+}
+
+fn reject_7(a: bool, b: bool, c: bool, d: bool, e: bool, f: bool, g: bool) {
+    if a && b && c && d && e && f && g { //~ WARNING this decision has 7 conditions
+        core::hint::black_box("not accepted");
+    }
+}
+
+fn main() {
+    accept_6(false, false, false, false, false, false);
+    reject_7(false, false, false, false, false, false, false);
+}
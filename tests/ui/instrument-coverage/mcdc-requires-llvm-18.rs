@@ -0,0 +1,9 @@
+// Checks that `-Zcoverage-options=mcdc` is rejected when rustc uses an LLVM without support for
+// MC/DC, like the LLVM 17 it is built with by default.
+//
+// ignore-llvm-version: 18 - 99
+// check-fail
+// compile-flags: -Cinstrument-coverage -Zcoverage-options=mcdc
+// normalize-stderr-test "uses LLVM [0-9]+\.[0-9]+\.[0-9]+" -> "uses LLVM $$LLVM_VERSION"
+
+fn main() {}
@@ -0,0 +1,51 @@
+// Checks the paths used in a crate against the rules of `-Z dependency-layering`.
+
+// compile-flags: -Z dependency-layering={{src-base}}/lint/auxiliary/layering-violations.txt
+
+#![allow(dead_code)]
+
+mod infra {
+    pub struct Database;
+
+    pub mod ids {
+        pub struct Id(pub u32);
+    }
+}
+
+// Re-exports don't sidestep the rules.
+mod reexport {
+    pub use crate::infra::Database;
+}
+
+mod domain {
+    use crate::infra::Database;
+    //~^ ERROR `crate::domain` must not depend on `crate::infra::Database`
+
+    pub fn load(_: &crate::reexport::Database) -> crate::infra::ids::Id {
+        //~^ ERROR `crate::domain` must not depend on `crate::infra::Database`
+        crate::infra::ids::Id(0)
+    }
+
+    pub mod nested {
+        pub fn count() -> usize {
+            let map = std::collections::HashMap::<u32, u32>::new();
+            //~^ ERROR `crate::domain::nested` must not depend on `std::collections::hash::map::HashMap`
+            map.len()
+        }
+
+        #[allow(layering_violations)]
+        pub fn allowed() -> std::collections::BTreeSet<u32> {
+            Default::default()
+        }
+    }
+}
+
+// Modules outside of `domain` are unrestricted.
+mod app {
+    pub fn run() {
+        let _ = crate::infra::Database;
+        let _ = std::collections::HashSet::<u32>::new();
+    }
+}
+
+fn main() {}
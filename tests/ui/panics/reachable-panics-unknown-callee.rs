@@ -0,0 +1,10 @@
+// build-fail
+// compile-flags: -Z report-reachable-panics=deny
+
+fn pid() -> u32 {
+    std::process::id() //~ ERROR unknown function `std::process::id` called in `pid`
+}
+
+fn main() {
+    pid();
+}
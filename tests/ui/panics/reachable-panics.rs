@@ -0,0 +1,31 @@
+// build-fail
+// edition:2021
+// compile-flags: -Z report-reachable-panics=deny -C overflow-checks=on -C opt-level=0
+
+fn index(values: &[u32], i: usize) -> u32 {
+    values[i] //~ ERROR panic in `index` reachable from `main`
+}
+
+fn add(a: u8, b: u8) -> u8 {
+    a + b //~ ERROR panic in `add` reachable from `exported`
+}
+
+fn check(value: u32) {
+    if value > 10 {
+        panic!("too large"); //~ ERROR panic in `check` reachable from `main`
+    }
+}
+
+#[allow(dead_code)]
+fn unused() {
+    panic!("never called");
+}
+
+#[no_mangle]
+pub extern "C" fn exported(x: u8) -> u8 {
+    add(x, 1)
+}
+
+fn main() {
+    check(index(&[1, 2, 3], 3));
+}
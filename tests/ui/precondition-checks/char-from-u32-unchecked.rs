@@ -0,0 +1,10 @@
+// run-fail
+// ignore-wasm32-bare: No panic messages
+// compile-flags: -Copt-level=3 -Cdebug-assertions=no -Zub-checks=yes
+// error-pattern: unsafe precondition(s) violated: char::from_u32_unchecked requires
+
+fn main() {
+    unsafe {
+        let _c = char::from_u32_unchecked(std::hint::black_box(0xD800));
+    }
+}
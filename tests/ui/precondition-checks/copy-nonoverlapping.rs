@@ -0,0 +1,12 @@
+// run-fail
+// ignore-wasm32-bare: No panic messages
+// compile-flags: -Copt-level=3 -Cdebug-assertions=no -Zub-checks=yes
+// error-pattern: unsafe precondition(s) violated: ptr::copy_nonoverlapping requires
+
+fn main() {
+    let mut a = [0u8; 4];
+    let p = a.as_mut_ptr();
+    unsafe {
+        std::ptr::copy_nonoverlapping(p, p.add(1), 2);
+    }
+}
@@ -0,0 +1,11 @@
+// run-fail
+// ignore-wasm32-bare: No panic messages
+// compile-flags: -Copt-level=3 -Cdebug-assertions=no -Zub-checks=yes
+// error-pattern: unsafe precondition(s) violated: NonNull::new_unchecked requires
+
+fn main() {
+    unsafe {
+        let ptr = std::hint::black_box(std::ptr::null_mut::<u8>());
+        let _p = std::ptr::NonNull::new_unchecked(ptr);
+    }
+}
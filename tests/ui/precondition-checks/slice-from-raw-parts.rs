@@ -0,0 +1,10 @@
+// run-fail
+// ignore-wasm32-bare: No panic messages
+// compile-flags: -Copt-level=3 -Cdebug-assertions=no -Zub-checks=yes
+// error-pattern: unsafe precondition(s) violated: slice::from_raw_parts requires
+
+fn main() {
+    unsafe {
+        let _s: &[u8] = std::slice::from_raw_parts(std::ptr::null(), 1);
+    }
+}
@@ -0,0 +1,11 @@
+// run-fail
+// ignore-wasm32-bare: No panic messages
+// compile-flags: -Copt-level=3 -Cdebug-assertions=no -Zub-checks=yes
+// error-pattern: unsafe precondition(s) violated: slice::get_unchecked requires
+
+fn main() {
+    let s = [1u8, 2, 3];
+    unsafe {
+        let _x = s.get_unchecked(std::hint::black_box(3));
+    }
+}
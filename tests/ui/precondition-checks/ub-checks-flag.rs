@@ -0,0 +1,13 @@
+// run-pass
+// revisions: default yes no
+// [default] compile-flags: -Cdebug-assertions=yes
+// [yes] compile-flags: -Cdebug-assertions=no -Zub-checks=yes
+// [no] compile-flags: -Cdebug-assertions=yes -Zub-checks=no
+
+// Checks that `-Zub-checks` defaults to `-Cdebug-assertions`, and can override it.
+
+#![feature(core_intrinsics)]
+
+fn main() {
+    assert_eq!(core::intrinsics::ub_checks(), !cfg!(no));
+}
@@ -0,0 +1,12 @@
+// run-fail
+// ignore-wasm32-bare: No panic messages
+// compile-flags: -Copt-level=3 -Cdebug-assertions=no -Zub-checks=yes
+// error-pattern: unsafe precondition(s) violated: u8::unchecked_add cannot overflow
+
+#![feature(unchecked_math)]
+
+fn main() {
+    unsafe {
+        let _x = std::hint::black_box(200u8).unchecked_add(100);
+    }
+}
@@ -0,0 +1,12 @@
+// run-fail
+// ignore-wasm32-bare: No panic messages
+// compile-flags: -Copt-level=3 -Cdebug-assertions=no -Zub-checks=yes
+// error-pattern: unsafe precondition(s) violated: i32::unchecked_mul cannot overflow
+
+#![feature(unchecked_math)]
+
+fn main() {
+    unsafe {
+        let _x = std::hint::black_box(i32::MAX).unchecked_mul(2);
+    }
+}
@@ -0,0 +1,33 @@
+// compile-flags: -Z record-used-items
+
+pub fn used() {}
+
+pub fn unused() {}
+
+pub struct Thing;
+
+impl Thing {
+    pub fn new() -> Thing {
+        Thing
+    }
+
+    pub fn used_method(&self) {}
+
+    pub fn unused_method(&self) {}
+
+    fn private_method(&self) {}
+}
+
+pub mod nested {
+    pub struct Unused;
+
+    pub trait Used {
+        fn method(&self) {}
+    }
+
+    impl Used for super::Thing {}
+}
+
+mod private {
+    pub fn unreachable() {}
+}
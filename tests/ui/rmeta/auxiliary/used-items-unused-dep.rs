@@ -0,0 +1 @@
+pub fn never_called() {}
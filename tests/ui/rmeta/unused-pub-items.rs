@@ -0,0 +1,18 @@
+// Checks that `-Z report-unused-pub-items` reports the public items of upstream crates compiled
+// with `-Z record-used-items` that are never used, and the unused dependencies.
+
+// aux-build:used-items-lib.rs
+// aux-crate:used_items_unused_dep=used-items-unused-dep.rs
+// compile-flags: -Z report-unused-pub-items
+// check-pass
+
+extern crate used_items_lib;
+
+use used_items_lib::nested::Used;
+
+fn main() {
+    used_items_lib::used();
+    let thing = used_items_lib::Thing::new();
+    thing.used_method();
+    thing.method();
+}
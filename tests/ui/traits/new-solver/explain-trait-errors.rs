@@ -0,0 +1,18 @@
+// compile-flags: -Ztrait-solver=next -Zexplain-trait-errors
+
+// Check that `-Zexplain-trait-errors` explains which field keeps an auto trait from being
+// implemented.
+
+use std::rc::Rc;
+
+struct Foo {
+    x: Rc<u8>,
+    y: u32,
+}
+
+fn is_send<T: Send>() {}
+
+fn main() {
+    is_send::<Foo>();
+    //~^ ERROR `Foo` cannot be sent between threads safely
+}